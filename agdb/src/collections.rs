@@ -2,4 +2,5 @@ pub mod bit_set;
 pub mod indexed_map;
 pub mod map;
 pub mod multi_map;
+pub mod ordered_multi_map;
 pub mod vec;
@@ -0,0 +1,499 @@
+use crate::DbError;
+use crate::DbErrorType;
+use crate::StorageData;
+use crate::collections::vec::DbVec;
+use crate::collections::vec::VecValue;
+use crate::storage::Storage;
+use crate::storage::StorageIndex;
+use std::ops::Range;
+
+/// Maximum number of pairs stored in a single page.
+const PAGE_LEN: u64 = 256;
+
+/// Multi map that keeps its `(key, value)` pairs sorted by
+/// key and then by value. The pairs are stored in pages of
+/// at most `PAGE_LEN` pairs listed in order in the root vector
+/// so inserting or removing a pair moves only the pairs in its
+/// page. The pairs are addressed by their position across all
+/// the pages so the lookups are binary searches and the pairs
+/// can be iterated in order (or in reverse) starting at any
+/// position.
+pub struct DbOrderedMultiMap<K, T, D>
+where
+    K: VecValue<D>,
+    T: VecValue<D>,
+    D: StorageData,
+{
+    len: u64,
+    pages: Vec<DbVec<(K, T), D>>,
+    root: DbVec<u64, D>,
+    starts: Vec<u64>,
+}
+
+impl<K, T, D> DbOrderedMultiMap<K, T, D>
+where
+    K: Clone + Ord + VecValue<D>,
+    T: Clone + Ord + VecValue<D>,
+    D: StorageData,
+{
+    pub fn new(storage: &mut Storage<D>) -> Result<Self, DbError> {
+        Ok(Self {
+            len: 0,
+            pages: vec![],
+            root: DbVec::new(storage)?,
+            starts: vec![],
+        })
+    }
+
+    pub fn from_storage(storage: &Storage<D>, index: StorageIndex) -> Result<Self, DbError> {
+        let root = DbVec::<u64, D>::from_storage(storage, index)?;
+        let mut pages = vec![];
+
+        for page_index in root.iter(storage) {
+            pages.push(DbVec::from_storage(
+                storage,
+                StorageIndex::from(page_index),
+            )?);
+        }
+
+        let mut map = Self {
+            len: 0,
+            pages,
+            root,
+            starts: vec![],
+        };
+        map.update_starts(0);
+
+        Ok(map)
+    }
+
+    pub fn entry(&self, storage: &Storage<D>, pos: u64) -> Result<(K, T), DbError> {
+        if self.len <= pos {
+            return Err(DbError::collections(
+                DbErrorType::OutOfBounds,
+                format!("Index ({pos}) out of bounds ({})", self.len),
+            ));
+        }
+
+        let (page, pos) = self.locate(pos);
+        self.pages[page].value(storage, pos)
+    }
+
+    pub fn insert(&mut self, storage: &mut Storage<D>, key: &K, value: &T) -> Result<(), DbError> {
+        let id = storage.transaction();
+
+        if self.pages.is_empty() {
+            let page = DbVec::new(storage)?;
+            self.insert_page(storage, 0, page)?;
+        }
+
+        let pos = self.position(storage, key, value)?;
+        let (mut page, mut pos) = if pos == self.len {
+            let page = self.pages.len() - 1;
+            (page, self.pages[page].len())
+        } else {
+            self.locate(pos)
+        };
+
+        if self.pages[page].len() == PAGE_LEN {
+            // Appending past the last page starts a new one rather
+            // than splitting it so that inserting the pairs in order
+            // leaves the pages full.
+            let at = if page == self.pages.len() - 1 && pos == PAGE_LEN {
+                PAGE_LEN
+            } else {
+                PAGE_LEN / 2
+            };
+            let next = self.pages[page].split_off(storage, at)?;
+            self.insert_page(storage, page + 1, next)?;
+
+            if at <= pos {
+                page += 1;
+                pos -= at;
+            }
+        }
+
+        self.pages[page].insert(storage, pos, &(key.clone(), value.clone()))?;
+        self.update_starts(page + 1);
+        storage.commit(id)
+    }
+
+    pub fn iter<'a>(&'a self, storage: &'a Storage<D>) -> impl Iterator<Item = (K, T)> + 'a {
+        self.pages.iter().flat_map(move |page| page.iter(storage))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns the position of the first pair for which the `predicate`
+    /// returns `false`. The `predicate` must be `true` for a (possibly empty)
+    /// prefix of the keys and `false` for the rest.
+    pub fn partition_point<P: Fn(&K) -> bool>(
+        &self,
+        storage: &Storage<D>,
+        predicate: P,
+    ) -> Result<u64, DbError> {
+        self.partition_point_in(storage, 0..self.len(), |key, _| predicate(key))
+    }
+
+    /// Returns the position of the first pair in the `range` for which
+    /// the `predicate` returns `false` (or the end of the `range`). The
+    /// `predicate` must be `true` for a (possibly empty) prefix of the
+    /// pairs in the `range` and `false` for the rest.
+    pub fn partition_point_in<P: Fn(&K, &T) -> bool>(
+        &self,
+        storage: &Storage<D>,
+        range: Range<u64>,
+        predicate: P,
+    ) -> Result<u64, DbError> {
+        let mut low = range.start;
+        let mut high = range.end;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (key, value) = self.entry(storage, mid)?;
+
+            if predicate(&key, &value) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
+    pub fn remove_from_storage(self, storage: &mut Storage<D>) -> Result<(), DbError> {
+        let id = storage.transaction();
+
+        for page in self.pages {
+            page.remove_from_storage(storage)?;
+        }
+
+        self.root.remove_from_storage(storage)?;
+        storage.commit(id)
+    }
+
+    pub fn remove_value(
+        &mut self,
+        storage: &mut Storage<D>,
+        key: &K,
+        value: &T,
+    ) -> Result<(), DbError> {
+        let pos = self.position(storage, key, value)?;
+
+        if pos < self.len && self.entry(storage, pos)? == (key.clone(), value.clone()) {
+            let (page, pos) = self.locate(pos);
+            let id = storage.transaction();
+            self.pages[page].remove(storage, pos)?;
+
+            if self.pages[page].is_empty() {
+                self.root.remove(storage, page as u64)?;
+                self.pages.remove(page).remove_from_storage(storage)?;
+            }
+
+            self.update_starts(page);
+            storage.commit(id)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn shrink_to_fit(&mut self, storage: &mut Storage<D>) -> Result<(), DbError> {
+        for page in &mut self.pages {
+            page.shrink_to_fit(storage)?;
+        }
+
+        self.root.shrink_to_fit(storage)
+    }
+
+    pub fn storage_index(&self) -> StorageIndex {
+        self.root.storage_index()
+    }
+
+    pub fn values(&self, storage: &Storage<D>, key: &K) -> Result<Vec<T>, DbError> {
+        let start = self.partition_point(storage, |k| k < key)?;
+        let end = self.partition_point(storage, |k| k <= key)?;
+        let mut values = vec![];
+
+        for pos in start..end {
+            values.push(self.entry(storage, pos)?.1);
+        }
+
+        Ok(values)
+    }
+
+    fn insert_page(
+        &mut self,
+        storage: &mut Storage<D>,
+        page: usize,
+        data: DbVec<(K, T), D>,
+    ) -> Result<(), DbError> {
+        self.root
+            .insert(storage, page as u64, &data.storage_index().0)?;
+        self.pages.insert(page, data);
+        self.starts.insert(page, 0);
+        self.update_starts(page);
+        Ok(())
+    }
+
+    /// Returns the page containing the pair at `pos` (that must
+    /// be less than `len`) and the position of the pair in the page.
+    fn locate(&self, pos: u64) -> (usize, u64) {
+        let page = self.starts.partition_point(|start| *start <= pos) - 1;
+        (page, pos - self.starts[page])
+    }
+
+    fn position(&self, storage: &Storage<D>, key: &K, value: &T) -> Result<u64, DbError> {
+        let mut low = 0;
+        let mut high = self.len;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (k, v) = self.entry(storage, mid)?;
+
+            if (&k, &v) < (key, value) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
+    /// Recalculates the starting positions of the pages
+    /// from the page `from` onwards and the total length.
+    fn update_starts(&mut self, from: usize) {
+        self.starts.resize(self.pages.len(), 0);
+        let mut start = match from {
+            0 => 0,
+            _ => self.starts[from - 1] + self.pages[from - 1].len(),
+        };
+
+        for page in from..self.pages.len() {
+            self.starts[page] = start;
+            start += self.pages[page].len();
+        }
+
+        self.len = start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryStorage;
+
+    #[test]
+    fn insert_keeps_order() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        map.insert(&mut storage, &3, &1).unwrap();
+        map.insert(&mut storage, &1, &2).unwrap();
+        map.insert(&mut storage, &2, &3).unwrap();
+        map.insert(&mut storage, &1, &1).unwrap();
+
+        assert_eq!(
+            map.iter(&storage).collect::<Vec<(u64, u64)>>(),
+            vec![(1, 1), (1, 2), (2, 3), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn from_storage() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let index;
+
+        {
+            let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+            map.insert(&mut storage, &2, &1).unwrap();
+            map.insert(&mut storage, &1, &1).unwrap();
+            index = map.storage_index();
+        }
+
+        let map = DbOrderedMultiMap::<u64, u64, _>::from_storage(&storage, index).unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.entry(&storage, 0).unwrap(), (1, 1));
+        assert_eq!(map.entry(&storage, 1).unwrap(), (2, 1));
+    }
+
+    #[test]
+    fn insert_in_order_fills_pages() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        for key in 0..1000 {
+            map.insert(&mut storage, &key, &0).unwrap();
+        }
+
+        assert_eq!(
+            map.pages
+                .iter()
+                .map(|page| page.len())
+                .collect::<Vec<u64>>(),
+            vec![256, 256, 256, 232]
+        );
+        assert_eq!(map.entry(&storage, 700).unwrap(), (700, 0));
+    }
+
+    #[test]
+    fn insert_splits_pages() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let index;
+
+        {
+            let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+            for i in 0..2000 {
+                let key = i * 7919 % 2000;
+                map.insert(&mut storage, &(key / 2), &key).unwrap();
+            }
+
+            assert!(map.pages.len() > 8);
+            assert!(map.pages.iter().all(|page| page.len() <= PAGE_LEN));
+            index = map.storage_index();
+        }
+
+        let map = DbOrderedMultiMap::<u64, u64, _>::from_storage(&storage, index).unwrap();
+
+        assert_eq!(map.len(), 2000);
+        assert_eq!(
+            map.iter(&storage).collect::<Vec<(u64, u64)>>(),
+            (0..2000)
+                .map(|key| (key / 2, key))
+                .collect::<Vec<(u64, u64)>>()
+        );
+        assert_eq!(map.entry(&storage, 1234).unwrap(), (617, 1234));
+        assert_eq!(map.values(&storage, &617).unwrap(), vec![1234, 1235]);
+        assert_eq!(map.partition_point(&storage, |k| *k < 900).unwrap(), 1800);
+    }
+
+    #[test]
+    fn entry_out_of_bounds() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+        map.insert(&mut storage, &1, &1).unwrap();
+
+        assert_eq!(
+            map.entry(&storage, 1),
+            Err(DbError::collections(
+                DbErrorType::OutOfBounds,
+                "Index (1) out of bounds (1)"
+            ))
+        );
+    }
+
+    #[test]
+    fn partition_point() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        for key in [5, 1, 3, 3, 7] {
+            map.insert(&mut storage, &key, &key).unwrap();
+        }
+
+        assert_eq!(map.partition_point(&storage, |k| *k < 3).unwrap(), 1);
+        assert_eq!(map.partition_point(&storage, |k| *k <= 3).unwrap(), 3);
+        assert_eq!(map.partition_point(&storage, |k| *k < 10).unwrap(), 5);
+        assert_eq!(map.partition_point(&storage, |_| false).unwrap(), 0);
+    }
+
+    #[test]
+    fn partition_point_in() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        for (key, value) in [(1, 1), (3, 2), (3, 4), (3, 6), (5, 1)] {
+            map.insert(&mut storage, &key, &value).unwrap();
+        }
+
+        let after = |k: &u64, v: &u64| (*k, *v) <= (3, 4);
+
+        assert_eq!(map.partition_point_in(&storage, 1..4, after).unwrap(), 3);
+        assert_eq!(map.partition_point_in(&storage, 3..4, after).unwrap(), 3);
+        assert_eq!(map.partition_point_in(&storage, 0..2, after).unwrap(), 2);
+        assert_eq!(map.partition_point_in(&storage, 4..5, after).unwrap(), 4);
+        assert_eq!(map.partition_point_in(&storage, 2..2, after).unwrap(), 2);
+    }
+
+    #[test]
+    fn remove_value() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        map.insert(&mut storage, &1, &1).unwrap();
+        map.insert(&mut storage, &1, &2).unwrap();
+        map.insert(&mut storage, &2, &1).unwrap();
+
+        map.remove_value(&mut storage, &1, &2).unwrap();
+        map.remove_value(&mut storage, &3, &1).unwrap();
+
+        assert_eq!(
+            map.iter(&storage).collect::<Vec<(u64, u64)>>(),
+            vec![(1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn remove_empties_pages() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        for key in 0..600 {
+            map.insert(&mut storage, &key, &key).unwrap();
+        }
+
+        for key in (0..600).rev().step_by(2) {
+            map.remove_value(&mut storage, &key, &key).unwrap();
+        }
+
+        assert_eq!(map.len(), 300);
+        assert_eq!(map.entry(&storage, 299).unwrap(), (598, 598));
+
+        for key in (0..600).step_by(2) {
+            map.remove_value(&mut storage, &key, &key).unwrap();
+        }
+
+        assert_eq!(map.len(), 0);
+        assert!(map.pages.is_empty());
+
+        map.insert(&mut storage, &1, &1).unwrap();
+
+        assert_eq!(
+            map.iter(&storage).collect::<Vec<(u64, u64)>>(),
+            vec![(1, 1)]
+        );
+    }
+
+    #[test]
+    fn remove_from_storage() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<String, u64, _>::new(&mut storage).unwrap();
+        map.insert(&mut storage, &"some long key value".to_string(), &1)
+            .unwrap();
+        let len = storage.len();
+
+        map.remove_from_storage(&mut storage).unwrap();
+        storage.optimize_storage().unwrap();
+
+        assert!(storage.len() < len);
+    }
+
+    #[test]
+    fn values() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut map = DbOrderedMultiMap::<u64, u64, _>::new(&mut storage).unwrap();
+
+        map.insert(&mut storage, &1, &3).unwrap();
+        map.insert(&mut storage, &2, &2).unwrap();
+        map.insert(&mut storage, &1, &1).unwrap();
+
+        assert_eq!(map.values(&storage, &1).unwrap(), vec![1, 3]);
+        assert_eq!(map.values(&storage, &2).unwrap(), vec![2]);
+        assert_eq!(map.values(&storage, &3).unwrap(), Vec::<u64>::new());
+    }
+}
@@ -12,6 +12,7 @@ where
     D: StorageData,
 {
     fn capacity(&self) -> u64;
+    fn insert(&mut self, storage: &mut Storage<D>, index: u64, value: &T) -> Result<(), E>;
     fn len(&self) -> u64;
     fn reallocate(&mut self, storage: &mut Storage<D>, capacity: u64) -> Result<(), E>;
     fn remove_from_storage(self, storage: &mut Storage<D>) -> Result<(), E>;
@@ -65,6 +66,29 @@ impl<D: StorageData> VecValue<D> for i64 {
     }
 }
 
+impl<D: StorageData, K: VecValue<D>, T: VecValue<D>> VecValue<D> for (K, T) {
+    fn store(&self, storage: &mut Storage<D>) -> Result<Vec<u8>, DbError> {
+        let key = self.0.store(storage)?;
+        let value = self.1.store(storage)?;
+        Ok([key, value].concat())
+    }
+
+    fn load(storage: &Storage<D>, bytes: &[u8]) -> Result<Self, DbError> {
+        let key = K::load(storage, bytes)?;
+        let value = T::load(storage, &bytes[K::storage_len() as usize..])?;
+        Ok((key, value))
+    }
+
+    fn remove(storage: &mut Storage<D>, bytes: &[u8]) -> Result<(), DbError> {
+        K::remove(storage, bytes)?;
+        T::remove(storage, &bytes[K::storage_len() as usize..])
+    }
+
+    fn storage_len() -> u64 {
+        K::storage_len() + T::storage_len()
+    }
+}
+
 impl<D: StorageData> VecValue<D> for String {
     fn store(&self, storage: &mut Storage<D>) -> Result<Vec<u8>, DbError> {
         let index = storage.insert(self)?;
@@ -119,6 +143,27 @@ where
         self.capacity
     }
 
+    fn insert(&mut self, storage: &mut Storage<D>, index: u64, value: &T) -> Result<(), E> {
+        let id = storage.transaction();
+
+        if index < self.len() {
+            storage.move_at(
+                self.storage_index,
+                Self::offset(index),
+                Self::offset(index + 1),
+                T::storage_len() * (self.len() - index),
+            )?;
+        }
+
+        let bytes = value.store(storage)?;
+        storage.insert_bytes_at(self.storage_index, Self::offset(index), &bytes)?;
+        self.len += 1;
+        storage.insert_at(self.storage_index, 0, &self.len)?;
+        storage.commit(id)?;
+
+        Ok(())
+    }
+
     fn len(&self) -> u64 {
         self.len
     }
@@ -291,11 +336,22 @@ where
         self.data.len()
     }
 
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
+    pub fn insert(&mut self, storage: &mut Storage<D>, index: u64, value: &T) -> Result<(), E> {
+        if self.len() < index {
+            return Err(E::from(DbError::collections(
+                DbErrorType::OutOfBounds,
+                format!("Index ({index}) out of bounds ({})", self.len()),
+            )));
+        }
+
+        self.grow(storage)?;
+        self.data.insert(storage, index, value)
+    }
+
     pub fn iter<'a>(&'a self, storage: &'a Storage<D>) -> VecIterator<'a, T, D, Data, E> {
         VecIterator {
             index: 0,
@@ -305,17 +361,7 @@ where
     }
 
     pub fn push(&mut self, storage: &mut Storage<D>, value: &T) -> Result<(), E> {
-        if self.data.len() == self.data.capacity() {
-            self.data.reallocate(
-                storage,
-                match self.capacity() {
-                    0 => 1,
-                    1 => 2,
-                    _ => self.capacity() + self.capacity() / 2,
-                },
-            )?;
-        }
-
+        self.grow(storage)?;
         self.data.resize(storage, self.data.len() + 1, value)
     }
 
@@ -365,6 +411,21 @@ where
         self.data.value(storage, index)
     }
 
+    fn grow(&mut self, storage: &mut Storage<D>) -> Result<(), E> {
+        if self.data.len() == self.data.capacity() {
+            self.data.reallocate(
+                storage,
+                match self.capacity() {
+                    0 => 1,
+                    1 => 2,
+                    _ => self.capacity() + self.capacity() / 2,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
     fn validate_index(&self, index: u64) -> Result<(), E> {
         if self.len() <= index {
             return Err(E::from(DbError::collections(
@@ -415,6 +476,45 @@ where
         })
     }
 
+    /// Moves the values starting at `at` into a new vector. The
+    /// values are moved as they are stored so nothing they refer
+    /// to in the storage is copied or removed.
+    pub fn split_off(&mut self, storage: &mut Storage<D>, at: u64) -> Result<Self, DbError> {
+        if self.len() < at {
+            return Err(DbError::collections(
+                DbErrorType::OutOfBounds,
+                format!("Index ({at}) out of bounds ({})", self.len()),
+            ));
+        }
+
+        let len = self.len() - at;
+        let mut bytes = len.serialize();
+
+        if len != 0 {
+            bytes.extend_from_slice(&storage.value_as_bytes_at_size(
+                self.data.storage_index,
+                DbVecData::<T, D, DbError>::offset(at),
+                T::storage_len() * len,
+            )?);
+        }
+
+        let id = storage.transaction();
+        let storage_index = storage.insert_bytes(&bytes)?;
+        storage.insert_at(self.data.storage_index, 0, &at)?;
+        storage.commit(id)?;
+        self.data.len = at;
+
+        Ok(Self {
+            phantom_data: PhantomData,
+            data: DbVecData {
+                capacity: len,
+                len,
+                storage_index,
+                phantom_data: PhantomData,
+            },
+        })
+    }
+
     pub fn storage_index(&self) -> StorageIndex {
         self.data.storage_index
     }
@@ -428,6 +528,44 @@ mod tests {
     use crate::storage::file_storage_memory_mapped::FileStorageMemoryMapped;
     use crate::test_utilities::test_file::TestFile;
 
+    #[test]
+    fn corrupted_element_detected_by_whole_value_reads_only() {
+        let test_file = TestFile::new();
+        let value = 0x1122334455667788_u64;
+        let mut vec;
+
+        {
+            let mut storage =
+                Storage::<FileStorageMemoryMapped>::new(test_file.file_name()).unwrap();
+            vec = DbVec::<u64, FileStorageMemoryMapped>::new(&mut storage).unwrap();
+            vec.push(&mut storage, &1).unwrap();
+            vec.push(&mut storage, &value).unwrap();
+        }
+
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            let pos = bytes
+                .windows(8)
+                .position(|window| window == value.serialize())
+                .unwrap();
+            bytes[pos] ^= 1;
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        let storage = Storage::<FileStorageMemoryMapped>::new(test_file.file_name()).unwrap();
+
+        // reading a single element does not check the value checksum
+        assert_eq!(vec.value(&storage, 1).unwrap(), value ^ 1);
+        assert!(
+            DbVec::<u64, FileStorageMemoryMapped>::from_storage(&storage, vec.storage_index())
+                .err()
+                .unwrap()
+                .description
+                .starts_with("Record value checksum mismatch")
+        );
+        assert_eq!(storage.verify().len(), 1);
+    }
+
     #[test]
     fn from_storage_index() {
         let test_file = TestFile::new();
@@ -490,6 +628,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insert() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::new(test_file.file_name()).unwrap();
+
+        let mut vec = DbVec::<String, FileStorageMemoryMapped>::new(&mut storage).unwrap();
+        vec.insert(&mut storage, 0, &"World".to_string()).unwrap();
+        vec.insert(&mut storage, 0, &"Hello".to_string()).unwrap();
+        vec.insert(&mut storage, 2, &"!".to_string()).unwrap();
+        vec.insert(&mut storage, 1, &", ".to_string()).unwrap();
+
+        assert_eq!(
+            vec.iter(&storage).collect::<Vec<String>>(),
+            vec!["Hello", ", ", "World", "!"]
+        );
+    }
+
+    #[test]
+    fn insert_out_of_bounds() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::new(test_file.file_name()).unwrap();
+
+        let mut vec = DbVec::<String, FileStorageMemoryMapped>::new(&mut storage).unwrap();
+
+        assert_eq!(
+            vec.insert(&mut storage, 1, &"Hello".to_string()),
+            Err(DbError::collections(
+                DbErrorType::OutOfBounds,
+                "Index (1) out of bounds (0)"
+            ))
+        );
+    }
+
     #[test]
     fn is_empty() {
         let test_file = TestFile::new();
@@ -817,6 +988,51 @@ mod tests {
         assert_eq!(vec.capacity(), 0);
     }
 
+    #[test]
+    fn split_off() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::new(test_file.file_name()).unwrap();
+
+        let mut vec = DbVec::<String, FileStorageMemoryMapped>::new(&mut storage).unwrap();
+        vec.push(&mut storage, &"Hello".to_string()).unwrap();
+        vec.push(&mut storage, &", ".to_string()).unwrap();
+        vec.push(&mut storage, &"World".to_string()).unwrap();
+        vec.push(&mut storage, &"!".to_string()).unwrap();
+
+        let other = vec.split_off(&mut storage, 1).unwrap();
+        let empty = vec.split_off(&mut storage, 1).unwrap();
+
+        assert_eq!(vec.iter(&storage).collect::<Vec<String>>(), vec!["Hello"]);
+        assert_eq!(
+            other.iter(&storage).collect::<Vec<String>>(),
+            vec![", ", "World", "!"]
+        );
+        assert_eq!(empty.len(), 0);
+
+        let other =
+            DbVec::<String, FileStorageMemoryMapped>::from_storage(&storage, other.storage_index())
+                .unwrap();
+
+        assert_eq!(other.len(), 3);
+        assert_eq!(other.value(&storage, 2).unwrap(), "!");
+    }
+
+    #[test]
+    fn split_off_out_of_bounds() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::new(test_file.file_name()).unwrap();
+
+        let mut vec = DbVec::<String, FileStorageMemoryMapped>::new(&mut storage).unwrap();
+
+        assert_eq!(
+            vec.split_off(&mut storage, 1).err(),
+            Some(DbError::collections(
+                DbErrorType::OutOfBounds,
+                "Index (1) out of bounds (0)"
+            ))
+        );
+    }
+
     #[test]
     fn swap() {
         let test_file = TestFile::new();
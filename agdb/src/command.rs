@@ -13,11 +13,13 @@ pub enum Command {
         to: GraphIndex,
     },
     InsertIndex {
-        key: DbValue,
+        keys: Vec<DbValue>,
+        ordered: bool,
+        unique: bool,
     },
     InsertToIndex {
-        key: DbValue,
-        value: DbValue,
+        keys: Vec<DbValue>,
+        values: Vec<DbValue>,
         id: DbId,
     },
     InsertNode,
@@ -32,7 +34,7 @@ pub enum Command {
         index: GraphIndex,
     },
     RemoveIndex {
-        key: DbValue,
+        keys: Vec<DbValue>,
     },
     RemoveKeyValue {
         id: DbId,
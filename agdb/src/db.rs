@@ -5,35 +5,52 @@ pub mod db_id;
 pub mod db_index;
 pub mod db_key_order;
 pub mod db_key_value;
+pub mod db_salvage;
 pub mod db_type;
 pub mod db_value;
+pub mod db_verify;
 
 mod db_search_handlers;
 mod db_value_index;
 
 use self::db_error::DbError;
 use self::db_error::DbErrorType;
+use self::db_search_handlers::AfterHandler;
 use self::db_search_handlers::DefaultHandler;
+use self::db_search_handlers::IndexHandler;
 use self::db_search_handlers::LimitHandler;
 use self::db_search_handlers::LimitOffsetHandler;
 use self::db_search_handlers::OffsetHandler;
 use self::db_search_handlers::PathHandler;
+use self::db_search_handlers::WeightedPathHandler;
+use crate::AdjacentCondition;
+use crate::AdjacentQuantifier;
+use crate::AnalyticsAlgorithm;
+use crate::Comparison;
 use crate::DbId;
 use crate::DbKeyValue;
 use crate::DbValue;
+use crate::KeyValueComparison;
+use crate::MatchQuery;
+use crate::MatchStep;
 use crate::QueryResult;
 use crate::SearchQueryAlgorithm;
+use crate::SearchQueryDirection;
+use crate::Snapshot;
 use crate::StorageData;
 use crate::Transaction;
 use crate::TransactionMut;
 use crate::collections::indexed_map::DbIndexedMap;
 use crate::command::Command;
+use crate::db::db_index::DbIndexIds;
 use crate::db::db_index::DbIndexes;
 use crate::db::db_key_value::DbKeyValues;
 use crate::graph::DbGraph;
 use crate::graph::GraphIndex;
+use crate::graph_analytics::GraphAnalytics;
 use crate::graph_search::GraphSearch;
 use crate::graph_search::SearchControl;
+use crate::graph_search::SearchHandler;
 use crate::query::Query;
 use crate::query::QueryMut;
 use crate::query::query_condition::QueryCondition;
@@ -44,13 +61,58 @@ use crate::query::query_id::QueryId;
 use crate::storage::Storage;
 use crate::storage::StorageIndex;
 use crate::storage::any_storage::AnyStorage;
+#[cfg(feature = "encryption")]
+use crate::storage::encrypted_storage::Encrypted;
+#[cfg(feature = "encryption")]
+use crate::storage::encrypted_storage::EncryptionKey;
 use crate::storage::file_storage::FileStorage;
 use crate::storage::file_storage_memory_mapped::FileStorageMemoryMapped;
 use crate::storage::memory_storage::MemoryStorage;
 use crate::utilities::serialize::Serialize;
 use crate::utilities::serialize::SerializeStatic;
+use std::collections::HashSet;
 
-const CURRENT_VERSION: u64 = 1;
+const CURRENT_VERSION: u64 = 2;
+
+fn join_values(values: &[DbValue]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn join_ids(ids: &[DbId]) -> String {
+    ids.iter()
+        .map(|id| id.0.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub(crate) fn cursor_element_not_found(id: DbId) -> DbError {
+    DbError::query(
+        DbErrorType::InvalidCursor,
+        format!(
+            "Cursor is no longer valid as its last element ({}) is no longer found",
+            id.0
+        ),
+    )
+}
+
+/// Returns the `ids` following the `after` id
+/// that must be among them (if given).
+fn ids_after(ids: Vec<DbId>, after: Option<DbId>) -> Result<Vec<DbId>, DbError> {
+    let Some(after) = after else {
+        return Ok(ids);
+    };
+
+    let pos = ids
+        .iter()
+        .position(|id| *id == after)
+        .ok_or_else(|| cursor_element_not_found(after))?;
+
+    Ok(ids[pos + 1..].to_vec())
+}
 
 #[derive(Default)]
 struct DbStorageIndex {
@@ -218,6 +280,9 @@ pub type DbTransaction<'a> = Transaction<'a, FileStorageMemoryMapped>;
 /// A convenience alias for the [`TransactionMut`] type for the default [`Db`].
 pub type DbTransactionMut<'a> = TransactionMut<'a, FileStorageMemoryMapped>;
 
+/// A convenience alias for the [`Snapshot`] type for the default [`Db`].
+pub type DbSnapshot = Snapshot<FileStorageMemoryMapped>;
+
 /// The file based implementation of the database (full ACID) with write ahead logging and
 /// but minimum memory footprint but slower than the default [`Db`]. You can load the file
 /// created with [`Db`] and vice versa.
@@ -229,6 +294,9 @@ pub type DbFileTransaction<'a> = Transaction<'a, FileStorage>;
 /// A convenience alias for the [`TransactionMut`] type for the default [`DbFile`].
 pub type DbFileTransactionMut<'a> = TransactionMut<'a, FileStorage>;
 
+/// A convenience alias for the [`Snapshot`] type for the default [`DbFile`].
+pub type DbFileSnapshot = Snapshot<FileStorage>;
+
 /// The purely in-memory implementation of the database. It has no persistence but offers
 /// unmatched performance.
 pub type DbMemory = DbImpl<MemoryStorage>;
@@ -239,6 +307,9 @@ pub type DbMemoryTransaction<'a> = Transaction<'a, MemoryStorage>;
 /// A convenience alias for the [`TransactionMut`] type for the default [`DbMemory`].
 pub type DbMemoryTransactionMut<'a> = TransactionMut<'a, MemoryStorage>;
 
+/// A convenience alias for the [`Snapshot`] type for the default [`DbMemory`].
+pub type DbMemorySnapshot = Snapshot<MemoryStorage>;
+
 /// A convenience alias for a Db type that can use any implemented storage (mapper, memory or file).
 pub type DbAny = DbImpl<AnyStorage>;
 
@@ -248,6 +319,9 @@ pub type DbAnyTransaction<'a> = Transaction<'a, AnyStorage>;
 /// A convenience alias for the [`TransactionMut`] type for the default [`DbAny`].
 pub type DbAnyTransactionMut<'a> = TransactionMut<'a, AnyStorage>;
 
+/// A convenience alias for the [`Snapshot`] type for the default [`DbAny`].
+pub type DbAnySnapshot = Snapshot<AnyStorage>;
+
 impl<Store: StorageData> std::fmt::Debug for DbImpl<Store> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("agdb::Db").finish_non_exhaustive()
@@ -360,6 +434,27 @@ impl<Store: StorageData> DbImpl<Store> {
         self.storage.name()
     }
 
+    /// Converts the values of the `keys` stored prior to the `Bool` and
+    /// `DateTime` value variants. The `bool` used to be stored as `U64`
+    /// (0 or 1) and the `SystemTime` as `Bytes` which are no longer equal
+    /// to the `bool` and `SystemTime` values in the queries (including
+    /// those of the `DbType` structs). As such values cannot be told apart
+    /// from genuine numbers and bytes only the given `keys` are converted
+    /// and any other values they hold are left as they are. The indexes
+    /// are updated accordingly. Runs as a single transaction and returns
+    /// the number of converted values.
+    pub fn migrate_legacy_values(&mut self, keys: &[DbValue]) -> Result<u64, DbError> {
+        let result = self.migrate_legacy_values_impl(keys);
+
+        if result.is_ok() {
+            self.commit()?;
+        } else {
+            self.rollback()?;
+        }
+
+        result
+    }
+
     /// Reclaims no longer used segments of the database file by packing all
     /// used storage segments together. This operation is done automatically
     /// when the database goes out of scope. In long running programs it might
@@ -375,6 +470,17 @@ impl<Store: StorageData> DbImpl<Store> {
         self.storage.rename(filename)
     }
 
+    /// Enables compression of string and bytes values that are at least
+    /// `threshold` bytes long. Pass `None` to disable the compression
+    /// (default). Only affects values written from now on. The setting
+    /// is not persisted but the compressed values are marked as such and
+    /// can always be read back (the `compression` feature is required).
+    /// Values that would not get smaller are stored uncompressed.
+    #[cfg(feature = "compression")]
+    pub fn set_compression_threshold(&mut self, threshold: Option<u64>) {
+        self.storage.set_compression_threshold(threshold);
+    }
+
     /// Executes immutable transaction. The transaction is running a closure `f`
     /// that will receive `&Transaction` object to run `exec` queries as if run
     /// on the main database object. You shall specify the return type `T`
@@ -449,6 +555,44 @@ impl<Store: StorageData> DbImpl<Store> {
         self.optimize_storage()
     }
 
+    /// Takes a read-only point-in-time snapshot of the database. The
+    /// [`Snapshot`] can be queried from other threads while this database
+    /// is being written to without blocking the writer (and vice versa)
+    /// and it will keep returning the data as it was when it was taken.
+    /// Taking a snapshot is cheap as it shares the record table and the
+    /// data with the database. While any snapshot is alive the storage
+    /// is accessed through a lock and the writes preserve the original
+    /// content of the pages they overwrite. The preserved pages are
+    /// released when the snapshot is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agdb::{DbMemory, QueryBuilder};
+    ///
+    /// let mut db = DbMemory::new("test").unwrap();
+    /// db.exec_mut(QueryBuilder::insert().nodes().count(1).query()).unwrap();
+    /// let snapshot = db.snapshot().unwrap();
+    /// db.exec_mut(QueryBuilder::insert().nodes().count(1).query()).unwrap();
+    ///
+    /// let count = QueryBuilder::select().node_count().query();
+    /// assert_eq!(snapshot.exec(&count).unwrap().result, 1);
+    /// assert_eq!(db.exec(&count).unwrap().result, 2);
+    /// ```
+    pub fn snapshot(&mut self) -> Result<Snapshot<Store>, DbError> {
+        let storage = self.storage.snapshot()?;
+        let index = storage.value::<DbStorageIndex>(StorageIndex(1))?;
+
+        Ok(Snapshot::new(Self {
+            graph: DbGraph::from_storage(&storage, index.graph)?,
+            aliases: DbIndexedMap::from_storage(&storage, index.aliases)?,
+            indexes: DbIndexes::from_storage(&storage, index.indexes)?,
+            values: DbKeyValues::from_storage(&storage, index.values)?,
+            storage,
+            undo_stack: vec![],
+        }))
+    }
+
     pub(crate) fn commit(&mut self) -> Result<(), DbError> {
         self.undo_stack.clear();
         Ok(())
@@ -467,23 +611,23 @@ impl<Store: StorageData> DbImpl<Store> {
                     .graph
                     .insert_edge(&mut self.storage, *from, *to)
                     .map(|_| ())?,
-                Command::InsertIndex { key } => {
-                    self.indexes.insert(&mut self.storage, key.clone())?;
+                Command::InsertIndex {
+                    keys,
+                    ordered,
+                    unique,
+                } => {
+                    self.indexes
+                        .insert(&mut self.storage, keys.clone(), *ordered, *unique)?;
                 }
-                Command::InsertToIndex { key, value, id } => self
+                Command::InsertToIndex { keys, values, id } => self
                     .indexes
-                    .index_mut(key)
+                    .index_mut(keys)
                     .expect("index not found during rollback")
-                    .ids_mut()
-                    .insert(&mut self.storage, value, id)?,
+                    .insert(&mut self.storage, values, id)?,
                 Command::InsertKeyValue { id, key_value } => {
-                    if let Some(index) = self.indexes.index_mut(&key_value.key) {
-                        index
-                            .ids_mut()
-                            .insert(&mut self.storage, &key_value.value, id)?;
-                    }
                     self.values
-                        .insert_value(&mut self.storage, id.as_index(), key_value)?
+                        .insert_value(&mut self.storage, id.as_index(), key_value)?;
+                    self.insert_to_indexes(*id, key_value)?;
                 }
                 Command::InsertNode => self.graph.insert_node(&mut self.storage).map(|_| ())?,
                 Command::RemoveAlias { alias } => {
@@ -492,13 +636,9 @@ impl<Store: StorageData> DbImpl<Store> {
                 Command::RemoveEdge { index } => {
                     self.graph.remove_edge(&mut self.storage, *index)?
                 }
-                Command::RemoveIndex { key } => self.indexes.remove(&mut self.storage, key)?,
+                Command::RemoveIndex { keys } => self.indexes.remove(&mut self.storage, keys)?,
                 Command::RemoveKeyValue { id, key_value } => {
-                    if let Some(index) = self.indexes.index_mut(&key_value.key) {
-                        index
-                            .ids_mut()
-                            .remove_value(&mut self.storage, &key_value.value, id)?;
-                    }
+                    self.remove_from_indexes(*id, key_value)?;
                     self.values
                         .remove_value(&mut self.storage, id.as_index(), &key_value.key)?
                 }
@@ -510,15 +650,8 @@ impl<Store: StorageData> DbImpl<Store> {
                         .values
                         .insert_or_replace(&mut self.storage, id.as_index(), key_value)?
                         .expect("old value not found during rollback");
-
-                    if let Some(index) = self.indexes.index_mut(&old.key) {
-                        index
-                            .ids_mut()
-                            .remove_value(&mut self.storage, &old.value, id)?;
-                        index
-                            .ids_mut()
-                            .insert(&mut self.storage, &key_value.value, id)?;
-                    }
+                    self.remove_from_indexes(*id, &old)?;
+                    self.insert_to_indexes(*id, key_value)?;
 
                     return Ok(());
                 }
@@ -539,6 +672,38 @@ impl<Store: StorageData> DbImpl<Store> {
         self.aliases.iter(&self.storage).collect()
     }
 
+    pub(crate) fn analytics(
+        &self,
+        ids: Option<&[DbId]>,
+        algorithm: AnalyticsAlgorithm,
+    ) -> Vec<(DbId, DbValue)> {
+        let nodes = ids.map(|ids| ids.iter().map(|id| GraphIndex(id.0)).collect::<Vec<_>>());
+        let analytics = GraphAnalytics::new(&self.graph, &self.storage, nodes.as_deref());
+
+        fn to_values<T: Into<DbValue>>(values: Vec<(GraphIndex, T)>) -> Vec<(DbId, DbValue)> {
+            values
+                .into_iter()
+                .map(|(index, value)| (DbId(index.0), value.into()))
+                .collect()
+        }
+
+        match algorithm {
+            AnalyticsAlgorithm::WeaklyConnectedComponents => {
+                to_values(analytics.weakly_connected_components())
+            }
+            AnalyticsAlgorithm::StronglyConnectedComponents => {
+                to_values(analytics.strongly_connected_components())
+            }
+            AnalyticsAlgorithm::PageRank(options) => {
+                to_values(analytics.page_rank(options.damping, options.iterations))
+            }
+            AnalyticsAlgorithm::DegreeCentrality => to_values(analytics.degree_centrality()),
+            AnalyticsAlgorithm::BetweennessCentrality => {
+                to_values(analytics.betweenness_centrality())
+            }
+        }
+    }
+
     pub(crate) fn db_id(&self, query_id: &QueryId) -> Result<DbId, DbError> {
         match query_id {
             QueryId::Id(id) => Ok(DbId(self.graph_index(id.0)?.0)),
@@ -599,8 +764,8 @@ impl<Store: StorageData> DbImpl<Store> {
             .indexes()
             .iter()
             .map(|index| DbKeyValue {
-                key: index.key().clone(),
-                value: index.ids().len().into(),
+                key: index.key(),
+                value: index.len().into(),
             })
             .collect()
     }
@@ -630,41 +795,99 @@ impl<Store: StorageData> DbImpl<Store> {
         Ok(DbId(index.0))
     }
 
-    pub(crate) fn insert_index(&mut self, key: &DbValue) -> Result<u64, DbError> {
-        if self.indexes.index(key).is_some() {
+    pub(crate) fn insert_index(
+        &mut self,
+        keys: &[DbValue],
+        ordered: bool,
+        unique: bool,
+    ) -> Result<u64, DbError> {
+        if keys.is_empty() {
+            return Err(DbError::db(
+                DbErrorType::NotEnoughData,
+                "Index requires at least one key",
+            ));
+        }
+
+        if keys
+            .iter()
+            .enumerate()
+            .any(|(i, key)| keys[..i].contains(key))
+        {
             return Err(DbError::db(
                 DbErrorType::NotAllowed,
-                format!("Index '{key}' already exists"),
+                format!("Index keys '{}' must be unique", join_values(keys)),
             ));
         }
 
-        self.undo_stack
-            .push(Command::RemoveIndex { key: key.clone() });
+        if self.indexes.index(keys).is_some() {
+            return Err(DbError::db(
+                DbErrorType::NotAllowed,
+                format!("Index '{}' already exists", join_values(keys)),
+            ));
+        }
+
+        self.undo_stack.push(Command::RemoveIndex {
+            keys: keys.to_vec(),
+        });
 
-        let index = self.indexes.insert(&mut self.storage, key.clone())?;
+        let index = self
+            .indexes
+            .insert(&mut self.storage, keys.to_vec(), ordered, unique)?;
+        let mut conflicts: Vec<(Vec<DbValue>, Vec<DbId>)> = vec![];
 
         for i in 1..self.values.len() {
-            let kvs = self.values.values(&self.storage, i)?;
-            let db_id = if self
-                .graph
-                .node(&self.storage, GraphIndex(i as i64))
-                .is_some()
-            {
-                DbId(i as i64)
-            } else {
-                DbId(-(i as i64))
-            };
+            let kvs = self.values.values_by_keys(&self.storage, i, keys)?;
 
-            for kv in kvs {
-                if kv.key == *key {
-                    index
-                        .ids_mut()
-                        .insert(&mut self.storage, &kv.value, &db_id)?;
+            if kvs.len() == keys.len() {
+                let db_id = if self
+                    .graph
+                    .node(&self.storage, GraphIndex(i as i64))
+                    .is_some()
+                {
+                    DbId(i as i64)
+                } else {
+                    DbId(-(i as i64))
+                };
+                let values = kvs.into_iter().map(|kv| kv.value).collect::<Vec<DbValue>>();
+
+                if unique {
+                    let ids = index.values(&self.storage, &values)?;
+
+                    if !ids.is_empty() {
+                        if let Some((_, conflict)) =
+                            conflicts.iter_mut().find(|(v, _)| *v == values)
+                        {
+                            conflict.push(db_id);
+                        } else {
+                            conflicts.push((values.clone(), [ids, vec![db_id]].concat()));
+                        }
+                    }
                 }
+
+                index.insert(&mut self.storage, &values, &db_id)?;
             }
         }
 
-        Ok(index.ids().len())
+        if !conflicts.is_empty() {
+            return Err(DbError::db(
+                DbErrorType::UniqueViolation,
+                format!(
+                    "Unique index '{}' cannot be created over duplicate values: {}",
+                    join_values(keys),
+                    conflicts
+                        .iter()
+                        .map(|(values, ids)| format!(
+                            "'{}' (ids: {})",
+                            join_values(values),
+                            join_ids(ids)
+                        ))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            ));
+        }
+
+        Ok(index.len())
     }
 
     pub(crate) fn insert_new_alias(&mut self, db_id: DbId, alias: &String) -> Result<(), DbError> {
@@ -688,19 +911,14 @@ impl<Store: StorageData> DbImpl<Store> {
         db_id: DbId,
         key_value: &DbKeyValue,
     ) -> Result<(), DbError> {
-        if let Some(index) = self.indexes.index_mut(&key_value.key) {
-            index
-                .ids_mut()
-                .insert(&mut self.storage, &key_value.value, &db_id)?;
-        }
-
+        self.validate_unique_indexes(db_id, key_value)?;
         self.undo_stack.push(Command::RemoveKeyValue {
             id: db_id,
             key_value: key_value.clone(),
         });
         self.values
             .insert_value(&mut self.storage, db_id.as_index(), key_value)?;
-        Ok(())
+        self.insert_to_indexes(db_id, key_value)
     }
 
     pub(crate) fn insert_or_replace_key_value(
@@ -708,37 +926,56 @@ impl<Store: StorageData> DbImpl<Store> {
         db_id: DbId,
         key_value: &DbKeyValue,
     ) -> Result<(), DbError> {
+        self.validate_unique_indexes(db_id, key_value)?;
+
         if let Some(old) =
             self.values
                 .insert_or_replace(&mut self.storage, db_id.as_index(), key_value)?
         {
-            if let Some(index) = self.indexes.index_mut(&old.key) {
-                index
-                    .ids_mut()
-                    .remove_value(&mut self.storage, &old.value, &db_id)?;
-                index
-                    .ids_mut()
-                    .insert(&mut self.storage, &key_value.value, &db_id)?;
-            }
-
+            self.remove_from_indexes(db_id, &old)?;
             self.undo_stack.push(Command::ReplaceKeyValue {
                 id: db_id,
                 key_value: old,
             });
         } else {
-            if let Some(index) = self.indexes.index_mut(&key_value.key) {
-                index
-                    .ids_mut()
-                    .insert(&mut self.storage, &key_value.value, &db_id)?;
-            }
-
             self.undo_stack.push(Command::RemoveKeyValue {
                 id: db_id,
                 key_value: key_value.clone(),
             });
         }
 
-        Ok(())
+        self.insert_to_indexes(db_id, key_value)
+    }
+
+    fn migrate_legacy_values_impl(&mut self, keys: &[DbValue]) -> Result<u64, DbError> {
+        let ids = self.search_from(
+            DbId(0),
+            SearchQueryAlgorithm::Elements,
+            SearchQueryDirection::Forward,
+            0,
+            0,
+            &vec![],
+        )?;
+        let mut count = 0;
+
+        for id in ids {
+            for key in keys {
+                if let Some(value) = self.value(id, key)?.and_then(|v| v.legacy_converted()) {
+                    let key_value = DbKeyValue {
+                        key: key.clone(),
+                        value,
+                    };
+                    self.insert_or_replace_key_value(id, &key_value)?;
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub(crate) fn value(&self, db_id: DbId, key: &DbValue) -> Result<Option<DbValue>, DbError> {
+        self.values.value(&self.storage, db_id.as_index(), key)
     }
 
     pub(crate) fn keys(&self, db_id: DbId) -> Result<Vec<DbValue>, DbError> {
@@ -797,25 +1034,28 @@ impl<Store: StorageData> DbImpl<Store> {
         Ok(false)
     }
 
-    pub(crate) fn remove_index(&mut self, key: &DbValue) -> Result<u64, DbError> {
+    pub(crate) fn remove_index(&mut self, keys: &[DbValue]) -> Result<u64, DbError> {
         let mut count = None;
 
-        if let Some(index) = self.indexes.index(key) {
-            for (value, id) in index.ids().iter(&self.storage) {
+        if let Some(index) = self.indexes.index(keys) {
+            for (values, id) in index.entries(&self.storage) {
                 self.undo_stack.push(Command::InsertToIndex {
-                    key: key.clone(),
-                    value: value.clone(),
+                    keys: keys.to_vec(),
+                    values,
                     id,
                 });
             }
 
-            count = Some(index.ids().len());
+            count = Some((index.len(), index.is_ordered(), index.is_unique()));
         }
 
-        if let Some(count) = count {
-            self.undo_stack
-                .push(Command::InsertIndex { key: key.clone() });
-            self.indexes.remove(&mut self.storage, key)?;
+        if let Some((count, ordered, unique)) = count {
+            self.undo_stack.push(Command::InsertIndex {
+                keys: keys.to_vec(),
+                ordered,
+                unique,
+            });
+            self.indexes.remove(&mut self.storage, keys)?;
             Ok(count)
         } else {
             Ok(0)
@@ -831,153 +1071,300 @@ impl<Store: StorageData> DbImpl<Store> {
             .reserve_capacity(&mut self.storage, db_id.as_index(), additional)
     }
 
+    /// Searches the index of the `key_value` key for the elements
+    /// whose values satisfy its comparison. When the `after` value
+    /// and id of the last element returned by a previous search are
+    /// given the search resumes right after it. Ordered indexes look
+    /// up the position following the entry so it need not exist
+    /// anymore but the other indexes require the id to be found.
     pub(crate) fn search_index(
         &self,
-        key: &DbValue,
-        value: &DbValue,
+        key_value: &KeyValueComparison,
+        reverse: bool,
+        limit: u64,
+        offset: u64,
+        after: Option<(&DbValue, DbId)>,
+        conditions: &[QueryCondition],
     ) -> Result<Vec<DbId>, DbError> {
-        if let Some(index) = self.indexes.index(key) {
-            Ok(index.ids().values(&self.storage, value)?)
+        let keys = std::slice::from_ref(&key_value.key);
+        let index = self.indexes.index(keys).ok_or(DbError::db(
+            DbErrorType::NotFound,
+            format!("Index '{}' not found", key_value.key),
+        ))?;
+        let mut handler = IndexHandler::new(limit, offset, self, conditions);
+
+        if let DbIndexIds::Ordered(ids) = index.ids() {
+            let mut ranges = ids.ranges(&self.storage, &key_value.value)?;
+
+            if let Some(after) = after {
+                ranges = ids.ranges_after(&self.storage, ranges, after, reverse)?;
+            }
+
+            ids.visit_sorted(&self.storage, ranges, reverse, |value, id| {
+                Ok(key_value.value.compare(&value) && handler.process(id)?)
+            })?;
+        } else if let Comparison::Equal(value) = &key_value.value {
+            let mut ids = index.values(&self.storage, std::slice::from_ref(value))?;
+
+            for equivalent in value.numeric_equivalents() {
+                ids.extend(index.values(&self.storage, &[equivalent])?);
+            }
+
+            if reverse {
+                ids.reverse();
+            }
+
+            for id in ids_after(ids, after.map(|(_, id)| id))? {
+                if handler.process(id)? {
+                    break;
+                }
+            }
         } else {
-            Err(DbError::db(
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                format!(
+                    "Index '{}' is not ordered and supports only equality comparison",
+                    key_value.key
+                ),
+            ));
+        }
+
+        Ok(handler.ids)
+    }
+
+    /// Returns the keys of the index whose leading keys are the `leading`
+    /// keys and, unless the `rest` is empty, whose other keys are the
+    /// `rest`. Both are matched regardless of their order. The index
+    /// with exactly these keys is preferred over the longer ones.
+    pub(crate) fn composite_index_keys(
+        &self,
+        leading: &[DbValue],
+        rest: &[DbValue],
+    ) -> Result<Vec<DbValue>, DbError> {
+        let same_keys = |keys: &[DbValue], other: &[DbValue]| {
+            keys.len() == other.len() && keys.iter().all(|key| other.contains(key))
+        };
+        let candidates = self.indexes.indexes().iter().filter(|index| {
+            let keys = index.keys();
+
+            leading.len() <= keys.len()
+                && same_keys(&keys[..leading.len()], leading)
+                && (rest.is_empty() || same_keys(&keys[leading.len()..], rest))
+        });
+        let len = leading.len() + rest.len();
+        let mut found = None;
+
+        for index in candidates {
+            if index.keys().len() == len {
+                found = Some(index);
+                break;
+            }
+
+            found.get_or_insert(index);
+        }
+
+        found.map(|index| index.keys().to_vec()).ok_or_else(|| {
+            DbError::db(
                 DbErrorType::NotFound,
-                format!("Index '{key}' not found"),
-            ))
+                format!(
+                    "Index '{}' not found",
+                    join_values(&[leading, rest].concat())
+                ),
+            )
+        })
+    }
+
+    /// Searches the composite index over `keys` for elements whose
+    /// values of the leading keys equal to `values`. Lookups by
+    /// fewer values than there are keys require ordered index. The
+    /// search resumes after the `after` values of all the keys and
+    /// the id the same way as `search_index()`.
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn search_composite_index(
+        &self,
+        keys: &[DbValue],
+        values: &[DbValue],
+        reverse: bool,
+        limit: u64,
+        offset: u64,
+        after: Option<(&[DbValue], DbId)>,
+        conditions: &[QueryCondition],
+    ) -> Result<Vec<DbId>, DbError> {
+        let index = self.indexes.index(keys).ok_or(DbError::db(
+            DbErrorType::NotFound,
+            format!("Index '{}' not found", join_values(keys)),
+        ))?;
+        let mut handler = IndexHandler::new(limit, offset, self, conditions);
+
+        if let DbIndexIds::CompositeOrdered(ids) = index.ids() {
+            let mut range = ids.prefix_range(&self.storage, values)?;
+
+            if let Some(after) = after {
+                range = ids.range_after(&self.storage, range, after, reverse)?;
+            }
+
+            let positions: Box<dyn Iterator<Item = u64>> = if reverse {
+                Box::new(range.rev())
+            } else {
+                Box::new(range)
+            };
+
+            for pos in positions {
+                if handler.process(ids.entry(&self.storage, pos)?.1)? {
+                    break;
+                }
+            }
+        } else if values.len() == keys.len() {
+            let mut ids = index.values(&self.storage, values)?;
+
+            if reverse {
+                ids.reverse();
+            }
+
+            for id in ids_after(ids, after.map(|(_, id)| id))? {
+                if handler.process(id)? {
+                    break;
+                }
+            }
+        } else {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                format!(
+                    "Index '{}' is not ordered and supports only lookups by all of its keys",
+                    join_values(keys)
+                ),
+            ));
         }
+
+        Ok(handler.ids)
     }
 
     pub(crate) fn search_from(
         &self,
         from: DbId,
         algorithm: SearchQueryAlgorithm,
+        direction: SearchQueryDirection,
         limit: u64,
         offset: u64,
         conditions: &Vec<QueryCondition>,
     ) -> Result<Vec<DbId>, DbError> {
-        let search = GraphSearch::from((&self.graph, &self.storage));
+        let index = GraphIndex(from.0);
 
         let indexes = match (limit, offset) {
-            (0, 0) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search(
-                    GraphIndex(from.0),
-                    DefaultHandler::new(self, conditions),
-                )?,
-                SearchQueryAlgorithm::DepthFirst => search.depth_first_search(
-                    GraphIndex(from.0),
-                    DefaultHandler::new(self, conditions),
-                )?,
-                _ => search.elements(DefaultHandler::new(self, conditions))?,
-            },
-
-            (_, 0) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search(
-                    GraphIndex(from.0),
-                    LimitHandler::new(limit, self, conditions),
-                )?,
-                SearchQueryAlgorithm::DepthFirst => search.depth_first_search(
-                    GraphIndex(from.0),
-                    LimitHandler::new(limit, self, conditions),
-                )?,
-                _ => search.elements(LimitHandler::new(limit, self, conditions))?,
-            },
-
-            (0, _) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search(
-                    GraphIndex(from.0),
-                    OffsetHandler::new(offset, self, conditions),
-                )?,
-                SearchQueryAlgorithm::DepthFirst => search.depth_first_search(
-                    GraphIndex(from.0),
-                    OffsetHandler::new(offset, self, conditions),
-                )?,
-                _ => search.elements(OffsetHandler::new(offset, self, conditions))?,
-            },
-
-            (_, _) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search(
-                    GraphIndex(from.0),
-                    LimitOffsetHandler::new(limit, offset, self, conditions),
-                )?,
-                SearchQueryAlgorithm::DepthFirst => search.depth_first_search(
-                    GraphIndex(from.0),
-                    LimitOffsetHandler::new(limit, offset, self, conditions),
-                )?,
-                _ => search.elements(LimitOffsetHandler::new(limit, offset, self, conditions))?,
-            },
+            (0, 0) => self.search_graph(
+                index,
+                algorithm,
+                direction,
+                DefaultHandler::new(self, conditions),
+            )?,
+
+            (_, 0) => self.search_graph(
+                index,
+                algorithm,
+                direction,
+                LimitHandler::new(limit, self, conditions),
+            )?,
+
+            (0, _) => self.search_graph(
+                index,
+                algorithm,
+                direction,
+                OffsetHandler::new(offset, self, conditions),
+            )?,
+
+            (_, _) => self.search_graph(
+                index,
+                algorithm,
+                direction,
+                LimitOffsetHandler::new(limit, offset, self, conditions),
+            )?,
         };
 
         Ok(indexes.iter().map(|index| DbId(index.0)).collect())
     }
 
-    pub(crate) fn search_to(
+    /// Searches the graph like `search_from()` but skips the elements
+    /// up to and including the `after` element returned last by a
+    /// previous search. The graph is traversed again from the start
+    /// as the traversal state is not kept between the searches. Fails
+    /// if the `after` element is no longer reached by the search.
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn search_from_after(
         &self,
-        to: DbId,
+        from: DbId,
         algorithm: SearchQueryAlgorithm,
+        direction: SearchQueryDirection,
+        after: DbId,
         limit: u64,
         offset: u64,
         conditions: &Vec<QueryCondition>,
     ) -> Result<Vec<DbId>, DbError> {
-        let search = GraphSearch::from((&self.graph, &self.storage));
-
-        let indexes = match (limit, offset) {
-            (0, 0) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search_reverse(
-                    GraphIndex(to.0),
-                    DefaultHandler::new(self, conditions),
-                )?,
-                _ => search.depth_first_search_reverse(
-                    GraphIndex(to.0),
-                    DefaultHandler::new(self, conditions),
-                )?,
-            },
-
-            (_, 0) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search_reverse(
-                    GraphIndex(to.0),
-                    LimitHandler::new(limit, self, conditions),
-                )?,
-                _ => search.depth_first_search_reverse(
-                    GraphIndex(to.0),
-                    LimitHandler::new(limit, self, conditions),
-                )?,
-            },
-
-            (0, _) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search_reverse(
-                    GraphIndex(to.0),
-                    OffsetHandler::new(offset, self, conditions),
-                )?,
-                _ => search.depth_first_search_reverse(
-                    GraphIndex(to.0),
-                    OffsetHandler::new(offset, self, conditions),
-                )?,
-            },
-
-            (_, _) => match algorithm {
-                SearchQueryAlgorithm::BreadthFirst => search.breadth_first_search_reverse(
-                    GraphIndex(to.0),
-                    LimitOffsetHandler::new(limit, offset, self, conditions),
-                )?,
-                _ => search.depth_first_search_reverse(
-                    GraphIndex(to.0),
-                    LimitOffsetHandler::new(limit, offset, self, conditions),
-                )?,
-            },
-        };
+        let mut found = false;
+        let indexes = self.search_graph(
+            GraphIndex(from.0),
+            algorithm,
+            direction,
+            AfterHandler::new(
+                GraphIndex(after.0),
+                &mut found,
+                limit,
+                offset,
+                self,
+                conditions,
+            ),
+        )?;
+
+        if !found {
+            return Err(cursor_element_not_found(after));
+        }
 
         Ok(indexes.iter().map(|index| DbId(index.0)).collect())
     }
 
+    fn search_graph<Handler: SearchHandler>(
+        &self,
+        index: GraphIndex,
+        algorithm: SearchQueryAlgorithm,
+        direction: SearchQueryDirection,
+        handler: Handler,
+    ) -> Result<Vec<GraphIndex>, DbError> {
+        let search = GraphSearch::from((&self.graph, &self.storage));
+
+        match (algorithm, direction) {
+            (SearchQueryAlgorithm::BreadthFirst, SearchQueryDirection::Forward) => {
+                search.breadth_first_search(index, handler)
+            }
+            (SearchQueryAlgorithm::BreadthFirst, SearchQueryDirection::Reverse) => {
+                search.breadth_first_search_reverse(index, handler)
+            }
+            (SearchQueryAlgorithm::BreadthFirst, SearchQueryDirection::Both) => {
+                search.breadth_first_search_both(index, handler)
+            }
+            (SearchQueryAlgorithm::DepthFirst, SearchQueryDirection::Forward) => {
+                search.depth_first_search(index, handler)
+            }
+            (SearchQueryAlgorithm::DepthFirst, SearchQueryDirection::Reverse) => {
+                search.depth_first_search_reverse(index, handler)
+            }
+            (SearchQueryAlgorithm::DepthFirst, SearchQueryDirection::Both) => {
+                search.depth_first_search_both(index, handler)
+            }
+            _ => search.elements(handler),
+        }
+    }
+
     pub(crate) fn search_from_to(
         &self,
         from: DbId,
         to: DbId,
+        direction: SearchQueryDirection,
         conditions: &Vec<QueryCondition>,
     ) -> Result<Vec<DbId>, DbError> {
         Ok(GraphSearch::from((&self.graph, &self.storage))
             .path(
                 GraphIndex(from.0),
                 GraphIndex(to.0),
+                direction,
                 PathHandler::new(self, conditions),
             )?
             .iter()
@@ -985,6 +1372,234 @@ impl<Store: StorageData> DbImpl<Store> {
             .collect())
     }
 
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn search_paths(
+        &self,
+        from: DbId,
+        to: DbId,
+        direction: SearchQueryDirection,
+        max_paths: u64,
+        max_depth: u64,
+        weight: Option<&DbValue>,
+        conditions: &Vec<QueryCondition>,
+    ) -> Result<Vec<Vec<(DbId, f64)>>, DbError> {
+        let search = GraphSearch::from((&self.graph, &self.storage));
+        let (from, to) = (GraphIndex(from.0), GraphIndex(to.0));
+        let paths = if let Some(key) = weight {
+            let handler = WeightedPathHandler::new(self, conditions, key);
+            search.paths(from, to, direction, handler, max_paths, max_depth)?
+        } else {
+            let handler = PathHandler::new(self, conditions);
+            search.paths(from, to, direction, handler, max_paths, max_depth)?
+        };
+
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(|(index, weight)| (DbId(index.0), weight))
+                    .collect()
+            })
+            .collect())
+    }
+
+    pub(crate) fn search_from_to_weighted(
+        &self,
+        from: DbId,
+        to: DbId,
+        direction: SearchQueryDirection,
+        key: &DbValue,
+        conditions: &Vec<QueryCondition>,
+    ) -> Result<Vec<(DbId, f64)>, DbError> {
+        Ok(GraphSearch::from((&self.graph, &self.storage))
+            .weighted_path(
+                GraphIndex(from.0),
+                GraphIndex(to.0),
+                direction,
+                WeightedPathHandler::new(self, conditions, key),
+            )?
+            .into_iter()
+            .map(|(index, weight)| (DbId(index.0), weight))
+            .collect())
+    }
+
+    /// Nodes reachable from `from` in topological order that
+    /// satisfy the `conditions` as if found by breadth-first search.
+    pub(crate) fn search_topological(
+        &self,
+        from: DbId,
+        direction: SearchQueryDirection,
+        conditions: &Vec<QueryCondition>,
+    ) -> Result<Vec<DbId>, DbError> {
+        let order = GraphSearch::from((&self.graph, &self.storage))
+            .topological_sort(GraphIndex(from.0), direction)?
+            .into_iter()
+            .map(|index| DbId(index.0));
+
+        if conditions.is_empty() {
+            return Ok(order.collect());
+        }
+
+        let found = self
+            .search_from(
+                from,
+                SearchQueryAlgorithm::BreadthFirst,
+                direction,
+                0,
+                0,
+                conditions,
+            )?
+            .into_iter()
+            .collect::<HashSet<DbId>>();
+
+        Ok(order.filter(|id| found.contains(id)).collect())
+    }
+
+    /// Ids bound to the variables of the pattern for each of its
+    /// matches in the order they were found respecting `offset`
+    /// and `limit` of the `query`.
+    pub(crate) fn search_pattern(&self, query: &MatchQuery) -> Result<Vec<Vec<DbId>>, DbError> {
+        let max = if query.limit == 0 {
+            0
+        } else {
+            query.offset + query.limit
+        };
+        // The ids narrow down the candidates only if no other
+        // condition can admit a node on its own.
+        let narrowing = query.node.conditions.iter().all(|condition| {
+            condition.logic == QueryConditionLogic::And
+                && condition.modifier == QueryConditionModifier::None
+        });
+        let candidates = match query.node.conditions.first() {
+            Some(QueryCondition {
+                data: QueryConditionData::Ids(ids),
+                ..
+            }) if narrowing => {
+                let mut candidates = vec![];
+
+                for id in ids {
+                    let index = GraphIndex(self.db_id(id)?.0);
+
+                    if index.is_node() {
+                        candidates.push(index);
+                    }
+                }
+
+                candidates
+            }
+            _ => self
+                .graph
+                .node_iter(&self.storage)
+                .map(|node| node.index())
+                .collect(),
+        };
+        let mut matches = vec![];
+        let mut binding = vec![];
+
+        for index in candidates {
+            if self
+                .evaluate_conditions(index, 0, &query.node.conditions)?
+                .is_true()
+            {
+                binding.push(index);
+                let more =
+                    self.search_pattern_steps(&query.steps, &mut binding, &mut matches, max)?;
+                binding.pop();
+
+                if !more {
+                    break;
+                }
+            }
+        }
+
+        Ok(matches
+            .into_iter()
+            .skip(query.offset as usize)
+            .map(|ids: Vec<GraphIndex>| ids.into_iter().map(|index| DbId(index.0)).collect())
+            .collect())
+    }
+
+    fn search_pattern_steps(
+        &self,
+        steps: &[MatchStep],
+        binding: &mut Vec<GraphIndex>,
+        matches: &mut Vec<Vec<GraphIndex>>,
+        max: u64,
+    ) -> Result<bool, DbError> {
+        let Some((step, steps)) = steps.split_first() else {
+            matches.push(binding.clone());
+            return Ok(max == 0 || (matches.len() as u64) < max);
+        };
+        let node = binding[binding.len() - 1];
+        let distance = binding.len() as u64;
+
+        for edge in self.adjacent_indexes(node, step.direction) {
+            if binding.contains(&edge)
+                || !self
+                    .evaluate_conditions(edge, distance, &step.edge.conditions)?
+                    .is_true()
+            {
+                continue;
+            }
+
+            let from = self.graph.edge_from(&self.storage, edge);
+            let to = self.graph.edge_to(&self.storage, edge);
+            let next = match step.direction {
+                SearchQueryDirection::Forward => to,
+                SearchQueryDirection::Reverse => from,
+                SearchQueryDirection::Both if from == node => to,
+                SearchQueryDirection::Both => from,
+            };
+
+            if !self
+                .evaluate_conditions(next, distance + 1, &step.node.conditions)?
+                .is_true()
+            {
+                continue;
+            }
+
+            binding.extend([edge, next]);
+            let more = self.search_pattern_steps(steps, binding, matches, max)?;
+            binding.truncate(binding.len() - 2);
+
+            if !more {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Fails if a new edge `from` -> `to` would close a cycle, i.e.
+    /// if there already is a path from `to` to `from`.
+    pub(crate) fn validate_acyclic_edge(&self, from: DbId, to: DbId) -> Result<(), DbError> {
+        let mut cycle = if from == to {
+            vec![to]
+        } else {
+            self.search_from_to(to, from, SearchQueryDirection::Forward, &vec![])?
+        };
+
+        if cycle.is_empty() {
+            return Ok(());
+        }
+
+        cycle.push(to);
+
+        Err(DbError::db(
+            DbErrorType::Cycle,
+            format!(
+                "Edge from '{}' to '{}' would create a cycle: {}",
+                from.0,
+                to.0,
+                cycle
+                    .iter()
+                    .map(|id| id.0.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            ),
+        ))
+    }
+
     pub(crate) fn values(&self, db_id: DbId) -> Result<Vec<DbKeyValue>, DbError> {
         self.values.values(&self.storage, db_id.as_index())
     }
@@ -994,8 +1609,52 @@ impl<Store: StorageData> DbImpl<Store> {
         db_id: DbId,
         keys: &[DbValue],
     ) -> Result<Vec<DbKeyValue>, DbError> {
-        self.values
-            .values_by_keys(&self.storage, db_id.as_index(), keys)
+        let values = self
+            .values
+            .values_by_keys(&self.storage, db_id.as_index(), keys)?;
+
+        if values.len() == keys.len() {
+            return Ok(values);
+        }
+
+        let mut result = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            if let Some(kv) = values.iter().find(|kv| kv.key == *key) {
+                result.push(kv.clone());
+            } else if let Some(value) = self.nested_value(db_id.as_index(), key)? {
+                result.push(DbKeyValue {
+                    key: key.clone(),
+                    value,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the value of the `key` of the element at `index`. If the
+    /// key does not exist but is a string containing `.` it is treated
+    /// as a path to a value nested in a map or list.
+    fn key_value(&self, index: u64, key: &DbValue) -> Result<Option<DbValue>, DbError> {
+        if let Some(value) = self.values.value(&self.storage, index, key)? {
+            return Ok(Some(value));
+        }
+
+        self.nested_value(index, key)
+    }
+
+    fn nested_value(&self, index: u64, key: &DbValue) -> Result<Option<DbValue>, DbError> {
+        if let DbValue::String(path) = key
+            && let Some((root, path)) = path.split_once('.')
+            && let Some(value) = self.values.value(&self.storage, index, &root.into())?
+        {
+            return Ok(value
+                .nested(&path.split('.').collect::<Vec<&str>>())
+                .cloned());
+        }
+
+        Ok(None)
     }
 
     fn graph_index(&self, id: i64) -> Result<GraphIndex, DbError> {
@@ -1097,11 +1756,7 @@ impl<Store: StorageData> DbImpl<Store> {
 
         for key_value in self.values.values(&self.storage, db_id.as_index())? {
             if keys.contains(&key_value.key) {
-                if let Some(index) = self.indexes.index_mut(&key_value.key) {
-                    index
-                        .ids_mut()
-                        .remove_value(&mut self.storage, &key_value.value, &db_id)?;
-                }
+                self.remove_from_indexes(db_id, &key_value)?;
                 self.values
                     .remove_value(&mut self.storage, db_id.as_index(), &key_value.key)?;
                 self.undo_stack.push(Command::InsertKeyValue {
@@ -1115,14 +1770,95 @@ impl<Store: StorageData> DbImpl<Store> {
         Ok(result)
     }
 
-    fn remove_all_values(&mut self, db_id: DbId) -> Result<(), DbError> {
-        for key_value in self.values.values(&self.storage, db_id.as_index())? {
-            if let Some(index) = self.indexes.index_mut(&key_value.key) {
-                index
-                    .ids_mut()
-                    .remove_value(&mut self.storage, &key_value.value, &db_id)?;
+    fn insert_to_indexes(&mut self, db_id: DbId, key_value: &DbKeyValue) -> Result<(), DbError> {
+        for index in self.indexes.indexes_mut() {
+            if let Some(values) =
+                Self::index_values(&self.storage, &self.values, index.keys(), db_id, key_value)?
+            {
+                index.insert(&mut self.storage, &values, &db_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_from_indexes(&mut self, db_id: DbId, key_value: &DbKeyValue) -> Result<(), DbError> {
+        for index in self.indexes.indexes_mut() {
+            if let Some(values) =
+                Self::index_values(&self.storage, &self.values, index.keys(), db_id, key_value)?
+            {
+                index.remove_value(&mut self.storage, &values, &db_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the values of the index `keys` of the element `db_id` with
+    /// the value of the `key_value` key taken from the `key_value` itself.
+    /// Returns `None` if the index does not cover the `key_value` key or
+    /// if the element is missing any of the other keys.
+    fn index_values(
+        storage: &Storage<Store>,
+        values: &DbKeyValues<Store>,
+        keys: &[DbValue],
+        db_id: DbId,
+        key_value: &DbKeyValue,
+    ) -> Result<Option<Vec<DbValue>>, DbError> {
+        if !keys.contains(&key_value.key) {
+            return Ok(None);
+        }
+
+        if keys.len() == 1 {
+            return Ok(Some(vec![key_value.value.clone()]));
+        }
+
+        let kvs = values.values_by_keys(storage, db_id.as_index(), keys)?;
+        let mut index_values = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            if *key == key_value.key {
+                index_values.push(key_value.value.clone());
+            } else if let Some(kv) = kvs.iter().find(|kv| kv.key == *key) {
+                index_values.push(kv.value.clone());
+            } else {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(index_values))
+    }
+
+    /// Fails if setting the `key_value` on the element `db_id` would
+    /// make it share the indexed values with another element in any
+    /// of the unique indexes.
+    fn validate_unique_indexes(&self, db_id: DbId, key_value: &DbKeyValue) -> Result<(), DbError> {
+        for index in self.indexes.indexes().iter().filter(|i| i.is_unique()) {
+            if let Some(values) =
+                Self::index_values(&self.storage, &self.values, index.keys(), db_id, key_value)?
+                && let Some(id) = index
+                    .values(&self.storage, &values)?
+                    .into_iter()
+                    .find(|id| *id != db_id)
+            {
+                return Err(DbError::db(
+                    DbErrorType::UniqueViolation,
+                    format!(
+                        "Value '{}' of unique index '{}' already exists (id: {})",
+                        join_values(&values),
+                        join_values(index.keys()),
+                        id.0
+                    ),
+                ));
             }
+        }
 
+        Ok(())
+    }
+
+    fn remove_all_values(&mut self, db_id: DbId) -> Result<(), DbError> {
+        for key_value in self.values.values(&self.storage, db_id.as_index())? {
+            self.remove_from_indexes(db_id, &key_value)?;
             self.undo_stack.push(Command::InsertKeyValue {
                 id: db_id,
                 key_value,
@@ -1155,13 +1891,16 @@ impl<Store: StorageData> DbImpl<Store> {
             };
             storage.insert_at(StorageIndex(1), 0, &db_storage_index)?;
         } else {
-            let index = if let Ok(index) = storage.value::<DbStorageIndex>(StorageIndex(1)) {
+            let mut index = if let Ok(index) = storage.value::<DbStorageIndex>(StorageIndex(1)) {
                 index
             } else {
-                legacy::convert_to_current_version(&mut storage)?;
-                storage.value::<DbStorageIndex>(StorageIndex(1))?
+                legacy::convert_to_version_1(&mut storage)?
             };
 
+            if index.version < CURRENT_VERSION {
+                index = legacy::convert_to_version_2(&mut storage, index)?;
+            }
+
             graph_storage = DbGraph::from_storage(&storage, index.graph)?;
             aliases_storage = DbIndexedMap::from_storage(&storage, index.aliases)?;
             indexes_storage = DbIndexes::from_storage(&storage, index.indexes)?;
@@ -1228,12 +1967,24 @@ impl<Store: StorageData> DbImpl<Store> {
                 })))
             }
             QueryConditionData::KeyValue(kvc) => Ok(SearchControl::Continue(
-                if let Some(value) = self.values.value(&self.storage, index.as_u64(), &kvc.key)? {
+                if let Some(value) = self.key_value(index.as_u64(), &kvc.key)? {
                     kvc.value.compare(&value)
                 } else {
                     false
                 },
             )),
+            QueryConditionData::KeyKey(kvc) => {
+                let left = self.key_value(index.as_u64(), &kvc.key)?;
+                let right = self.key_value(index.as_u64(), kvc.value.value())?;
+
+                Ok(SearchControl::Continue(
+                    if let (Some(left), Some(right)) = (left, right) {
+                        kvc.value.with_value(right).compare(&left)
+                    } else {
+                        false
+                    },
+                ))
+            }
             QueryConditionData::Keys(values) => {
                 let keys = self.values.keys(&self.storage, index.as_u64())?;
                 Ok(SearchControl::Continue(
@@ -1244,7 +1995,92 @@ impl<Store: StorageData> DbImpl<Store> {
             QueryConditionData::Where(conditions) => {
                 self.evaluate_conditions(index, distance, conditions)
             }
+            QueryConditionData::Adjacent(condition) => Ok(SearchControl::Continue(
+                self.evaluate_adjacent(index, distance, condition)?,
+            )),
+        }
+    }
+
+    fn evaluate_adjacent(
+        &self,
+        index: GraphIndex,
+        distance: u64,
+        condition: &AdjacentCondition,
+    ) -> Result<bool, DbError> {
+        let mut count = 0;
+
+        for adjacent in self.adjacent_indexes(index, condition.direction) {
+            let passed = self
+                .evaluate_conditions(adjacent, distance + 1, &condition.conditions)?
+                .is_true();
+
+            match condition.quantifier {
+                AdjacentQuantifier::Any if passed => return Ok(true),
+                AdjacentQuantifier::All if !passed => return Ok(false),
+                _ => {}
+            }
+
+            if passed {
+                count += 1;
+            }
+        }
+
+        Ok(match &condition.quantifier {
+            AdjacentQuantifier::Any => false,
+            AdjacentQuantifier::All => true,
+            AdjacentQuantifier::Count(comparison) => comparison.compare(count),
+        })
+    }
+
+    fn adjacent_indexes(
+        &self,
+        index: GraphIndex,
+        direction: SearchQueryDirection,
+    ) -> Vec<GraphIndex> {
+        let mut indexes = vec![];
+
+        if index.is_node() {
+            if let Some(node) = self.graph.node(&self.storage, index) {
+                if direction != SearchQueryDirection::Reverse {
+                    indexes.extend(node.edge_iter_from().map(|edge| edge.index()));
+                }
+
+                if direction != SearchQueryDirection::Forward {
+                    indexes.extend(
+                        node.edge_iter_to()
+                            .filter(|edge| {
+                                direction == SearchQueryDirection::Reverse
+                                    || edge.index_from() != index
+                            })
+                            .map(|edge| edge.index()),
+                    );
+                }
+            }
+        } else if let Some(edge) = self.graph.edge(&self.storage, index) {
+            let (from, to) = (edge.index_from(), edge.index_to());
+
+            if direction != SearchQueryDirection::Reverse {
+                indexes.push(to);
+            }
+
+            if direction == SearchQueryDirection::Reverse
+                || (direction == SearchQueryDirection::Both && from != to)
+            {
+                indexes.push(from);
+            }
         }
+
+        indexes
+    }
+
+    pub(crate) fn satisfies_conditions(
+        &self,
+        db_id: DbId,
+        conditions: &[QueryCondition],
+    ) -> Result<bool, DbError> {
+        Ok(self
+            .evaluate_conditions(GraphIndex(db_id.0), 0, conditions)?
+            .is_true())
     }
 
     pub(crate) fn evaluate_conditions(
@@ -1289,11 +2125,53 @@ impl<Store: StorageData> DbImpl<Store> {
 
 impl<Store: StorageData> Drop for DbImpl<Store> {
     fn drop(&mut self) {
-        let _ = self.storage.optimize_storage();
+        if !self.storage.is_snapshot() {
+            let _ = self.storage.optimize_storage();
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl<D: StorageData> DbImpl<Encrypted<D>> {
+    /// Tries to create or load `filename` as a database encrypted
+    /// with `key`. Loading fails if the `key` does not match the one
+    /// the database was created with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let _test_file = agdb::test_utilities::test_file::TestFile::from("encrypted_db.agdb");
+    /// use agdb::{DbImpl, Encrypted, FileStorage};
+    ///
+    /// let key = [7_u8; 32];
+    /// let mut db = DbImpl::<Encrypted<FileStorage>>::new_encrypted("encrypted_db.agdb", &key).unwrap();
+    /// ```
+    pub fn new_encrypted(filename: &str, key: &EncryptionKey) -> Result<Self, DbError> {
+        match Encrypted::with_key(filename, key).and_then(Self::with_data) {
+            Ok(db) => Ok(db),
+            Err(error) => {
+                let mut db_error = DbError::db(
+                    DbErrorType::DbCreate,
+                    format!("Failed to create database: {filename}"),
+                );
+                db_error.cause = Some(Box::new(error));
+                Err(db_error)
+            }
+        }
     }
 }
 
 impl DbAny {
+    /// Creates a new DbAny instance using DbFile encrypted with `key`.
+    #[cfg(feature = "encryption")]
+    pub fn new_encrypted(filename: &str, key: &EncryptionKey) -> Result<Self, DbError> {
+        Self::try_new_any(filename, |filename| {
+            Self::try_new_with_storage(Storage::with_data(AnyStorage::Encrypted(
+                Encrypted::with_key(filename, key)?,
+            ))?)
+        })
+    }
+
     /// Creates a new DbAny instance using DbFile.
     pub fn new_file(filename: &str) -> Result<Self, DbError> {
         Self::try_new_any(filename, Self::try_new_file)
@@ -1311,7 +2189,7 @@ impl DbAny {
 
     fn try_new_any(
         filename: &str,
-        init: fn(&str) -> Result<DbImpl<AnyStorage>, DbError>,
+        init: impl FnOnce(&str) -> Result<DbImpl<AnyStorage>, DbError>,
     ) -> Result<Self, DbError> {
         match init(filename) {
             Ok(db) => Ok(db),
@@ -1345,7 +2223,8 @@ impl DbAny {
     }
 }
 
-// TODO: Remove this at some point in the future as it provides support for databases created in <= 0.10.0.
+// TODO: Remove this at some point in the future as it provides support for databases created
+// in <= 0.10.0 (version 0) and databases without ordered indexes support (version 1).
 mod legacy {
     use crate::DbError;
     use crate::DbId;
@@ -1353,9 +2232,12 @@ mod legacy {
     use crate::StorageData;
     use crate::collections::map::MapIterator;
     use crate::collections::multi_map::MultiMapStorage;
-    use crate::db::CURRENT_VERSION;
+    use crate::collections::vec::DbVec;
+    use crate::collections::vec::VecValue;
     use crate::db::DbStorageIndex;
+    use crate::db::db_index::DbIndexStorageIndex;
     use crate::db::db_key_value::DbKeyValues;
+    use crate::db::db_value_index::DbValueIndex;
     use crate::storage::Storage;
     use crate::storage::StorageIndex;
     use crate::utilities::serialize::Serialize;
@@ -1403,7 +2285,36 @@ mod legacy {
         }
     }
 
-    pub fn convert_to_current_version<D: StorageData>(
+    pub(crate) struct DbIndexStorageIndexLegacy {
+        pub(crate) key_index: DbValueIndex,
+        pub(crate) ids_index: StorageIndex,
+    }
+
+    impl<D: StorageData> VecValue<D> for DbIndexStorageIndexLegacy {
+        fn store(&self, _storage: &mut Storage<D>) -> Result<Vec<u8>, DbError> {
+            Ok([self.key_index.serialize(), self.ids_index.serialize()].concat())
+        }
+
+        fn load(_storage: &Storage<D>, bytes: &[u8]) -> Result<Self, DbError> {
+            let key_index = DbValueIndex::deserialize(bytes)?;
+            let ids_index =
+                StorageIndex::deserialize(&bytes[key_index.serialized_size() as usize..])?;
+            Ok(Self {
+                key_index,
+                ids_index,
+            })
+        }
+
+        fn remove(_storage: &mut Storage<D>, _bytes: &[u8]) -> Result<(), DbError> {
+            Ok(())
+        }
+
+        fn storage_len() -> u64 {
+            DbValueIndex::serialized_size_static() + StorageIndex::serialized_size_static()
+        }
+    }
+
+    pub fn convert_to_version_1<D: StorageData>(
         storage: &mut Storage<D>,
     ) -> Result<DbStorageIndex, DbError> {
         let legacy_index = storage.value::<DbStorageIndexLegacy>(StorageIndex(1))?;
@@ -1432,7 +2343,7 @@ mod legacy {
         legacy_values.remove_from_storage(storage)?;
 
         let db_storage_index = DbStorageIndex {
-            version: CURRENT_VERSION,
+            version: 1,
             graph: legacy_index.graph,
             aliases: legacy_index.aliases,
             indexes: legacy_index.indexes,
@@ -1444,6 +2355,39 @@ mod legacy {
         storage.optimize_storage()?;
         Ok(db_storage_index)
     }
+
+    pub fn convert_to_version_2<D: StorageData>(
+        storage: &mut Storage<D>,
+        index: DbStorageIndex,
+    ) -> Result<DbStorageIndex, DbError> {
+        let legacy_indexes =
+            DbVec::<DbIndexStorageIndexLegacy, D>::from_storage(storage, index.indexes)?;
+        let t = storage.transaction();
+        let mut indexes = DbVec::<DbIndexStorageIndex, D>::new(storage)?;
+
+        for legacy_index in legacy_indexes.iter(storage).collect::<Vec<_>>() {
+            indexes.push(
+                storage,
+                &DbIndexStorageIndex {
+                    key_index: legacy_index.key_index,
+                    ids_index: legacy_index.ids_index,
+                    flags: 0,
+                },
+            )?;
+        }
+
+        legacy_indexes.remove_from_storage(storage)?;
+
+        let db_storage_index = DbStorageIndex {
+            version: 2,
+            indexes: indexes.storage_index(),
+            ..index
+        };
+
+        storage.replace(StorageIndex(1), &db_storage_index)?;
+        storage.commit(t)?;
+        Ok(db_storage_index)
+    }
 }
 
 #[cfg(test)]
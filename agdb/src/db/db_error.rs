@@ -11,13 +11,20 @@ use std::sync::PoisonError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DbErrorType {
+    Corrupted,
+    Cycle,
     DbCreate,
+    InvalidCursor,
     InvalidIndex,
+    InvalidPattern,
+    InvalidQuery,
     NotAllowed,
     NotEnoughData,
     NotFound,
     OutOfBounds,
+    PreconditionFailed,
     TypeError,
+    UniqueViolation,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -105,13 +112,20 @@ impl DbError {
 impl Display for DbErrorType {
     fn fmt(&self, f: &mut Formatter<'_>) -> FMTResult {
         match self {
+            DbErrorType::Corrupted => write!(f, "Corrupted"),
+            DbErrorType::Cycle => write!(f, "Cycle"),
             DbErrorType::DbCreate => write!(f, "DbCreate"),
+            DbErrorType::InvalidCursor => write!(f, "InvalidCursor"),
             DbErrorType::InvalidIndex => write!(f, "InvalidIndex"),
+            DbErrorType::InvalidPattern => write!(f, "InvalidPattern"),
+            DbErrorType::InvalidQuery => write!(f, "InvalidQuery"),
             DbErrorType::NotEnoughData => write!(f, "NotEnoughData"),
             DbErrorType::NotFound => write!(f, "NotFound"),
             DbErrorType::OutOfBounds => write!(f, "OutOfBounds"),
             DbErrorType::NotAllowed => write!(f, "NotAllowed"),
+            DbErrorType::PreconditionFailed => write!(f, "PreconditionFailed"),
             DbErrorType::TypeError => write!(f, "TypeError"),
+            DbErrorType::UniqueViolation => write!(f, "UniqueViolation"),
         }
     }
 }
@@ -1,8 +1,10 @@
+use crate::Comparison;
 use crate::DbError;
 use crate::DbId;
 use crate::DbValue;
 use crate::StorageData;
 use crate::collections::multi_map::MultiMapStorage;
+use crate::collections::ordered_multi_map::DbOrderedMultiMap;
 use crate::collections::vec::DbVec;
 use crate::collections::vec::VecValue;
 use crate::db::db_value_index::DbValueIndex;
@@ -10,20 +12,46 @@ use crate::storage::Storage;
 use crate::storage::StorageIndex;
 use crate::utilities::serialize::Serialize;
 use crate::utilities::serialize::SerializeStatic;
+use std::mem::discriminant;
+use std::ops::Range;
+
+const ORDERED_INDEX_FLAG: u64 = 1;
+const COMPOSITE_INDEX_FLAG: u64 = 2;
+const UNIQUE_INDEX_FLAG: u64 = 4;
+
+/// Positions of the values of a numeric type in an ordered
+/// index along with the smallest and the largest value of the type.
+struct NumericSection {
+    first: DbValue,
+    last: DbValue,
+    range: Range<u64>,
+}
 
 #[derive(Copy, Clone)]
 pub struct DbIndexStorageIndex {
-    key_index: DbValueIndex,
-    ids_index: StorageIndex,
+    pub(crate) key_index: DbValueIndex,
+    pub(crate) ids_index: StorageIndex,
+    pub(crate) flags: u64,
+}
+
+pub enum DbIndexIds<D>
+where
+    D: StorageData,
+{
+    Hash(MultiMapStorage<DbValue, DbId, D>),
+    Ordered(DbOrderedMultiMap<DbValue, DbId, D>),
+    CompositeHash(MultiMapStorage<Vec<DbValue>, DbId, D>),
+    CompositeOrdered(DbOrderedMultiMap<Vec<DbValue>, DbId, D>),
 }
 
 pub struct DbIndex<D>
 where
     D: StorageData,
 {
-    key: DbValue,
+    keys: Vec<DbValue>,
     key_index: DbValueIndex,
-    ids: MultiMapStorage<DbValue, DbId, D>,
+    ids: DbIndexIds<D>,
+    unique: bool,
 }
 
 pub struct DbIndexes<D>
@@ -40,55 +68,546 @@ where
 {
     pub fn from_storage(storage: &Storage<D>, index: DbIndexStorageIndex) -> Result<Self, DbError> {
         let key_index = index.key_index;
-        let key = DbValue::load_db_value(key_index, storage)?;
-        let ids = MultiMapStorage::from_storage(storage, index.ids_index)?;
+        let ordered = index.flags & ORDERED_INDEX_FLAG != 0;
+
+        let (keys, ids) = if index.flags & COMPOSITE_INDEX_FLAG != 0 {
+            let keys = load_db_values(storage, key_index)?;
+            let ids = if ordered {
+                DbIndexIds::CompositeOrdered(DbOrderedMultiMap::from_storage(
+                    storage,
+                    index.ids_index,
+                )?)
+            } else {
+                DbIndexIds::CompositeHash(MultiMapStorage::from_storage(storage, index.ids_index)?)
+            };
+            (keys, ids)
+        } else {
+            let keys = vec![DbValue::load_db_value(key_index, storage)?];
+            let ids = if ordered {
+                DbIndexIds::Ordered(DbOrderedMultiMap::from_storage(storage, index.ids_index)?)
+            } else {
+                DbIndexIds::Hash(MultiMapStorage::from_storage(storage, index.ids_index)?)
+            };
+            (keys, ids)
+        };
 
         Ok(Self {
+            keys,
             key_index,
-            key,
             ids,
+            unique: index.flags & UNIQUE_INDEX_FLAG != 0,
         })
     }
 
-    pub fn ids(&self) -> &MultiMapStorage<DbValue, DbId, D> {
+    pub fn entries(&self, storage: &Storage<D>) -> Vec<(Vec<DbValue>, DbId)> {
+        match &self.ids {
+            DbIndexIds::Hash(ids) => ids.iter(storage).map(|(k, id)| (vec![k], id)).collect(),
+            DbIndexIds::Ordered(ids) => ids.iter(storage).map(|(k, id)| (vec![k], id)).collect(),
+            DbIndexIds::CompositeHash(ids) => ids.iter(storage).collect(),
+            DbIndexIds::CompositeOrdered(ids) => ids.iter(storage).collect(),
+        }
+    }
+
+    pub fn ids(&self) -> &DbIndexIds<D> {
         &self.ids
     }
 
-    pub fn ids_mut(&mut self) -> &mut MultiMapStorage<DbValue, DbId, D> {
-        &mut self.ids
+    /// Inserts the `id` under the `values` that must contain
+    /// one value for each of the index keys.
+    pub fn insert(
+        &mut self,
+        storage: &mut Storage<D>,
+        values: &[DbValue],
+        id: &DbId,
+    ) -> Result<(), DbError> {
+        match &mut self.ids {
+            DbIndexIds::Hash(ids) => ids.insert(storage, &values[0], id),
+            DbIndexIds::Ordered(ids) => ids.insert(storage, &values[0], id),
+            DbIndexIds::CompositeHash(ids) => ids.insert(storage, &values.to_vec(), id),
+            DbIndexIds::CompositeOrdered(ids) => ids.insert(storage, &values.to_vec(), id),
+        }
     }
 
-    pub fn key(&self) -> &DbValue {
-        &self.key
+    pub fn is_composite(&self) -> bool {
+        self.keys.len() > 1
     }
 
-    pub fn new(key: DbValue, storage: &mut Storage<D>) -> Result<Self, DbError> {
-        let key_index = key.store_db_value(storage)?;
-        let ids = MultiMapStorage::new(storage)?;
+    pub fn is_ordered(&self) -> bool {
+        matches!(
+            self.ids,
+            DbIndexIds::Ordered(_) | DbIndexIds::CompositeOrdered(_)
+        )
+    }
+
+    /// Returns the key the index is reported under. Composite
+    /// indexes are reported as a list of their keys.
+    pub fn key(&self) -> DbValue {
+        if self.is_composite() {
+            DbValue::VecString(self.keys.iter().map(|k| k.to_string()).collect())
+        } else {
+            self.keys[0].clone()
+        }
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    pub fn keys(&self) -> &[DbValue] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> u64 {
+        match &self.ids {
+            DbIndexIds::Hash(ids) => ids.len(),
+            DbIndexIds::Ordered(ids) => ids.len(),
+            DbIndexIds::CompositeHash(ids) => ids.len(),
+            DbIndexIds::CompositeOrdered(ids) => ids.len(),
+        }
+    }
+
+    pub fn new(
+        keys: Vec<DbValue>,
+        ordered: bool,
+        unique: bool,
+        storage: &mut Storage<D>,
+    ) -> Result<Self, DbError> {
+        let (key_index, ids) = if keys.len() > 1 {
+            let key_index = store_db_values(storage, &keys)?;
+            let ids = if ordered {
+                DbIndexIds::CompositeOrdered(DbOrderedMultiMap::new(storage)?)
+            } else {
+                DbIndexIds::CompositeHash(MultiMapStorage::new(storage)?)
+            };
+            (key_index, ids)
+        } else {
+            let key_index = keys[0].store_db_value(storage)?;
+            let ids = if ordered {
+                DbIndexIds::Ordered(DbOrderedMultiMap::new(storage)?)
+            } else {
+                DbIndexIds::Hash(MultiMapStorage::new(storage)?)
+            };
+            (key_index, ids)
+        };
 
         Ok(Self {
-            key,
+            keys,
             key_index,
             ids,
+            unique,
         })
     }
 
     pub fn remove_from_storage(self, storage: &mut Storage<D>) -> Result<(), DbError> {
         let id = storage.transaction();
-        DbValue::remove(storage, &self.key_index.data())?;
-        self.ids.remove_from_storage(storage)?;
+
+        match self.ids {
+            DbIndexIds::Hash(ids) => {
+                DbValue::remove(storage, &self.key_index.data())?;
+                ids.remove_from_storage(storage)?;
+            }
+            DbIndexIds::Ordered(ids) => {
+                DbValue::remove(storage, &self.key_index.data())?;
+                ids.remove_from_storage(storage)?;
+            }
+            DbIndexIds::CompositeHash(ids) => {
+                <Vec<DbValue> as VecValue<D>>::remove(storage, &self.key_index.data())?;
+                ids.remove_from_storage(storage)?;
+            }
+            DbIndexIds::CompositeOrdered(ids) => {
+                <Vec<DbValue> as VecValue<D>>::remove(storage, &self.key_index.data())?;
+                ids.remove_from_storage(storage)?;
+            }
+        }
+
         storage.commit(id)
     }
 
+    pub fn remove_value(
+        &mut self,
+        storage: &mut Storage<D>,
+        values: &[DbValue],
+        id: &DbId,
+    ) -> Result<(), DbError> {
+        match &mut self.ids {
+            DbIndexIds::Hash(ids) => ids.remove_value(storage, &values[0], id),
+            DbIndexIds::Ordered(ids) => ids.remove_value(storage, &values[0], id),
+            DbIndexIds::CompositeHash(ids) => ids.remove_value(storage, &values.to_vec(), id),
+            DbIndexIds::CompositeOrdered(ids) => ids.remove_value(storage, &values.to_vec(), id),
+        }
+    }
+
     pub fn shrink_to_fit(&mut self, storage: &mut Storage<D>) -> Result<(), DbError> {
-        self.ids.shrink_to_fit(storage)
+        match &mut self.ids {
+            DbIndexIds::Hash(ids) => ids.shrink_to_fit(storage),
+            DbIndexIds::Ordered(ids) => ids.shrink_to_fit(storage),
+            DbIndexIds::CompositeHash(ids) => ids.shrink_to_fit(storage),
+            DbIndexIds::CompositeOrdered(ids) => ids.shrink_to_fit(storage),
+        }
     }
 
     pub fn storage_index(&self) -> DbIndexStorageIndex {
+        let (ids_index, flags) = match &self.ids {
+            DbIndexIds::Hash(ids) => (ids.storage_index(), 0),
+            DbIndexIds::Ordered(ids) => (ids.storage_index(), ORDERED_INDEX_FLAG),
+            DbIndexIds::CompositeHash(ids) => (ids.storage_index(), COMPOSITE_INDEX_FLAG),
+            DbIndexIds::CompositeOrdered(ids) => (
+                ids.storage_index(),
+                COMPOSITE_INDEX_FLAG | ORDERED_INDEX_FLAG,
+            ),
+        };
+
         DbIndexStorageIndex {
             key_index: self.key_index,
-            ids_index: self.ids.storage_index(),
+            ids_index,
+            flags: if self.unique {
+                flags | UNIQUE_INDEX_FLAG
+            } else {
+                flags
+            },
+        }
+    }
+
+    /// Returns the ids stored under the `values` that must contain
+    /// one value for each of the index keys.
+    pub fn values(&self, storage: &Storage<D>, values: &[DbValue]) -> Result<Vec<DbId>, DbError> {
+        match &self.ids {
+            DbIndexIds::Hash(ids) => ids.values(storage, &values[0]),
+            DbIndexIds::Ordered(ids) => ids.values(storage, &values[0]),
+            DbIndexIds::CompositeHash(ids) => ids.values(storage, &values.to_vec()),
+            DbIndexIds::CompositeOrdered(ids) => ids.values(storage, &values.to_vec()),
+        }
+    }
+}
+
+impl<D> DbOrderedMultiMap<Vec<DbValue>, DbId, D>
+where
+    D: StorageData,
+{
+    /// Returns the range of positions in the index whose values
+    /// start with the `prefix` values.
+    pub fn prefix_range(
+        &self,
+        storage: &Storage<D>,
+        prefix: &[DbValue],
+    ) -> Result<Range<u64>, DbError> {
+        let start = self.partition_point(storage, |k| &k[..prefix.len()] < prefix)?;
+        let end = self.partition_point(storage, |k| &k[..prefix.len()] <= prefix)?;
+        Ok(start..end)
+    }
+
+    /// Narrows the `range` to the entries that follow the entry of
+    /// the `values` and the `id` in the iteration order (reversed
+    /// if `reverse`). The entry itself need not exist anymore.
+    pub fn range_after(
+        &self,
+        storage: &Storage<D>,
+        range: Range<u64>,
+        (values, id): (&[DbValue], DbId),
+        reverse: bool,
+    ) -> Result<Range<u64>, DbError> {
+        let ordering = |k: &Vec<DbValue>, i: &DbId| k.as_slice().cmp(values).then(i.cmp(&id));
+
+        Ok(if reverse {
+            range.start..self.partition_point_in(storage, range, |k, i| ordering(k, i).is_lt())?
+        } else {
+            self.partition_point_in(storage, range.clone(), |k, i| ordering(k, i).is_le())?
+                ..range.end
+        })
+    }
+}
+
+impl<D> DbOrderedMultiMap<DbValue, DbId, D>
+where
+    D: StorageData,
+{
+    /// Returns the ranges of positions in the index whose values may
+    /// satisfy the `comparison`. The ranges are ordered and do not
+    /// overlap. The values in the ranges still need to be tested against
+    /// the comparison as some comparisons (e.g. `Contains`) cannot be
+    /// narrowed down by the ordering and span the entire index. No range
+    /// spans numbers of different types so the ranges can be merged in
+    /// the sort order of the values (see `visit_sorted()`).
+    #[allow(clippy::single_range_in_vec_init)]
+    pub fn ranges(
+        &self,
+        storage: &Storage<D>,
+        comparison: &Comparison,
+    ) -> Result<Vec<Range<u64>>, DbError> {
+        let len = self.len();
+        let value = comparison.value();
+        let sections = self.numeric_sections(storage)?;
+
+        if matches!(value, DbValue::I64(_) | DbValue::U64(_) | DbValue::F64(_))
+            && matches!(
+                comparison,
+                Comparison::Equal(_)
+                    | Comparison::GreaterThan(_)
+                    | Comparison::GreaterThanOrEqual(_)
+                    | Comparison::LessThan(_)
+                    | Comparison::LessThanOrEqual(_)
+                    | Comparison::NotEqual(_)
+            )
+        {
+            return self.numeric_ranges(storage, comparison, &sections);
+        }
+
+        let ranges = match comparison {
+            Comparison::Equal(_) => {
+                vec![self.lower_bound(storage, value)?..self.upper_bound(storage, value)?]
+            }
+            Comparison::GreaterThan(_) => vec![self.upper_bound(storage, value)?..len],
+            Comparison::GreaterThanOrEqual(_) => vec![self.lower_bound(storage, value)?..len],
+            Comparison::LessThan(_) => vec![0..self.lower_bound(storage, value)?],
+            Comparison::LessThanOrEqual(_) => vec![0..self.upper_bound(storage, value)?],
+            Comparison::NotEqual(_) => vec![
+                0..self.lower_bound(storage, value)?,
+                self.upper_bound(storage, value)?..len,
+            ],
+            Comparison::StartsWith(_) => {
+                let mut ranges = vec![];
+
+                for prefix in starts_with_prefixes(value) {
+                    let start = self.lower_bound(storage, &prefix)?;
+                    let end = self.partition_point(storage, |k| {
+                        k < &prefix
+                            || (discriminant(k) == discriminant(&prefix) && comparison.compare(k))
+                    })?;
+                    ranges.push(start..end);
+                }
+
+                ranges
+            }
+            Comparison::Contains(_)
+            | Comparison::EndsWith(_)
+            | Comparison::EqualIgnoreCase(_)
+            | Comparison::ContainsIgnoreCase(_)
+            | Comparison::StartsWithIgnoreCase(_)
+            | Comparison::EndsWithIgnoreCase(_)
+            | Comparison::Matches(_) => vec![0..len],
+        };
+
+        Ok(ranges
+            .into_iter()
+            .flat_map(|range| {
+                let mut start = range.start;
+                let mut parts = vec![];
+
+                for section in &sections {
+                    for boundary in [section.range.start, section.range.end] {
+                        if start < boundary && boundary < range.end {
+                            parts.push(start..boundary);
+                            start = boundary;
+                        }
+                    }
+                }
+
+                parts.push(start..range.end);
+                parts
+            })
+            .collect())
+    }
+
+    /// Visits the entries in the `ranges` (see `ranges()`) in the sort
+    /// order of their values (see `DbValue::sort_cmp()`) merging the
+    /// ranges of the numbers of different types. Stops when the `visitor`
+    /// returns `true`.
+    pub fn visit_sorted(
+        &self,
+        storage: &Storage<D>,
+        ranges: Vec<Range<u64>>,
+        reverse: bool,
+        mut visitor: impl FnMut(DbValue, DbId) -> Result<bool, DbError>,
+    ) -> Result<(), DbError> {
+        let mut heads = vec![];
+
+        for mut range in ranges {
+            if let Some(entry) = self.next_entry(storage, &mut range, reverse)? {
+                heads.push((entry, range));
+            }
+        }
+
+        while let Some(i) = heads
+            .iter()
+            .enumerate()
+            .reduce(|first, second| {
+                let ordering = first.1.0.0.sort_cmp(&second.1.0.0);
+
+                if ordering.is_gt() != reverse {
+                    second
+                } else {
+                    first
+                }
+            })
+            .map(|(i, _)| i)
+        {
+            let ((value, id), mut range) = heads.remove(i);
+
+            if visitor(value, id)? {
+                break;
+            }
+
+            if let Some(entry) = self.next_entry(storage, &mut range, reverse)? {
+                heads.insert(i, (entry, range));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Narrows the `ranges` (see `ranges()`) to the entries that
+    /// `visit_sorted()` visits after the entry of the `value` and the
+    /// `id`. The entry itself need not exist anymore.
+    pub fn ranges_after(
+        &self,
+        storage: &Storage<D>,
+        ranges: Vec<Range<u64>>,
+        (value, id): (&DbValue, DbId),
+        reverse: bool,
+    ) -> Result<Vec<Range<u64>>, DbError> {
+        let ordering = |v: &DbValue, i: &DbId| v.sort_cmp(value).then(i.cmp(&id));
+        let mut result = vec![];
+
+        for range in ranges {
+            let range = if reverse {
+                range.start
+                    ..self.partition_point_in(storage, range, |v, i| ordering(v, i).is_lt())?
+            } else {
+                self.partition_point_in(storage, range.clone(), |v, i| ordering(v, i).is_le())?
+                    ..range.end
+            };
+
+            if !range.is_empty() {
+                result.push(range);
+            }
         }
+
+        Ok(result)
+    }
+
+    fn next_entry(
+        &self,
+        storage: &Storage<D>,
+        range: &mut Range<u64>,
+        reverse: bool,
+    ) -> Result<Option<(DbValue, DbId)>, DbError> {
+        let pos = if reverse {
+            range.next_back()
+        } else {
+            range.next()
+        };
+
+        pos.map(|pos| self.entry(storage, pos)).transpose()
+    }
+
+    /// Returns the ranges of positions of the `I64`, `U64` and `F64`
+    /// values in the index.
+    fn numeric_sections(&self, storage: &Storage<D>) -> Result<[NumericSection; 3], DbError> {
+        let section = |first: DbValue, last: DbValue| {
+            let range = self.lower_bound(storage, &first)?..self.upper_bound(storage, &last)?;
+            Ok::<_, DbError>(NumericSection { first, last, range })
+        };
+
+        Ok([
+            section(DbValue::I64(i64::MIN), DbValue::I64(i64::MAX))?,
+            section(DbValue::U64(u64::MIN), DbValue::U64(u64::MAX))?,
+            section(
+                DbValue::F64(f64::from_bits(u64::MAX).into()),
+                DbValue::F64(f64::from_bits(i64::MAX as u64).into()),
+            )?,
+        ])
+    }
+
+    /// Numbers are compared numerically across the types (see
+    /// `DbValue::compare()`) so each of the numeric sections of
+    /// the index is narrowed down separately.
+    fn numeric_ranges(
+        &self,
+        storage: &Storage<D>,
+        comparison: &Comparison,
+        sections: &[NumericSection; 3],
+    ) -> Result<Vec<Range<u64>>, DbError> {
+        let value = comparison.value();
+        let mut ranges = vec![];
+
+        if matches!(
+            comparison,
+            Comparison::LessThan(_) | Comparison::LessThanOrEqual(_) | Comparison::NotEqual(_)
+        ) {
+            ranges.push(0..sections[0].range.start);
+        }
+
+        for NumericSection {
+            first,
+            last,
+            range: section,
+        } in sections
+        {
+            let bound = |equal: bool| {
+                self.partition_point(storage, |k| {
+                    let ordering = k.numeric_cmp(value);
+                    k < first || (k <= last && (ordering.is_lt() || (equal && ordering.is_eq())))
+                })
+            };
+            let lower = bound(false)?;
+            let upper = bound(true)?;
+
+            match comparison {
+                Comparison::Equal(_) => ranges.push(lower..upper),
+                Comparison::GreaterThan(_) => ranges.push(upper..section.end),
+                Comparison::GreaterThanOrEqual(_) => ranges.push(lower..section.end),
+                Comparison::LessThan(_) => ranges.push(section.start..lower),
+                Comparison::LessThanOrEqual(_) => ranges.push(section.start..upper),
+                _ => {
+                    ranges.push(section.start..lower);
+                    ranges.push(upper..section.end);
+                }
+            }
+        }
+
+        if matches!(
+            comparison,
+            Comparison::GreaterThan(_)
+                | Comparison::GreaterThanOrEqual(_)
+                | Comparison::NotEqual(_)
+        ) {
+            ranges.push(sections[2].range.end..self.len());
+        }
+
+        ranges.retain(|range| !range.is_empty());
+
+        Ok(ranges)
+    }
+
+    fn lower_bound(&self, storage: &Storage<D>, value: &DbValue) -> Result<u64, DbError> {
+        self.partition_point(storage, |k| k < value)
+    }
+
+    fn upper_bound(&self, storage: &Storage<D>, value: &DbValue) -> Result<u64, DbError> {
+        self.partition_point(storage, |k| k <= value)
+    }
+}
+
+/// Returns the smallest values (one per each value type that could match)
+/// that satisfy the `StartsWith(value)` comparison. All the values that
+/// match the comparison follow their respective prefix in the index
+/// uninterrupted.
+fn starts_with_prefixes(value: &DbValue) -> Vec<DbValue> {
+    match value {
+        DbValue::String(v) => vec![
+            DbValue::String(v.clone()),
+            DbValue::VecString(vec![v.clone()]),
+        ],
+        DbValue::VecString(v) => vec![DbValue::String(v.concat()), DbValue::VecString(v.clone())],
+        DbValue::I64(v) => vec![DbValue::VecI64(vec![*v])],
+        DbValue::U64(v) => vec![DbValue::VecU64(vec![*v])],
+        DbValue::F64(v) => vec![DbValue::VecF64(vec![*v])],
+        DbValue::VecI64(_) | DbValue::VecU64(_) | DbValue::VecF64(_) => vec![value.clone()],
+        DbValue::Bytes(_)
+        | DbValue::Bool(_)
+        | DbValue::DateTime(_)
+        | DbValue::List(_)
+        | DbValue::Map(_) => vec![],
     }
 }
 
@@ -111,24 +630,30 @@ where
         })
     }
 
-    pub fn index(&self, key: &DbValue) -> Option<&DbIndex<D>> {
-        self.indexes.iter().find(|index| index.key() == key)
+    pub fn index(&self, keys: &[DbValue]) -> Option<&DbIndex<D>> {
+        self.indexes.iter().find(|index| index.keys() == keys)
     }
 
-    pub fn index_mut(&mut self, key: &DbValue) -> Option<&mut DbIndex<D>> {
-        self.indexes.iter_mut().find(|index| index.key() == key)
+    pub fn index_mut(&mut self, keys: &[DbValue]) -> Option<&mut DbIndex<D>> {
+        self.indexes.iter_mut().find(|index| index.keys() == keys)
     }
 
     pub fn indexes(&self) -> &[DbIndex<D>] {
         self.indexes.as_slice()
     }
 
+    pub fn indexes_mut(&mut self) -> &mut [DbIndex<D>] {
+        self.indexes.as_mut_slice()
+    }
+
     pub fn insert(
         &mut self,
         storage: &mut Storage<D>,
-        key: DbValue,
+        keys: Vec<DbValue>,
+        ordered: bool,
+        unique: bool,
     ) -> Result<&mut DbIndex<D>, DbError> {
-        let index = DbIndex::new(key, storage)?;
+        let index = DbIndex::new(keys, ordered, unique, storage)?;
         self.storage_indexes.push(storage, &index.storage_index())?;
         self.indexes.push(index);
         Ok(self.indexes.last_mut().unwrap())
@@ -141,8 +666,8 @@ where
         })
     }
 
-    pub fn remove(&mut self, storage: &mut Storage<D>, key: &DbValue) -> Result<(), DbError> {
-        if let Some(pos) = self.indexes.iter().position(|index| index.key() == key) {
+    pub fn remove(&mut self, storage: &mut Storage<D>, keys: &[DbValue]) -> Result<(), DbError> {
+        if let Some(pos) = self.indexes.iter().position(|index| index.keys() == keys) {
             self.storage_indexes.remove(storage, pos as u64)?;
             let index = self.indexes.remove(pos);
             index.remove_from_storage(storage)?;
@@ -194,19 +719,85 @@ impl<D: StorageData> VecValue<D> for DbValue {
     }
 }
 
+impl<D: StorageData> VecValue<D> for Vec<DbValue> {
+    fn store(&self, storage: &mut Storage<D>) -> Result<Vec<u8>, DbError> {
+        Ok(store_db_values(storage, self)?.data().to_vec())
+    }
+
+    fn load(storage: &Storage<D>, bytes: &[u8]) -> Result<Self, DbError> {
+        load_db_values(storage, DbValueIndex::deserialize(bytes)?)
+    }
+
+    fn remove(storage: &mut Storage<D>, bytes: &[u8]) -> Result<(), DbError> {
+        let index = DbValueIndex::deserialize(bytes)?;
+
+        if let DbValue::Bytes(value_indexes) = DbValue::load_db_value(index, storage)? {
+            for value_index in value_indexes.chunks(DbValueIndex::serialized_size_static() as usize)
+            {
+                DbValue::remove(storage, value_index)?;
+            }
+        }
+
+        DbValue::remove(storage, bytes)
+    }
+
+    fn storage_len() -> u64 {
+        DbValueIndex::serialized_size_static()
+    }
+}
+
+/// Stores the list of `values` as a single value consisting of
+/// the concatenated value indexes of the individual values.
+fn store_db_values<D: StorageData>(
+    storage: &mut Storage<D>,
+    values: &[DbValue],
+) -> Result<DbValueIndex, DbError> {
+    let mut bytes =
+        Vec::with_capacity(values.len() * DbValueIndex::serialized_size_static() as usize);
+
+    for value in values {
+        bytes.extend_from_slice(&value.store_db_value(storage)?.data());
+    }
+
+    DbValue::Bytes(bytes).store_db_value(storage)
+}
+
+fn load_db_values<D: StorageData>(
+    storage: &Storage<D>,
+    index: DbValueIndex,
+) -> Result<Vec<DbValue>, DbError> {
+    let mut values = vec![];
+
+    if let DbValue::Bytes(value_indexes) = DbValue::load_db_value(index, storage)? {
+        for value_index in value_indexes.chunks(DbValueIndex::serialized_size_static() as usize) {
+            values.push(DbValue::load_db_value(
+                DbValueIndex::deserialize(value_index)?,
+                storage,
+            )?);
+        }
+    }
+
+    Ok(values)
+}
+
 impl<D: StorageData> VecValue<D> for DbIndexStorageIndex {
     fn store(&self, _storage: &mut Storage<D>) -> Result<Vec<u8>, DbError> {
         let key_index = self.key_index.serialize();
         let ids_index = self.ids_index.serialize();
-        Ok([key_index, ids_index].concat())
+        let flags = self.flags.serialize();
+        Ok([key_index, ids_index, flags].concat())
     }
 
     fn load(_storage: &Storage<D>, bytes: &[u8]) -> Result<Self, DbError> {
         let key_index = DbValueIndex::deserialize(bytes)?;
-        let ids_index = StorageIndex::deserialize(&bytes[key_index.serialized_size() as usize..])?;
+        let offset = key_index.serialized_size() as usize;
+        let ids_index = StorageIndex::deserialize(&bytes[offset..])?;
+        let offset = offset + ids_index.serialized_size() as usize;
+        let flags = u64::deserialize(&bytes[offset..])?;
         Ok(Self {
             key_index,
             ids_index,
+            flags,
         })
     }
 
@@ -215,7 +806,9 @@ impl<D: StorageData> VecValue<D> for DbIndexStorageIndex {
     }
 
     fn storage_len() -> u64 {
-        DbValueIndex::serialized_size_static() + StorageIndex::serialized_size_static()
+        DbValueIndex::serialized_size_static()
+            + StorageIndex::serialized_size_static()
+            + u64::serialized_size_static()
     }
 }
 
@@ -230,10 +823,12 @@ mod tests {
         let left = DbIndexStorageIndex {
             key_index: DbValueIndex::new(),
             ids_index: StorageIndex::from(1),
+            flags: ORDERED_INDEX_FLAG,
         };
         let other = left.clone();
         assert_eq!(left.key_index, other.key_index);
         assert_eq!(left.ids_index, other.ids_index);
+        assert_eq!(left.flags, other.flags);
     }
 
     #[test]
@@ -243,12 +838,15 @@ mod tests {
 
         {
             let mut indexes = DbIndexes::new(&mut storage).unwrap();
-            indexes.insert(&mut storage, "username".into()).unwrap();
-            indexes.insert(&mut storage, "token".into()).unwrap();
-            let username_index = indexes.index_mut(&"username".into()).unwrap();
+            indexes
+                .insert(&mut storage, vec!["username".into()], false, false)
+                .unwrap();
+            indexes
+                .insert(&mut storage, vec!["token".into()], false, false)
+                .unwrap();
+            let username_index = indexes.index_mut(&["username".into()]).unwrap();
             username_index
-                .ids_mut()
-                .insert(&mut storage, &"user1".into(), &DbId(5))
+                .insert(&mut storage, &["user1".into()], &DbId(5))
                 .unwrap();
             index = indexes.storage_index();
         }
@@ -257,12 +855,11 @@ mod tests {
 
         assert_eq!(
             indexes
-                .index(&"username".into())
+                .index(&["username".into()])
                 .unwrap()
-                .ids()
-                .value(&storage, &"user1".into())
+                .values(&storage, &["user1".into()])
                 .unwrap(),
-            Some(DbId(5))
+            vec![DbId(5)]
         );
     }
 
@@ -270,36 +867,49 @@ mod tests {
     fn index() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        indexes.insert(&mut storage, "username".into()).unwrap();
-        indexes.insert(&mut storage, "token".into()).unwrap();
+        indexes
+            .insert(&mut storage, vec!["username".into()], false, false)
+            .unwrap();
+        indexes
+            .insert(&mut storage, vec!["token".into()], false, false)
+            .unwrap();
         assert_eq!(
-            indexes.index(&"username".into()).unwrap().key,
-            "username".into()
+            indexes.index(&["username".into()]).unwrap().keys(),
+            ["username".into()]
+        );
+        assert_eq!(
+            indexes.index(&["token".into()]).unwrap().keys(),
+            ["token".into()]
         );
-        assert_eq!(indexes.index(&"token".into()).unwrap().key, "token".into());
     }
 
     #[test]
     fn index_missing() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        indexes.insert(&mut storage, "username".into()).unwrap();
-        assert!(indexes.index(&"token".into()).is_none());
+        indexes
+            .insert(&mut storage, vec!["username".into()], false, false)
+            .unwrap();
+        assert!(indexes.index(&["token".into()]).is_none());
     }
 
     #[test]
     fn index_mut() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        indexes.insert(&mut storage, "username".into()).unwrap();
-        indexes.insert(&mut storage, "token".into()).unwrap();
+        indexes
+            .insert(&mut storage, vec!["username".into()], false, false)
+            .unwrap();
+        indexes
+            .insert(&mut storage, vec!["token".into()], false, false)
+            .unwrap();
         assert_eq!(
-            indexes.index_mut(&"username".into()).unwrap().key,
-            "username".into()
+            indexes.index_mut(&["username".into()]).unwrap().keys(),
+            ["username".into()]
         );
         assert_eq!(
-            indexes.index_mut(&"token".into()).unwrap().key,
-            "token".into()
+            indexes.index_mut(&["token".into()]).unwrap().keys(),
+            ["token".into()]
         );
     }
 
@@ -307,25 +917,24 @@ mod tests {
     fn index_mut_missing() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        indexes.insert(&mut storage, "username".into()).unwrap();
-        assert!(indexes.index_mut(&"token".into()).is_none());
+        indexes
+            .insert(&mut storage, vec!["username".into()], false, false)
+            .unwrap();
+        assert!(indexes.index_mut(&["token".into()]).is_none());
     }
 
     #[test]
     fn insert() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        let index = indexes.insert(&mut storage, "username".into()).unwrap();
-        index
-            .ids_mut()
-            .insert(&mut storage, &"user1".into(), &DbId(1))
+        let index = indexes
+            .insert(&mut storage, vec!["username".into()], false, false)
             .unwrap();
-        let id = index
-            .ids()
-            .value(&storage, &"user1".into())
-            .unwrap()
+        index
+            .insert(&mut storage, &["user1".into()], &DbId(1))
             .unwrap();
-        assert_eq!(id, DbId(1));
+        let ids = index.values(&storage, &["user1".into()]).unwrap();
+        assert_eq!(ids, vec![DbId(1)]);
     }
 
     #[test]
@@ -333,27 +942,35 @@ mod tests {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
         indexes
-            .insert(&mut storage, "username_which_is_long".into())
+            .insert(
+                &mut storage,
+                vec!["username_which_is_long".into()],
+                false,
+                false,
+            )
             .unwrap()
-            .ids_mut()
-            .insert(&mut storage, &"".into(), &DbId(0))
+            .insert(&mut storage, &["".into()], &DbId(0))
             .unwrap();
         indexes
-            .remove(&mut storage, &"username_which_is_long".into())
+            .remove(&mut storage, &["username_which_is_long".into()])
             .unwrap();
-        assert!(indexes.index(&"username_which_is_long".into()).is_none());
+        assert!(indexes.index(&["username_which_is_long".into()]).is_none());
     }
 
     #[test]
     fn remove_from_storage_db_index() {
         let mut storage: Storage<MemoryStorage> = Storage::new("test").unwrap();
-        let mut index =
-            DbIndex::new("username which is not exactly short".into(), &mut storage).unwrap();
+        let mut index = DbIndex::new(
+            vec!["username which is not exactly short".into()],
+            false,
+            false,
+            &mut storage,
+        )
+        .unwrap();
         index
-            .ids_mut()
             .insert(
                 &mut storage,
-                &"user some really long username".into(),
+                &["user some really long username".into()],
                 &DbId(1),
             )
             .unwrap();
@@ -363,11 +980,318 @@ mod tests {
         assert!(storage.len() < len);
     }
 
+    #[test]
+    fn ordered_from_storage() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let index;
+
+        {
+            let mut indexes = DbIndexes::new(&mut storage).unwrap();
+            let created = indexes
+                .insert(&mut storage, vec!["created".into()], true, false)
+                .unwrap();
+            created
+                .insert(&mut storage, &[20.into()], &DbId(2))
+                .unwrap();
+            created
+                .insert(&mut storage, &[10.into()], &DbId(1))
+                .unwrap();
+            index = indexes.storage_index();
+        }
+
+        let indexes = DbIndexes::from_storage(&storage, index).unwrap();
+        let created = indexes.index(&["created".into()]).unwrap();
+
+        assert!(created.is_ordered());
+        assert_eq!(
+            created.entries(&storage),
+            vec![(vec![10.into()], DbId(1)), (vec![20.into()], DbId(2))]
+        );
+    }
+
+    #[test]
+    fn ordered_ranges() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut index = DbIndex::new(vec!["k".into()], true, false, &mut storage).unwrap();
+
+        for (i, value) in [10, 20, 20, 30].into_iter().enumerate() {
+            index
+                .insert(&mut storage, &[value.into()], &DbId(i as i64 + 1))
+                .unwrap();
+        }
+
+        let DbIndexIds::Ordered(ids) = index.ids() else {
+            panic!("index is not ordered");
+        };
+        let ranges = |comparison| ids.ranges(&storage, &comparison).unwrap();
+
+        assert_eq!(ranges(Comparison::Equal(20.into())), vec![1..3]);
+        assert_eq!(ranges(Comparison::GreaterThan(20.into())), vec![3..4]);
+        assert_eq!(
+            ranges(Comparison::GreaterThanOrEqual(20.into())),
+            vec![1..4]
+        );
+        assert_eq!(ranges(Comparison::LessThan(20.into())), vec![0..1]);
+        assert_eq!(ranges(Comparison::LessThanOrEqual(20.into())), vec![0..3]);
+        assert_eq!(ranges(Comparison::NotEqual(20.into())), vec![0..1, 3..4]);
+        assert_eq!(ranges(Comparison::Contains(20.into())), vec![0..4]);
+    }
+
+    #[test]
+    fn ordered_ranges_numeric() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut index = DbIndex::new(vec!["k".into()], true, false, &mut storage).unwrap();
+        let values: Vec<DbValue> = vec![
+            10_i64.into(),
+            20_i64.into(),
+            15_u64.into(),
+            20_u64.into(),
+            20.0.into(),
+            25.5.into(),
+            "a".into(),
+        ];
+
+        for (i, value) in values.iter().enumerate() {
+            index
+                .insert(
+                    &mut storage,
+                    std::slice::from_ref(value),
+                    &DbId(i as i64 + 1),
+                )
+                .unwrap();
+        }
+
+        let DbIndexIds::Ordered(ids) = index.ids() else {
+            panic!("index is not ordered");
+        };
+        let ranges = |comparison| ids.ranges(&storage, &comparison).unwrap();
+        let sorted = |comparison, reverse| {
+            let mut result = vec![];
+            ids.visit_sorted(&storage, ranges(comparison), reverse, |_, id| {
+                result.push(id.0);
+                Ok(false)
+            })
+            .unwrap();
+            result
+        };
+
+        assert_eq!(ranges(Comparison::Equal(20.into())), vec![1..2, 3..4, 4..5]);
+        assert_eq!(
+            ranges(Comparison::GreaterThan(15_u64.into())),
+            vec![1..2, 3..4, 4..6, 6..7]
+        );
+        assert_eq!(ranges(Comparison::LessThan(15.5.into())), vec![0..1, 2..3]);
+        assert_eq!(
+            ranges(Comparison::NotEqual(20.0.into())),
+            vec![0..1, 2..3, 5..6, 6..7]
+        );
+        assert_eq!(
+            ranges(Comparison::Contains(20.into())),
+            vec![0..2, 2..4, 4..6, 6..7]
+        );
+        assert_eq!(
+            sorted(Comparison::GreaterThanOrEqual(0.into()), false),
+            vec![1, 3, 2, 4, 5, 6, 7]
+        );
+        assert_eq!(
+            sorted(Comparison::GreaterThanOrEqual(0.into()), true),
+            vec![7, 6, 5, 4, 2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn ordered_ranges_after() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut index = DbIndex::new(vec!["k".into()], true, false, &mut storage).unwrap();
+        let values: Vec<DbValue> = vec![
+            10_i64.into(),
+            20_i64.into(),
+            15_u64.into(),
+            20_u64.into(),
+            20.0.into(),
+            25.5.into(),
+            "a".into(),
+        ];
+
+        for (i, value) in values.iter().enumerate() {
+            index
+                .insert(
+                    &mut storage,
+                    std::slice::from_ref(value),
+                    &DbId(i as i64 + 1),
+                )
+                .unwrap();
+        }
+
+        let DbIndexIds::Ordered(ids) = index.ids() else {
+            panic!("index is not ordered");
+        };
+        let sorted_after = |value: DbValue, id, reverse| {
+            let ranges = ids
+                .ranges(&storage, &Comparison::GreaterThanOrEqual(0.into()))
+                .unwrap();
+            let ranges = ids
+                .ranges_after(&storage, ranges, (&value, DbId(id)), reverse)
+                .unwrap();
+            let mut result = vec![];
+            ids.visit_sorted(&storage, ranges, reverse, |_, id| {
+                result.push(id.0);
+                Ok(false)
+            })
+            .unwrap();
+            result
+        };
+
+        assert_eq!(sorted_after(20_u64.into(), 4, false), vec![5, 6, 7]);
+        assert_eq!(sorted_after(20_u64.into(), 4, true), vec![2, 3, 1]);
+        assert_eq!(sorted_after(20_i64.into(), 9, false), vec![4, 5, 6, 7]);
+        assert_eq!(sorted_after(20_i64.into(), 0, true), vec![3, 1]);
+        assert_eq!(sorted_after("a".into(), 7, false), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn ordered_ranges_starts_with() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut index = DbIndex::new(vec!["k".into()], true, false, &mut storage).unwrap();
+        let values: Vec<DbValue> = vec![
+            "ab".into(),
+            "abc".into(),
+            "b".into(),
+            vec!["ab".to_string()].into(),
+            vec!["ab".to_string(), "c".to_string()].into(),
+            vec!["abc".to_string()].into(),
+        ];
+
+        for (i, value) in values.iter().enumerate() {
+            index
+                .insert(
+                    &mut storage,
+                    std::slice::from_ref(value),
+                    &DbId(i as i64 + 1),
+                )
+                .unwrap();
+        }
+
+        let DbIndexIds::Ordered(ids) = index.ids() else {
+            panic!("index is not ordered");
+        };
+
+        assert_eq!(
+            ids.ranges(&storage, &Comparison::StartsWith("ab".into()))
+                .unwrap(),
+            vec![0..2, 3..5]
+        );
+        assert_eq!(
+            ids.ranges(&storage, &Comparison::StartsWith(1.into()))
+                .unwrap(),
+            vec![3..3]
+        );
+    }
+
     #[test]
     fn remove_missing() {
         let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
         let mut indexes = DbIndexes::new(&mut storage).unwrap();
-        indexes.remove(&mut storage, &"username".into()).unwrap();
-        assert!(indexes.index(&"username".into()).is_none());
+        indexes.remove(&mut storage, &["username".into()]).unwrap();
+        assert!(indexes.index(&["username".into()]).is_none());
+    }
+
+    #[test]
+    fn composite() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let index;
+
+        {
+            let mut indexes = DbIndexes::new(&mut storage).unwrap();
+            let composite = indexes
+                .insert(
+                    &mut storage,
+                    vec!["tenant".into(), "email".into()],
+                    true,
+                    false,
+                )
+                .unwrap();
+            composite
+                .insert(&mut storage, &["b".into(), "x".into()], &DbId(1))
+                .unwrap();
+            composite
+                .insert(&mut storage, &["a".into(), "y".into()], &DbId(2))
+                .unwrap();
+            composite
+                .insert(&mut storage, &["a".into(), "x".into()], &DbId(3))
+                .unwrap();
+            index = indexes.storage_index();
+        }
+
+        let indexes = DbIndexes::from_storage(&storage, index).unwrap();
+        assert!(indexes.index(&["tenant".into()]).is_none());
+        let composite = indexes.index(&["tenant".into(), "email".into()]).unwrap();
+
+        assert!(composite.is_composite());
+        assert!(composite.is_ordered());
+        assert_eq!(
+            composite
+                .values(&storage, &["a".into(), "y".into()])
+                .unwrap(),
+            vec![DbId(2)]
+        );
+
+        let DbIndexIds::CompositeOrdered(ids) = composite.ids() else {
+            panic!("index is not composite ordered");
+        };
+
+        assert_eq!(ids.prefix_range(&storage, &["a".into()]).unwrap(), 0..2);
+        assert_eq!(ids.prefix_range(&storage, &["b".into()]).unwrap(), 2..3);
+        assert_eq!(ids.prefix_range(&storage, &["c".into()]).unwrap(), 3..3);
+    }
+
+    #[test]
+    fn composite_remove_from_storage() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let mut index = DbIndex::new(
+            vec![
+                "first key which is long".into(),
+                "second key which is long".into(),
+            ],
+            false,
+            false,
+            &mut storage,
+        )
+        .unwrap();
+        index
+            .insert(
+                &mut storage,
+                &["some long value 1".into(), "some long value 2".into()],
+                &DbId(1),
+            )
+            .unwrap();
+        let len = storage.len();
+        index.remove_from_storage(&mut storage).unwrap();
+        storage.optimize_storage().unwrap();
+        assert!(storage.len() < len);
+    }
+
+    #[test]
+    fn unique_from_storage() {
+        let mut storage = Storage::<MemoryStorage>::new("test").unwrap();
+        let index;
+
+        {
+            let mut indexes = DbIndexes::new(&mut storage).unwrap();
+            indexes
+                .insert(&mut storage, vec!["email".into()], true, true)
+                .unwrap();
+            indexes
+                .insert(&mut storage, vec!["username".into()], false, false)
+                .unwrap();
+            index = indexes.storage_index();
+        }
+
+        let indexes = DbIndexes::from_storage(&storage, index).unwrap();
+        let email = indexes.index(&["email".into()]).unwrap();
+
+        assert!(email.is_unique());
+        assert!(email.is_ordered());
+        assert!(!indexes.index(&["username".into()]).unwrap().is_unique());
     }
 }
@@ -8,11 +8,12 @@ use crate::storage::Storage;
 use crate::storage::StorageIndex;
 use crate::utilities::serialize::Serialize;
 use crate::utilities::serialize::SerializeStatic;
+use crate::utilities::stable_hash::StableHash;
 
 /// Database key-value pair (aka property) attached to
 /// database elements. It can be constructed from a
 /// tuple of types that are convertible to `DbValue`.
-#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
@@ -228,6 +229,22 @@ impl<S: StorageData> DbKeyValues<S> {
         Ok(values.into_iter().map(|(_, kv)| kv).collect())
     }
 
+    /// Reads every key-value of the element at `index` returning
+    /// their count or the first error encountered.
+    pub fn verify(&self, storage: &Storage<S>, index: u64) -> Result<u64, DbError> {
+        if !self.valid_index(storage, index)? {
+            return Ok(0);
+        }
+
+        let kvs = self.kvs(storage, index)?;
+
+        for i in 0..kvs.len() {
+            kvs.value(storage, i)?;
+        }
+
+        Ok(kvs.len())
+    }
+
     fn kvs(&self, storage: &Storage<S>, index: u64) -> Result<DbVec<DbKeyValue, S>, DbError> {
         let storage_index = self.0.value(storage, index)?;
         DbVec::from_storage(storage, storage_index)
@@ -238,6 +255,14 @@ impl<S: StorageData> DbKeyValues<S> {
     }
 }
 
+impl StableHash for DbKeyValue {
+    fn stable_hash(&self) -> u64 {
+        let mut hash = self.key.stable_hash();
+        Self::add_to_hash(&mut hash, self.value.stable_hash());
+        hash
+    }
+}
+
 impl<K, T> From<(K, T)> for DbKeyValue
 where
     K: Into<DbValue>,
@@ -0,0 +1,388 @@
+use super::DbStorageIndex;
+use super::legacy;
+use crate::DbError;
+use crate::DbErrorType;
+use crate::DbId;
+use crate::DbImpl;
+use crate::DbIssue;
+use crate::DbIssueType;
+use crate::DbKeyValue;
+use crate::InsertIndexQuery;
+use crate::QueryBuilder;
+use crate::StorageData;
+use crate::collections::indexed_map::DbIndexedMap;
+use crate::db::db_index::DbIndexes;
+use crate::db::db_key_value::DbKeyValues;
+use crate::graph::DbGraph;
+use crate::storage::Storage;
+use crate::storage::StorageIndex;
+use crate::storage::file_storage::FileStorage;
+use crate::storage::memory_storage::MemoryStorage;
+use std::collections::HashSet;
+
+/// Result of [`DbImpl::salvage()`] with the number of the recovered
+/// elements and the list of data that had to be dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct DbSalvageReport {
+    /// Number of recovered nodes.
+    pub nodes: u64,
+
+    /// Number of recovered edges.
+    pub edges: u64,
+
+    /// Number of recovered aliases.
+    pub aliases: u64,
+
+    /// Number of recovered key-value pairs.
+    pub values: u64,
+
+    /// Number of rebuilt indexes.
+    pub indexes: u64,
+
+    /// Data that could not be recovered.
+    pub issues: Vec<DbIssue>,
+}
+
+impl DbSalvageReport {
+    /// Returns `true` if nothing had to be dropped.
+    pub fn is_complete(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+type SalvageStorage = Storage<MemoryStorage>;
+
+struct SalvagedData {
+    nodes: Vec<DbId>,
+    edges: Vec<(DbId, DbId, DbId)>,
+    aliases: Vec<(String, DbId)>,
+    values: Vec<(DbId, Vec<DbKeyValue>)>,
+    indexes: Vec<InsertIndexQuery>,
+}
+
+impl<Store: StorageData> DbImpl<Store> {
+    /// Reads whatever can still be decoded from the (possibly damaged)
+    /// database file `source` and rebuilds it as a new database `target`.
+    /// Damaged storage records are skipped, edges referencing lost nodes,
+    /// aliases of lost elements and unreadable values are dropped and the
+    /// indexes are rebuilt from the recovered values. The recovered elements
+    /// keep their original ids. Any pending write ahead log of `source`
+    /// is applied first as it would be on a regular load but the source
+    /// is otherwise not modified. The `target` must not exist.
+    ///
+    /// Returns the new database and the report of what was recovered and
+    /// what had to be dropped. Fails if the `source` does not exist, the
+    /// `target` already exists or if the database index record itself
+    /// cannot be read.
+    pub fn salvage(source: &str, target: &str) -> Result<(Self, DbSalvageReport), DbError> {
+        if !std::fs::exists(source)? {
+            return Err(DbError::db(
+                DbErrorType::NotFound,
+                format!("Database file '{source}' not found"),
+            ));
+        }
+
+        if std::fs::exists(target)? {
+            return Err(DbError::db(
+                DbErrorType::NotAllowed,
+                format!("Salvage target '{target}' already exists"),
+            ));
+        }
+
+        let mut report = DbSalvageReport::default();
+        let data = Self::read_salvage_source(source)?;
+        let (mut storage, skipped) = Storage::salvage(data)?;
+        report.issues.extend(
+            skipped.into_iter().map(|description| {
+                DbIssue::new(DbIssueType::UnreadableRecord, DbId(0), description)
+            }),
+        );
+
+        let salvaged = Self::read_salvaged(&mut storage, &mut report)?;
+        let mut db = Self::new(target)?;
+        db.rebuild_salvaged(salvaged, &mut report)?;
+
+        Ok((db, report))
+    }
+
+    fn read_salvage_source(source: &str) -> Result<MemoryStorage, DbError> {
+        let file = FileStorage::new(source)?;
+        let buffer = file.read(0, file.len())?.to_vec();
+
+        Ok(MemoryStorage::from_buffer(source, buffer))
+    }
+
+    fn read_salvaged(
+        storage: &mut SalvageStorage,
+        report: &mut DbSalvageReport,
+    ) -> Result<SalvagedData, DbError> {
+        let mut index = storage
+            .value::<DbStorageIndex>(StorageIndex(1))
+            .or_else(|_| legacy::convert_to_version_1(storage))
+            .map_err(|e| {
+                DbError::db(
+                    DbErrorType::Corrupted,
+                    format!("Database index record is unreadable: {}", e.description),
+                )
+            })?;
+
+        if index.version < super::CURRENT_VERSION {
+            index = legacy::convert_to_version_2(storage, index)?;
+        }
+
+        let mut data = SalvagedData {
+            nodes: vec![],
+            edges: vec![],
+            aliases: vec![],
+            values: vec![],
+            indexes: vec![],
+        };
+
+        Self::read_salvaged_graph(storage, &index, &mut data, report);
+        let elements = data
+            .nodes
+            .iter()
+            .copied()
+            .chain(data.edges.iter().map(|(id, _, _)| *id))
+            .collect::<HashSet<DbId>>();
+        Self::read_salvaged_aliases(storage, &index, &elements, &mut data, report);
+        Self::read_salvaged_values(storage, &index, &elements, &mut data, report);
+        Self::read_salvaged_indexes(storage, &index, &mut data, report);
+
+        Ok(data)
+    }
+
+    fn read_salvaged_graph(
+        storage: &SalvageStorage,
+        index: &DbStorageIndex,
+        data: &mut SalvagedData,
+        report: &mut DbSalvageReport,
+    ) {
+        let graph = match DbGraph::from_storage(storage, index.graph) {
+            Ok(graph) => graph,
+            Err(e) => {
+                report.issues.push(DbIssue::new(
+                    DbIssueType::UnreadableRecord,
+                    DbId(0),
+                    format!("Graph is unreadable: {}", e.description),
+                ));
+                return;
+            }
+        };
+
+        for element in graph.iter(storage) {
+            if element.is_node() {
+                data.nodes.push(DbId(element.0));
+                continue;
+            }
+
+            let from = graph.edge_from(storage, element);
+            let to = graph.edge_to(storage, element);
+
+            if let Some(node) = [from, to]
+                .into_iter()
+                .find(|node| graph.node(storage, *node).is_none())
+            {
+                report.issues.push(DbIssue::new(
+                    DbIssueType::DanglingEdge,
+                    DbId(element.0),
+                    format!("Edge '{}' references missing node '{}'", element.0, node.0),
+                ));
+            } else {
+                data.edges.push((DbId(element.0), DbId(from.0), DbId(to.0)));
+            }
+        }
+    }
+
+    fn read_salvaged_aliases(
+        storage: &SalvageStorage,
+        index: &DbStorageIndex,
+        elements: &HashSet<DbId>,
+        data: &mut SalvagedData,
+        report: &mut DbSalvageReport,
+    ) {
+        let aliases =
+            match DbIndexedMap::<String, DbId, MemoryStorage>::from_storage(storage, index.aliases)
+            {
+                Ok(aliases) => aliases,
+                Err(e) => {
+                    report.issues.push(DbIssue::new(
+                        DbIssueType::UnreadableRecord,
+                        DbId(0),
+                        format!("Aliases are unreadable: {}", e.description),
+                    ));
+                    return;
+                }
+            };
+        let mut aliased = HashSet::new();
+
+        for (alias, db_id) in aliases.iter(storage) {
+            if !elements.contains(&db_id) {
+                report.issues.push(DbIssue::new(
+                    DbIssueType::InvalidAlias,
+                    db_id,
+                    format!("Alias '{alias}' points to missing id '{}'", db_id.0),
+                ));
+            } else if !aliased.insert(db_id) {
+                report.issues.push(DbIssue::new(
+                    DbIssueType::InvalidAlias,
+                    db_id,
+                    format!("Alias '{alias}' is not the only alias of id '{}'", db_id.0),
+                ));
+            } else {
+                data.aliases.push((alias, db_id));
+            }
+        }
+    }
+
+    fn read_salvaged_values(
+        storage: &SalvageStorage,
+        index: &DbStorageIndex,
+        elements: &HashSet<DbId>,
+        data: &mut SalvagedData,
+        report: &mut DbSalvageReport,
+    ) {
+        let values = match DbKeyValues::from_storage(storage, index.values) {
+            Ok(values) => values,
+            Err(e) => {
+                report.issues.push(DbIssue::new(
+                    DbIssueType::UnreadableRecord,
+                    DbId(0),
+                    format!("Values are unreadable: {}", e.description),
+                ));
+                return;
+            }
+        };
+
+        let mut ids = elements.iter().copied().collect::<Vec<DbId>>();
+        ids.sort_by_key(|id| id.as_index());
+
+        for db_id in ids {
+            match values.values(storage, db_id.as_index()) {
+                Ok(kvs) if kvs.is_empty() => {}
+                Ok(kvs) => data.values.push((db_id, kvs)),
+                Err(e) => report.issues.push(DbIssue::new(
+                    DbIssueType::InvalidValues,
+                    db_id,
+                    format!(
+                        "Values of id '{}' cannot be read: {}",
+                        db_id.0, e.description
+                    ),
+                )),
+            }
+        }
+    }
+
+    fn read_salvaged_indexes(
+        storage: &SalvageStorage,
+        index: &DbStorageIndex,
+        data: &mut SalvagedData,
+        report: &mut DbSalvageReport,
+    ) {
+        match DbIndexes::from_storage(storage, index.indexes) {
+            Ok(indexes) => {
+                data.indexes = indexes
+                    .indexes()
+                    .iter()
+                    .map(|index| InsertIndexQuery {
+                        keys: index.keys().to_vec(),
+                        ordered: index.is_ordered(),
+                        unique: index.is_unique(),
+                    })
+                    .collect();
+            }
+            Err(e) => report.issues.push(DbIssue::new(
+                DbIssueType::UnreadableRecord,
+                DbId(0),
+                format!("Indexes are unreadable: {}", e.description),
+            )),
+        }
+    }
+
+    // Elements are recreated in place of placeholder nodes so that
+    // they keep their original ids: the graph reuses the most recently
+    // freed index first so removing the placeholder right before
+    // inserting an edge gives the edge the same index.
+    fn rebuild_salvaged(
+        &mut self,
+        data: SalvagedData,
+        report: &mut DbSalvageReport,
+    ) -> Result<(), DbError> {
+        let elements = data
+            .nodes
+            .iter()
+            .copied()
+            .chain(data.edges.iter().map(|(id, _, _)| *id))
+            .map(|id| id.as_index())
+            .collect::<HashSet<u64>>();
+        let count = elements.iter().max().copied().unwrap_or_default();
+
+        self.transaction_mut(|t| -> Result<(), DbError> {
+            if count != 0 {
+                t.exec_mut(QueryBuilder::insert().nodes().count(count).query())?;
+            }
+
+            for (db_id, from, to) in &data.edges {
+                t.exec_mut(QueryBuilder::remove().ids(DbId(-db_id.0)).query())?;
+                t.exec_mut(QueryBuilder::insert().edges().from(*from).to(*to).query())?;
+            }
+
+            t.exec_mut(
+                QueryBuilder::remove()
+                    .ids(
+                        (1..=count)
+                            .filter(|i| !elements.contains(i))
+                            .map(|i| DbId(i as i64))
+                            .collect::<Vec<DbId>>(),
+                    )
+                    .query(),
+            )?;
+
+            for (db_id, kvs) in &data.values {
+                report.values += kvs.len() as u64;
+                t.exec_mut(
+                    QueryBuilder::insert()
+                        .values([kvs.clone()])
+                        .ids(*db_id)
+                        .query(),
+                )?;
+            }
+
+            for (alias, db_id) in &data.aliases {
+                match t.exec_mut(QueryBuilder::insert().aliases(alias).ids(*db_id).query()) {
+                    Ok(_) => report.aliases += 1,
+                    Err(e) => report.issues.push(DbIssue::new(
+                        DbIssueType::InvalidAlias,
+                        *db_id,
+                        format!("Alias '{alias}' cannot be restored: {}", e.description),
+                    )),
+                }
+            }
+
+            Ok(())
+        })?;
+
+        report.nodes = data.nodes.len() as u64;
+        report.edges = data.edges.len() as u64;
+
+        for query in &data.indexes {
+            match self.exec_mut(query) {
+                Ok(_) => report.indexes += 1,
+                Err(e) => report.issues.push(DbIssue::new(
+                    DbIssueType::InvalidIndexEntry,
+                    DbId(0),
+                    format!(
+                        "Index '{}' cannot be rebuilt: {}",
+                        super::join_values(&query.keys),
+                        e.description
+                    ),
+                )),
+            }
+        }
+
+        Ok(())
+    }
+}
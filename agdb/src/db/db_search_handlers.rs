@@ -1,5 +1,8 @@
 use crate::DbError;
+use crate::DbErrorType;
+use crate::DbId;
 use crate::DbImpl;
+use crate::DbValue;
 use crate::QueryCondition;
 use crate::StorageData;
 use crate::graph::GraphIndex;
@@ -34,11 +37,35 @@ pub struct LimitOffsetHandler<'a, Store: StorageData> {
     conditions: &'a Vec<QueryCondition>,
 }
 
+pub struct AfterHandler<'a, Store: StorageData> {
+    after: GraphIndex,
+    found: &'a mut bool,
+    limit: u64,
+    offset: u64,
+    counter: u64,
+    db: &'a DbImpl<Store>,
+    conditions: &'a Vec<QueryCondition>,
+}
+
+pub struct IndexHandler<'a, Store: StorageData> {
+    limit: u64,
+    offset: u64,
+    counter: u64,
+    db: &'a DbImpl<Store>,
+    conditions: &'a [QueryCondition],
+    pub ids: Vec<DbId>,
+}
+
 pub struct PathHandler<'a, Store: StorageData> {
     db: &'a DbImpl<Store>,
     conditions: &'a Vec<QueryCondition>,
 }
 
+pub struct WeightedPathHandler<'a, Store: StorageData> {
+    handler: PathHandler<'a, Store>,
+    key: &'a DbValue,
+}
+
 impl<'a, Store: StorageData> DefaultHandler<'a, Store> {
     pub fn new(db: &'a DbImpl<Store>, conditions: &'a Vec<QueryCondition>) -> Self {
         Self { db, conditions }
@@ -84,12 +111,85 @@ impl<'a, Store: StorageData> LimitOffsetHandler<'a, Store> {
     }
 }
 
+impl<'a, Store: StorageData> AfterHandler<'a, Store> {
+    pub fn new(
+        after: GraphIndex,
+        found: &'a mut bool,
+        limit: u64,
+        offset: u64,
+        db: &'a DbImpl<Store>,
+        conditions: &'a Vec<QueryCondition>,
+    ) -> Self {
+        Self {
+            after,
+            found,
+            limit,
+            offset,
+            counter: 0,
+            db,
+            conditions,
+        }
+    }
+}
+
+impl<'a, Store: StorageData> IndexHandler<'a, Store> {
+    pub fn new(
+        limit: u64,
+        offset: u64,
+        db: &'a DbImpl<Store>,
+        conditions: &'a [QueryCondition],
+    ) -> Self {
+        Self {
+            limit,
+            offset,
+            counter: 0,
+            db,
+            conditions,
+            ids: vec![],
+        }
+    }
+
+    /// Processes the `id` found in the index and returns `true`
+    /// if the search is finished (the limit has been reached).
+    pub fn process(&mut self, id: DbId) -> Result<bool, DbError> {
+        if !self.conditions.is_empty()
+            && !self
+                .db
+                .evaluate_conditions(GraphIndex(id.0), 0, self.conditions)?
+                .is_true()
+        {
+            return Ok(false);
+        }
+
+        self.counter += 1;
+
+        if self.offset < self.counter {
+            self.ids.push(id);
+        }
+
+        Ok(self.limit != 0 && self.ids.len() as u64 == self.limit)
+    }
+}
+
 impl<'a, Store: StorageData> PathHandler<'a, Store> {
     pub fn new(db: &'a DbImpl<Store>, conditions: &'a Vec<QueryCondition>) -> Self {
         Self { db, conditions }
     }
 }
 
+impl<'a, Store: StorageData> WeightedPathHandler<'a, Store> {
+    pub fn new(
+        db: &'a DbImpl<Store>,
+        conditions: &'a Vec<QueryCondition>,
+        key: &'a DbValue,
+    ) -> Self {
+        Self {
+            handler: PathHandler::new(db, conditions),
+            key,
+        }
+    }
+}
+
 impl<Store: StorageData> SearchHandler for DefaultHandler<'_, Store> {
     fn process(&mut self, index: GraphIndex, distance: u64) -> Result<SearchControl, DbError> {
         self.db
@@ -150,6 +250,31 @@ impl<Store: StorageData> SearchHandler for LimitOffsetHandler<'_, Store> {
     }
 }
 
+impl<Store: StorageData> SearchHandler for AfterHandler<'_, Store> {
+    fn process(&mut self, index: GraphIndex, distance: u64) -> Result<SearchControl, DbError> {
+        let mut control = self
+            .db
+            .evaluate_conditions(index, distance, self.conditions)?;
+
+        if !*self.found {
+            *self.found = index == self.after;
+            control.set_value(false);
+            return Ok(control);
+        }
+
+        if control.is_true() {
+            self.counter += 1;
+            control.set_value(self.offset < self.counter);
+        }
+
+        if self.limit != 0 && self.counter == self.limit + self.offset {
+            Ok(SearchControl::Finish(control.is_true()))
+        } else {
+            Ok(control)
+        }
+    }
+}
+
 impl<Store: StorageData> PathSearchHandler for PathHandler<'_, Store> {
     fn process(&self, index: GraphIndex, distance: u64) -> Result<(u64, bool), DbError> {
         match self
@@ -161,3 +286,45 @@ impl<Store: StorageData> PathSearchHandler for PathHandler<'_, Store> {
         }
     }
 }
+
+impl<Store: StorageData> PathSearchHandler for WeightedPathHandler<'_, Store> {
+    fn process(&self, index: GraphIndex, distance: u64) -> Result<(u64, bool), DbError> {
+        self.handler.process(index, distance)
+    }
+
+    fn weight(&self, index: GraphIndex) -> Result<f64, DbError> {
+        let id = DbId(index.0);
+        let values = self
+            .handler
+            .db
+            .values_by_keys(id, std::slice::from_ref(self.key))?;
+
+        let weight = match values.first().map(|kv| &kv.value) {
+            None => return Ok(0.0),
+            Some(DbValue::I64(value)) => *value as f64,
+            Some(DbValue::U64(value)) => *value as f64,
+            Some(DbValue::F64(value)) => value.to_f64(),
+            Some(value) => {
+                return Err(DbError::query(
+                    DbErrorType::TypeError,
+                    format!(
+                        "Weight '{value}' of key '{}' is not numeric (id: {})",
+                        self.key, id.0
+                    ),
+                ));
+            }
+        };
+
+        if weight < 0.0 || weight.is_nan() {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                format!(
+                    "Weight '{weight}' of key '{}' must be a non-negative number (id: {})",
+                    self.key, id.0
+                ),
+            ));
+        }
+
+        Ok(weight)
+    }
+}
@@ -1,19 +1,23 @@
 use crate::AgdbSerialize;
 use crate::DbError;
 use crate::DbErrorType;
+use crate::DbKeyValue;
 use crate::DbTypeMarker;
 use crate::StorageData;
 use crate::db::db_f64::DbF64;
 use crate::db::db_value_index::DbValueIndex;
 use crate::storage::Storage;
 use crate::storage::StorageIndex;
+use crate::utilities::compression;
 use crate::utilities::stable_hash::StableHash;
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as DisplayResult;
 use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::time::SystemTime;
 
 /// Database value is a strongly types value.
@@ -58,6 +62,28 @@ pub enum DbValue {
 
     /// List of UTF-8 strings
     VecString(Vec<String>),
+
+    /// Boolean value
+    Bool(bool),
+
+    /// Point in time (UTC) as number of nanoseconds
+    /// since the Unix epoch (1970-01-01T00:00:00Z).
+    /// Negative values are the points in time before
+    /// the epoch.
+    DateTime(i64),
+
+    /// List of values of any type including
+    /// other lists and maps (nested document).
+    #[cfg_attr(feature = "openapi", schema(no_recursion))]
+    List(Vec<DbValue>),
+
+    /// Ordered map of keys to values of any type
+    /// including other lists and maps (nested document).
+    /// The nested values can be accessed with a path
+    /// of keys (and list indexes) separated by `.`,
+    /// e.g. `"address.city"` or `"tags.0"`.
+    #[cfg_attr(feature = "openapi", schema(no_recursion))]
+    Map(Vec<DbKeyValue>),
 }
 
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
@@ -72,6 +98,12 @@ const VEC_I64_META_VALUE: u8 = 6_u8;
 const VEC_U64_META_VALUE: u8 = 7_u8;
 const VEC_F64_META_VALUE: u8 = 8_u8;
 const VEC_STRING_META_VALUE: u8 = 9_u8;
+const BOOL_META_VALUE: u8 = 10_u8;
+const DATE_TIME_META_VALUE: u8 = 11_u8;
+const LIST_META_VALUE: u8 = 12_u8;
+const MAP_META_VALUE: u8 = 13_u8;
+const COMPRESSED_BYTES_META_VALUE: u8 = 14_u8;
+const COMPRESSED_STRING_META_VALUE: u8 = 15_u8;
 
 impl DbValue {
     /// Returns `&Vec<u8>` or an error if the value is
@@ -87,9 +119,94 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "bytes"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "bytes"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "bytes"),
+            DbValue::Bool(_) => Self::type_error("bool", "bytes"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "bytes"),
+            DbValue::List(_) => Self::type_error("list", "bytes"),
+            DbValue::Map(_) => Self::type_error("map", "bytes"),
+        }
+    }
+
+    /// Returns the number of nanoseconds since the Unix epoch
+    /// or an error if the value is of a different type.
+    pub fn date_time(&self) -> Result<i64, DbError> {
+        match self {
+            DbValue::Bytes(_) => Self::type_error("bytes", "datetime"),
+            DbValue::I64(_) => Self::type_error("i64", "datetime"),
+            DbValue::U64(_) => Self::type_error("u64", "datetime"),
+            DbValue::F64(_) => Self::type_error("f64", "datetime"),
+            DbValue::String(_) => Self::type_error("string", "datetime"),
+            DbValue::VecI64(_) => Self::type_error("vec<i64>", "datetime"),
+            DbValue::VecU64(_) => Self::type_error("vec<u64>", "datetime"),
+            DbValue::VecF64(_) => Self::type_error("vec<f64>", "datetime"),
+            DbValue::VecString(_) => Self::type_error("vec<string>", "datetime"),
+            DbValue::Bool(_) => Self::type_error("bool", "datetime"),
+            DbValue::DateTime(v) => Ok(*v),
+            DbValue::List(_) => Self::type_error("list", "datetime"),
+            DbValue::Map(_) => Self::type_error("map", "datetime"),
         }
     }
 
+    /// Returns `&Vec<DbValue>` or an error if the value is
+    /// of a different type.
+    pub fn list(&self) -> Result<&Vec<DbValue>, DbError> {
+        match self {
+            DbValue::Bytes(_) => Self::type_error("bytes", "list"),
+            DbValue::I64(_) => Self::type_error("i64", "list"),
+            DbValue::U64(_) => Self::type_error("u64", "list"),
+            DbValue::F64(_) => Self::type_error("f64", "list"),
+            DbValue::String(_) => Self::type_error("string", "list"),
+            DbValue::VecI64(_) => Self::type_error("vec<i64>", "list"),
+            DbValue::VecU64(_) => Self::type_error("vec<u64>", "list"),
+            DbValue::VecF64(_) => Self::type_error("vec<f64>", "list"),
+            DbValue::VecString(_) => Self::type_error("vec<string>", "list"),
+            DbValue::Bool(_) => Self::type_error("bool", "list"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "list"),
+            DbValue::List(v) => Ok(v),
+            DbValue::Map(_) => Self::type_error("map", "list"),
+        }
+    }
+
+    /// Returns `&Vec<DbKeyValue>` or an error if the value is
+    /// of a different type.
+    pub fn map(&self) -> Result<&Vec<DbKeyValue>, DbError> {
+        match self {
+            DbValue::Bytes(_) => Self::type_error("bytes", "map"),
+            DbValue::I64(_) => Self::type_error("i64", "map"),
+            DbValue::U64(_) => Self::type_error("u64", "map"),
+            DbValue::F64(_) => Self::type_error("f64", "map"),
+            DbValue::String(_) => Self::type_error("string", "map"),
+            DbValue::VecI64(_) => Self::type_error("vec<i64>", "map"),
+            DbValue::VecU64(_) => Self::type_error("vec<u64>", "map"),
+            DbValue::VecF64(_) => Self::type_error("vec<f64>", "map"),
+            DbValue::VecString(_) => Self::type_error("vec<string>", "map"),
+            DbValue::Bool(_) => Self::type_error("bool", "map"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "map"),
+            DbValue::List(_) => Self::type_error("list", "map"),
+            DbValue::Map(v) => Ok(v),
+        }
+    }
+
+    /// Returns the value nested in the `Map` or `List` under the
+    /// `path` of map keys and list indexes or `None` if there is
+    /// no such value. An empty path returns the value itself.
+    pub fn nested<S: AsRef<str>>(&self, path: &[S]) -> Option<&DbValue> {
+        let mut value = self;
+
+        for segment in path {
+            let segment = segment.as_ref();
+            value = match value {
+                DbValue::Map(map) => map
+                    .iter()
+                    .find(|kv| matches!(&kv.key, DbValue::String(key) if key == segment))
+                    .map(|kv| &kv.value)?,
+                DbValue::List(list) => list.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+
+        Some(value)
+    }
+
     /// Returns `&String` or an error if the value is
     /// of a different type.
     pub fn string(&self) -> Result<&String, DbError> {
@@ -103,6 +220,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "string"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "string"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "string"),
+            DbValue::Bool(_) => Self::type_error("bool", "string"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "string"),
+            DbValue::List(_) => Self::type_error("list", "string"),
+            DbValue::Map(_) => Self::type_error("map", "string"),
         }
     }
 
@@ -121,6 +242,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("Vec<u64>", "bool"),
             DbValue::VecF64(_) => Self::type_error("Vec<f64>", "bool"),
             DbValue::VecString(_) => Self::type_error("Vec<string>", "bool"),
+            DbValue::Bool(v) => Ok(*v),
+            DbValue::DateTime(_) => Self::type_error("datetime", "bool"),
+            DbValue::List(_) => Self::type_error("list", "bool"),
+            DbValue::Map(_) => Self::type_error("map", "bool"),
         }
     }
 
@@ -138,6 +263,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "f64"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "f64"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "f64"),
+            DbValue::Bool(_) => Self::type_error("bool", "f64"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "f64"),
+            DbValue::List(_) => Self::type_error("list", "f64"),
+            DbValue::Map(_) => Self::type_error("map", "f64"),
         }
     }
 
@@ -155,6 +284,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "i64"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "i64"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "i64"),
+            DbValue::Bool(_) => Self::type_error("bool", "i64"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "i64"),
+            DbValue::List(_) => Self::type_error("list", "i64"),
+            DbValue::Map(_) => Self::type_error("map", "i64"),
         }
     }
 
@@ -172,6 +305,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "u64"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "u64"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "u64"),
+            DbValue::Bool(_) => Self::type_error("bool", "u64"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "u64"),
+            DbValue::List(_) => Self::type_error("list", "u64"),
+            DbValue::Map(_) => Self::type_error("map", "u64"),
         }
     }
 
@@ -188,6 +325,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "vec<f64>"),
             DbValue::VecF64(v) => Ok(v),
             DbValue::VecString(_) => Self::type_error("vec<string>", "vec<f64>"),
+            DbValue::Bool(_) => Self::type_error("bool", "vec<f64>"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "vec<f64>"),
+            DbValue::List(_) => Self::type_error("list", "vec<f64>"),
+            DbValue::Map(_) => Self::type_error("map", "vec<f64>"),
         }
     }
 
@@ -204,6 +345,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "vec<i64>"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "vec<i64>"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "vec<i64>"),
+            DbValue::Bool(_) => Self::type_error("bool", "vec<i64>"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "vec<i64>"),
+            DbValue::List(_) => Self::type_error("list", "vec<i64>"),
+            DbValue::Map(_) => Self::type_error("map", "vec<i64>"),
         }
     }
 
@@ -220,6 +365,10 @@ impl DbValue {
             DbValue::VecU64(v) => Ok(v),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "vec<u64>"),
             DbValue::VecString(_) => Self::type_error("vec<string>", "vec<u64>"),
+            DbValue::Bool(_) => Self::type_error("bool", "vec<u64>"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "vec<u64>"),
+            DbValue::List(_) => Self::type_error("list", "vec<u64>"),
+            DbValue::Map(_) => Self::type_error("map", "vec<u64>"),
         }
     }
 
@@ -236,6 +385,10 @@ impl DbValue {
             DbValue::VecU64(_) => Self::type_error("vec<u64>", "vec<string>"),
             DbValue::VecF64(_) => Self::type_error("vec<f64>", "vec<string>"),
             DbValue::VecString(v) => Ok(v),
+            DbValue::Bool(_) => Self::type_error("bool", "vec<string>"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "vec<string>"),
+            DbValue::List(_) => Self::type_error("list", "vec<string>"),
+            DbValue::Map(_) => Self::type_error("map", "vec<string>"),
         }
     }
 
@@ -254,6 +407,106 @@ impl DbValue {
             DbValue::VecU64(v) => Ok(v.iter().map(|i| *i != 0).collect()),
             DbValue::VecF64(v) => Ok(v.iter().map(|i| *i != 0.0.into()).collect()),
             DbValue::VecString(v) => Ok(v.iter().map(|s| s == "true" || s == "1").collect()),
+            DbValue::Bool(_) => Self::type_error("bool", "Vec<bool>"),
+            DbValue::DateTime(_) => Self::type_error("datetime", "Vec<bool>"),
+            DbValue::List(_) => Self::type_error("list", "Vec<bool>"),
+            DbValue::Map(_) => Self::type_error("map", "Vec<bool>"),
+        }
+    }
+
+    /// Compares the values numerically if they are numbers of different
+    /// types (`I64`, `U64`, `F64`) and using the default ordering otherwise.
+    /// The numeric comparison is exact (no rounding or overflow). Returns
+    /// `None` if the numbers cannot be compared (one of them is NaN).
+    pub(crate) fn compare(&self, other: &DbValue) -> Option<Ordering> {
+        match (self, other) {
+            (DbValue::I64(left), DbValue::U64(right)) => Some(cmp_i64_u64(*left, *right)),
+            (DbValue::U64(left), DbValue::I64(right)) => Some(cmp_i64_u64(*right, *left).reverse()),
+            (DbValue::I64(left), DbValue::F64(right)) => cmp_i64_f64(*left, right.to_f64()),
+            (DbValue::F64(left), DbValue::I64(right)) => {
+                cmp_i64_f64(*right, left.to_f64()).map(Ordering::reverse)
+            }
+            (DbValue::U64(left), DbValue::F64(right)) => cmp_u64_f64(*left, right.to_f64()),
+            (DbValue::F64(left), DbValue::U64(right)) => {
+                cmp_u64_f64(*right, left.to_f64()).map(Ordering::reverse)
+            }
+            _ => Some(self.cmp(other)),
+        }
+    }
+
+    /// Same as `compare()` but orders positive NaN after and negative
+    /// NaN before all numbers of the other types.
+    pub(crate) fn numeric_cmp(&self, other: &DbValue) -> Ordering {
+        self.compare(other).unwrap_or_else(|| match (self, other) {
+            (DbValue::F64(nan), _) if nan.to_f64().is_sign_positive() => Ordering::Greater,
+            (DbValue::F64(_), _) => Ordering::Less,
+            (_, DbValue::F64(nan)) if nan.to_f64().is_sign_positive() => Ordering::Less,
+            _ => Ordering::Greater,
+        })
+    }
+
+    /// Total ordering of the values used for sorting. Unlike the default
+    /// ordering the numbers of different types are ordered by their
+    /// numeric value (see `numeric_cmp()`) with ties broken by type
+    /// (`I64` < `U64` < `F64`).
+    pub(crate) fn sort_cmp(&self, other: &DbValue) -> Ordering {
+        self.numeric_cmp(other).then_with(|| self.cmp(other))
+    }
+
+    /// Returns the numbers of the other numeric types that are equal
+    /// to this value (e.g. `U64(1)` and `F64(1.0)` for `I64(1)`).
+    pub(crate) fn numeric_equivalents(&self) -> Vec<DbValue> {
+        let (i, u, f) = match self {
+            DbValue::I64(v) => (None, u64::try_from(*v).ok(), exact_f64(i128::from(*v))),
+            DbValue::U64(v) => (i64::try_from(*v).ok(), None, exact_f64(i128::from(*v))),
+            DbValue::F64(v) => {
+                let v = v.to_f64();
+                let integer = (v.fract() == 0.0 && v.abs() < U64_LIMIT).then_some(v as i128);
+                (
+                    integer.and_then(|v| i64::try_from(v).ok()),
+                    integer.and_then(|v| u64::try_from(v).ok()),
+                    None,
+                )
+            }
+            _ => (None, None, None),
+        };
+
+        i.map(DbValue::I64)
+            .into_iter()
+            .chain(u.map(DbValue::U64))
+            .chain(f.map(|v| DbValue::F64(v.into())))
+            .collect()
+    }
+
+    /// Returns the value converted from the encoding used prior to
+    /// the `Bool` and `DateTime` variants: `U64` 0 or 1 (`bool`) to
+    /// `Bool`, 13 bytes of serialized `SystemTime` to `DateTime` and
+    /// serialized list of such bytes (`Vec<SystemTime>`) to the list
+    /// of `DateTime`. Returns `None` for other values.
+    pub(crate) fn legacy_converted(&self) -> Option<DbValue> {
+        match self {
+            DbValue::U64(v @ (0 | 1)) => Some(DbValue::Bool(*v == 1)),
+            DbValue::Bytes(bytes) if bytes.len() == 13 && bytes[12] <= 1 => {
+                SystemTime::deserialize(bytes).ok().map(DbValue::from)
+            }
+            DbValue::Bytes(bytes) => {
+                let values = Vec::<DbValue>::deserialize(bytes).ok()?;
+
+                if values.is_empty() {
+                    return None;
+                }
+
+                let values = values
+                    .iter()
+                    .map(|value| match value {
+                        DbValue::Bytes(_) => value.legacy_converted(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<DbValue>>>()?;
+
+                Some(DbValue::Bytes(AgdbSerialize::serialize(&values)))
+            }
+            _ => None,
         }
     }
 
@@ -307,6 +560,32 @@ impl DbValue {
             VEC_STRING_META_VALUE => {
                 DbValue::VecString(storage.value::<Vec<String>>(StorageIndex(value_index.index()))?)
             }
+            BOOL_META_VALUE => DbValue::Bool(value_index.value()[0] != 0),
+            DATE_TIME_META_VALUE => {
+                let mut bytes = [0_u8; 8];
+                bytes.copy_from_slice(value_index.value());
+                DbValue::DateTime(i64::from_le_bytes(bytes))
+            }
+            LIST_META_VALUE => {
+                DbValue::List(storage.value::<Vec<DbValue>>(StorageIndex(value_index.index()))?)
+            }
+            MAP_META_VALUE => {
+                DbValue::Map(storage.value::<Vec<DbKeyValue>>(StorageIndex(value_index.index()))?)
+            }
+            COMPRESSED_BYTES_META_VALUE => DbValue::Bytes(compression::decompress(
+                &storage.value_as_bytes(StorageIndex(value_index.index()))?,
+            )?),
+            COMPRESSED_STRING_META_VALUE => DbValue::String(
+                String::from_utf8(compression::decompress(
+                    &storage.value_as_bytes(StorageIndex(value_index.index()))?,
+                )?)
+                .map_err(|e| {
+                    DbError::db(
+                        DbErrorType::Corrupted,
+                        format!("Compressed string is not valid UTF-8: {e}"),
+                    )
+                })?,
+            ),
             _ => panic!(),
         })
     }
@@ -321,7 +600,14 @@ impl DbValue {
             DbValue::Bytes(v) => {
                 index.set_type(BYTES_META_VALUE);
                 if !index.set_value(v) {
-                    index.set_index(storage.insert_bytes(v)?.0);
+                    if let Some(compressed) =
+                        compression::compress(v, storage.compression_threshold())
+                    {
+                        index.set_type(COMPRESSED_BYTES_META_VALUE);
+                        index.set_index(storage.insert_bytes(&compressed)?.0);
+                    } else {
+                        index.set_index(storage.insert_bytes(v)?.0);
+                    }
                 }
             }
             DbValue::I64(v) => {
@@ -340,7 +626,14 @@ impl DbValue {
                 index.set_type(STRING_META_VALUE);
                 let bytes = v.as_bytes();
                 if !index.set_value(bytes) {
-                    index.set_index(storage.insert(v)?.0);
+                    if let Some(compressed) =
+                        compression::compress(bytes, storage.compression_threshold())
+                    {
+                        index.set_type(COMPRESSED_STRING_META_VALUE);
+                        index.set_index(storage.insert_bytes(&compressed)?.0);
+                    } else {
+                        index.set_index(storage.insert(v)?.0);
+                    }
                 }
             }
             DbValue::VecI64(v) => {
@@ -359,6 +652,22 @@ impl DbValue {
                 index.set_type(VEC_STRING_META_VALUE);
                 index.set_index(storage.insert(v)?.0);
             }
+            DbValue::Bool(v) => {
+                index.set_type(BOOL_META_VALUE);
+                index.set_value(&[u8::from(*v)]);
+            }
+            DbValue::DateTime(v) => {
+                index.set_type(DATE_TIME_META_VALUE);
+                index.set_value(&v.to_le_bytes());
+            }
+            DbValue::List(v) => {
+                index.set_type(LIST_META_VALUE);
+                index.set_index(storage.insert(v)?.0);
+            }
+            DbValue::Map(v) => {
+                index.set_type(MAP_META_VALUE);
+                index.set_index(storage.insert(v)?.0);
+            }
         }
 
         Ok(index)
@@ -373,6 +682,51 @@ impl DbValue {
     }
 }
 
+const I64_LIMIT: f64 = 9_223_372_036_854_775_808.0;
+const U64_LIMIT: f64 = 18_446_744_073_709_551_616.0;
+
+fn cmp_i64_u64(left: i64, right: u64) -> Ordering {
+    u64::try_from(left).map_or(Ordering::Less, |left| left.cmp(&right))
+}
+
+fn cmp_i64_f64(left: i64, right: f64) -> Option<Ordering> {
+    if right.is_nan() {
+        None
+    } else if right >= I64_LIMIT {
+        Some(Ordering::Less)
+    } else if right < -I64_LIMIT {
+        Some(Ordering::Greater)
+    } else {
+        Some(cmp_integer_f64(i128::from(left), right))
+    }
+}
+
+fn cmp_u64_f64(left: u64, right: f64) -> Option<Ordering> {
+    if right.is_nan() {
+        None
+    } else if right >= U64_LIMIT {
+        Some(Ordering::Less)
+    } else if right < 0.0 {
+        Some(Ordering::Greater)
+    } else {
+        Some(cmp_integer_f64(i128::from(left), right))
+    }
+}
+
+/// Compares the integer with the finite `right` within the range
+/// of the 64-bit integers where the truncation to integer is exact.
+fn cmp_integer_f64(left: i128, right: f64) -> Ordering {
+    let truncated = right.trunc();
+
+    left.cmp(&(truncated as i128))
+        .then_with(|| 0.0_f64.total_cmp(&(right - truncated)))
+}
+
+fn exact_f64(value: i128) -> Option<f64> {
+    let float = value as f64;
+    (float.abs() < U64_LIMIT && float as i128 == value).then_some(float)
+}
+
 impl Default for DbValue {
     fn default() -> Self {
         Self::I64(0)
@@ -393,7 +747,14 @@ impl From<&PathBuf> for DbValue {
 
 impl From<SystemTime> for DbValue {
     fn from(value: SystemTime) -> Self {
-        DbValue::Bytes(<SystemTime as AgdbSerialize>::serialize(&value))
+        let nanos = match value.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(duration) => i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX),
+            Err(e) => i64::try_from(e.duration().as_nanos())
+                .map(|nanos| -nanos)
+                .unwrap_or(i64::MIN),
+        };
+
+        DbValue::DateTime(nanos)
     }
 }
 
@@ -477,7 +838,7 @@ impl From<&str> for DbValue {
 
 impl From<bool> for DbValue {
     fn from(value: bool) -> Self {
-        DbValue::U64(if value { 1 } else { 0 })
+        DbValue::Bool(value)
     }
 }
 
@@ -553,6 +914,18 @@ impl From<Vec<bool>> for DbValue {
     }
 }
 
+impl From<Vec<DbValue>> for DbValue {
+    fn from(value: Vec<DbValue>) -> Self {
+        DbValue::List(value)
+    }
+}
+
+impl From<Vec<DbKeyValue>> for DbValue {
+    fn from(value: Vec<DbKeyValue>) -> Self {
+        DbValue::Map(value)
+    }
+}
+
 impl From<&[u8]> for DbValue {
     fn from(value: &[u8]) -> Self {
         DbValue::Bytes(value.to_vec())
@@ -719,7 +1092,10 @@ impl<T: Into<DbValue> + DbTypeMarker> From<Vec<T>> for DbValue {
                     .map(|v| v.string().unwrap().to_owned())
                     .collect(),
             ),
-            Some(DbValue::Bytes(_)) => DbValue::Bytes(crate::AgdbSerialize::serialize(&db_values)),
+            Some(DbValue::Bytes(_) | DbValue::Bool(_) | DbValue::DateTime(_)) => {
+                DbValue::Bytes(crate::AgdbSerialize::serialize(&db_values))
+            }
+            Some(DbValue::List(_) | DbValue::Map(_)) => DbValue::List(db_values),
             _ => DbValue::Bytes(Vec::new()),
         }
     }
@@ -803,6 +1179,18 @@ impl From<DbF64> for DbValues {
     }
 }
 
+impl From<DbValue> for DbValues {
+    fn from(value: DbValue) -> Self {
+        DbValues(vec![value])
+    }
+}
+
+impl From<&DbValue> for DbValues {
+    fn from(value: &DbValue) -> Self {
+        DbValues(vec![value.clone()])
+    }
+}
+
 impl From<&str> for DbValues {
     fn from(value: &str) -> Self {
         DbValues(vec![value.into()])
@@ -834,7 +1222,18 @@ impl TryFrom<DbValue> for SystemTime {
     type Error = DbError;
 
     fn try_from(value: DbValue) -> Result<Self, Self::Error> {
-        SystemTime::deserialize(value.bytes()?)
+        if let DbValue::Bytes(bytes) = &value {
+            return SystemTime::deserialize(bytes);
+        }
+
+        let nanos = value.date_time()?;
+        let duration = Duration::from_nanos(nanos.unsigned_abs());
+
+        Ok(if nanos < 0 {
+            SystemTime::UNIX_EPOCH - duration
+        } else {
+            SystemTime::UNIX_EPOCH + duration
+        })
     }
 }
 
@@ -957,6 +1356,7 @@ impl<T: TryFrom<DbValue, Error = DbError>> TryFrom<DbValue> for Vec<T> {
             DbValue::VecU64(v) => Ok(v.into_iter().map(DbValue::from).collect()),
             DbValue::VecF64(v) => Ok(v.into_iter().map(DbValue::from).collect()),
             DbValue::VecString(v) => Ok(v.into_iter().map(DbValue::from).collect()),
+            DbValue::List(v) => Ok(v),
             DbValue::Bytes(v) => {
                 if v.is_empty() {
                     Ok(vec![])
@@ -974,6 +1374,9 @@ impl<T: TryFrom<DbValue, Error = DbError>> TryFrom<DbValue> for Vec<T> {
             DbValue::U64(_) => DbValue::type_error("u64", "Vec<DbValue>"),
             DbValue::F64(_) => DbValue::type_error("f64", "Vec<DbValue>"),
             DbValue::String(_) => DbValue::type_error("string", "Vec<DbValue>"),
+            DbValue::Bool(_) => DbValue::type_error("bool", "Vec<DbValue>"),
+            DbValue::DateTime(_) => DbValue::type_error("datetime", "Vec<DbValue>"),
+            DbValue::Map(_) => DbValue::type_error("map", "Vec<DbValue>"),
         }?;
         db_values
             .into_iter()
@@ -1015,6 +1418,24 @@ impl Display for DbValue {
                     .join(", ")
             ),
             DbValue::VecString(v) => write!(f, "[{}]", v.join(", ")),
+            DbValue::Bool(v) => write!(f, "{v}"),
+            DbValue::DateTime(v) => write!(f, "{}", format_date_time(*v)),
+            DbValue::List(v) => write!(
+                f,
+                "[{}]",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            DbValue::Map(v) => write!(
+                f,
+                "{{{}}}",
+                v.iter()
+                    .map(|kv| format!("{}: {}", kv.key, kv.value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -1031,10 +1452,57 @@ impl StableHash for DbValue {
             DbValue::VecU64(value) => value.stable_hash(),
             DbValue::VecF64(value) => value.stable_hash(),
             DbValue::VecString(value) => value.stable_hash(),
+            DbValue::Bool(value) => u64::from(*value).stable_hash(),
+            DbValue::DateTime(value) => value.stable_hash(),
+            DbValue::List(value) => value.stable_hash(),
+            DbValue::Map(value) => value.stable_hash(),
         }
     }
 }
 
+/// Formats the nanoseconds since the Unix epoch as
+/// RFC 3339 UTC timestamp, e.g. `2024-05-06T07:08:09.123456789Z`.
+fn format_date_time(nanos: i64) -> String {
+    const NANOS_PER_SECOND: i64 = 1_000_000_000;
+    const SECONDS_PER_DAY: i64 = 86_400;
+
+    let seconds = nanos.div_euclid(NANOS_PER_SECOND);
+    let fraction = nanos.rem_euclid(NANOS_PER_SECOND);
+    let days = seconds.div_euclid(SECONDS_PER_DAY);
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+
+    // Converts days since the epoch to the civil date (proleptic
+    // Gregorian calendar) counting in 400 year eras starting on March 1st.
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let mut result = format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    );
+
+    if fraction != 0 {
+        result.push_str(&format!(".{fraction:09}"));
+    }
+
+    result.push('Z');
+    result
+}
+
 /// Enables `PathBuf` to be stored as a vector in the database by marking it with the `DbTypeMarker` trait.
 impl DbTypeMarker for PathBuf {}
 
@@ -1153,6 +1621,16 @@ mod tests {
             ),
             "[Hello, World]"
         );
+        assert_eq!(format!("{}", DbValue::from(true)), "true");
+        assert_eq!(format!("{}", DbValue::DateTime(0)), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format!("{}", DbValue::DateTime(1_709_251_199_000_000_001)),
+            "2024-02-29T23:59:59.000000001Z"
+        );
+        assert_eq!(
+            format!("{}", DbValue::DateTime(-1)),
+            "1969-12-31T23:59:59.999999999Z"
+        );
     }
 
     #[test]
@@ -1193,6 +1671,11 @@ mod tests {
             DbValue::VecF64 { .. }
         ));
         assert!(matches!(DbValue::from(vec![""]), DbValue::VecString { .. }));
+        assert!(matches!(DbValue::from(true), DbValue::Bool { .. }));
+        assert!(matches!(
+            DbValue::from(SystemTime::now()),
+            DbValue::DateTime { .. }
+        ));
     }
 
     #[test]
@@ -1206,6 +1689,99 @@ mod tests {
         assert_ne!(DbValue::from(vec![1_u64]).stable_hash(), 0);
         assert_ne!(DbValue::from(vec![1.0_f64]).stable_hash(), 0);
         assert_ne!(DbValue::from(vec![""]).stable_hash(), 0);
+        assert_ne!(DbValue::from(true).stable_hash(), 0);
+        assert_ne!(DbValue::DateTime(1).stable_hash(), 0);
+    }
+
+    #[test]
+    fn date_time() {
+        assert_eq!(DbValue::DateTime(-5).date_time().unwrap(), -5);
+        assert_eq!(
+            DbValue::from(1_i64).date_time(),
+            Err(DbError::db(
+                DbErrorType::TypeError,
+                "Cannot convert 'i64' to 'datetime'."
+            ))
+        );
+        assert_eq!(
+            DbValue::DateTime(1).to_i64(),
+            Err(DbError::db(
+                DbErrorType::TypeError,
+                "Cannot convert 'datetime' to 'i64'."
+            ))
+        );
+        assert!(DbValue::DateTime(1) < DbValue::DateTime(2));
+        assert!(DbValue::DateTime(-1) < DbValue::DateTime(0));
+    }
+
+    #[test]
+    fn nested() {
+        let value = DbValue::Map(vec![
+            ("name", "John").into(),
+            (
+                "tags",
+                DbValue::List(vec!["a".into(), DbValue::Map(vec![("b", 1).into()])]),
+            )
+                .into(),
+        ]);
+
+        assert_eq!(value.nested::<&str>(&[]), Some(&value));
+        assert_eq!(value.nested(&["name"]), Some(&DbValue::from("John")));
+        assert_eq!(value.nested(&["tags", "1", "b"]), Some(&DbValue::from(1)));
+        assert_eq!(value.nested(&["tags", "2"]), None);
+        assert_eq!(value.nested(&["tags", "x"]), None);
+        assert_eq!(value.nested(&["name", "0"]), None);
+        assert_eq!(value.list(), DbValue::type_error("map", "list"));
+        assert_eq!(value.map().unwrap().len(), 2);
+        assert_eq!(
+            value.to_string(),
+            "{name: John, tags: [a, {b: 1}]}".to_string()
+        );
+    }
+
+    #[test]
+    fn nested_storage() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let value = DbValue::Map(vec![
+            ("city", "Prague").into(),
+            ("tags", DbValue::List(vec![1.into(), "x".into()])).into(),
+        ]);
+
+        let index = value.store_db_value(&mut storage).unwrap();
+
+        assert_eq!(DbValue::load_db_value(index, &storage).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn compressed_storage() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        storage.set_compression_threshold(Some(32));
+        let string = DbValue::from("abc".repeat(100));
+        let bytes = DbValue::from(vec![1_u8; 300]);
+        let small = DbValue::from("abcabcabcabcabcabc");
+
+        let string_index = string.store_db_value(&mut storage).unwrap();
+        let bytes_index = bytes.store_db_value(&mut storage).unwrap();
+        let small_index = small.store_db_value(&mut storage).unwrap();
+
+        assert_eq!(string_index.get_type(), COMPRESSED_STRING_META_VALUE);
+        assert_eq!(bytes_index.get_type(), COMPRESSED_BYTES_META_VALUE);
+        assert_eq!(small_index.get_type(), STRING_META_VALUE);
+        assert_eq!(
+            DbValue::load_db_value(string_index, &storage).unwrap(),
+            string
+        );
+        assert_eq!(
+            DbValue::load_db_value(bytes_index, &storage).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            DbValue::load_db_value(small_index, &storage).unwrap(),
+            small
+        );
     }
 
     #[test]
@@ -2191,6 +2767,10 @@ mod tests {
         let db_time = db_value.try_into().unwrap();
 
         assert_eq!(before_epoch, db_time);
+
+        let legacy = DbValue::Bytes(AgdbSerialize::serialize(&time));
+        let time_back: SystemTime = legacy.try_into().unwrap();
+        assert_eq!(time, time_back);
     }
 
     #[test]
@@ -2313,4 +2893,129 @@ mod tests {
 
         assert_eq!(original, struct_back);
     }
+
+    #[test]
+    fn compare_numeric() {
+        let compare = |left: DbValue, right: DbValue| left.compare(&right);
+
+        assert_eq!(compare(5_i64.into(), 3_u64.into()), Some(Ordering::Greater));
+        assert_eq!(compare(3_u64.into(), 5_i64.into()), Some(Ordering::Less));
+        assert_eq!(compare(5_i64.into(), 5_u64.into()), Some(Ordering::Equal));
+        assert_eq!(compare((-1_i64).into(), 0_u64.into()), Some(Ordering::Less));
+        assert_eq!(
+            compare(i64::MAX.into(), u64::MAX.into()),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare(5_i64.into(), 5.0.into()), Some(Ordering::Equal));
+        assert_eq!(compare(5_i64.into(), 5.5.into()), Some(Ordering::Less));
+        assert_eq!(
+            compare((-5_i64).into(), (-5.5).into()),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare(0_i64.into(), (-0.0).into()), Some(Ordering::Equal));
+        assert_eq!(compare(5.5.into(), 5_u64.into()), Some(Ordering::Greater));
+        assert_eq!(
+            compare(0_u64.into(), (-0.5).into()),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(i64::MAX.into(), 9_223_372_036_854_775_808.0.into()),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(i64::MIN.into(), (-9_223_372_036_854_775_808.0).into()),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare(u64::MAX.into(), 18_446_744_073_709_551_616.0.into()),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(
+                9_007_199_254_740_993_i64.into(),
+                9_007_199_254_740_992.0.into()
+            ),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare(1_i64.into(), f64::INFINITY.into()),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare(1_u64.into(), f64::NEG_INFINITY.into()),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare(1_i64.into(), f64::NAN.into()), None);
+        assert_eq!(compare(f64::NAN.into(), 1_u64.into()), None);
+        assert_eq!(
+            compare(f64::NAN.into(), f64::NAN.into()),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare(1_i64.into(), "a".into()), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn sort_cmp() {
+        let mut values: Vec<DbValue> = vec![
+            "a".into(),
+            f64::NAN.into(),
+            3_u64.into(),
+            2.5.into(),
+            (-f64::NAN).into(),
+            2_u64.into(),
+            2_i64.into(),
+            2.0.into(),
+            (-1_i64).into(),
+            vec![0_u8].into(),
+        ];
+        values.sort_by(|l, r| l.sort_cmp(r));
+
+        assert_eq!(
+            values,
+            vec![
+                vec![0_u8].into(),
+                (-f64::NAN).into(),
+                (-1_i64).into(),
+                2_i64.into(),
+                2_u64.into(),
+                2.0.into(),
+                2.5.into(),
+                3_u64.into(),
+                f64::NAN.into(),
+                "a".into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_equivalents() {
+        assert_eq!(
+            DbValue::from(1_i64).numeric_equivalents(),
+            vec![1_u64.into(), 1.0.into()]
+        );
+        assert_eq!(
+            DbValue::from(-1_i64).numeric_equivalents(),
+            vec![(-1.0).into()]
+        );
+        assert_eq!(
+            DbValue::from(u64::MAX).numeric_equivalents(),
+            Vec::<DbValue>::new()
+        );
+        assert_eq!(
+            DbValue::from(2.0).numeric_equivalents(),
+            vec![2_i64.into(), 2_u64.into()]
+        );
+        assert_eq!(
+            DbValue::from(2.5).numeric_equivalents(),
+            Vec::<DbValue>::new()
+        );
+        assert_eq!(
+            DbValue::from(f64::NAN).numeric_equivalents(),
+            Vec::<DbValue>::new()
+        );
+        assert_eq!(
+            DbValue::from("1").numeric_equivalents(),
+            Vec::<DbValue>::new()
+        );
+    }
 }
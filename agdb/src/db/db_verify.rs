@@ -0,0 +1,311 @@
+use crate::DbId;
+use crate::DbImpl;
+use crate::DbValue;
+use crate::StorageData;
+use crate::graph::GraphIndex;
+
+/// Kind of the inconsistency found by [`DbImpl::verify()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub enum DbIssueType {
+    /// Storage record that cannot be read or whose header
+    /// no longer matches the storage index.
+    UnreadableRecord,
+
+    /// Edge whose origin or destination node does not exist.
+    DanglingEdge,
+
+    /// Outgoing or incoming edge list of a node that contains
+    /// foreign or removed edges or does not match the edge count.
+    InvalidEdgeList,
+
+    /// Alias pointing to a missing element or not mapped back to it.
+    InvalidAlias,
+
+    /// Index entry pointing to a missing element or to values
+    /// the element no longer has, or an element missing from the index.
+    InvalidIndexEntry,
+
+    /// Element values that cannot be read or that belong to
+    /// a removed element.
+    InvalidValues,
+}
+
+/// Single inconsistency found by [`DbImpl::verify()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct DbIssue {
+    /// Kind of the inconsistency.
+    pub ty: DbIssueType,
+
+    /// Affected element. Id == 0 if the issue is not
+    /// related to any element (e.g. storage records).
+    pub id: DbId,
+
+    /// Description of the inconsistency.
+    pub description: String,
+}
+
+/// Result of [`DbImpl::verify()`] listing all inconsistencies
+/// found in the database. Empty list means the database is consistent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct DbVerifyReport {
+    /// List of inconsistencies found.
+    pub issues: Vec<DbIssue>,
+}
+
+impl DbIssue {
+    pub(crate) fn new(ty: DbIssueType, id: DbId, description: String) -> Self {
+        Self {
+            ty,
+            id,
+            description,
+        }
+    }
+}
+
+impl DbVerifyReport {
+    /// Returns `true` if no inconsistencies were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl<Store: StorageData> DbImpl<Store> {
+    /// Walks the storage records, the graph, the aliases, the indexes and the
+    /// values of the database and reports any inconsistencies found such as
+    /// dangling edges, index entries pointing to removed elements or unreadable
+    /// records. The database is not modified. The check reads the entire database
+    /// so it can take a while for large data sets.
+    pub fn verify(&self) -> DbVerifyReport {
+        let mut issues = self
+            .storage
+            .verify()
+            .into_iter()
+            .map(|description| DbIssue::new(DbIssueType::UnreadableRecord, DbId(0), description))
+            .collect::<Vec<DbIssue>>();
+        issues.extend(self.graph.verify(&self.storage));
+        self.verify_aliases(&mut issues);
+        self.verify_indexes(&mut issues);
+        self.verify_values(&mut issues);
+
+        DbVerifyReport { issues }
+    }
+
+    fn element_id(&self, index: u64) -> Option<DbId> {
+        let index = index as i64;
+
+        if self.graph.node(&self.storage, GraphIndex(index)).is_some() {
+            Some(DbId(index))
+        } else if self.graph.edge(&self.storage, GraphIndex(-index)).is_some() {
+            Some(DbId(-index))
+        } else {
+            None
+        }
+    }
+
+    fn is_element(&self, db_id: DbId) -> bool {
+        self.element_id(db_id.as_index()) == Some(db_id)
+    }
+
+    fn verify_aliases(&self, issues: &mut Vec<DbIssue>) {
+        for (alias, db_id) in self.aliases.iter(&self.storage) {
+            if !self.is_element(db_id) {
+                issues.push(DbIssue::new(
+                    DbIssueType::InvalidAlias,
+                    db_id,
+                    format!("Alias '{alias}' points to missing id '{}'", db_id.0),
+                ));
+            } else if self.aliases.key(&self.storage, &db_id).ok().flatten() != Some(alias.clone())
+            {
+                issues.push(DbIssue::new(
+                    DbIssueType::InvalidAlias,
+                    db_id,
+                    format!("Alias '{alias}' is not mapped back from id '{}'", db_id.0),
+                ));
+            }
+        }
+    }
+
+    fn verify_indexes(&self, issues: &mut Vec<DbIssue>) {
+        for index in self.indexes.indexes() {
+            let keys = index.keys();
+            let name = super::join_values(keys);
+
+            for (values, db_id) in index.entries(&self.storage) {
+                if !self.is_element(db_id) {
+                    issues.push(DbIssue::new(
+                        DbIssueType::InvalidIndexEntry,
+                        db_id,
+                        format!(
+                            "Index '{name}' entry '{}' points to missing id '{}'",
+                            super::join_values(&values),
+                            db_id.0
+                        ),
+                    ));
+                } else if self.indexed_values(db_id, keys).as_ref() != Some(&values) {
+                    issues.push(DbIssue::new(
+                        DbIssueType::InvalidIndexEntry,
+                        db_id,
+                        format!(
+                            "Index '{name}' entry '{}' does not match the values of id '{}'",
+                            super::join_values(&values),
+                            db_id.0
+                        ),
+                    ));
+                }
+            }
+
+            for i in 1..self.values.len() {
+                let Some(db_id) = self.element_id(i) else {
+                    continue;
+                };
+                let Some(values) = self.indexed_values(db_id, keys) else {
+                    continue;
+                };
+
+                if !index
+                    .values(&self.storage, &values)
+                    .unwrap_or_default()
+                    .contains(&db_id)
+                {
+                    issues.push(DbIssue::new(
+                        DbIssueType::InvalidIndexEntry,
+                        db_id,
+                        format!("Id '{}' is missing from index '{name}'", db_id.0),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn verify_values(&self, issues: &mut Vec<DbIssue>) {
+        for i in 1..self.values.len() {
+            let element = self.element_id(i);
+
+            match self.values.verify(&self.storage, i) {
+                Err(e) => issues.push(DbIssue::new(
+                    DbIssueType::InvalidValues,
+                    element.unwrap_or(DbId(i as i64)),
+                    format!("Values of index '{i}' cannot be read: {}", e.description),
+                )),
+                Ok(count) if count != 0 && element.is_none() => issues.push(DbIssue::new(
+                    DbIssueType::InvalidValues,
+                    DbId(i as i64),
+                    format!("Values of index '{i}' belong to a removed element"),
+                )),
+                Ok(_) => {}
+            }
+        }
+    }
+
+    fn indexed_values(&self, db_id: DbId, keys: &[DbValue]) -> Option<Vec<DbValue>> {
+        let kvs = self
+            .values
+            .values_by_keys(&self.storage, db_id.as_index(), keys)
+            .ok()?;
+
+        if kvs.len() != keys.len() {
+            return None;
+        }
+
+        Some(kvs.into_iter().map(|kv| kv.value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Db;
+    use crate::QueryBuilder;
+    use crate::test_utilities::test_file::TestFile;
+
+    #[test]
+    fn derived_from_debug() {
+        let _ = format!("{:?}", DbVerifyReport::default());
+    }
+
+    #[test]
+    fn verify_removed_node() {
+        let test_file = TestFile::new();
+        let mut db = Db::new(test_file.file_name()).unwrap();
+        db.exec_mut(QueryBuilder::insert().index("key").query())
+            .unwrap();
+        db.exec_mut(
+            QueryBuilder::insert()
+                .nodes()
+                .aliases(["a", "b"])
+                .values([[("key", 1).into()], [("key", 2).into()]])
+                .query(),
+        )
+        .unwrap();
+
+        assert!(db.verify().is_ok());
+
+        db.graph
+            .remove_node(&mut db.storage, GraphIndex(1))
+            .unwrap();
+
+        assert_eq!(
+            db.verify().issues,
+            vec![
+                DbIssue::new(
+                    DbIssueType::InvalidAlias,
+                    DbId(1),
+                    "Alias 'a' points to missing id '1'".to_string()
+                ),
+                DbIssue::new(
+                    DbIssueType::InvalidIndexEntry,
+                    DbId(1),
+                    "Index 'key' entry '1' points to missing id '1'".to_string()
+                ),
+                DbIssue::new(
+                    DbIssueType::InvalidValues,
+                    DbId(1),
+                    "Values of index '1' belong to a removed element".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_index_mismatch() {
+        let test_file = TestFile::new();
+        let mut db = Db::new(test_file.file_name()).unwrap();
+        db.exec_mut(QueryBuilder::insert().index("key").query())
+            .unwrap();
+        db.exec_mut(
+            QueryBuilder::insert()
+                .nodes()
+                .values([[("key", 1).into()]])
+                .query(),
+        )
+        .unwrap();
+        db.values
+            .insert_or_replace(&mut db.storage, 1, &("key", 2).into())
+            .unwrap();
+
+        assert_eq!(
+            db.verify().issues,
+            vec![
+                DbIssue::new(
+                    DbIssueType::InvalidIndexEntry,
+                    DbId(1),
+                    "Index 'key' entry '1' does not match the values of id '1'".to_string()
+                ),
+                DbIssue::new(
+                    DbIssueType::InvalidIndexEntry,
+                    DbId(1),
+                    "Id '1' is missing from index 'key'".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use crate::DbError;
 use crate::DbErrorType;
+use crate::DbId;
+use crate::DbIssue;
+use crate::DbIssueType;
 use crate::StorageData;
 use crate::collections::vec::DbVec;
 use crate::storage::Storage;
@@ -679,6 +682,57 @@ where
         self.data.shrink_to_fit(storage)
     }
 
+    /// Checks that every edge connects two valid nodes and that the
+    /// outgoing and incoming edge lists of every node contain only
+    /// its own valid edges and match the stored edge counts.
+    pub fn verify(&self, storage: &Storage<D>) -> Vec<DbIssue> {
+        let mut issues = vec![];
+        let capacity = self.data.capacity().unwrap_or_default() as i64;
+
+        for i in 1..capacity {
+            let index = GraphIndex::from(i);
+
+            match self.is_removed_index(storage, index) {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => {
+                    issues.push(DbIssue::new(
+                        DbIssueType::InvalidEdgeList,
+                        DbId(i),
+                        e.description,
+                    ));
+                    continue;
+                }
+            }
+
+            if self.is_valid_edge(storage, index).unwrap_or_default() {
+                let edge = GraphIndex::from(-i);
+
+                for node in [self.edge_from(storage, edge), self.edge_to(storage, edge)] {
+                    if self.validate_node(storage, node).is_err() {
+                        issues.push(DbIssue::new(
+                            DbIssueType::DanglingEdge,
+                            DbId(edge.0),
+                            format!("Edge '{}' references missing node '{}'", edge.0, node.0),
+                        ));
+                    }
+                }
+            } else {
+                for from in [true, false] {
+                    if let Err(e) = self.verify_edge_list(storage, index, capacity, from) {
+                        issues.push(DbIssue::new(
+                            DbIssueType::InvalidEdgeList,
+                            DbId(i),
+                            e.description,
+                        ));
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
     fn free_index(&mut self, storage: &mut Storage<D>, index: GraphIndex) -> Result<(), DbError> {
         let next_free = self.data.from_meta(storage, GraphIndex::default())?;
         self.data.set_from_meta(storage, index, next_free)?;
@@ -917,6 +971,66 @@ where
         self.data.set_to_meta(storage, node, count + 1)
     }
 
+    fn verify_edge_list(
+        &self,
+        storage: &Storage<D>,
+        node: GraphIndex,
+        capacity: i64,
+        from: bool,
+    ) -> Result<(), DbError> {
+        let (direction, mut edge, expected) = if from {
+            (
+                "outgoing",
+                self.first_edge_from(storage, node)?,
+                self.edge_count_from(storage, node)?,
+            )
+        } else {
+            (
+                "incoming",
+                self.first_edge_to(storage, node)?,
+                self.edge_count_to(storage, node)?,
+            )
+        };
+        let mut count = 0;
+
+        while edge.is_valid() {
+            let owner = if from {
+                self.edge_from(storage, edge)
+            } else {
+                self.edge_to(storage, edge)
+            };
+
+            if count == capacity || self.validate_edge(storage, edge).is_err() || owner != node {
+                return Err(DbError::graph(
+                    DbErrorType::InvalidIndex,
+                    format!(
+                        "Node '{}' {direction} edge list contains invalid edge '{}'",
+                        node.0, edge.0
+                    ),
+                ));
+            }
+
+            count += 1;
+            edge = if from {
+                self.next_edge_from(storage, edge)?
+            } else {
+                self.next_edge_to(storage, edge)?
+            };
+        }
+
+        if count != expected {
+            return Err(DbError::graph(
+                DbErrorType::InvalidIndex,
+                format!(
+                    "Node '{}' {direction} edge count ({expected}) does not match its edge list ({count})",
+                    node.0
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
     fn validate_edge(&self, storage: &Storage<D>, index: GraphIndex) -> Result<(), DbError> {
         if !self.is_valid_index(storage, index)? || !self.is_valid_edge(storage, index)? {
             return Err(Self::invalid_index(index));
@@ -1049,6 +1163,43 @@ mod tests {
         assert_eq!(graph.edge(&storage, index).unwrap().index(), index);
     }
 
+    #[test]
+    fn verify() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorageMemoryMapped>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let edge = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node1).unwrap();
+
+        assert_eq!(graph.verify(&storage), vec![]);
+
+        graph.data.set_to(&mut storage, edge, -10).unwrap();
+        graph.data.set_from_meta(&mut storage, node2, 3).unwrap();
+
+        assert_eq!(
+            graph.verify(&storage),
+            vec![
+                DbIssue::new(
+                    DbIssueType::InvalidEdgeList,
+                    DbId(2),
+                    "Node '2' outgoing edge count (3) does not match its edge list (1)".to_string()
+                ),
+                DbIssue::new(
+                    DbIssueType::InvalidEdgeList,
+                    DbId(2),
+                    "Node '2' incoming edge list contains invalid edge '-3'".to_string()
+                ),
+                DbIssue::new(
+                    DbIssueType::DanglingEdge,
+                    DbId(-3),
+                    "Edge '-3' references missing node '10'".to_string()
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn edge_from_index_missing() {
         let test_file = TestFile::new();
@@ -0,0 +1,438 @@
+use crate::StorageData;
+use crate::graph::GraphData;
+use crate::graph::GraphImpl;
+use crate::graph::GraphIndex;
+use crate::storage::Storage;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// Snapshot of the graph (or a subgraph induced by a set of nodes)
+/// as adjacency lists used to run the whole graph algorithms. Parallel
+/// edges are kept so each edge is counted individually.
+pub struct GraphAnalytics {
+    nodes: Vec<GraphIndex>,
+    edges_from: Vec<Vec<usize>>,
+    edges_to: Vec<Vec<usize>>,
+}
+
+impl GraphAnalytics {
+    /// Collects the `nodes` (all nodes of the graph if `None`) and
+    /// the edges between them. Indexes that are not nodes are skipped.
+    pub fn new<D, Data>(
+        graph: &GraphImpl<D, Data>,
+        storage: &Storage<D>,
+        nodes: Option<&[GraphIndex]>,
+    ) -> Self
+    where
+        Data: GraphData<D>,
+        D: StorageData,
+    {
+        let nodes = match nodes {
+            Some(nodes) => nodes.iter().copied().filter(|i| i.is_node()).collect(),
+            None => graph
+                .node_iter(storage)
+                .map(|node| node.index())
+                .collect::<Vec<GraphIndex>>(),
+        };
+        let mut positions = HashMap::with_capacity(nodes.len());
+        let nodes = nodes
+            .into_iter()
+            .filter(|index| {
+                let position = positions.len();
+                positions.insert(*index, position).is_none()
+            })
+            .collect::<Vec<GraphIndex>>();
+        let mut edges_from = vec![vec![]; nodes.len()];
+        let mut edges_to = vec![vec![]; nodes.len()];
+
+        for (from, index) in nodes.iter().enumerate() {
+            if let Some(node) = graph.node(storage, *index) {
+                for edge in node.edge_iter_from() {
+                    if let Some(to) = positions.get(&edge.index_to()) {
+                        edges_from[from].push(*to);
+                        edges_to[*to].push(from);
+                    }
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            edges_from,
+            edges_to,
+        }
+    }
+
+    /// Numbers the components of nodes connected by edges in any
+    /// direction from 0 in the order of their first node.
+    pub fn weakly_connected_components(&self) -> Vec<(GraphIndex, u64)> {
+        let mut components = vec![None; self.nodes.len()];
+        let mut component = 0;
+
+        for start in 0..self.nodes.len() {
+            if components[start].is_some() {
+                continue;
+            }
+
+            let mut stack = vec![start];
+            components[start] = Some(component);
+
+            while let Some(node) = stack.pop() {
+                for next in self.edges_from[node].iter().chain(&self.edges_to[node]) {
+                    if components[*next].is_none() {
+                        components[*next] = Some(component);
+                        stack.push(*next);
+                    }
+                }
+            }
+
+            component += 1;
+        }
+
+        self.zip(components.into_iter().map(Option::unwrap_or_default))
+    }
+
+    /// Numbers the components of nodes that are all reachable from
+    /// each other following the edges from 0 in the order of their
+    /// first node. Uses iterative Tarjan's algorithm.
+    pub fn strongly_connected_components(&self) -> Vec<(GraphIndex, u64)> {
+        let count = self.nodes.len();
+        let mut order = vec![None; count];
+        let mut low_link = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = vec![];
+        let mut components = vec![0_usize; count];
+        let mut component = 0;
+        let mut visited = 0;
+
+        for start in 0..count {
+            if order[start].is_some() {
+                continue;
+            }
+
+            let mut calls = vec![(start, 0)];
+
+            while let Some((node, edge)) = calls.pop() {
+                if edge == 0 {
+                    order[node] = Some(visited);
+                    low_link[node] = visited;
+                    visited += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+
+                if let Some(next) = self.edges_from[node].get(edge) {
+                    calls.push((node, edge + 1));
+
+                    match order[*next] {
+                        None => calls.push((*next, 0)),
+                        Some(next_order) if on_stack[*next] => {
+                            low_link[node] = low_link[node].min(next_order);
+                        }
+                        Some(_) => {}
+                    }
+
+                    continue;
+                }
+
+                if Some(low_link[node]) == order[node] {
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        components[member] = component;
+
+                        if member == node {
+                            break;
+                        }
+                    }
+
+                    component += 1;
+                }
+
+                if let Some((parent, _)) = calls.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[node]);
+                }
+            }
+        }
+
+        // Tarjan's algorithm finds the components in reverse
+        // topological order so they are renumbered by their first node.
+        let mut numbers = vec![None; component];
+        let mut next_number = 0;
+        let components = components.into_iter().map(|component| {
+            *numbers[component].get_or_insert_with(|| {
+                next_number += 1;
+                next_number - 1
+            })
+        });
+
+        self.zip(components.collect::<Vec<u64>>())
+    }
+
+    /// Computes PageRank of the nodes with the `damping` factor
+    /// over given number of `iterations`. The rank of the nodes
+    /// without outgoing edges is distributed evenly to all nodes.
+    pub fn page_rank(&self, damping: f64, iterations: u64) -> Vec<(GraphIndex, f64)> {
+        let count = self.nodes.len() as f64;
+        let mut ranks = vec![1.0 / count; self.nodes.len()];
+
+        for _ in 0..iterations {
+            let dangling = (0..self.nodes.len())
+                .filter(|node| self.edges_from[*node].is_empty())
+                .map(|node| ranks[node])
+                .sum::<f64>();
+            let base = (1.0 - damping) / count + damping * dangling / count;
+
+            ranks = (0..self.nodes.len())
+                .map(|node| {
+                    base + damping
+                        * self.edges_to[node]
+                            .iter()
+                            .map(|from| ranks[*from] / self.edges_from[*from].len() as f64)
+                            .sum::<f64>()
+                })
+                .collect();
+        }
+
+        self.zip(ranks)
+    }
+
+    /// Counts the incoming and outgoing edges of the nodes. Self
+    /// referential edges are counted twice.
+    pub fn degree_centrality(&self) -> Vec<(GraphIndex, u64)> {
+        self.zip(
+            (0..self.nodes.len())
+                .map(|node| (self.edges_from[node].len() + self.edges_to[node].len()) as u64),
+        )
+    }
+
+    /// Computes the number of shortest paths between all other pairs of
+    /// nodes passing through each node using Brandes' algorithm. Paths
+    /// follow the edges and parallel edges do not count as separate paths.
+    pub fn betweenness_centrality(&self) -> Vec<(GraphIndex, f64)> {
+        let count = self.nodes.len();
+        let neighbours = self
+            .edges_from
+            .iter()
+            .map(|edges| {
+                let mut edges = edges.clone();
+                edges.sort_unstable();
+                edges.dedup();
+                edges
+            })
+            .collect::<Vec<Vec<usize>>>();
+        let mut centrality = vec![0.0; count];
+
+        for source in 0..count {
+            let mut order = vec![];
+            let mut predecessors = vec![vec![]; count];
+            let mut paths = vec![0.0; count];
+            let mut distances = vec![None; count];
+            let mut queue = VecDeque::from([source]);
+            paths[source] = 1.0;
+            distances[source] = Some(0);
+
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                let distance = distances[node].unwrap_or_default() + 1;
+
+                for next in &neighbours[node] {
+                    if distances[*next].is_none() {
+                        distances[*next] = Some(distance);
+                        queue.push_back(*next);
+                    }
+
+                    if distances[*next] == Some(distance) {
+                        paths[*next] += paths[node];
+                        predecessors[*next].push(node);
+                    }
+                }
+            }
+
+            let mut dependencies = vec![0.0; count];
+
+            while let Some(node) = order.pop() {
+                for predecessor in &predecessors[node] {
+                    dependencies[*predecessor] +=
+                        paths[*predecessor] / paths[node] * (1.0 + dependencies[node]);
+                }
+
+                if node != source {
+                    centrality[node] += dependencies[node];
+                }
+            }
+        }
+
+        self.zip(centrality)
+    }
+
+    fn zip<T, I: IntoIterator<Item = T>>(&self, values: I) -> Vec<(GraphIndex, T)> {
+        self.nodes.iter().copied().zip(values).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DbGraph;
+    use crate::storage::file_storage::FileStorage;
+    use crate::test_utilities::test_file::TestFile;
+
+    fn values<T>(result: Vec<(GraphIndex, T)>) -> Vec<T> {
+        result.into_iter().map(|(_, value)| value).collect()
+    }
+
+    #[test]
+    fn empty_graph() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let graph = DbGraph::new(&mut storage).unwrap();
+        let analytics = GraphAnalytics::new(&graph, &storage, None);
+
+        assert!(analytics.weakly_connected_components().is_empty());
+        assert!(analytics.strongly_connected_components().is_empty());
+        assert!(analytics.page_rank(0.85, 10).is_empty());
+        assert!(analytics.degree_centrality().is_empty());
+        assert!(analytics.betweenness_centrality().is_empty());
+    }
+
+    #[test]
+    fn connected_components() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+        let node5 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+        graph.insert_edge(&mut storage, node3, node2).unwrap();
+        graph.insert_edge(&mut storage, node5, node4).unwrap();
+
+        let analytics = GraphAnalytics::new(&graph, &storage, None);
+
+        assert_eq!(
+            analytics.weakly_connected_components(),
+            vec![(node1, 0), (node2, 0), (node3, 0), (node4, 1), (node5, 1)]
+        );
+        assert_eq!(
+            analytics.strongly_connected_components(),
+            vec![(node1, 0), (node2, 1), (node3, 1), (node4, 2), (node5, 3)]
+        );
+    }
+
+    #[test]
+    fn strongly_connected_cycle() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+        graph.insert_edge(&mut storage, node3, node1).unwrap();
+        graph.insert_edge(&mut storage, node3, node4).unwrap();
+        graph.insert_edge(&mut storage, node4, node4).unwrap();
+
+        let analytics = GraphAnalytics::new(&graph, &storage, None);
+
+        assert_eq!(
+            values(analytics.strongly_connected_components()),
+            vec![0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn page_rank() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node3).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+
+        let ranks = values(GraphAnalytics::new(&graph, &storage, None).page_rank(0.85, 50));
+
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!((ranks[0] - ranks[1]).abs() < 1e-9);
+        assert!(ranks[2] > ranks[0]);
+    }
+
+    #[test]
+    fn degree_centrality() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+        graph.insert_edge(&mut storage, node3, node3).unwrap();
+
+        let analytics = GraphAnalytics::new(&graph, &storage, None);
+
+        assert_eq!(values(analytics.degree_centrality()), vec![2, 3, 3]);
+    }
+
+    #[test]
+    fn betweenness_centrality() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node1, node3).unwrap();
+        graph.insert_edge(&mut storage, node2, node4).unwrap();
+        graph.insert_edge(&mut storage, node3, node4).unwrap();
+        graph.insert_edge(&mut storage, node2, node4).unwrap();
+
+        let analytics = GraphAnalytics::new(&graph, &storage, None);
+
+        assert_eq!(
+            values(analytics.betweenness_centrality()),
+            vec![0.0, 0.5, 0.5, 0.0]
+        );
+    }
+
+    #[test]
+    fn subgraph() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+
+        let analytics = GraphAnalytics::new(&graph, &storage, Some(&[node3, edge1, node1, node3]));
+
+        assert_eq!(
+            analytics.weakly_connected_components(),
+            vec![(node3, 0), (node1, 1)]
+        );
+        assert_eq!(analytics.degree_centrality(), vec![(node3, 0), (node1, 0)]);
+    }
+}
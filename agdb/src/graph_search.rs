@@ -1,20 +1,29 @@
 pub mod path_search;
 
 mod breadth_first_search;
+mod breadth_first_search_both;
 mod breadth_first_search_reverse;
 mod depth_first_search;
+mod depth_first_search_both;
 mod depth_first_search_reverse;
 mod element_search;
+mod paths_search;
 mod search_impl;
+mod topological_sort;
 
 use self::breadth_first_search::BreadthFirstSearch;
+use self::breadth_first_search_both::BreadthFirstSearchBoth;
 use self::breadth_first_search_reverse::BreadthFirstSearchReverse;
 use self::depth_first_search::DepthFirstSearch;
+use self::depth_first_search_both::DepthFirstSearchBoth;
 use self::depth_first_search_reverse::DepthFirstSearchReverse;
 use self::path_search::PathSearch;
 use self::path_search::PathSearchHandler;
+use self::paths_search::PathsSearch;
 use self::search_impl::SearchImpl;
+use self::topological_sort::TopologicalSort;
 use crate::DbError;
+use crate::SearchQueryDirection;
 use crate::StorageData;
 use crate::graph::GraphData;
 use crate::graph::GraphImpl;
@@ -77,6 +86,19 @@ where
         }
     }
 
+    pub fn breadth_first_search_both<Handler: SearchHandler>(
+        &self,
+        index: GraphIndex,
+        handler: Handler,
+    ) -> Result<Vec<GraphIndex>, DbError> {
+        if self.is_valid_index(index) {
+            SearchImpl::<'a, D, Data, BreadthFirstSearchBoth>::new(self.graph, self.storage, index)
+                .search(handler)
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn depth_first_search<Handler: SearchHandler>(
         &self,
         index: GraphIndex,
@@ -103,15 +125,98 @@ where
         }
     }
 
+    pub fn depth_first_search_both<Handler: SearchHandler>(
+        &self,
+        index: GraphIndex,
+        handler: Handler,
+    ) -> Result<Vec<GraphIndex>, DbError> {
+        if self.is_valid_index(index) {
+            SearchImpl::<'a, D, Data, DepthFirstSearchBoth>::new(self.graph, self.storage, index)
+                .search(handler)
+        } else {
+            Ok(vec![])
+        }
+    }
+
     pub fn path<Handler: PathSearchHandler>(
         &self,
         from: GraphIndex,
         to: GraphIndex,
+        direction: SearchQueryDirection,
+        handler: Handler,
+    ) -> Result<Vec<GraphIndex>, DbError> {
+        if from != to && self.is_valid_node(from) && self.is_valid_node(to) {
+            PathSearch::<D, Data, Handler>::new(
+                self.graph,
+                self.storage,
+                from,
+                to,
+                direction,
+                handler,
+            )?
+            .search()
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Finds up to `max_paths` (0 = all) simple paths of at most
+    /// `max_depth` edges (0 = unlimited) from the shortest.
+    pub fn paths<Handler: PathSearchHandler>(
+        &self,
+        from: GraphIndex,
+        to: GraphIndex,
+        direction: SearchQueryDirection,
         handler: Handler,
+        max_paths: u64,
+        max_depth: u64,
+    ) -> Result<Vec<Vec<(GraphIndex, f64)>>, DbError> {
+        if from != to && self.is_valid_node(from) && self.is_valid_node(to) {
+            PathsSearch::<D, Data, Handler>::new(
+                self.graph,
+                self.storage,
+                from,
+                to,
+                direction,
+                handler,
+            )?
+            .search(max_paths, max_depth)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Orders the nodes reachable from `index` so that following
+    /// any edge in the `direction` leads to a later node.
+    pub fn topological_sort(
+        &self,
+        index: GraphIndex,
+        direction: SearchQueryDirection,
     ) -> Result<Vec<GraphIndex>, DbError> {
+        if self.is_valid_node(index) {
+            TopologicalSort::new(self.graph, self.storage, direction).sort(index)
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    pub fn weighted_path<Handler: PathSearchHandler>(
+        &self,
+        from: GraphIndex,
+        to: GraphIndex,
+        direction: SearchQueryDirection,
+        handler: Handler,
+    ) -> Result<Vec<(GraphIndex, f64)>, DbError> {
         if from != to && self.is_valid_node(from) && self.is_valid_node(to) {
-            PathSearch::<D, Data, Handler>::new(self.graph, self.storage, from, to, handler)
-                .search()
+            PathSearch::<D, Data, Handler>::new(
+                self.graph,
+                self.storage,
+                from,
+                to,
+                direction,
+                handler,
+            )?
+            .weighted_search()
         } else {
             Ok(vec![])
         }
@@ -0,0 +1,160 @@
+use super::search_impl::SearchIndex;
+use super::search_impl::SearchIterator;
+use crate::StorageData;
+use crate::graph::GraphData;
+use crate::graph::GraphImpl;
+use crate::graph::GraphIndex;
+use crate::storage::Storage;
+
+pub struct DepthFirstSearchBoth {
+    stack: Vec<SearchIndex>,
+}
+
+impl<D> SearchIterator<D> for DepthFirstSearchBoth
+where
+    D: StorageData,
+{
+    fn new(index: GraphIndex) -> Self {
+        Self {
+            stack: vec![SearchIndex { index, distance: 0 }],
+        }
+    }
+
+    fn expand<Data: GraphData<D>>(
+        &mut self,
+        current_index: SearchIndex,
+        graph: &GraphImpl<D, Data>,
+        storage: &Storage<D>,
+        follow: bool,
+    ) {
+        if !follow {
+            return;
+        }
+
+        let distance = current_index.distance + 1;
+
+        if current_index.index.is_node() {
+            if let Some(node) = graph.node(storage, current_index.index) {
+                let edges = node
+                    .edge_iter_from()
+                    .chain(node.edge_iter_to())
+                    .map(|edge| edge.index())
+                    .collect::<Vec<GraphIndex>>();
+
+                for index in edges.into_iter().rev() {
+                    self.stack.push(SearchIndex { index, distance });
+                }
+            }
+        } else {
+            // One of the ends is the element the edge was reached from
+            // and is skipped as already visited.
+            for index in [
+                graph.edge_from(storage, current_index.index),
+                graph.edge_to(storage, current_index.index),
+            ] {
+                self.stack.push(SearchIndex { index, distance });
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<SearchIndex> {
+        self.stack.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SearchControl;
+    use super::super::SearchHandler;
+    use super::*;
+    use crate::DbError;
+    use crate::graph::DbGraph;
+    use crate::graph::GraphIndex;
+    use crate::graph_search::GraphSearch;
+    use crate::storage::file_storage::FileStorage;
+    use crate::test_utilities::test_file::TestFile;
+
+    struct Handler {
+        pub processor: fn(GraphIndex, u64) -> SearchControl,
+    }
+
+    impl Default for Handler {
+        fn default() -> Self {
+            Self {
+                processor: |_index: GraphIndex, _distance: u64| SearchControl::Continue(true),
+            }
+        }
+    }
+
+    impl SearchHandler for Handler {
+        fn process(&mut self, index: GraphIndex, distance: u64) -> Result<SearchControl, DbError> {
+            Ok((self.processor)(index, distance))
+        }
+    }
+
+    #[test]
+    fn empty_graph_both() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let graph = DbGraph::new(&mut storage).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .depth_first_search_both(GraphIndex::default(), Handler::default());
+
+        assert_eq!(result, Ok(vec![]));
+    }
+
+    #[test]
+    fn search_both() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let edge2 = graph.insert_edge(&mut storage, node3, node2).unwrap();
+        let edge3 = graph.insert_edge(&mut storage, node2, node4).unwrap();
+        let edge4 = graph.insert_edge(&mut storage, node4, node4).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .depth_first_search_both(node2, Handler::default());
+
+        assert_eq!(
+            result,
+            Ok(vec![node2, edge3, node4, edge4, edge2, node3, edge1, node1])
+        );
+    }
+
+    #[test]
+    fn stop_at_distance() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let edge2 = graph.insert_edge(&mut storage, node3, node1).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage)).depth_first_search_both(
+            node1,
+            Handler {
+                processor: |_index: GraphIndex, distance: u64| {
+                    if distance == 2 {
+                        SearchControl::Stop(true)
+                    } else {
+                        SearchControl::Continue(true)
+                    }
+                },
+            },
+        );
+
+        assert_eq!(result, Ok(vec![node1, edge1, node2, edge2, node3]));
+    }
+}
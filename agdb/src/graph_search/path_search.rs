@@ -1,4 +1,5 @@
 use crate::DbError;
+use crate::SearchQueryDirection;
 use crate::StorageData;
 use crate::collections::bit_set::BitSet;
 use crate::graph::GraphData;
@@ -9,12 +10,47 @@ use std::cmp::Ordering;
 
 pub trait PathSearchHandler {
     fn process(&self, index: GraphIndex, distance: u64) -> Result<(u64, bool), DbError>;
+
+    /// Returns the weight of the element that is added to the total
+    /// weight of the path. Paths with lower total weight are preferred.
+    fn weight(&self, _index: GraphIndex) -> Result<f64, DbError> {
+        Ok(0.0)
+    }
+}
+
+/// Returns the edges of the node at `index` paired with the nodes
+/// they lead to when followed in the `direction`.
+pub(crate) fn path_edges<D, Data>(
+    graph: &GraphImpl<D, Data>,
+    storage: &Storage<D>,
+    index: GraphIndex,
+    direction: SearchQueryDirection,
+) -> Vec<(GraphIndex, GraphIndex)>
+where
+    Data: GraphData<D>,
+    D: StorageData,
+{
+    let node = graph
+        .node(storage, index)
+        .expect("unexpected invalid node index");
+    let mut edges = vec![];
+
+    if direction != SearchQueryDirection::Reverse {
+        edges.extend(node.edge_iter_from().map(|e| (e.index(), e.index_to())));
+    }
+
+    if direction != SearchQueryDirection::Forward {
+        edges.extend(node.edge_iter_to().map(|e| (e.index(), e.index_from())));
+    }
+
+    edges
 }
 
 #[derive(Clone)]
 struct Path {
-    elements: Vec<(GraphIndex, bool)>,
+    elements: Vec<(GraphIndex, bool, f64)>,
     cost: u64,
+    weight: f64,
 }
 
 pub struct PathSearch<'a, D, Data, Handler>
@@ -25,11 +61,12 @@ where
 {
     current_path: Path,
     destination: GraphIndex,
+    direction: SearchQueryDirection,
     graph: &'a GraphImpl<D, Data>,
     storage: &'a Storage<D>,
     handler: Handler,
     paths: Vec<Path>,
-    result: Vec<(GraphIndex, bool)>,
+    result: Vec<(GraphIndex, bool, f64)>,
     visited: BitSet,
 }
 
@@ -44,35 +81,56 @@ where
         storage: &'a Storage<D>,
         from: GraphIndex,
         to: GraphIndex,
+        direction: SearchQueryDirection,
         handler: Handler,
-    ) -> Self {
+    ) -> Result<Self, DbError> {
         let add = handler.process(from, 0).unwrap_or_default();
+        let weight = handler.weight(from)?;
 
-        Self {
+        Ok(Self {
             current_path: Path {
                 elements: vec![],
                 cost: 0,
+                weight: 0.0,
             },
             destination: to,
+            direction,
             graph,
             storage,
             handler,
             paths: vec![Path {
-                elements: vec![(from, add.1)],
+                elements: vec![(from, add.1, weight)],
                 cost: 0,
+                weight,
             }],
             result: vec![],
             visited: BitSet::new(),
-        }
+        })
     }
 
     pub fn search(&mut self) -> Result<Vec<GraphIndex>, DbError> {
+        Ok(self
+            .weighted_search()?
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect())
+    }
+
+    /// Returns the elements of the path with the lowest total weight
+    /// each paired with the weight of the path up to and including
+    /// that element.
+    pub fn weighted_search(&mut self) -> Result<Vec<(GraphIndex, f64)>, DbError> {
         while !self.is_finished() {
             self.sort_paths();
             self.process_last_path()?;
         }
 
-        Ok(self.result.iter().filter(|e| e.1).map(|e| e.0).collect())
+        Ok(self
+            .result
+            .iter()
+            .filter(|e| e.1)
+            .map(|e| (e.0, e.2))
+            .collect())
     }
 
     fn expand_edge(
@@ -86,7 +144,8 @@ where
             .process(index, self.current_path.elements.len() as u64 + 1)?;
 
         if cost.0 != 0 && !self.visited.value(node_index.as_u64()) {
-            path.elements.push((index, cost.1));
+            path.weight += self.handler.weight(index)?;
+            path.elements.push((index, cost.1, path.weight));
             path.cost += cost.0;
             self.expand_node(path, node_index)?;
         }
@@ -100,7 +159,8 @@ where
             .process(index, self.current_path.elements.len() as u64 + 1)?;
 
         if cost.0 != 0 {
-            path.elements.push((index, cost.1));
+            path.weight += self.handler.weight(index)?;
+            path.elements.push((index, cost.1, path.weight));
             path.cost += cost.0;
             self.paths.push(path);
         }
@@ -109,12 +169,8 @@ where
     }
 
     fn expand(&mut self, index: GraphIndex) -> Result<(), DbError> {
-        let node = self
-            .graph
-            .node(self.storage, index)
-            .expect("unexpected invalid node index");
-        for edge in node.edge_iter_from() {
-            self.expand_edge(self.current_path.clone(), edge.index(), edge.index_to())?;
+        for (edge, node) in path_edges(self.graph, self.storage, index, self.direction) {
+            self.expand_edge(self.current_path.clone(), edge, node)?;
         }
 
         Ok(())
@@ -150,13 +206,17 @@ where
         self.current_path = self.paths.pop().unwrap_or(Path {
             elements: vec![],
             cost: 0,
+            weight: 0.0,
         });
         self.process_path()
     }
 
     fn sort_paths(&mut self) {
         self.paths.sort_by(|left, right| {
-            let ordering = left.cost.cmp(&right.cost);
+            let ordering = left
+                .weight
+                .total_cmp(&right.weight)
+                .then_with(|| left.cost.cmp(&right.cost));
 
             if ordering == Ordering::Equal {
                 return left.elements.len().cmp(&right.elements.len()).reverse();
@@ -193,6 +253,20 @@ mod tests {
         }
     }
 
+    struct WeightedHandler {
+        pub weigher: fn(GraphIndex) -> f64,
+    }
+
+    impl PathSearchHandler for WeightedHandler {
+        fn process(&self, _index: GraphIndex, _distance: u64) -> Result<(u64, bool), DbError> {
+            Ok((1, true))
+        }
+
+        fn weight(&self, index: GraphIndex) -> Result<f64, DbError> {
+            Ok((self.weigher)(index))
+        }
+    }
+
     #[test]
     fn circular_path() {
         let test_file = TestFile::new();
@@ -201,7 +275,12 @@ mod tests {
         let node = graph.insert_node(&mut storage).unwrap();
         let _edge = graph.insert_edge(&mut storage, node, node).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node, node, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node,
+            node,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![]));
     }
@@ -215,6 +294,7 @@ mod tests {
         let result = GraphSearch::from((&graph, &storage)).path(
             GraphIndex::default(),
             GraphIndex::default(),
+            SearchQueryDirection::Forward,
             Handler::default(),
         );
 
@@ -237,7 +317,12 @@ mod tests {
         let edge3 = graph.insert_edge(&mut storage, node2, node3).unwrap();
         let _edge4 = graph.insert_edge(&mut storage, node2, node3).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node1, node3, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node1,
+            node3,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![node1, edge1, node2, edge3, node3]));
     }
@@ -249,7 +334,12 @@ mod tests {
         let mut graph = DbGraph::new(&mut storage).unwrap();
         let node = graph.insert_node(&mut storage).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node, node, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node,
+            node,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![]));
     }
@@ -268,11 +358,55 @@ mod tests {
         let _edge2 = graph.insert_edge(&mut storage, node1, node2).unwrap();
         let _edge3 = graph.insert_edge(&mut storage, node2, node3).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node1, node3, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node1,
+            node3,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![node1, edge1, node3]));
     }
 
+    #[test]
+    fn reverse_and_both_directions() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let edge2 = graph.insert_edge(&mut storage, node3, node2).unwrap();
+
+        let search = GraphSearch::from((&graph, &storage));
+
+        assert_eq!(
+            search.path(
+                node1,
+                node3,
+                SearchQueryDirection::Forward,
+                Handler::default()
+            ),
+            Ok(vec![])
+        );
+        assert_eq!(
+            search.path(
+                node2,
+                node1,
+                SearchQueryDirection::Reverse,
+                Handler::default()
+            ),
+            Ok(vec![node2, edge1, node1])
+        );
+        assert_eq!(
+            search.path(node1, node3, SearchQueryDirection::Both, Handler::default()),
+            Ok(vec![node1, edge1, node2, edge2, node3])
+        );
+    }
+
     #[test]
     fn single_path() {
         let test_file = TestFile::new();
@@ -286,7 +420,12 @@ mod tests {
         let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
         let edge2 = graph.insert_edge(&mut storage, node2, node3).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node1, node3, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node1,
+            node3,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![node1, edge1, node2, edge2, node3]));
     }
@@ -308,6 +447,7 @@ mod tests {
         let result = GraphSearch::from((&graph, &storage)).path(
             node1,
             node3,
+            SearchQueryDirection::Forward,
             Handler {
                 processor: |index: GraphIndex, _distance: u64| {
                     if index.0 == -4 {
@@ -334,7 +474,12 @@ mod tests {
 
         let _edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
 
-        let result = GraphSearch::from((&graph, &storage)).path(node1, node3, Handler::default());
+        let result = GraphSearch::from((&graph, &storage)).path(
+            node1,
+            node3,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+        );
 
         assert_eq!(result, Ok(vec![]));
     }
@@ -356,6 +501,7 @@ mod tests {
         let result = GraphSearch::from((&graph, &storage)).path(
             node1,
             node3,
+            SearchQueryDirection::Forward,
             Handler {
                 processor: |index: GraphIndex, _distance: u64| (1, index.is_node()),
             },
@@ -381,6 +527,7 @@ mod tests {
         let result = GraphSearch::from((&graph, &storage)).path(
             node1,
             node3,
+            SearchQueryDirection::Forward,
             Handler {
                 processor: |index: GraphIndex, _distance: u64| (1, index.is_edge()),
             },
@@ -388,4 +535,39 @@ mod tests {
 
         assert_eq!(result, Ok(vec![edge1, edge3]));
     }
+
+    #[test]
+    fn weighted_path() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let _edge1 = graph.insert_edge(&mut storage, node1, node3).unwrap();
+        let edge2 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let edge3 = graph.insert_edge(&mut storage, node2, node3).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage)).weighted_path(
+            node1,
+            node3,
+            SearchQueryDirection::Forward,
+            WeightedHandler {
+                weigher: |index: GraphIndex| if index.0 == -4 { 5.0 } else { 1.0 },
+            },
+        );
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                (node1, 1.0),
+                (edge2, 2.0),
+                (node2, 3.0),
+                (edge3, 4.0),
+                (node3, 5.0)
+            ])
+        );
+    }
 }
@@ -0,0 +1,384 @@
+use crate::DbError;
+use crate::SearchQueryDirection;
+use crate::StorageData;
+use crate::graph::GraphData;
+use crate::graph::GraphImpl;
+use crate::graph::GraphIndex;
+use crate::graph_search::path_search::PathSearchHandler;
+use crate::graph_search::path_search::path_edges;
+use crate::storage::Storage;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+struct Path {
+    elements: Vec<(GraphIndex, bool, f64)>,
+    cost: u64,
+    weight: f64,
+    depth: u64,
+    sequence: u64,
+}
+
+/// Search of multiple simple paths (not visiting any node twice)
+/// between two nodes. The paths are found in the order of their
+/// total weight (and cost) so the first `max_paths` found are the
+/// shortest ones.
+pub struct PathsSearch<'a, D, Data, Handler>
+where
+    Data: GraphData<D>,
+    D: StorageData,
+    Handler: PathSearchHandler,
+{
+    destination: GraphIndex,
+    direction: SearchQueryDirection,
+    graph: &'a GraphImpl<D, Data>,
+    storage: &'a Storage<D>,
+    handler: Handler,
+    paths: BinaryHeap<Path>,
+    result: Vec<Vec<(GraphIndex, f64)>>,
+    sequence: u64,
+}
+
+impl<'a, D, Data, Handler> PathsSearch<'a, D, Data, Handler>
+where
+    Data: GraphData<D>,
+    D: StorageData,
+    Handler: PathSearchHandler,
+{
+    pub fn new(
+        graph: &'a GraphImpl<D, Data>,
+        storage: &'a Storage<D>,
+        from: GraphIndex,
+        to: GraphIndex,
+        direction: SearchQueryDirection,
+        handler: Handler,
+    ) -> Result<Self, DbError> {
+        let add = handler.process(from, 0).unwrap_or_default();
+        let weight = handler.weight(from)?;
+        let mut paths = BinaryHeap::new();
+        paths.push(Path {
+            elements: vec![(from, add.1, weight)],
+            cost: 0,
+            weight,
+            depth: 0,
+            sequence: 0,
+        });
+
+        Ok(Self {
+            destination: to,
+            direction,
+            graph,
+            storage,
+            handler,
+            paths,
+            result: vec![],
+            sequence: 0,
+        })
+    }
+
+    /// Returns at most `max_paths` paths (0 = all) of at most
+    /// `max_depth` edges (0 = unlimited) each as a list of elements
+    /// paired with the weight of the path up to and including the element.
+    pub fn search(
+        &mut self,
+        max_paths: u64,
+        max_depth: u64,
+    ) -> Result<Vec<Vec<(GraphIndex, f64)>>, DbError> {
+        while let Some(path) = self.paths.pop() {
+            let index = path.elements.last().map_or(GraphIndex::default(), |e| e.0);
+
+            if index == self.destination {
+                self.result.push(
+                    path.elements
+                        .iter()
+                        .filter(|e| e.1)
+                        .map(|e| (e.0, e.2))
+                        .collect(),
+                );
+
+                if self.result.len() as u64 == max_paths {
+                    break;
+                }
+            } else if max_depth == 0 || path.depth < max_depth {
+                self.expand(&path, index)?;
+            }
+        }
+
+        Ok(std::mem::take(&mut self.result))
+    }
+
+    fn expand(&mut self, path: &Path, index: GraphIndex) -> Result<(), DbError> {
+        for (edge, node_index) in path_edges(self.graph, self.storage, index, self.direction) {
+            if !path.elements.iter().any(|e| e.0 == node_index) {
+                self.expand_edge(path, edge, node_index)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expand_edge(
+        &mut self,
+        path: &Path,
+        index: GraphIndex,
+        node_index: GraphIndex,
+    ) -> Result<(), DbError> {
+        let distance = path.elements.len() as u64;
+        let edge_cost = self.handler.process(index, distance)?;
+
+        if edge_cost.0 == 0 {
+            return Ok(());
+        }
+
+        let node_cost = self.handler.process(node_index, distance + 1)?;
+
+        if node_cost.0 == 0 {
+            return Ok(());
+        }
+
+        let edge_weight = path.weight + self.handler.weight(index)?;
+        let node_weight = edge_weight + self.handler.weight(node_index)?;
+        let mut elements = Vec::with_capacity(path.elements.len() + 2);
+        elements.extend_from_slice(&path.elements);
+        elements.push((index, edge_cost.1, edge_weight));
+        elements.push((node_index, node_cost.1, node_weight));
+
+        self.sequence += 1;
+        self.paths.push(Path {
+            elements,
+            cost: path.cost + edge_cost.0 + node_cost.0,
+            weight: node_weight,
+            depth: path.depth + 1,
+            sequence: self.sequence,
+        });
+
+        Ok(())
+    }
+}
+
+impl PartialEq for Path {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Path {}
+
+impl PartialOrd for Path {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Path {
+    /// The "greatest" path is the one with the lowest weight, then
+    /// cost, then with the most elements and finally the one found
+    /// first so that it is the first to be popped off the `BinaryHeap`.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.weight
+            .total_cmp(&other.weight)
+            .then_with(|| self.cost.cmp(&other.cost))
+            .reverse()
+            .then_with(|| self.elements.len().cmp(&other.elements.len()))
+            .then_with(|| self.sequence.cmp(&other.sequence).reverse())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DbGraph;
+    use crate::graph_search::GraphSearch;
+    use crate::storage::file_storage::FileStorage;
+    use crate::test_utilities::test_file::TestFile;
+
+    struct Handler {
+        pub processor: fn(GraphIndex, u64) -> (u64, bool),
+    }
+
+    impl Default for Handler {
+        fn default() -> Self {
+            Self {
+                processor: |_index: GraphIndex, _distance: u64| (1_u64, true),
+            }
+        }
+    }
+
+    impl PathSearchHandler for Handler {
+        fn process(&self, index: GraphIndex, distance: u64) -> Result<(u64, bool), DbError> {
+            Ok((self.processor)(index, distance))
+        }
+    }
+
+    fn ids(paths: Vec<Vec<(GraphIndex, f64)>>) -> Vec<Vec<i64>> {
+        paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|(index, _)| index.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn all_paths() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let edge2 = graph.insert_edge(&mut storage, node2, node4).unwrap();
+        let edge3 = graph.insert_edge(&mut storage, node1, node3).unwrap();
+        let edge4 = graph.insert_edge(&mut storage, node3, node2).unwrap();
+        let _edge5 = graph.insert_edge(&mut storage, node2, node1).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .paths(
+                node1,
+                node4,
+                SearchQueryDirection::Forward,
+                Handler::default(),
+                0,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(
+            ids(result),
+            vec![
+                vec![node1.0, edge1.0, node2.0, edge2.0, node4.0],
+                vec![
+                    node1.0, edge3.0, node3.0, edge4.0, node2.0, edge2.0, node4.0
+                ]
+            ]
+        );
+    }
+
+    #[test]
+    fn max_depth() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let _edge2 = graph.insert_edge(&mut storage, node2, node3).unwrap();
+        let edge3 = graph.insert_edge(&mut storage, node1, node3).unwrap();
+        let edge4 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .paths(
+                node1,
+                node3,
+                SearchQueryDirection::Forward,
+                Handler::default(),
+                0,
+                1,
+            )
+            .unwrap();
+        assert_eq!(ids(result), vec![vec![node1.0, edge3.0, node3.0]]);
+
+        let result = GraphSearch::from((&graph, &storage))
+            .paths(
+                node1,
+                node2,
+                SearchQueryDirection::Forward,
+                Handler::default(),
+                0,
+                1,
+            )
+            .unwrap();
+        assert_eq!(
+            ids(result),
+            vec![
+                vec![node1.0, edge4.0, node2.0],
+                vec![node1.0, edge1.0, node2.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn max_paths() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let _edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let _edge2 = graph.insert_edge(&mut storage, node2, node3).unwrap();
+        let edge3 = graph.insert_edge(&mut storage, node1, node3).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .paths(
+                node1,
+                node3,
+                SearchQueryDirection::Forward,
+                Handler::default(),
+                1,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(ids(result), vec![vec![node1.0, edge3.0, node3.0]]);
+    }
+
+    #[test]
+    fn filtered_and_stopped() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        let _edge1 = graph.insert_edge(&mut storage, node1, node2).unwrap();
+        let _edge2 = graph.insert_edge(&mut storage, node2, node3).unwrap();
+        let _edge3 = graph.insert_edge(&mut storage, node1, node3).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage))
+            .paths(
+                node1,
+                node3,
+                SearchQueryDirection::Forward,
+                Handler {
+                    processor: |index: GraphIndex, _distance: u64| match index.0 {
+                        -6 => (0, true),
+                        _ => (1, index.is_node()),
+                    },
+                },
+                0,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(ids(result), vec![vec![node1.0, node2.0, node3.0]]);
+    }
+
+    #[test]
+    fn same_node() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+        let node = graph.insert_node(&mut storage).unwrap();
+        let _edge = graph.insert_edge(&mut storage, node, node).unwrap();
+
+        let result = GraphSearch::from((&graph, &storage)).paths(
+            node,
+            node,
+            SearchQueryDirection::Forward,
+            Handler::default(),
+            0,
+            0,
+        );
+
+        assert_eq!(result, Ok(vec![]));
+    }
+}
@@ -0,0 +1,199 @@
+use super::path_search::path_edges;
+use crate::DbError;
+use crate::DbErrorType;
+use crate::SearchQueryDirection;
+use crate::StorageData;
+use crate::collections::bit_set::BitSet;
+use crate::graph::GraphData;
+use crate::graph::GraphImpl;
+use crate::graph::GraphIndex;
+use crate::storage::Storage;
+
+struct Frame {
+    index: GraphIndex,
+    edges: Vec<(GraphIndex, GraphIndex)>,
+    next: usize,
+}
+
+pub struct TopologicalSort<'a, D, Data>
+where
+    Data: GraphData<D>,
+    D: StorageData,
+{
+    direction: SearchQueryDirection,
+    graph: &'a GraphImpl<D, Data>,
+    storage: &'a Storage<D>,
+    finished: BitSet,
+    on_stack: BitSet,
+    stack: Vec<Frame>,
+    order: Vec<GraphIndex>,
+}
+
+impl<'a, D, Data> TopologicalSort<'a, D, Data>
+where
+    Data: GraphData<D>,
+    D: StorageData,
+{
+    pub fn new(
+        graph: &'a GraphImpl<D, Data>,
+        storage: &'a Storage<D>,
+        direction: SearchQueryDirection,
+    ) -> Self {
+        Self {
+            direction,
+            graph,
+            storage,
+            finished: BitSet::new(),
+            on_stack: BitSet::new(),
+            stack: vec![],
+            order: vec![],
+        }
+    }
+
+    /// Orders the nodes reachable from the `index` so that every edge
+    /// followed in the `direction` leads to a later node. Fails if
+    /// the reachable part of the graph contains a cycle listing the
+    /// elements of the first cycle found.
+    pub fn sort(mut self, index: GraphIndex) -> Result<Vec<GraphIndex>, DbError> {
+        self.push(index);
+
+        while let Some(frame) = self.stack.last_mut() {
+            if let Some((_, next)) = frame.edges.get(frame.next).copied() {
+                frame.next += 1;
+
+                if self.on_stack.value(next.as_u64()) {
+                    return Err(self.cycle(next));
+                }
+
+                if !self.finished.value(next.as_u64()) {
+                    self.push(next);
+                }
+            } else {
+                let index = frame.index;
+                self.stack.pop();
+                self.on_stack.unset(index.as_u64());
+                self.finished.set(index.as_u64());
+                self.order.push(index);
+            }
+        }
+
+        self.order.reverse();
+
+        Ok(self.order)
+    }
+
+    fn push(&mut self, index: GraphIndex) {
+        self.on_stack.set(index.as_u64());
+        self.stack.push(Frame {
+            index,
+            edges: path_edges(self.graph, self.storage, index, self.direction),
+            next: 0,
+        });
+    }
+
+    fn cycle(&self, start: GraphIndex) -> DbError {
+        let mut cycle = vec![];
+
+        for frame in self.stack.iter().skip_while(|frame| frame.index != start) {
+            cycle.push(frame.index.0.to_string());
+            cycle.push(frame.edges[frame.next - 1].0.0.to_string());
+        }
+
+        cycle.push(start.0.to_string());
+
+        DbError::graph(
+            DbErrorType::Cycle,
+            format!("Cycle detected: {}", cycle.join(" -> ")),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DbGraph;
+    use crate::storage::file_storage::FileStorage;
+    use crate::test_utilities::test_file::TestFile;
+
+    #[test]
+    fn single_node() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+        let node = graph.insert_node(&mut storage).unwrap();
+
+        let result = TopologicalSort::new(&graph, &storage, SearchQueryDirection::Forward)
+            .sort(node)
+            .unwrap();
+
+        assert_eq!(result, vec![node]);
+    }
+
+    #[test]
+    fn diamond() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+        let node4 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node1, node3).unwrap();
+        graph.insert_edge(&mut storage, node2, node4).unwrap();
+        graph.insert_edge(&mut storage, node3, node4).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+
+        let result = TopologicalSort::new(&graph, &storage, SearchQueryDirection::Forward)
+            .sort(node1)
+            .unwrap();
+
+        assert_eq!(result, vec![node1, node2, node3, node4]);
+
+        let result = TopologicalSort::new(&graph, &storage, SearchQueryDirection::Reverse)
+            .sort(node4)
+            .unwrap();
+
+        assert_eq!(result, vec![node4, node3, node2, node1]);
+    }
+
+    #[test]
+    fn cycle() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        let node2 = graph.insert_node(&mut storage).unwrap();
+        let node3 = graph.insert_node(&mut storage).unwrap();
+
+        graph.insert_edge(&mut storage, node1, node2).unwrap();
+        graph.insert_edge(&mut storage, node2, node3).unwrap();
+        graph.insert_edge(&mut storage, node3, node2).unwrap();
+
+        let error = TopologicalSort::new(&graph, &storage, SearchQueryDirection::Forward)
+            .sort(node1)
+            .unwrap_err();
+
+        assert_eq!(error.ty, DbErrorType::Cycle);
+        assert_eq!(error.description, "Cycle detected: 2 -> -5 -> 3 -> -6 -> 2");
+    }
+
+    #[test]
+    fn self_loop() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let mut graph = DbGraph::new(&mut storage).unwrap();
+
+        let node1 = graph.insert_node(&mut storage).unwrap();
+        graph.insert_edge(&mut storage, node1, node1).unwrap();
+
+        let error = TopologicalSort::new(&graph, &storage, SearchQueryDirection::Forward)
+            .sort(node1)
+            .unwrap_err();
+
+        assert_eq!(error.description, "Cycle detected: 1 -> -2 -> 1");
+    }
+}
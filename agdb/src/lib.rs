@@ -25,9 +25,11 @@ mod collections;
 mod command;
 mod db;
 mod graph;
+mod graph_analytics;
 mod graph_search;
 mod query;
 mod query_builder;
+mod snapshot;
 mod storage;
 mod transaction;
 mod transaction_mut;
@@ -38,6 +40,10 @@ pub mod test_utilities;
 
 #[cfg(any(feature = "serde", feature = "openapi"))]
 pub use query::QueryType;
+#[cfg(any(feature = "serde", feature = "openapi"))]
+pub use query::query_text::parse_queries;
+#[cfg(any(feature = "serde", feature = "openapi"))]
+pub use query::query_text::parse_query;
 
 #[cfg(feature = "derive")]
 pub use agdb_derive::{DbElement, DbSerialize, DbType, DbTypeMarker, DbValue};
@@ -55,9 +61,13 @@ pub use {
     query::query_aliases::QueryAliases,
     query::query_values::MultiValues,
     query::query_values::SingleValues,
+    query_builder::expect::Expect,
+    query_builder::expect::ExpectIds,
     query_builder::insert::Insert,
     query_builder::insert_aliases::InsertAliases,
     query_builder::insert_aliases::InsertAliasesIds,
+    query_builder::insert_analytics::InsertAnalytics,
+    query_builder::insert_analytics::InsertAnalyticsIds,
     query_builder::insert_edge::InsertEdges,
     query_builder::insert_edge::InsertEdgesEach,
     query_builder::insert_edge::InsertEdgesFrom,
@@ -65,13 +75,20 @@ pub use {
     query_builder::insert_edge::InsertEdgesIds,
     query_builder::insert_edge::InsertEdgesValues,
     query_builder::insert_index::InsertIndex,
+    query_builder::insert_index::InsertIndexOrdered,
+    query_builder::insert_index::InsertIndexUnique,
     query_builder::insert_nodes::InsertNodes,
     query_builder::insert_nodes::InsertNodesAliases,
     query_builder::insert_nodes::InsertNodesCount,
     query_builder::insert_nodes::InsertNodesIds,
     query_builder::insert_nodes::InsertNodesValues,
+    query_builder::insert_update::InsertUpdate,
+    query_builder::insert_update::InsertUpdateIds,
     query_builder::insert_values::InsertValues,
     query_builder::insert_values::InsertValuesIds,
+    query_builder::match_::Match,
+    query_builder::match_::MatchEdge,
+    query_builder::match_::MatchNode,
     query_builder::remove::Remove,
     query_builder::remove_aliases::RemoveAliases,
     query_builder::remove_ids::RemoveIds,
@@ -84,6 +101,8 @@ pub use {
     query_builder::search::SearchIndex as SearchIndexBuilder,
     query_builder::search::SearchIndexValue,
     query_builder::search::SearchOrderBy,
+    query_builder::search::SearchPaths,
+    query_builder::search::SearchPathsFrom,
     query_builder::search::SearchQueryBuilder,
     query_builder::search::SearchQueryBuilderDef,
     query_builder::search::SearchTo,
@@ -92,6 +111,10 @@ pub use {
     query_builder::select::Select,
     query_builder::select_aliases::SelectAliases,
     query_builder::select_aliases::SelectAliasesIds,
+    query_builder::select_aggregate::SelectAggregate,
+    query_builder::select_aggregate::SelectAggregateIds,
+    query_builder::select_analytics::SelectAnalytics,
+    query_builder::select_analytics::SelectAnalyticsIds,
     query_builder::select_edge_count::SelectEdgeCount,
     query_builder::select_edge_count::SelectEdgeCountIds,
     query_builder::select_ids::SelectIds,
@@ -110,15 +133,19 @@ pub use {
 
 pub use db::Db;
 pub use db::DbAny;
+pub use db::DbAnySnapshot;
 pub use db::DbAnyTransaction;
 pub use db::DbAnyTransactionMut;
 pub use db::DbFile;
+pub use db::DbFileSnapshot;
 pub use db::DbFileTransaction;
 pub use db::DbFileTransactionMut;
 pub use db::DbImpl;
 pub use db::DbMemory;
+pub use db::DbMemorySnapshot;
 pub use db::DbMemoryTransaction;
 pub use db::DbMemoryTransactionMut;
+pub use db::DbSnapshot;
 pub use db::DbTransaction;
 pub use db::DbTransactionMut;
 pub use db::db_element::DbElement;
@@ -130,16 +157,30 @@ pub use db::db_id::DbId;
 pub use db::db_key_order::DbKeyOrder;
 pub use db::db_key_order::DbKeyOrders;
 pub use db::db_key_value::DbKeyValue;
+pub use db::db_salvage::DbSalvageReport;
 pub use db::db_type::DbType;
 pub use db::db_type::DbTypeMarker;
 pub use db::db_value::DbValue;
+pub use db::db_verify::DbIssue;
+pub use db::db_verify::DbIssueType;
+pub use db::db_verify::DbVerifyReport;
 pub use query::Query;
 pub use query::QueryMut;
+pub use query::aggregate_query::AggregateFunction;
+pub use query::aggregate_query::AggregateFunctions;
+pub use query::aggregate_query::AggregateQuery;
+pub use query::expect_query::ExpectQuery;
 pub use query::insert_aliases_query::InsertAliasesQuery;
+pub use query::insert_analytics_query::InsertAnalyticsQuery;
 pub use query::insert_edges_query::InsertEdgesQuery;
 pub use query::insert_index_query::InsertIndexQuery;
 pub use query::insert_nodes_query::InsertNodesQuery;
 pub use query::insert_values_query::InsertValuesQuery;
+pub use query::match_query::MatchQuery;
+pub use query::match_query::MatchStep;
+pub use query::match_query::MatchVariable;
+pub use query::query_condition::AdjacentCondition;
+pub use query::query_condition::AdjacentQuantifier;
 pub use query::query_condition::Comparison;
 pub use query::query_condition::CountComparison;
 pub use query::query_condition::KeyValueComparison;
@@ -157,18 +198,31 @@ pub use query::remove_query::RemoveQuery;
 pub use query::remove_values_query::RemoveValuesQuery;
 pub use query::search_query::SearchQuery;
 pub use query::search_query::SearchQueryAlgorithm;
+pub use query::search_query::SearchQueryDirection;
 pub use query::select_aliases_query::SelectAliasesQuery;
 pub use query::select_all_aliases_query::SelectAllAliasesQuery;
+pub use query::select_analytics_query::AnalyticsAlgorithm;
+pub use query::select_analytics_query::PageRankOptions;
+pub use query::select_analytics_query::SelectAnalyticsQuery;
 pub use query::select_edge_count_query::SelectEdgeCountQuery;
 pub use query::select_indexes_query::SelectIndexesQuery;
 pub use query::select_key_count_query::SelectKeyCountQuery;
 pub use query::select_keys_query::SelectKeysQuery;
 pub use query::select_node_count::SelectNodeCountQuery;
 pub use query::select_values_query::SelectValuesQuery;
+pub use query::update_values_query::KeyValueUpdate;
+pub use query::update_values_query::KeyValueUpdates;
+pub use query::update_values_query::UpdateValuesQuery;
+pub use query::update_values_query::ValueUpdate;
 pub use query_builder::QueryBuilder;
+pub use snapshot::Snapshot;
 pub use storage::StorageData;
 pub use storage::StorageSlice;
 pub use storage::any_storage::AnyStorage;
+#[cfg(feature = "encryption")]
+pub use storage::encrypted_storage::Encrypted;
+#[cfg(feature = "encryption")]
+pub use storage::encrypted_storage::EncryptionKey;
 pub use storage::file_storage::FileStorage;
 pub use storage::file_storage_memory_mapped::FileStorageMemoryMapped;
 pub use storage::memory_storage::MemoryStorage;
@@ -1,13 +1,19 @@
+pub mod aggregate_query;
+pub mod expect_query;
 pub mod insert_aliases_query;
+pub mod insert_analytics_query;
 pub mod insert_edges_query;
 pub mod insert_index_query;
 pub mod insert_nodes_query;
 pub mod insert_values_query;
+pub mod match_query;
 pub mod query_aliases;
 pub mod query_condition;
 pub mod query_id;
 pub mod query_ids;
 pub mod query_result;
+#[cfg(any(feature = "serde", feature = "openapi"))]
+pub mod query_text;
 pub mod query_values;
 pub mod remove_aliases_query;
 pub mod remove_index_query;
@@ -16,12 +22,16 @@ pub mod remove_values_query;
 pub mod search_query;
 pub mod select_aliases_query;
 pub mod select_all_aliases_query;
+pub mod select_analytics_query;
 pub mod select_edge_count_query;
 pub mod select_indexes_query;
 pub mod select_key_count_query;
 pub mod select_keys_query;
 pub mod select_node_count;
 pub mod select_values_query;
+pub mod update_values_query;
+
+mod search_cursor;
 
 use crate::DbError;
 use crate::DbImpl;
@@ -42,10 +52,12 @@ pub trait QueryMut {
 
 #[cfg(any(feature = "serde", feature = "openapi"))]
 use crate::{
-    InsertAliasesQuery, InsertEdgesQuery, InsertIndexQuery, InsertNodesQuery, InsertValuesQuery,
-    RemoveAliasesQuery, RemoveIndexQuery, RemoveQuery, RemoveValuesQuery, SearchQuery,
-    SelectAliasesQuery, SelectAllAliasesQuery, SelectEdgeCountQuery, SelectIndexesQuery,
+    AggregateQuery, ExpectQuery, InsertAliasesQuery, InsertAnalyticsQuery, InsertEdgesQuery,
+    InsertIndexQuery, InsertNodesQuery, InsertValuesQuery, MatchQuery, RemoveAliasesQuery,
+    RemoveIndexQuery, RemoveQuery, RemoveValuesQuery, SearchQuery, SelectAliasesQuery,
+    SelectAllAliasesQuery, SelectAnalyticsQuery, SelectEdgeCountQuery, SelectIndexesQuery,
     SelectKeyCountQuery, SelectKeysQuery, SelectNodeCountQuery, SelectValuesQuery,
+    UpdateValuesQuery,
 };
 
 /// Convenience enum for serializing/deserializing queries.
@@ -57,11 +69,15 @@ use crate::{
 #[derive(Clone, Debug, PartialEq)]
 #[expect(clippy::large_enum_variant)]
 pub enum QueryType {
+    Aggregate(AggregateQuery),
+    Expect(ExpectQuery),
     InsertAlias(InsertAliasesQuery),
+    InsertAnalytics(InsertAnalyticsQuery),
     InsertEdges(InsertEdgesQuery),
     InsertIndex(InsertIndexQuery),
     InsertNodes(InsertNodesQuery),
     InsertValues(InsertValuesQuery),
+    Match(MatchQuery),
     Remove(RemoveQuery),
     RemoveAliases(RemoveAliasesQuery),
     RemoveIndex(RemoveIndexQuery),
@@ -69,12 +85,28 @@ pub enum QueryType {
     Search(SearchQuery),
     SelectAliases(SelectAliasesQuery),
     SelectAllAliases(SelectAllAliasesQuery),
+    SelectAnalytics(SelectAnalyticsQuery),
     SelectEdgeCount(SelectEdgeCountQuery),
     SelectIndexes(SelectIndexesQuery),
     SelectKeys(SelectKeysQuery),
     SelectKeyCount(SelectKeyCountQuery),
     SelectNodeCount(SelectNodeCountQuery),
     SelectValues(SelectValuesQuery),
+    UpdateValues(UpdateValuesQuery),
+}
+
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<AggregateQuery> for QueryType {
+    fn from(value: AggregateQuery) -> Self {
+        QueryType::Aggregate(value)
+    }
+}
+
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<ExpectQuery> for QueryType {
+    fn from(value: ExpectQuery) -> Self {
+        QueryType::Expect(value)
+    }
 }
 
 #[cfg(any(feature = "serde", feature = "openapi"))]
@@ -84,6 +116,13 @@ impl From<InsertAliasesQuery> for QueryType {
     }
 }
 
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<InsertAnalyticsQuery> for QueryType {
+    fn from(value: InsertAnalyticsQuery) -> Self {
+        QueryType::InsertAnalytics(value)
+    }
+}
+
 #[cfg(any(feature = "serde", feature = "openapi"))]
 impl From<InsertIndexQuery> for QueryType {
     fn from(value: InsertIndexQuery) -> Self {
@@ -112,6 +151,13 @@ impl From<InsertValuesQuery> for QueryType {
     }
 }
 
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<MatchQuery> for QueryType {
+    fn from(value: MatchQuery) -> Self {
+        QueryType::Match(value)
+    }
+}
+
 #[cfg(any(feature = "serde", feature = "openapi"))]
 impl From<RemoveQuery> for QueryType {
     fn from(value: RemoveQuery) -> Self {
@@ -161,6 +207,13 @@ impl From<SelectAllAliasesQuery> for QueryType {
     }
 }
 
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<SelectAnalyticsQuery> for QueryType {
+    fn from(value: SelectAnalyticsQuery) -> Self {
+        QueryType::SelectAnalytics(value)
+    }
+}
+
 #[cfg(any(feature = "serde", feature = "openapi"))]
 impl From<SelectEdgeCountQuery> for QueryType {
     fn from(value: SelectEdgeCountQuery) -> Self {
@@ -203,12 +256,20 @@ impl From<SelectValuesQuery> for QueryType {
     }
 }
 
+#[cfg(any(feature = "serde", feature = "openapi"))]
+impl From<UpdateValuesQuery> for QueryType {
+    fn from(value: UpdateValuesQuery) -> Self {
+        QueryType::UpdateValues(value)
+    }
+}
+
 #[cfg(any(feature = "serde", feature = "openapi"))]
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::AgdbSerialize;
     use crate::DbKeyOrder;
+    use crate::KeyValueUpdate;
     use crate::QueryBuilder;
 
     #[test]
@@ -246,6 +307,28 @@ mod tests {
             QueryBuilder::remove().index("key").query().into(),
             QueryBuilder::remove().values("key").ids(1).query().into(),
             QueryBuilder::remove().ids("node1").query().into(),
+            QueryBuilder::expect()
+                .ids(1)
+                .where_()
+                .key("key")
+                .value(1)
+                .query()
+                .query()
+                .into(),
+            QueryBuilder::insert()
+                .update(KeyValueUpdate::increment("key", 1))
+                .ids(1)
+                .query()
+                .into(),
+            QueryBuilder::match_()
+                .node("a")
+                .edge("e")
+                .where_()
+                .keys("key")
+                .query()
+                .node("b")
+                .query()
+                .into(),
             QueryBuilder::search()
                 .depth_first()
                 .to(1)
@@ -0,0 +1,314 @@
+use crate::DbElement;
+use crate::DbError;
+use crate::DbErrorType;
+use crate::DbId;
+use crate::DbImpl;
+use crate::DbKeyValue;
+use crate::DbValue;
+use crate::Query;
+use crate::QueryIds;
+use crate::QueryResult;
+use crate::SearchQuery;
+use crate::StorageData;
+use crate::query_builder::search::SearchQueryBuilder;
+use std::collections::HashMap;
+
+/// Aggregate function computed over the values
+/// of a key. The computed value is stored in the
+/// result under the name of the function, e.g.
+/// `String("sum")`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregateFunction {
+    /// Number of elements with the key as `u64`.
+    Count,
+
+    /// Sum of the numeric values. It is `i64` or `u64`
+    /// if all of the values are of that type and `f64`
+    /// otherwise.
+    Sum,
+
+    /// Smallest of the values.
+    Min,
+
+    /// Largest of the values.
+    Max,
+
+    /// Arithmetic mean of the numeric values as `f64`.
+    Avg,
+}
+
+/// Convenience wrapper for passing one or more
+/// aggregate functions to the query builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct AggregateFunctions(pub Vec<AggregateFunction>);
+
+/// Query to compute aggregates over values of a `key`
+/// of the elements given by `ids`. All ids must exist in the
+/// database. Elements without the `key` are skipped.
+///
+/// If `group_by` is set the elements are grouped by the value of
+/// that key (elements without it are skipped) and the aggregates
+/// are computed for each group. Otherwise all elements form a
+/// single group.
+///
+/// The result will be number of groups and each group
+/// as an element with the default id. It carries
+/// the `group_by` key with the value of the group (if set)
+/// and a property per function. The `Sum`, `Min`, `Max` and `Avg`
+/// are omitted for an empty group. The `Sum` and `Avg` fail if
+/// any of the values is not numeric.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateQuery {
+    /// Ids of the elements to aggregate.
+    pub ids: QueryIds,
+
+    /// Key whose values are aggregated.
+    pub key: DbValue,
+
+    /// Aggregate functions to compute.
+    pub functions: Vec<AggregateFunction>,
+
+    /// Optional key whose values split the
+    /// elements into groups.
+    pub group_by: Option<DbValue>,
+}
+
+impl Query for AggregateQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let mut result = QueryResult::default();
+
+        let db_ids = match &self.ids {
+            QueryIds::Ids(ids) => {
+                let mut db_ids = Vec::with_capacity(ids.len());
+
+                for query_id in ids {
+                    db_ids.push(db.db_id(query_id)?);
+                }
+
+                db_ids
+            }
+            QueryIds::Search(search_query) => search_query.search(db)?,
+        };
+
+        let mut keys = vec![self.key.clone()];
+        let mut groups: Vec<(Option<DbValue>, Vec<DbValue>)> = vec![];
+        let mut group_indexes: HashMap<DbValue, usize> = HashMap::new();
+
+        if let Some(group_by) = &self.group_by {
+            keys.push(group_by.clone());
+        } else {
+            groups.push((None, vec![]));
+        }
+
+        for id in db_ids {
+            let values = db.values_by_keys(id, &keys)?;
+            let Some(value) = find_value(&values, &self.key) else {
+                continue;
+            };
+
+            let index = if let Some(group_by) = &self.group_by {
+                let Some(group) = find_value(&values, group_by) else {
+                    continue;
+                };
+
+                *group_indexes.entry(group.clone()).or_insert_with(|| {
+                    groups.push((Some(group.clone()), vec![]));
+                    groups.len() - 1
+                })
+            } else {
+                0
+            };
+
+            groups[index].1.push(value.clone());
+        }
+
+        for (group, values) in groups {
+            let mut element_values = vec![];
+
+            if let (Some(group_by), Some(group)) = (&self.group_by, group) {
+                element_values.push((group_by.clone(), group).into());
+            }
+
+            for function in &self.functions {
+                if let Some(value) = self.aggregate(*function, &values)? {
+                    element_values.push((function.name(), value).into());
+                }
+            }
+
+            result.elements.push(DbElement {
+                id: DbId::default(),
+                from: DbId::default(),
+                to: DbId::default(),
+                values: element_values,
+            });
+        }
+
+        result.result = result.elements.len() as u64;
+
+        Ok(result)
+    }
+}
+
+impl Query for &AggregateQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
+
+impl SearchQueryBuilder for AggregateQuery {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        if let QueryIds::Search(search) = &mut self.ids {
+            search
+        } else {
+            panic!("Expected search query");
+        }
+    }
+}
+
+impl AggregateFunction {
+    fn name(&self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+            AggregateFunction::Avg => "avg",
+        }
+    }
+}
+
+impl AggregateQuery {
+    fn aggregate(
+        &self,
+        function: AggregateFunction,
+        values: &[DbValue],
+    ) -> Result<Option<DbValue>, DbError> {
+        if values.is_empty() && function != AggregateFunction::Count {
+            return Ok(None);
+        }
+
+        Ok(Some(match function {
+            AggregateFunction::Count => (values.len() as u64).into(),
+            AggregateFunction::Sum => self.sum(values)?,
+            AggregateFunction::Min => values
+                .iter()
+                .min_by(|l, r| l.sort_cmp(r))
+                .cloned()
+                .unwrap_or_default(),
+            AggregateFunction::Max => values
+                .iter()
+                .max_by(|l, r| l.sort_cmp(r))
+                .cloned()
+                .unwrap_or_default(),
+            AggregateFunction::Avg => {
+                let mut sum = 0.0;
+
+                for value in values {
+                    sum += self.to_f64(value)?;
+                }
+
+                (sum / values.len() as f64).into()
+            }
+        }))
+    }
+
+    fn sum(&self, values: &[DbValue]) -> Result<DbValue, DbError> {
+        let overflow = || {
+            DbError::query(
+                DbErrorType::OutOfBounds,
+                format!("Sum of values of key '{}' overflows", self.key),
+            )
+        };
+
+        if values.iter().all(|v| matches!(v, DbValue::I64(_))) {
+            let mut sum = 0_i64;
+
+            for value in values {
+                sum = sum.checked_add(value.to_i64()?).ok_or_else(overflow)?;
+            }
+
+            Ok(sum.into())
+        } else if values.iter().all(|v| matches!(v, DbValue::U64(_))) {
+            let mut sum = 0_u64;
+
+            for value in values {
+                sum = sum.checked_add(value.to_u64()?).ok_or_else(overflow)?;
+            }
+
+            Ok(sum.into())
+        } else {
+            let mut sum = 0.0;
+
+            for value in values {
+                sum += self.to_f64(value)?;
+            }
+
+            Ok(sum.into())
+        }
+    }
+
+    fn to_f64(&self, value: &DbValue) -> Result<f64, DbError> {
+        match value {
+            DbValue::I64(v) => Ok(*v as f64),
+            DbValue::U64(v) => Ok(*v as f64),
+            DbValue::F64(v) => Ok(v.to_f64()),
+            _ => Err(DbError::query(
+                DbErrorType::TypeError,
+                format!("Value '{value}' of key '{}' is not numeric", self.key),
+            )),
+        }
+    }
+}
+
+impl From<Vec<AggregateFunction>> for AggregateFunctions {
+    fn from(functions: Vec<AggregateFunction>) -> Self {
+        Self(functions)
+    }
+}
+
+impl From<&[AggregateFunction]> for AggregateFunctions {
+    fn from(functions: &[AggregateFunction]) -> Self {
+        Self(functions.to_vec())
+    }
+}
+
+impl<const N: usize> From<[AggregateFunction; N]> for AggregateFunctions {
+    fn from(functions: [AggregateFunction; N]) -> Self {
+        Self(functions.to_vec())
+    }
+}
+
+impl From<AggregateFunction> for AggregateFunctions {
+    fn from(function: AggregateFunction) -> Self {
+        Self(vec![function])
+    }
+}
+
+fn find_value<'a>(values: &'a [DbKeyValue], key: &DbValue) -> Option<&'a DbValue> {
+    values.iter().find(|kv| kv.key == *key).map(|kv| &kv.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn missing_search() {
+        AggregateQuery {
+            ids: QueryIds::Ids(vec![]),
+            key: DbValue::default(),
+            functions: vec![],
+            group_by: None,
+        }
+        .search_mut();
+    }
+}
@@ -0,0 +1,85 @@
+use crate::DbElement;
+use crate::DbError;
+use crate::DbErrorType;
+use crate::DbImpl;
+use crate::Query;
+use crate::QueryCondition;
+use crate::QueryIds;
+use crate::QueryResult;
+use crate::StorageData;
+use crate::query::query_condition::condition_patterns;
+use crate::utilities::regex::PatternScope;
+
+/// Query to check that all `ids` satisfy the `conditions`
+/// (e.g. that the `version` of an element is still `7`). All
+/// ids must exist in the database. When any of the elements
+/// does not satisfy the conditions the query fails with
+/// `DbErrorType::PreconditionFailed` which, when used in a
+/// transaction or a batch of queries, prevents the following
+/// queries from running and rolls back the preceding ones.
+/// It allows multiple clients to safely modify the same data
+/// using optimistic concurrency (compare-and-set).
+///
+/// The result will be number of checked elements and the
+/// list of the checked elements (without properties).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpectQuery {
+    /// Ids of the elements to check
+    pub ids: QueryIds,
+
+    /// Conditions every element must satisfy
+    pub conditions: Vec<QueryCondition>,
+}
+
+impl Query for ExpectQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let mut patterns = vec![];
+        condition_patterns(&self.conditions, &mut patterns);
+        let _patterns = PatternScope::new(patterns)?;
+        let mut result = QueryResult::default();
+
+        let db_ids = match &self.ids {
+            QueryIds::Ids(ids) => {
+                let mut db_ids = Vec::with_capacity(ids.len());
+
+                for query_id in ids {
+                    db_ids.push(db.db_id(query_id)?);
+                }
+
+                db_ids
+            }
+            QueryIds::Search(search_query) => search_query.search(db)?,
+        };
+
+        result.elements.reserve(db_ids.len());
+        result.result = db_ids.len() as u64;
+
+        for id in db_ids {
+            if !db.satisfies_conditions(id, &self.conditions)? {
+                return Err(DbError::query(
+                    DbErrorType::PreconditionFailed,
+                    format!("Precondition failed for id '{}'", id.0),
+                ));
+            }
+
+            result.elements.push(DbElement {
+                id,
+                from: db.from_id(id)?,
+                to: db.to_id(id)?,
+                values: vec![],
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+impl Query for &ExpectQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
@@ -81,6 +81,7 @@ mod tests {
     use crate::Db;
     use crate::DbId;
     use crate::SearchQueryAlgorithm;
+    use crate::SearchQueryDirection;
     use crate::query::query_id::QueryId;
     use crate::query::search_query::SearchQuery;
     use crate::test_utilities::test_file::TestFile;
@@ -92,12 +93,15 @@ mod tests {
         let query = InsertAliasesQuery {
             ids: QueryIds::Search(SearchQuery {
                 algorithm: SearchQueryAlgorithm::BreadthFirst,
+                direction: SearchQueryDirection::Forward,
                 origin: QueryId::Id(DbId(0)),
                 destination: QueryId::Id(DbId(0)),
                 limit: 0,
                 offset: 0,
                 order_by: vec![],
                 conditions: vec![],
+                weight: None,
+                after: None,
             }),
             aliases: vec![],
         };
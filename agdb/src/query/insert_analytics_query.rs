@@ -0,0 +1,79 @@
+use crate::AnalyticsAlgorithm;
+use crate::DbError;
+use crate::DbImpl;
+use crate::QueryIds;
+use crate::QueryMut;
+use crate::QueryResult;
+use crate::SearchQuery;
+use crate::StorageData;
+use crate::query::select_analytics_query::analytics;
+use crate::query_builder::search::SearchQueryBuilder;
+
+/// Query to compute graph analytics over the nodes of the
+/// database and store the results as properties of the nodes.
+/// If the `ids` are empty the whole graph is analysed, otherwise
+/// only the subgraph of the given nodes and edges between them.
+/// Any edge ids are ignored. All of the ids must exist in the database.
+///
+/// Each node will have its score inserted or replaced under the key
+/// named after the algorithm (see `AnalyticsAlgorithm`).
+///
+/// The result will be number of inserted/updated values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertAnalyticsQuery {
+    /// Ids of the nodes to analyse. If empty
+    /// all nodes in the database are analysed.
+    pub ids: QueryIds,
+
+    /// Algorithm to compute.
+    pub algorithm: AnalyticsAlgorithm,
+}
+
+impl QueryMut for InsertAnalyticsQuery {
+    fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let mut result = QueryResult::default();
+        let key = self.algorithm.key();
+
+        for (id, value) in analytics(db, &self.ids, self.algorithm)? {
+            db.insert_or_replace_key_value(id, &(key, value).into())?;
+            result.result += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+impl QueryMut for &InsertAnalyticsQuery {
+    fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
+
+impl SearchQueryBuilder for InsertAnalyticsQuery {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        if let QueryIds::Search(search) = &mut self.ids {
+            search
+        } else {
+            panic!("Expected search query");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn missing_search() {
+        InsertAnalyticsQuery {
+            ids: QueryIds::Ids(vec![]),
+            algorithm: AnalyticsAlgorithm::DegreeCentrality,
+        }
+        .search_mut();
+    }
+}
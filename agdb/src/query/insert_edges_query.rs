@@ -25,6 +25,10 @@ use crate::query::query_values::QueryValues;
 /// have equal length to the `values` (or the `Single` variant must
 /// be used).
 ///
+/// If `acyclic` is set the query fails if any of the new edges
+/// would create a cycle in the graph (including the edges inserted
+/// by the same query) and no edges are inserted.
+///
 /// The result will contain number of edges inserted or udpated and elements
 /// with their ids, origin and destination, but no properties.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -50,6 +54,11 @@ pub struct InsertEdgesQuery {
     /// If `true` create an edge between each origin
     /// and destination.
     pub each: bool,
+
+    /// If `true` the new edges must not create
+    /// a cycle in the graph.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub acyclic: bool,
 }
 
 impl QueryMut for InsertEdgesQuery {
@@ -157,6 +166,10 @@ impl InsertEdgesQuery {
         let values = self.values(from.len())?;
 
         for ((from, to), key_values) in from.iter().zip(to).zip(values) {
+            if self.acyclic {
+                db.validate_acyclic_edge(*from, *to)?;
+            }
+
             let db_id = db.insert_edge(*from, *to)?;
             ids.push(db_id);
 
@@ -183,6 +196,10 @@ impl InsertEdgesQuery {
 
         for from in from {
             for to in to {
+                if self.acyclic {
+                    db.validate_acyclic_edge(*from, *to)?;
+                }
+
                 let db_id = db.insert_edge(*from, *to)?;
                 ids.push(db_id);
 
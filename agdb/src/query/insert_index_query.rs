@@ -6,21 +6,38 @@ use crate::QueryResult;
 use crate::StorageData;
 
 /// Query to create a new index on
-/// a given key.
+/// a given key or keys. Index over multiple
+/// keys (composite index) contains only the
+/// elements that have all of the keys. Ordered
+/// indexes keep their values sorted and support
+/// range (or prefix) searches. Unique indexes
+/// reject values already held by another element.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
 #[derive(Clone, Debug, PartialEq)]
-pub struct InsertIndexQuery(pub DbValue);
+pub struct InsertIndexQuery {
+    /// Key(s) to be indexed.
+    pub keys: Vec<DbValue>,
+
+    /// Whether the index keeps its values sorted allowing
+    /// range comparisons and ordered traversal.
+    pub ordered: bool,
+
+    /// Whether the index allows each value (or combination
+    /// of values) to be held by at most one element.
+    pub unique: bool,
+}
 
 impl QueryMut for InsertIndexQuery {
     fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
-        let result = db.insert_index(&self.0)?;
+        let result = db.insert_index(&self.keys, self.ordered, self.unique)?;
 
         Ok(QueryResult {
             result,
             elements: vec![],
+            cursor: None,
         })
     }
 }
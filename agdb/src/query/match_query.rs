@@ -0,0 +1,129 @@
+use crate::DbElement;
+use crate::DbError;
+use crate::DbImpl;
+use crate::Query;
+use crate::QueryCondition;
+use crate::QueryResult;
+use crate::SearchQueryDirection;
+use crate::StorageData;
+use crate::query::query_condition::condition_patterns;
+use crate::utilities::regex::PatternScope;
+
+/// Named element of a pattern. Only elements satisfying
+/// the `conditions` can be bound to the variable. Empty
+/// conditions match any element.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchVariable {
+    /// Name of the variable reported in the results.
+    pub name: String,
+
+    /// Conditions the bound element must satisfy.
+    pub conditions: Vec<QueryCondition>,
+}
+
+/// Single hop of a pattern from the previous node
+/// over an edge to the next node.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchStep {
+    /// Direction of the edge relative to the previous
+    /// node: `Forward` follows outgoing edges, `Reverse`
+    /// incoming edges and `Both` any of them.
+    pub direction: SearchQueryDirection,
+
+    /// Edge variable.
+    pub edge: MatchVariable,
+
+    /// Node variable the edge leads to.
+    pub node: MatchVariable,
+}
+
+/// Query to find all occurrences of a path pattern in the
+/// graph. The pattern starts with a `node` variable followed
+/// by the `steps` each adding an edge and a node. Every
+/// variable is evaluated with the same conditions as the
+/// `SearchQuery` with the distance being its position in the
+/// pattern (the first node is at distance 0, the first edge at 1 etc.).
+///
+/// All nodes are tried as the start of the pattern unless the first
+/// condition of the `node` variable is `ids` in which case only those
+/// elements are tried. An edge can be bound only once in each match
+/// while nodes can repeat (e.g. to match cycles).
+///
+/// The result will be number of elements bound to the variables
+/// of all the matches listed in the pattern order for each match.
+/// Each element carries the `match` (0-based index of the match
+/// including the offset) and the `variable` (name) properties.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchQuery {
+    /// The first node of the pattern.
+    pub node: MatchVariable,
+
+    /// Edges and nodes following the first node.
+    pub steps: Vec<MatchStep>,
+
+    /// How many matches to skip.
+    pub offset: u64,
+
+    /// Maximum number of matches to return. 0 means unlimited.
+    pub limit: u64,
+}
+
+impl Query for MatchQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let _patterns = self.pattern_scope()?;
+        let mut result = QueryResult::default();
+        let variables = std::iter::once(&self.node)
+            .chain(self.steps.iter().flat_map(|step| [&step.edge, &step.node]))
+            .map(|variable| &variable.name)
+            .collect::<Vec<&String>>();
+
+        for (index, ids) in db.search_pattern(self)?.into_iter().enumerate() {
+            let index = self.offset + index as u64;
+
+            for (id, name) in ids.into_iter().zip(&variables) {
+                result.elements.push(DbElement {
+                    id,
+                    from: db.from_id(id)?,
+                    to: db.to_id(id)?,
+                    values: vec![("match", index).into(), ("variable", name.as_str()).into()],
+                });
+            }
+        }
+
+        result.result = result.elements.len() as u64;
+
+        Ok(result)
+    }
+}
+
+impl Query for &MatchQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
+
+impl MatchQuery {
+    fn pattern_scope(&self) -> Result<PatternScope, DbError> {
+        let mut result = vec![];
+        condition_patterns(&self.node.conditions, &mut result);
+
+        for step in &self.steps {
+            condition_patterns(&step.edge.conditions, &mut result);
+            condition_patterns(&step.node.conditions, &mut result);
+        }
+
+        PatternScope::new(result)
+    }
+}
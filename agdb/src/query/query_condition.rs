@@ -1,6 +1,8 @@
 use crate::DbValue;
 use crate::QueryId;
+use crate::SearchQueryDirection;
 use crate::graph_search::SearchControl;
+use crate::utilities::regex;
 
 /// Logical operator for query conditions
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -85,6 +87,18 @@ pub enum QueryConditionData {
 
     /// Nested list of conditions (equivalent to brackets).
     Where(Vec<QueryCondition>),
+
+    /// Tests if the current element has both the property `key`
+    /// and the property named by the value of the `comparison`
+    /// and their values evaluate true against the comparison
+    /// (e.g. `spent > budget`).
+    KeyKey(KeyValueComparison),
+
+    /// Evaluates the nested conditions against the elements
+    /// adjacent to the current element (edges of a node or
+    /// nodes of an edge) and tests the number of the matching
+    /// ones with the quantifier.
+    Adjacent(AdjacentCondition),
 }
 
 /// Query condition. The condition consists of
@@ -139,10 +153,15 @@ pub enum CountComparison {
 /// by `key()` condition. Supports
 /// the usual set of named comparisons: `==, !=, <, <=, >, =>`
 /// plus `contains()`. The comparisons are type
-/// strict except for the `contains` comparison
-/// which allows vectorized version of the base type. Notably
+/// strict except for the numbers (`I64`, `U64`, `F64`)
+/// that are compared by their numeric value regardless
+/// of the type and the `contains` comparison
+/// which allows vectorized version of the base type
+/// and any value to be looked up in a list. Notably
 /// however it does not support the `bytes` and integral types
 /// where the "contains" makes little sense (i.e. does 3 contain 1?).
+/// The `*IgnoreCase` variants and the regular expression
+/// `Matches` apply to `String` and `VecString` values only.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -175,6 +194,68 @@ pub enum Comparison {
 
     /// property.ends_with(this)
     EndsWith(DbValue),
+
+    /// property == this (case insensitive, `String` & `VecString` only)
+    EqualIgnoreCase(DbValue),
+
+    /// property.contains(this) (case insensitive, `String` & `VecString` only)
+    ContainsIgnoreCase(DbValue),
+
+    /// property.starts_with(this) (case insensitive, `String` & `VecString` only)
+    StartsWithIgnoreCase(DbValue),
+
+    /// property.ends_with(this) (case insensitive, `String` & `VecString` only)
+    EndsWithIgnoreCase(DbValue),
+
+    /// Regular expression `this` (a `String`) matches the property
+    /// or any element of it for `VecString`. The match is not anchored
+    /// unless the pattern uses `^` and/or `$`. Use `(?i)` flag for
+    /// case insensitive matching. Requires the `regex` feature, the
+    /// queries using it fail with an error otherwise (as they do
+    /// when the pattern is invalid).
+    Matches(DbValue),
+}
+
+/// Quantifier of the `Adjacent` condition telling how many of
+/// the adjacent elements must satisfy the nested conditions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub enum AdjacentQuantifier {
+    /// At least one adjacent element.
+    Any,
+
+    /// All adjacent elements. Passes if there are none.
+    All,
+
+    /// The number of the adjacent elements satisfying
+    /// the conditions is tested with the count comparison
+    /// (e.g. Equal, GreaterThan).
+    Count(CountComparison),
+}
+
+/// Condition evaluated against the elements adjacent
+/// to the current element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct AdjacentCondition {
+    /// Which adjacent elements are evaluated. `Forward` selects
+    /// the outgoing edges of a node and the `to` node of an edge,
+    /// `Reverse` the incoming edges of a node and the `from` node
+    /// of an edge and `Both` selects all of them.
+    pub direction: SearchQueryDirection,
+
+    /// How many of the adjacent elements must satisfy the conditions.
+    pub quantifier: AdjacentQuantifier,
+
+    /// Conditions evaluated against each of the adjacent elements
+    /// as if they were one step further from the search origin.
+    pub conditions: Vec<QueryCondition>,
 }
 
 /// Comparison of a value stored under specific `key` to
@@ -185,7 +266,9 @@ pub enum Comparison {
 #[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
 pub struct KeyValueComparison {
-    /// Property key
+    /// Property key. A string key that does not exist
+    /// as is but contains `.` is treated as a path to
+    /// a value nested in a map or list, e.g. `"address.city"`.
     pub key: DbValue,
 
     /// Comparison operator (e.g. Equal, GreaterThan etc.)
@@ -248,12 +331,12 @@ impl CountComparison {
 impl Comparison {
     pub(crate) fn compare(&self, left: &DbValue) -> bool {
         match self {
-            Comparison::Equal(right) => left == right,
-            Comparison::GreaterThan(right) => left > right,
-            Comparison::GreaterThanOrEqual(right) => left >= right,
-            Comparison::LessThan(right) => left < right,
-            Comparison::LessThanOrEqual(right) => left <= right,
-            Comparison::NotEqual(right) => left != right,
+            Comparison::Equal(right) => left.compare(right).is_some_and(|o| o.is_eq()),
+            Comparison::GreaterThan(right) => left.compare(right).is_some_and(|o| o.is_gt()),
+            Comparison::GreaterThanOrEqual(right) => left.compare(right).is_some_and(|o| o.is_ge()),
+            Comparison::LessThan(right) => left.compare(right).is_some_and(|o| o.is_lt()),
+            Comparison::LessThanOrEqual(right) => left.compare(right).is_some_and(|o| o.is_le()),
+            Comparison::NotEqual(right) => !left.compare(right).is_some_and(|o| o.is_eq()),
 
             Comparison::Contains(right) => match (left, right) {
                 (DbValue::String(left), DbValue::String(right)) => left.contains(right),
@@ -276,6 +359,10 @@ impl Comparison {
                 (DbValue::VecString(left), DbValue::VecString(right)) => {
                     right.iter().all(|x| left.contains(x))
                 }
+                (DbValue::List(left), DbValue::List(right)) => {
+                    right.iter().all(|x| left.contains(x))
+                }
+                (DbValue::List(left), right) => left.contains(right),
                 _ => false,
             },
 
@@ -310,6 +397,27 @@ impl Comparison {
                 (DbValue::VecString(left), DbValue::VecString(right)) => left.ends_with(right),
                 _ => false,
             },
+
+            Comparison::EqualIgnoreCase(right) => {
+                compare_ignore_case(Comparison::Equal, left, right)
+            }
+            Comparison::ContainsIgnoreCase(right) => {
+                compare_ignore_case(Comparison::Contains, left, right)
+            }
+            Comparison::StartsWithIgnoreCase(right) => {
+                compare_ignore_case(Comparison::StartsWith, left, right)
+            }
+            Comparison::EndsWithIgnoreCase(right) => {
+                compare_ignore_case(Comparison::EndsWith, left, right)
+            }
+
+            Comparison::Matches(right) => match (left, right) {
+                (DbValue::String(left), DbValue::String(pattern)) => regex::is_match(pattern, left),
+                (DbValue::VecString(left), DbValue::String(pattern)) => {
+                    left.iter().any(|x| regex::is_match(pattern, x))
+                }
+                _ => false,
+            },
         }
     }
 
@@ -323,7 +431,39 @@ impl Comparison {
             | Comparison::NotEqual(value)
             | Comparison::Contains(value)
             | Comparison::StartsWith(value)
-            | Comparison::EndsWith(value) => value,
+            | Comparison::EndsWith(value)
+            | Comparison::EqualIgnoreCase(value)
+            | Comparison::ContainsIgnoreCase(value)
+            | Comparison::StartsWithIgnoreCase(value)
+            | Comparison::EndsWithIgnoreCase(value)
+            | Comparison::Matches(value) => value,
+        }
+    }
+
+    /// Returns the same comparison with the `value`.
+    pub(crate) fn with_value(&self, value: DbValue) -> Comparison {
+        match self {
+            Comparison::Equal(_) => Comparison::Equal(value),
+            Comparison::GreaterThan(_) => Comparison::GreaterThan(value),
+            Comparison::GreaterThanOrEqual(_) => Comparison::GreaterThanOrEqual(value),
+            Comparison::LessThan(_) => Comparison::LessThan(value),
+            Comparison::LessThanOrEqual(_) => Comparison::LessThanOrEqual(value),
+            Comparison::NotEqual(_) => Comparison::NotEqual(value),
+            Comparison::Contains(_) => Comparison::Contains(value),
+            Comparison::StartsWith(_) => Comparison::StartsWith(value),
+            Comparison::EndsWith(_) => Comparison::EndsWith(value),
+            Comparison::EqualIgnoreCase(_) => Comparison::EqualIgnoreCase(value),
+            Comparison::ContainsIgnoreCase(_) => Comparison::ContainsIgnoreCase(value),
+            Comparison::StartsWithIgnoreCase(_) => Comparison::StartsWithIgnoreCase(value),
+            Comparison::EndsWithIgnoreCase(_) => Comparison::EndsWithIgnoreCase(value),
+            Comparison::Matches(_) => Comparison::Matches(value),
+        }
+    }
+
+    pub(crate) fn pattern(&self) -> Option<&str> {
+        match self {
+            Comparison::Matches(DbValue::String(pattern)) => Some(pattern),
+            _ => None,
         }
     }
 }
@@ -340,6 +480,42 @@ impl<T: Into<DbValue>> From<T> for Comparison {
     }
 }
 
+/// Collects the regular expressions of the `Matches` comparisons
+/// of the `conditions` including the nested ones.
+pub(crate) fn condition_patterns<'a>(conditions: &'a [QueryCondition], result: &mut Vec<&'a str>) {
+    for condition in conditions {
+        match &condition.data {
+            QueryConditionData::KeyValue(kvc) => result.extend(kvc.value.pattern()),
+            QueryConditionData::Where(conditions)
+            | QueryConditionData::Adjacent(AdjacentCondition { conditions, .. }) => {
+                condition_patterns(conditions, result)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn compare_ignore_case(
+    comparison: fn(DbValue) -> Comparison,
+    left: &DbValue,
+    right: &DbValue,
+) -> bool {
+    match (to_lowercase(left), to_lowercase(right)) {
+        (Some(left), Some(right)) => comparison(right).compare(&left),
+        _ => false,
+    }
+}
+
+fn to_lowercase(value: &DbValue) -> Option<DbValue> {
+    match value {
+        DbValue::String(value) => Some(DbValue::String(value.to_lowercase())),
+        DbValue::VecString(value) => Some(DbValue::VecString(
+            value.iter().map(|x| x.to_lowercase()).collect(),
+        )),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -594,4 +770,99 @@ mod tests {
 
         assert!(!Comparison::EndsWith("abc".into()).compare(&1.into()));
     }
+
+    #[test]
+    fn compare_numeric() {
+        assert!(Comparison::Equal(1.into()).compare(&1_u64.into()));
+        assert!(Comparison::Equal(1.0.into()).compare(&1.into()));
+        assert!(Comparison::GreaterThan(3_u64.into()).compare(&5.into()));
+        assert!(!Comparison::GreaterThan(5_u64.into()).compare(&(-5).into()));
+        assert!(Comparison::GreaterThanOrEqual(4.5.into()).compare(&5_u64.into()));
+        assert!(Comparison::LessThan(5.into()).compare(&4.5.into()));
+        assert!(Comparison::LessThanOrEqual(u64::MAX.into()).compare(&i64::MAX.into()));
+        assert!(Comparison::NotEqual(1.into()).compare(&1.5.into()));
+
+        assert!(!Comparison::Equal(1.into()).compare(&f64::NAN.into()));
+        assert!(!Comparison::LessThan(1.into()).compare(&f64::NAN.into()));
+        assert!(!Comparison::GreaterThanOrEqual(1.into()).compare(&f64::NAN.into()));
+        assert!(Comparison::NotEqual(1.into()).compare(&f64::NAN.into()));
+    }
+
+    #[test]
+    fn ignore_case() {
+        assert!(Comparison::EqualIgnoreCase("aBc".into()).compare(&"AbC".into()));
+        assert!(!Comparison::EqualIgnoreCase("aBc".into()).compare(&"AbCd".into()));
+        assert!(Comparison::ContainsIgnoreCase("ŽLU".into()).compare(&"ažluť".into()));
+        assert!(Comparison::StartsWithIgnoreCase("HELLO".into()).compare(&"hello world".into()));
+        assert!(Comparison::EndsWithIgnoreCase("WORLD".into()).compare(&"hello world".into()));
+        assert!(!Comparison::EndsWithIgnoreCase("HELLO".into()).compare(&"hello world".into()));
+
+        let list = vec!["Alice".to_string(), "BOB".to_string()].into();
+        assert!(Comparison::ContainsIgnoreCase("bob".into()).compare(&list));
+        assert!(Comparison::StartsWithIgnoreCase("alice".into()).compare(&list));
+        assert!(
+            Comparison::EqualIgnoreCase(vec!["ALICE".to_string(), "bob".to_string()].into())
+                .compare(&list)
+        );
+
+        assert!(!Comparison::EqualIgnoreCase(1.into()).compare(&1.into()));
+        assert!(!Comparison::ContainsIgnoreCase("1".into()).compare(&vec![1].into()));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches() {
+        let _patterns =
+            crate::utilities::regex::PatternScope::new(["^a.c$", "(?i)world", "^b", "^c", "1"])
+                .unwrap();
+        assert!(Comparison::Matches("^a.c$".into()).compare(&"abc".into()));
+        assert!(!Comparison::Matches("^a.c$".into()).compare(&"abcd".into()));
+        assert!(Comparison::Matches("(?i)world".into()).compare(&"Hello World".into()));
+        assert!(
+            Comparison::Matches("^b".into())
+                .compare(&vec!["abc".to_string(), "bcd".to_string()].into())
+        );
+        assert!(
+            !Comparison::Matches("^c".into())
+                .compare(&vec!["abc".to_string(), "bcd".to_string()].into())
+        );
+        assert!(!Comparison::Matches("1".into()).compare(&1.into()));
+        assert!(!Comparison::Matches(1.into()).compare(&"1".into()));
+    }
+
+    #[test]
+    fn with_value() {
+        let comparisons = [
+            Comparison::Equal(0.into()),
+            Comparison::GreaterThan(0.into()),
+            Comparison::GreaterThanOrEqual(0.into()),
+            Comparison::LessThan(0.into()),
+            Comparison::LessThanOrEqual(0.into()),
+            Comparison::NotEqual(0.into()),
+            Comparison::Contains(0.into()),
+            Comparison::StartsWith(0.into()),
+            Comparison::EndsWith(0.into()),
+            Comparison::EqualIgnoreCase(0.into()),
+            Comparison::ContainsIgnoreCase(0.into()),
+            Comparison::StartsWithIgnoreCase(0.into()),
+            Comparison::EndsWithIgnoreCase(0.into()),
+            Comparison::Matches(0.into()),
+        ];
+
+        for comparison in comparisons {
+            let other = comparison.with_value(1.into());
+            assert_eq!(other.value(), &DbValue::I64(1));
+            assert_eq!(
+                std::mem::discriminant(&other),
+                std::mem::discriminant(&comparison)
+            );
+        }
+    }
+
+    #[test]
+    fn pattern() {
+        assert_eq!(Comparison::Matches("^a".into()).pattern(), Some("^a"));
+        assert_eq!(Comparison::Matches(1.into()).pattern(), None);
+        assert_eq!(Comparison::Equal("^a".into()).pattern(), None);
+    }
 }
@@ -83,6 +83,7 @@ mod tests {
     use super::*;
     use crate::DbId;
     use crate::query::search_query::SearchQueryAlgorithm;
+    use crate::query::search_query::SearchQueryDirection;
 
     #[test]
     #[allow(clippy::redundant_clone)]
@@ -109,12 +110,15 @@ mod tests {
     fn get_ids_from_search() {
         let ids = QueryIds::Search(SearchQuery {
             algorithm: SearchQueryAlgorithm::BreadthFirst,
+            direction: SearchQueryDirection::Forward,
             origin: QueryId::Id(DbId(0)),
             destination: QueryId::Id(DbId(0)),
             limit: 0,
             offset: 0,
             order_by: vec![],
             conditions: vec![],
+            weight: None,
+            after: None,
         })
         .get_ids();
 
@@ -20,6 +20,13 @@ pub struct QueryResult {
     /// List of elements yielded by the query
     /// possibly with a list of properties.
     pub elements: Vec<DbElement>,
+
+    /// Opaque continuation cursor set by the search queries
+    /// that reached their `limit`. Running the same query
+    /// with the cursor (`search().after(cursor)`) yields the
+    /// next page of the results.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cursor: Option<String>,
 }
 
 impl QueryResult {
@@ -69,10 +76,12 @@ mod test {
         let result = QueryResult {
             result: 0,
             elements: vec![],
+            cursor: None,
         };
         let other = QueryResult {
             result: 1,
             elements: vec![],
+            cursor: None,
         };
 
         assert!(result < other);
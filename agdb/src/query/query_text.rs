@@ -0,0 +1,1788 @@
+use crate::AdjacentCondition;
+use crate::AdjacentQuantifier;
+use crate::AggregateFunction;
+use crate::AggregateQuery;
+use crate::AnalyticsAlgorithm;
+use crate::Comparison;
+use crate::CountComparison;
+use crate::DbError;
+use crate::DbId;
+use crate::DbKeyOrder;
+use crate::DbKeyValue;
+use crate::DbValue;
+use crate::ExpectQuery;
+use crate::InsertAliasesQuery;
+use crate::InsertAnalyticsQuery;
+use crate::InsertEdgesQuery;
+use crate::InsertIndexQuery;
+use crate::InsertNodesQuery;
+use crate::InsertValuesQuery;
+use crate::KeyValueComparison;
+use crate::KeyValueUpdate;
+use crate::MatchQuery;
+use crate::MatchStep;
+use crate::MatchVariable;
+use crate::PageRankOptions;
+use crate::QueryCondition;
+use crate::QueryConditionData;
+use crate::QueryConditionLogic;
+use crate::QueryConditionModifier;
+use crate::QueryId;
+use crate::QueryIds;
+use crate::QueryType;
+use crate::QueryValues;
+use crate::RemoveAliasesQuery;
+use crate::RemoveIndexQuery;
+use crate::RemoveQuery;
+use crate::RemoveValuesQuery;
+use crate::SearchQuery;
+use crate::SearchQueryAlgorithm;
+use crate::SearchQueryDirection;
+use crate::SelectAliasesQuery;
+use crate::SelectAllAliasesQuery;
+use crate::SelectAnalyticsQuery;
+use crate::SelectEdgeCountQuery;
+use crate::SelectIndexesQuery;
+use crate::SelectKeyCountQuery;
+use crate::SelectKeysQuery;
+use crate::SelectNodeCountQuery;
+use crate::SelectValuesQuery;
+use crate::UpdateValuesQuery;
+use crate::ValueUpdate;
+use crate::db::db_error::DbErrorType;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+const PUNCTUATION: [&str; 19] = [
+    "<-[", "]->", "!=", ">=", "<=", "-[", "]-", "(", ")", "[", "]", "{", "}", ",", ":", ";", "=",
+    ">", "<",
+];
+
+const ALL_FUNCTIONS: [AggregateFunction; 5] = [
+    AggregateFunction::Count,
+    AggregateFunction::Sum,
+    AggregateFunction::Min,
+    AggregateFunction::Max,
+    AggregateFunction::Avg,
+];
+
+/// Parses a single query from its text form. The text form
+/// is what the `Display` implementation of `QueryType` produces,
+/// e.g. `search from "users" where key "age" > 18`. An optional
+/// trailing `;` is allowed.
+///
+/// ```
+/// use agdb::QueryBuilder;
+/// use agdb::QueryType;
+///
+/// let query = agdb::parse_query("select ids [1, 2]").unwrap();
+/// assert_eq!(query, QueryType::from(QueryBuilder::select().ids([1, 2]).query()));
+/// ```
+pub fn parse_query(text: &str) -> Result<QueryType, DbError> {
+    let mut parser = Parser::new(text)?;
+    let query = parser.query()?;
+    parser.eat_punct(";");
+    parser.end()?;
+    Ok(query)
+}
+
+/// Parses a list of queries separated by `;` from their text form.
+/// See [`parse_query`] for details.
+pub fn parse_queries(text: &str) -> Result<Vec<QueryType>, DbError> {
+    let mut parser = Parser::new(text)?;
+    let mut queries = vec![];
+
+    while !parser.at_end() {
+        queries.push(parser.query()?);
+
+        if !parser.eat_punct(";") {
+            break;
+        }
+    }
+
+    parser.end()?;
+    Ok(queries)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Bytes(Vec<u8>),
+    End,
+    Float(f64),
+    Ident(String),
+    Int(i128),
+    Punct(&'static str),
+    String(String),
+    UInt(u64),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+fn invalid_query(reason: &str, position: usize) -> DbError {
+    DbError::query(
+        DbErrorType::InvalidQuery,
+        format!("Invalid query: {reason} at position {position}"),
+    )
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, DbError> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let next = chars.get(i + 1).copied().unwrap_or_default();
+
+        let token = if c == '"' {
+            let (value, end) = string_literal(&chars, i)?;
+            i = end;
+            Token::String(value)
+        } else if c == '0' && next == 'x' {
+            i += 2;
+            let begin = i;
+
+            while i < chars.len() && chars[i].is_ascii_hexdigit() {
+                i += 1;
+            }
+
+            let digits = chars[begin..i].iter().collect::<String>();
+
+            if digits.len() % 2 != 0 {
+                return Err(invalid_query("odd number of hex digits", start));
+            }
+
+            Token::Bytes(
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|d| u8::from_str_radix(&digits[d..d + 2], 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map_err(|_| invalid_query("invalid bytes", start))?,
+            )
+        } else if c.is_ascii_digit() || (c == '-' && next.is_ascii_digit()) {
+            let (token, end) = number_literal(&chars, i)?;
+            i = end;
+            token
+        } else if c == '-' && chars[i + 1..].starts_with(&['i', 'n', 'f']) {
+            i += 4;
+            Token::Float(f64::NEG_INFINITY)
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            Token::Ident(chars[start..i].iter().collect())
+        } else if let Some(punct) = PUNCTUATION.iter().find(|p| {
+            let p = p.chars().collect::<Vec<char>>();
+            chars[i..].starts_with(&p)
+        }) {
+            i += punct.chars().count();
+            Token::Punct(punct)
+        } else {
+            return Err(invalid_query(&format!("unexpected character '{c}'"), start));
+        };
+
+        if i < chars.len()
+            && matches!(
+                token,
+                Token::Bytes(_) | Token::Float(_) | Token::Int(_) | Token::UInt(_)
+            )
+            && (chars[i].is_alphanumeric() || chars[i] == '_')
+        {
+            return Err(invalid_query("invalid number", start));
+        }
+
+        tokens.push((token, start));
+    }
+
+    tokens.push((Token::End, chars.len()));
+
+    Ok(tokens)
+}
+
+fn string_literal(chars: &[char], start: usize) -> Result<(String, usize), DbError> {
+    let mut value = String::new();
+    let mut i = start + 1;
+
+    loop {
+        match chars.get(i) {
+            None => return Err(invalid_query("unterminated string", start)),
+            Some('"') => return Ok((value, i + 1)),
+            Some('\\') => {
+                i += 1;
+
+                match chars.get(i) {
+                    Some('\\') => value.push('\\'),
+                    Some('"') => value.push('"'),
+                    Some('\'') => value.push('\''),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('0') => value.push('\0'),
+                    Some('u') if chars.get(i + 1) == Some(&'{') => {
+                        let begin = i + 2;
+                        let end = chars[begin..]
+                            .iter()
+                            .position(|c| *c == '}')
+                            .map(|p| begin + p)
+                            .ok_or_else(|| invalid_query("invalid escape", i - 1))?;
+                        let code =
+                            u32::from_str_radix(&chars[begin..end].iter().collect::<String>(), 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| invalid_query("invalid escape", i - 1))?;
+                        value.push(code);
+                        i = end;
+                    }
+                    _ => return Err(invalid_query("invalid escape", i - 1)),
+                }
+            }
+            Some(c) => value.push(*c),
+        }
+
+        i += 1;
+    }
+}
+
+fn number_literal(chars: &[char], start: usize) -> Result<(Token, usize), DbError> {
+    let mut i = start + 1;
+    let mut float = false;
+    let digits = |i: &mut usize| {
+        while *i < chars.len() && chars[*i].is_ascii_digit() {
+            *i += 1;
+        }
+    };
+
+    digits(&mut i);
+
+    if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+        float = true;
+        i += 1;
+        digits(&mut i);
+    }
+
+    if matches!(chars.get(i), Some('e' | 'E')) {
+        let mut exponent = i + 1;
+
+        if matches!(chars.get(exponent), Some('+' | '-')) {
+            exponent += 1;
+        }
+
+        if chars.get(exponent).is_some_and(|c| c.is_ascii_digit()) {
+            float = true;
+            i = exponent;
+            digits(&mut i);
+        }
+    }
+
+    let text = chars[start..i].iter().collect::<String>();
+
+    if float {
+        let value = text
+            .parse::<f64>()
+            .map_err(|_| invalid_query("invalid number", start))?;
+        return Ok((Token::Float(value), i));
+    }
+
+    if chars.get(i) == Some(&'u') && chars[start] != '-' {
+        let value = text
+            .parse::<u64>()
+            .map_err(|_| invalid_query("number out of range", start))?;
+        return Ok((Token::UInt(value), i + 1));
+    }
+
+    let value = text
+        .parse::<i128>()
+        .map_err(|_| invalid_query("number out of range", start))?;
+
+    Ok((Token::Int(value), i))
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self, DbError> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+
+        if token != Token::End {
+            self.pos += 1;
+        }
+
+        token
+    }
+
+    fn error(&self, reason: &str) -> DbError {
+        invalid_query(reason, self.tokens[self.pos].1)
+    }
+
+    fn at_end(&self) -> bool {
+        *self.peek() == Token::End
+    }
+
+    fn end(&self) -> Result<(), DbError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected token"))
+        }
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Token::Punct(p) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), DbError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{punct}'")))
+        }
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        if matches!(self.peek(), Token::Ident(i) if i == ident) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_ident(&mut self, ident: &str) -> Result<(), DbError> {
+        if self.eat_ident(ident) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{ident}'")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, DbError> {
+        match self.peek().clone() {
+            Token::Ident(ident) => {
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected keyword")),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, DbError>,
+    ) -> Result<Vec<T>, DbError> {
+        let mut items = vec![];
+
+        while !self.eat_punct(close) {
+            items.push(item(self)?);
+
+            if !self.eat_punct(",") {
+                self.expect_punct(close)?;
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn one_or_list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, DbError>,
+    ) -> Result<Vec<T>, DbError> {
+        if self.eat_punct("[") {
+            self.list("]", item)
+        } else {
+            Ok(vec![item(self)?])
+        }
+    }
+
+    fn u64(&mut self) -> Result<u64, DbError> {
+        match self.peek().clone() {
+            Token::Int(value) if (0..=u64::MAX as i128).contains(&value) => {
+                self.pos += 1;
+                Ok(value as u64)
+            }
+            Token::UInt(value) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("expected unsigned integer")),
+        }
+    }
+
+    fn i64(&mut self) -> Result<i64, DbError> {
+        match self.peek().clone() {
+            Token::Int(value) => {
+                let value = i64::try_from(value).map_err(|_| self.error("number out of range"))?;
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("expected integer")),
+        }
+    }
+
+    fn f64(&mut self) -> Result<f64, DbError> {
+        match self.peek().clone() {
+            Token::Float(value) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            Token::Int(value) => {
+                self.pos += 1;
+                Ok(value as f64)
+            }
+            _ => Err(self.error("expected number")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, DbError> {
+        match self.peek().clone() {
+            Token::String(value) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("expected string")),
+        }
+    }
+
+    fn value(&mut self) -> Result<DbValue, DbError> {
+        let error = self.error("expected value");
+
+        Ok(match self.next() {
+            Token::Bytes(value) => DbValue::Bytes(value),
+            Token::Float(value) => DbValue::F64(value.into()),
+            Token::Int(_) => {
+                self.pos -= 1;
+                DbValue::I64(self.i64()?)
+            }
+            Token::String(value) => DbValue::String(value),
+            Token::UInt(value) => DbValue::U64(value),
+            Token::Punct("[") => self.vector(None)?,
+            Token::Punct("{") => DbValue::Map(self.list("}", Self::key_value)?),
+            Token::Ident(ident) => match ident.as_str() {
+                "true" => DbValue::Bool(true),
+                "false" => DbValue::Bool(false),
+                "nan" => DbValue::F64(f64::NAN.into()),
+                "inf" => DbValue::F64(f64::INFINITY.into()),
+                "datetime" => {
+                    self.expect_punct("(")?;
+                    let value = self.i64()?;
+                    self.expect_punct(")")?;
+                    DbValue::DateTime(value)
+                }
+                "list" | "i64" | "u64" | "f64" | "string" => {
+                    self.expect_punct("[")?;
+                    self.vector(Some(&ident))?
+                }
+                _ => return Err(error),
+            },
+            _ => return Err(error),
+        })
+    }
+
+    fn vector(&mut self, ty: Option<&str>) -> Result<DbValue, DbError> {
+        let error = self.error("mixed vector element types");
+        let values = self.list("]", Self::value)?;
+
+        let ty = match ty {
+            Some(ty) => ty,
+            None if values.iter().all(|v| matches!(v, DbValue::I64(_))) => "i64",
+            None if values.iter().all(|v| matches!(v, DbValue::U64(_))) => "u64",
+            None if values.iter().all(|v| matches!(v, DbValue::F64(_))) => "f64",
+            None if values.iter().all(|v| matches!(v, DbValue::String(_))) => "string",
+            None => "list",
+        };
+
+        let values = values.into_iter();
+
+        Ok(match ty {
+            "i64" => DbValue::VecI64(
+                values
+                    .map(|v| match v {
+                        DbValue::I64(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<i64>>>()
+                    .ok_or(error)?,
+            ),
+            "u64" => DbValue::VecU64(
+                values
+                    .map(|v| match v {
+                        DbValue::U64(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<u64>>>()
+                    .ok_or(error)?,
+            ),
+            "f64" => DbValue::VecF64(
+                values
+                    .map(|v| match v {
+                        DbValue::F64(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(error)?,
+            ),
+            "string" => DbValue::VecString(
+                values
+                    .map(|v| match v {
+                        DbValue::String(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<String>>>()
+                    .ok_or(error)?,
+            ),
+            _ => DbValue::List(values.collect()),
+        })
+    }
+
+    fn key_value(&mut self) -> Result<DbKeyValue, DbError> {
+        let key = self.value()?;
+        self.expect_punct(":")?;
+        let value = self.value()?;
+        Ok(DbKeyValue { key, value })
+    }
+
+    fn keys(&mut self) -> Result<Vec<DbValue>, DbError> {
+        self.one_or_list(Self::value)
+    }
+
+    fn aliases(&mut self) -> Result<Vec<String>, DbError> {
+        self.one_or_list(Self::string)
+    }
+
+    fn query_id(&mut self) -> Result<QueryId, DbError> {
+        match self.peek() {
+            Token::String(_) => Ok(QueryId::Alias(self.string()?)),
+            Token::Int(_) => Ok(QueryId::Id(DbId(self.i64()?))),
+            _ => Err(self.error("expected id or alias")),
+        }
+    }
+
+    fn query_ids(&mut self) -> Result<QueryIds, DbError> {
+        if self.eat_punct("(") {
+            self.expect_ident("search")?;
+            let search = self.search()?;
+            self.expect_punct(")")?;
+            Ok(QueryIds::Search(search))
+        } else {
+            Ok(QueryIds::Ids(self.one_or_list(Self::query_id)?))
+        }
+    }
+
+    fn query_values(&mut self) -> Result<QueryValues, DbError> {
+        if self.eat_punct("[") {
+            Ok(QueryValues::Multi(self.list("]", |p| {
+                p.expect_punct("{")?;
+                p.list("}", Self::key_value)
+            })?))
+        } else {
+            self.expect_punct("{")?;
+            Ok(QueryValues::Single(self.list("}", Self::key_value)?))
+        }
+    }
+
+    fn algorithm(&mut self) -> Result<AnalyticsAlgorithm, DbError> {
+        let error = self.error("unknown analytics algorithm");
+
+        Ok(match self.ident()?.as_str() {
+            "weakly_connected_components" => AnalyticsAlgorithm::WeaklyConnectedComponents,
+            "strongly_connected_components" => AnalyticsAlgorithm::StronglyConnectedComponents,
+            "degree_centrality" => AnalyticsAlgorithm::DegreeCentrality,
+            "betweenness_centrality" => AnalyticsAlgorithm::BetweennessCentrality,
+            "page_rank" => {
+                self.expect_punct("(")?;
+                let damping = self.f64()?;
+                self.expect_punct(",")?;
+                let iterations = self.u64()?;
+                self.expect_punct(")")?;
+                AnalyticsAlgorithm::PageRank(PageRankOptions {
+                    damping,
+                    iterations,
+                })
+            }
+            _ => return Err(error),
+        })
+    }
+
+    fn function(&mut self) -> Result<AggregateFunction, DbError> {
+        let error = self.error("unknown aggregate function");
+
+        Ok(match self.ident()?.as_str() {
+            "count" => AggregateFunction::Count,
+            "sum" => AggregateFunction::Sum,
+            "min" => AggregateFunction::Min,
+            "max" => AggregateFunction::Max,
+            "avg" => AggregateFunction::Avg,
+            _ => return Err(error),
+        })
+    }
+
+    fn update(&mut self) -> Result<KeyValueUpdate, DbError> {
+        let error = self.error("unknown value update");
+        let update: fn(DbValue) -> ValueUpdate = match self.ident()?.as_str() {
+            "increment" => ValueUpdate::Increment,
+            "append" => ValueUpdate::Append,
+            "remove_item" => ValueUpdate::RemoveItem,
+            "set_if_absent" => ValueUpdate::SetIfAbsent,
+            "min" => ValueUpdate::Min,
+            "max" => ValueUpdate::Max,
+            _ => return Err(error),
+        };
+        let key = self.value()?;
+
+        Ok(KeyValueUpdate {
+            key,
+            update: update(self.value()?),
+        })
+    }
+
+    fn query(&mut self) -> Result<QueryType, DbError> {
+        let error = self.error("unknown query");
+
+        match self.ident()?.as_str() {
+            "expect" => {
+                self.expect_ident("ids")?;
+                let ids = self.query_ids()?;
+                let conditions = if self.eat_ident("where") {
+                    self.conditions()?
+                } else {
+                    vec![]
+                };
+
+                Ok(QueryType::Expect(ExpectQuery { ids, conditions }))
+            }
+            "insert" => self.insert(),
+            "remove" => self.remove(),
+            "select" => self.select(),
+            "search" => Ok(QueryType::Search(self.search()?)),
+            "match" => Ok(QueryType::Match(self.match_()?)),
+            _ => Err(error),
+        }
+    }
+
+    fn insert(&mut self) -> Result<QueryType, DbError> {
+        let error = self.error("unknown insert query");
+
+        Ok(match self.ident()?.as_str() {
+            "aliases" => {
+                let aliases = self.aliases()?;
+                self.expect_ident("ids")?;
+                QueryType::InsertAlias(InsertAliasesQuery {
+                    ids: self.query_ids()?,
+                    aliases,
+                })
+            }
+            "analytics" => {
+                let algorithm = self.algorithm()?;
+                let ids = if self.eat_ident("ids") {
+                    self.query_ids()?
+                } else {
+                    QueryIds::Ids(vec![])
+                };
+                QueryType::InsertAnalytics(InsertAnalyticsQuery { ids, algorithm })
+            }
+            "edges" => {
+                let mut query = InsertEdgesQuery {
+                    from: QueryIds::Ids(vec![]),
+                    to: QueryIds::Ids(vec![]),
+                    ids: QueryIds::Ids(vec![]),
+                    values: QueryValues::Single(vec![]),
+                    each: false,
+                    acyclic: false,
+                };
+
+                loop {
+                    if self.eat_ident("from") {
+                        query.from = self.query_ids()?;
+                    } else if self.eat_ident("to") {
+                        query.to = self.query_ids()?;
+                    } else if self.eat_ident("ids") {
+                        query.ids = self.query_ids()?;
+                    } else if self.eat_ident("each") {
+                        query.each = true;
+                    } else if self.eat_ident("acyclic") {
+                        query.acyclic = true;
+                    } else if self.eat_ident("values") {
+                        query.values = self.query_values()?;
+                    } else {
+                        break;
+                    }
+                }
+
+                QueryType::InsertEdges(query)
+            }
+            "index" => {
+                let mut query = InsertIndexQuery {
+                    keys: self.keys()?,
+                    ordered: false,
+                    unique: false,
+                };
+
+                loop {
+                    if self.eat_ident("ordered") {
+                        query.ordered = true;
+                    } else if self.eat_ident("unique") {
+                        query.unique = true;
+                    } else {
+                        break;
+                    }
+                }
+
+                QueryType::InsertIndex(query)
+            }
+            "nodes" => {
+                let mut query = InsertNodesQuery {
+                    count: 0,
+                    values: QueryValues::Single(vec![]),
+                    aliases: vec![],
+                    ids: QueryIds::Ids(vec![]),
+                };
+
+                loop {
+                    if self.eat_ident("count") {
+                        query.count = self.u64()?;
+                    } else if self.eat_ident("aliases") {
+                        query.aliases = self.aliases()?;
+                    } else if self.eat_ident("ids") {
+                        query.ids = self.query_ids()?;
+                    } else if self.eat_ident("values") {
+                        query.values = self.query_values()?;
+                    } else {
+                        break;
+                    }
+                }
+
+                QueryType::InsertNodes(query)
+            }
+            "update" => {
+                let updates = self.one_or_list(Self::update)?;
+                self.expect_ident("ids")?;
+                QueryType::UpdateValues(UpdateValuesQuery {
+                    ids: self.query_ids()?,
+                    updates,
+                })
+            }
+            "values" => {
+                let values = self.query_values()?;
+                self.expect_ident("ids")?;
+                QueryType::InsertValues(InsertValuesQuery {
+                    ids: self.query_ids()?,
+                    values,
+                })
+            }
+            _ => return Err(error),
+        })
+    }
+
+    fn remove(&mut self) -> Result<QueryType, DbError> {
+        let error = self.error("unknown remove query");
+
+        Ok(match self.ident()?.as_str() {
+            "aliases" => QueryType::RemoveAliases(RemoveAliasesQuery(self.aliases()?)),
+            "ids" => QueryType::Remove(RemoveQuery(self.query_ids()?)),
+            "index" => QueryType::RemoveIndex(RemoveIndexQuery(self.keys()?)),
+            "values" => {
+                let keys = self.keys()?;
+                self.expect_ident("ids")?;
+                QueryType::RemoveValues(RemoveValuesQuery(SelectValuesQuery {
+                    keys,
+                    ids: self.query_ids()?,
+                }))
+            }
+            _ => return Err(error),
+        })
+    }
+
+    fn select(&mut self) -> Result<QueryType, DbError> {
+        let error = self.error("unknown select query");
+
+        Ok(match self.ident()?.as_str() {
+            "aggregate" => {
+                let key = self.value()?;
+                let mut functions = ALL_FUNCTIONS.to_vec();
+                let mut group_by = None;
+
+                loop {
+                    if self.eat_ident("functions") {
+                        functions = self.one_or_list(Self::function)?;
+                    } else if self.eat_ident("group_by") {
+                        group_by = Some(self.value()?);
+                    } else {
+                        break;
+                    }
+                }
+
+                self.expect_ident("ids")?;
+                QueryType::Aggregate(AggregateQuery {
+                    ids: self.query_ids()?,
+                    key,
+                    functions,
+                    group_by,
+                })
+            }
+            "aliases" => {
+                if self.eat_ident("ids") {
+                    QueryType::SelectAliases(SelectAliasesQuery(self.query_ids()?))
+                } else {
+                    QueryType::SelectAllAliases(SelectAllAliasesQuery {})
+                }
+            }
+            "analytics" => {
+                let algorithm = self.algorithm()?;
+                let ids = if self.eat_ident("ids") {
+                    self.query_ids()?
+                } else {
+                    QueryIds::Ids(vec![])
+                };
+                QueryType::SelectAnalytics(SelectAnalyticsQuery { ids, algorithm })
+            }
+            "edge_count" => {
+                let from = self.eat_ident("from");
+                let to = self.eat_ident("to");
+                self.expect_ident("ids")?;
+                QueryType::SelectEdgeCount(SelectEdgeCountQuery {
+                    ids: self.query_ids()?,
+                    from,
+                    to,
+                })
+            }
+            "ids" => QueryType::SelectValues(SelectValuesQuery {
+                keys: vec![],
+                ids: self.query_ids()?,
+            }),
+            "indexes" => QueryType::SelectIndexes(SelectIndexesQuery {}),
+            "key_count" => {
+                self.expect_ident("ids")?;
+                QueryType::SelectKeyCount(SelectKeyCountQuery(self.query_ids()?))
+            }
+            "keys" => {
+                self.expect_ident("ids")?;
+                QueryType::SelectKeys(SelectKeysQuery(self.query_ids()?))
+            }
+            "node_count" => QueryType::SelectNodeCount(SelectNodeCountQuery {}),
+            "values" => {
+                let keys = self.keys()?;
+                self.expect_ident("ids")?;
+                QueryType::SelectValues(SelectValuesQuery {
+                    keys,
+                    ids: self.query_ids()?,
+                })
+            }
+            _ => return Err(error),
+        })
+    }
+
+    fn search(&mut self) -> Result<SearchQuery, DbError> {
+        let mut query = SearchQuery::new();
+
+        loop {
+            if self.eat_ident("breadth_first") {
+                query.algorithm = SearchQueryAlgorithm::BreadthFirst;
+            } else if self.eat_ident("depth_first") {
+                query.algorithm = SearchQueryAlgorithm::DepthFirst;
+            } else if self.eat_ident("elements") {
+                query.algorithm = SearchQueryAlgorithm::Elements;
+            } else if self.eat_ident("index") {
+                query.algorithm = SearchQueryAlgorithm::Index;
+            } else if self.eat_ident("topological") {
+                query.algorithm = SearchQueryAlgorithm::Topological;
+            } else if self.eat_ident("shortest_paths") {
+                self.expect_punct("(")?;
+                query.algorithm = SearchQueryAlgorithm::ShortestPaths(self.u64()?);
+                self.expect_punct(")")?;
+            } else if self.eat_ident("all_paths") {
+                self.expect_punct("(")?;
+                query.algorithm = SearchQueryAlgorithm::AllPaths(self.u64()?);
+                self.expect_punct(")")?;
+            } else if let Some(direction) = self.direction() {
+                query.direction = direction;
+            } else if self.eat_ident("from") {
+                query.origin = self.query_id()?;
+            } else if self.eat_ident("to") {
+                query.destination = self.query_id()?;
+            } else if self.eat_ident("order_by") {
+                query.order_by = self.one_or_list(Self::order)?;
+            } else if self.eat_ident("offset") {
+                query.offset = self.u64()?;
+            } else if self.eat_ident("limit") {
+                query.limit = self.u64()?;
+            } else if self.eat_ident("weight") {
+                query.weight = Some(self.value()?);
+            } else if self.eat_ident("after") {
+                query.after = Some(self.string()?);
+            } else if self.eat_ident("where") {
+                query.conditions = self.conditions()?;
+                break;
+            } else {
+                break;
+            }
+        }
+
+        Ok(query)
+    }
+
+    fn direction(&mut self) -> Option<SearchQueryDirection> {
+        if self.eat_ident("forward") {
+            Some(SearchQueryDirection::Forward)
+        } else if self.eat_ident("reverse") {
+            Some(SearchQueryDirection::Reverse)
+        } else if self.eat_ident("both") {
+            Some(SearchQueryDirection::Both)
+        } else {
+            None
+        }
+    }
+
+    fn order(&mut self) -> Result<DbKeyOrder, DbError> {
+        if self.eat_ident("asc") {
+            Ok(DbKeyOrder::Asc(self.value()?))
+        } else if self.eat_ident("desc") {
+            Ok(DbKeyOrder::Desc(self.value()?))
+        } else {
+            Err(self.error("expected 'asc' or 'desc'"))
+        }
+    }
+
+    fn conditions(&mut self) -> Result<Vec<QueryCondition>, DbError> {
+        let mut conditions = vec![];
+        let mut logic = if self.eat_ident("or") {
+            QueryConditionLogic::Or
+        } else {
+            self.eat_ident("and");
+            QueryConditionLogic::And
+        };
+
+        if matches!(
+            self.peek(),
+            Token::End | Token::Punct(")" | "]->" | "]-" | ";")
+        ) {
+            return Ok(conditions);
+        }
+
+        loop {
+            let modifier = if self.eat_ident("not") {
+                QueryConditionModifier::Not
+            } else if self.eat_ident("beyond") {
+                QueryConditionModifier::Beyond
+            } else if self.eat_ident("not_beyond") {
+                QueryConditionModifier::NotBeyond
+            } else {
+                QueryConditionModifier::None
+            };
+
+            conditions.push(QueryCondition {
+                logic,
+                modifier,
+                data: self.condition_data()?,
+            });
+
+            if self.eat_ident("and") {
+                logic = QueryConditionLogic::And;
+            } else if self.eat_ident("or") {
+                logic = QueryConditionLogic::Or;
+            } else {
+                break;
+            }
+        }
+
+        Ok(conditions)
+    }
+
+    fn condition_data(&mut self) -> Result<QueryConditionData, DbError> {
+        if self.eat_punct("(") {
+            let conditions = self.conditions()?;
+            self.expect_punct(")")?;
+            return Ok(QueryConditionData::Where(conditions));
+        }
+
+        let error = self.error("unknown condition");
+
+        Ok(match self.ident()?.as_str() {
+            "adjacent" => {
+                let direction = self
+                    .direction()
+                    .ok_or_else(|| self.error("expected direction"))?;
+                let quantifier = if self.eat_ident("any") {
+                    AdjacentQuantifier::Any
+                } else if self.eat_ident("all") {
+                    AdjacentQuantifier::All
+                } else if self.eat_ident("count") {
+                    AdjacentQuantifier::Count(self.count_comparison()?)
+                } else {
+                    return Err(self.error("expected 'any', 'all' or 'count'"));
+                };
+                self.expect_punct("(")?;
+                let conditions = self.conditions()?;
+                self.expect_punct(")")?;
+                QueryConditionData::Adjacent(AdjacentCondition {
+                    direction,
+                    quantifier,
+                    conditions,
+                })
+            }
+            "distance" => QueryConditionData::Distance(self.count_comparison()?),
+            "edge" => QueryConditionData::Edge,
+            "edge_count" => QueryConditionData::EdgeCount(self.count_comparison()?),
+            "edge_count_from" => QueryConditionData::EdgeCountFrom(self.count_comparison()?),
+            "edge_count_to" => QueryConditionData::EdgeCountTo(self.count_comparison()?),
+            "ids" => QueryConditionData::Ids(self.one_or_list(Self::query_id)?),
+            "key" => {
+                let key = self.value()?;
+                let comparison = self.comparison()?;
+
+                if self.eat_ident("key") {
+                    QueryConditionData::KeyKey(KeyValueComparison {
+                        key,
+                        value: comparison(self.value()?),
+                    })
+                } else {
+                    QueryConditionData::KeyValue(KeyValueComparison {
+                        key,
+                        value: comparison(self.value()?),
+                    })
+                }
+            }
+            "keys" => QueryConditionData::Keys(self.keys()?),
+            "node" => QueryConditionData::Node,
+            _ => return Err(error),
+        })
+    }
+
+    fn count_comparison(&mut self) -> Result<CountComparison, DbError> {
+        let error = self.error("expected comparison");
+
+        let comparison = match self.next() {
+            Token::Punct("=") => CountComparison::Equal,
+            Token::Punct("!=") => CountComparison::NotEqual,
+            Token::Punct(">") => CountComparison::GreaterThan,
+            Token::Punct(">=") => CountComparison::GreaterThanOrEqual,
+            Token::Punct("<") => CountComparison::LessThan,
+            Token::Punct("<=") => CountComparison::LessThanOrEqual,
+            _ => return Err(error),
+        };
+
+        Ok(comparison(self.u64()?))
+    }
+
+    fn comparison(&mut self) -> Result<fn(DbValue) -> Comparison, DbError> {
+        let error = self.error("expected comparison");
+
+        Ok(match self.next() {
+            Token::Punct("=") => Comparison::Equal,
+            Token::Punct("!=") => Comparison::NotEqual,
+            Token::Punct(">") => Comparison::GreaterThan,
+            Token::Punct(">=") => Comparison::GreaterThanOrEqual,
+            Token::Punct("<") => Comparison::LessThan,
+            Token::Punct("<=") => Comparison::LessThanOrEqual,
+            Token::Ident(ident) => match ident.as_str() {
+                "contains" => Comparison::Contains,
+                "starts_with" => Comparison::StartsWith,
+                "ends_with" => Comparison::EndsWith,
+                "equal_ignore_case" => Comparison::EqualIgnoreCase,
+                "contains_ignore_case" => Comparison::ContainsIgnoreCase,
+                "starts_with_ignore_case" => Comparison::StartsWithIgnoreCase,
+                "ends_with_ignore_case" => Comparison::EndsWithIgnoreCase,
+                "matches" => Comparison::Matches,
+                _ => return Err(error),
+            },
+            _ => return Err(error),
+        })
+    }
+
+    fn match_(&mut self) -> Result<MatchQuery, DbError> {
+        let mut query = MatchQuery {
+            node: self.match_node()?,
+            ..Default::default()
+        };
+
+        loop {
+            let (edge, direction) = if self.eat_punct("-[") {
+                let edge = self.match_variable()?;
+
+                if self.eat_punct("]->") {
+                    (edge, SearchQueryDirection::Forward)
+                } else {
+                    self.expect_punct("]-")?;
+                    (edge, SearchQueryDirection::Both)
+                }
+            } else if self.eat_punct("<-[") {
+                let edge = self.match_variable()?;
+                self.expect_punct("]-")?;
+                (edge, SearchQueryDirection::Reverse)
+            } else {
+                break;
+            };
+
+            query.steps.push(MatchStep {
+                direction,
+                edge,
+                node: self.match_node()?,
+            });
+        }
+
+        loop {
+            if self.eat_ident("offset") {
+                query.offset = self.u64()?;
+            } else if self.eat_ident("limit") {
+                query.limit = self.u64()?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(query)
+    }
+
+    fn match_node(&mut self) -> Result<MatchVariable, DbError> {
+        self.expect_punct("(")?;
+        let variable = self.match_variable()?;
+        self.expect_punct(")")?;
+        Ok(variable)
+    }
+
+    fn match_variable(&mut self) -> Result<MatchVariable, DbError> {
+        let name = match self.peek().clone() {
+            Token::String(name) => {
+                self.pos += 1;
+                name
+            }
+            Token::Ident(name) if name != "where" => {
+                self.pos += 1;
+                name
+            }
+            _ => String::new(),
+        };
+
+        let conditions = if self.eat_ident("where") {
+            self.conditions()?
+        } else {
+            vec![]
+        };
+
+        Ok(MatchVariable { name, conditions })
+    }
+}
+
+fn join<T>(
+    f: &mut Formatter<'_>,
+    items: &[T],
+    mut item: impl FnMut(&mut Formatter<'_>, &T) -> FmtResult,
+) -> FmtResult {
+    for (i, value) in items.iter().enumerate() {
+        if i != 0 {
+            write!(f, ", ")?;
+        }
+
+        item(f, value)?;
+    }
+
+    Ok(())
+}
+
+fn write_list<T>(
+    f: &mut Formatter<'_>,
+    items: &[T],
+    item: impl FnMut(&mut Formatter<'_>, &T) -> FmtResult,
+) -> FmtResult {
+    write!(f, "[")?;
+    join(f, items, item)?;
+    write!(f, "]")
+}
+
+fn write_f64(f: &mut Formatter<'_>, value: f64) -> FmtResult {
+    if value.is_nan() {
+        write!(f, "nan")
+    } else if value == f64::INFINITY {
+        write!(f, "inf")
+    } else if value == f64::NEG_INFINITY {
+        write!(f, "-inf")
+    } else {
+        write!(f, "{value:?}")
+    }
+}
+
+fn write_value(f: &mut Formatter<'_>, value: &DbValue) -> FmtResult {
+    match value {
+        DbValue::Bytes(value) => {
+            write!(f, "0x")?;
+            value.iter().try_for_each(|b| write!(f, "{b:02x}"))
+        }
+        DbValue::I64(value) => write!(f, "{value}"),
+        DbValue::U64(value) => write!(f, "{value}u"),
+        DbValue::F64(value) => write_f64(f, value.to_f64()),
+        DbValue::String(value) => write!(f, "{value:?}"),
+        DbValue::VecI64(values) => write_list(f, values, |f, v| write!(f, "{v}")),
+        DbValue::VecU64(values) if values.is_empty() => write!(f, "u64[]"),
+        DbValue::VecU64(values) => write_list(f, values, |f, v| write!(f, "{v}u")),
+        DbValue::VecF64(values) if values.is_empty() => write!(f, "f64[]"),
+        DbValue::VecF64(values) => write_list(f, values, |f, v| write_f64(f, v.to_f64())),
+        DbValue::VecString(values) if values.is_empty() => write!(f, "string[]"),
+        DbValue::VecString(values) => write_list(f, values, |f, v| write!(f, "{v:?}")),
+        DbValue::Bool(value) => write!(f, "{value}"),
+        DbValue::DateTime(value) => write!(f, "datetime({value})"),
+        DbValue::List(values) => {
+            write!(f, "list")?;
+            write_list(f, values, write_value)
+        }
+        DbValue::Map(values) => write_key_values(f, values),
+    }
+}
+
+fn write_key_values(f: &mut Formatter<'_>, values: &[DbKeyValue]) -> FmtResult {
+    write!(f, "{{")?;
+    join(f, values, |f, kv| {
+        write_value(f, &kv.key)?;
+        write!(f, ": ")?;
+        write_value(f, &kv.value)
+    })?;
+    write!(f, "}}")
+}
+
+fn write_query_values(f: &mut Formatter<'_>, values: &QueryValues) -> FmtResult {
+    match values {
+        QueryValues::Single(values) => write_key_values(f, values),
+        QueryValues::Multi(values) => write_list(f, values, |f, v| write_key_values(f, v)),
+    }
+}
+
+fn write_query_id(f: &mut Formatter<'_>, id: &QueryId) -> FmtResult {
+    match id {
+        QueryId::Id(id) => write!(f, "{}", id.0),
+        QueryId::Alias(alias) => write!(f, "{alias:?}"),
+    }
+}
+
+fn write_query_ids(f: &mut Formatter<'_>, ids: &QueryIds) -> FmtResult {
+    match ids {
+        QueryIds::Ids(ids) if ids.len() == 1 => write_query_id(f, &ids[0]),
+        QueryIds::Ids(ids) => write_list(f, ids, write_query_id),
+        QueryIds::Search(search) => {
+            write!(f, "(")?;
+            write_search(f, search)?;
+            write!(f, ")")
+        }
+    }
+}
+
+fn write_aliases(f: &mut Formatter<'_>, aliases: &[String]) -> FmtResult {
+    write_list(f, aliases, |f, alias| write!(f, "{alias:?}"))
+}
+
+fn write_keys(f: &mut Formatter<'_>, keys: &[DbValue]) -> FmtResult {
+    write_list(f, keys, write_value)
+}
+
+fn write_algorithm(f: &mut Formatter<'_>, algorithm: &AnalyticsAlgorithm) -> FmtResult {
+    match algorithm {
+        AnalyticsAlgorithm::WeaklyConnectedComponents => write!(f, "weakly_connected_components"),
+        AnalyticsAlgorithm::StronglyConnectedComponents => {
+            write!(f, "strongly_connected_components")
+        }
+        AnalyticsAlgorithm::PageRank(options) => {
+            write!(f, "page_rank(")?;
+            write_f64(f, options.damping)?;
+            write!(f, ", {})", options.iterations)
+        }
+        AnalyticsAlgorithm::DegreeCentrality => write!(f, "degree_centrality"),
+        AnalyticsAlgorithm::BetweennessCentrality => write!(f, "betweenness_centrality"),
+    }
+}
+
+fn write_analytics_ids(f: &mut Formatter<'_>, ids: &QueryIds) -> FmtResult {
+    if *ids != QueryIds::Ids(vec![]) {
+        write!(f, " ids ")?;
+        write_query_ids(f, ids)?;
+    }
+
+    Ok(())
+}
+
+fn write_function(f: &mut Formatter<'_>, function: &AggregateFunction) -> FmtResult {
+    match function {
+        AggregateFunction::Count => write!(f, "count"),
+        AggregateFunction::Sum => write!(f, "sum"),
+        AggregateFunction::Min => write!(f, "min"),
+        AggregateFunction::Max => write!(f, "max"),
+        AggregateFunction::Avg => write!(f, "avg"),
+    }
+}
+
+fn write_update(f: &mut Formatter<'_>, update: &KeyValueUpdate) -> FmtResult {
+    let (name, value) = match &update.update {
+        ValueUpdate::Increment(value) => ("increment", value),
+        ValueUpdate::Append(value) => ("append", value),
+        ValueUpdate::RemoveItem(value) => ("remove_item", value),
+        ValueUpdate::SetIfAbsent(value) => ("set_if_absent", value),
+        ValueUpdate::Min(value) => ("min", value),
+        ValueUpdate::Max(value) => ("max", value),
+    };
+    write!(f, "{name} ")?;
+    write_value(f, &update.key)?;
+    write!(f, " ")?;
+    write_value(f, value)
+}
+
+fn write_direction(f: &mut Formatter<'_>, direction: &SearchQueryDirection) -> FmtResult {
+    match direction {
+        SearchQueryDirection::Forward => write!(f, "forward"),
+        SearchQueryDirection::Reverse => write!(f, "reverse"),
+        SearchQueryDirection::Both => write!(f, "both"),
+    }
+}
+
+fn write_search(f: &mut Formatter<'_>, search: &SearchQuery) -> FmtResult {
+    write!(f, "search")?;
+
+    match search.algorithm {
+        SearchQueryAlgorithm::BreadthFirst => {}
+        SearchQueryAlgorithm::DepthFirst => write!(f, " depth_first")?,
+        SearchQueryAlgorithm::Index => write!(f, " index")?,
+        SearchQueryAlgorithm::Elements => write!(f, " elements")?,
+        SearchQueryAlgorithm::ShortestPaths(k) => write!(f, " shortest_paths({k})")?,
+        SearchQueryAlgorithm::AllPaths(depth) => write!(f, " all_paths({depth})")?,
+        SearchQueryAlgorithm::Topological => write!(f, " topological")?,
+    }
+
+    if search.direction != SearchQueryDirection::Forward {
+        write!(f, " ")?;
+        write_direction(f, &search.direction)?;
+    }
+
+    if search.origin != QueryId::Id(DbId(0)) {
+        write!(f, " from ")?;
+        write_query_id(f, &search.origin)?;
+    }
+
+    if search.destination != QueryId::Id(DbId(0)) {
+        write!(f, " to ")?;
+        write_query_id(f, &search.destination)?;
+    }
+
+    if !search.order_by.is_empty() {
+        write!(f, " order_by ")?;
+        write_list(f, &search.order_by, |f, order| match order {
+            DbKeyOrder::Asc(key) => {
+                write!(f, "asc ")?;
+                write_value(f, key)
+            }
+            DbKeyOrder::Desc(key) => {
+                write!(f, "desc ")?;
+                write_value(f, key)
+            }
+        })?;
+    }
+
+    if search.offset != 0 {
+        write!(f, " offset {}", search.offset)?;
+    }
+
+    if search.limit != 0 {
+        write!(f, " limit {}", search.limit)?;
+    }
+
+    if let Some(weight) = &search.weight {
+        write!(f, " weight ")?;
+        write_value(f, weight)?;
+    }
+
+    if let Some(after) = &search.after {
+        write!(f, " after {after:?}")?;
+    }
+
+    if !search.conditions.is_empty() {
+        write!(f, " where ")?;
+        write_conditions(f, &search.conditions)?;
+    }
+
+    Ok(())
+}
+
+fn write_conditions(f: &mut Formatter<'_>, conditions: &[QueryCondition]) -> FmtResult {
+    for (i, condition) in conditions.iter().enumerate() {
+        match condition.logic {
+            QueryConditionLogic::And if i != 0 => write!(f, " and ")?,
+            QueryConditionLogic::And => {}
+            QueryConditionLogic::Or if i != 0 => write!(f, " or ")?,
+            QueryConditionLogic::Or => write!(f, "or ")?,
+        }
+
+        match condition.modifier {
+            QueryConditionModifier::None => {}
+            QueryConditionModifier::Beyond => write!(f, "beyond ")?,
+            QueryConditionModifier::Not => write!(f, "not ")?,
+            QueryConditionModifier::NotBeyond => write!(f, "not_beyond ")?,
+        }
+
+        write_condition_data(f, &condition.data)?;
+    }
+
+    Ok(())
+}
+
+fn write_condition_data(f: &mut Formatter<'_>, data: &QueryConditionData) -> FmtResult {
+    match data {
+        QueryConditionData::Distance(comparison) => {
+            write!(f, "distance ")?;
+            write_count_comparison(f, comparison)
+        }
+        QueryConditionData::Edge => write!(f, "edge"),
+        QueryConditionData::EdgeCount(comparison) => {
+            write!(f, "edge_count ")?;
+            write_count_comparison(f, comparison)
+        }
+        QueryConditionData::EdgeCountFrom(comparison) => {
+            write!(f, "edge_count_from ")?;
+            write_count_comparison(f, comparison)
+        }
+        QueryConditionData::EdgeCountTo(comparison) => {
+            write!(f, "edge_count_to ")?;
+            write_count_comparison(f, comparison)
+        }
+        QueryConditionData::Ids(ids) => {
+            write!(f, "ids ")?;
+            write_list(f, ids, write_query_id)
+        }
+        QueryConditionData::KeyValue(comparison) => {
+            write!(f, "key ")?;
+            write_value(f, &comparison.key)?;
+            write!(f, " ")?;
+            write_comparison(f, &comparison.value, "")
+        }
+        QueryConditionData::Keys(keys) => {
+            write!(f, "keys ")?;
+            write_keys(f, keys)
+        }
+        QueryConditionData::Node => write!(f, "node"),
+        QueryConditionData::Where(conditions) => {
+            write!(f, "(")?;
+            write_conditions(f, conditions)?;
+            write!(f, ")")
+        }
+        QueryConditionData::KeyKey(comparison) => {
+            write!(f, "key ")?;
+            write_value(f, &comparison.key)?;
+            write!(f, " ")?;
+            write_comparison(f, &comparison.value, "key ")
+        }
+        QueryConditionData::Adjacent(adjacent) => {
+            write!(f, "adjacent ")?;
+            write_direction(f, &adjacent.direction)?;
+
+            match &adjacent.quantifier {
+                AdjacentQuantifier::Any => write!(f, " any")?,
+                AdjacentQuantifier::All => write!(f, " all")?,
+                AdjacentQuantifier::Count(comparison) => {
+                    write!(f, " count ")?;
+                    write_count_comparison(f, comparison)?;
+                }
+            }
+
+            write!(f, " (")?;
+            write_conditions(f, &adjacent.conditions)?;
+            write!(f, ")")
+        }
+    }
+}
+
+fn write_count_comparison(f: &mut Formatter<'_>, comparison: &CountComparison) -> FmtResult {
+    match comparison {
+        CountComparison::Equal(value) => write!(f, "= {value}"),
+        CountComparison::GreaterThan(value) => write!(f, "> {value}"),
+        CountComparison::GreaterThanOrEqual(value) => write!(f, ">= {value}"),
+        CountComparison::LessThan(value) => write!(f, "< {value}"),
+        CountComparison::LessThanOrEqual(value) => write!(f, "<= {value}"),
+        CountComparison::NotEqual(value) => write!(f, "!= {value}"),
+    }
+}
+
+fn write_comparison(f: &mut Formatter<'_>, comparison: &Comparison, prefix: &str) -> FmtResult {
+    let (operator, value) = match comparison {
+        Comparison::Equal(value) => ("=", value),
+        Comparison::GreaterThan(value) => (">", value),
+        Comparison::GreaterThanOrEqual(value) => (">=", value),
+        Comparison::LessThan(value) => ("<", value),
+        Comparison::LessThanOrEqual(value) => ("<=", value),
+        Comparison::NotEqual(value) => ("!=", value),
+        Comparison::Contains(value) => ("contains", value),
+        Comparison::StartsWith(value) => ("starts_with", value),
+        Comparison::EndsWith(value) => ("ends_with", value),
+        Comparison::EqualIgnoreCase(value) => ("equal_ignore_case", value),
+        Comparison::ContainsIgnoreCase(value) => ("contains_ignore_case", value),
+        Comparison::StartsWithIgnoreCase(value) => ("starts_with_ignore_case", value),
+        Comparison::EndsWithIgnoreCase(value) => ("ends_with_ignore_case", value),
+        Comparison::Matches(value) => ("matches", value),
+    };
+
+    write!(f, "{operator} {prefix}")?;
+    write_value(f, value)
+}
+
+fn write_match_variable(f: &mut Formatter<'_>, variable: &MatchVariable) -> FmtResult {
+    let mut chars = variable.name.chars();
+    let identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && variable.name != "where";
+
+    if identifier {
+        write!(f, "{}", variable.name)?;
+    } else if !variable.name.is_empty() {
+        write!(f, "{:?}", variable.name)?;
+    }
+
+    if !variable.conditions.is_empty() {
+        if !variable.name.is_empty() {
+            write!(f, " ")?;
+        }
+
+        write!(f, "where ")?;
+        write_conditions(f, &variable.conditions)?;
+    }
+
+    Ok(())
+}
+
+fn write_match(f: &mut Formatter<'_>, query: &MatchQuery) -> FmtResult {
+    write!(f, "match (")?;
+    write_match_variable(f, &query.node)?;
+    write!(f, ")")?;
+
+    for step in &query.steps {
+        match step.direction {
+            SearchQueryDirection::Forward => write!(f, " -[")?,
+            SearchQueryDirection::Reverse => write!(f, " <-[")?,
+            SearchQueryDirection::Both => write!(f, " -[")?,
+        }
+
+        write_match_variable(f, &step.edge)?;
+
+        match step.direction {
+            SearchQueryDirection::Forward => write!(f, "]-> (")?,
+            SearchQueryDirection::Reverse | SearchQueryDirection::Both => write!(f, "]- (")?,
+        }
+
+        write_match_variable(f, &step.node)?;
+        write!(f, ")")?;
+    }
+
+    if query.offset != 0 {
+        write!(f, " offset {}", query.offset)?;
+    }
+
+    if query.limit != 0 {
+        write!(f, " limit {}", query.limit)?;
+    }
+
+    Ok(())
+}
+
+impl Display for QueryType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            QueryType::Aggregate(query) => {
+                write!(f, "select aggregate ")?;
+                write_value(f, &query.key)?;
+
+                if query.functions != ALL_FUNCTIONS {
+                    write!(f, " functions ")?;
+                    write_list(f, &query.functions, write_function)?;
+                }
+
+                if let Some(group_by) = &query.group_by {
+                    write!(f, " group_by ")?;
+                    write_value(f, group_by)?;
+                }
+
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::Expect(query) => {
+                write!(f, "expect ids ")?;
+                write_query_ids(f, &query.ids)?;
+
+                if !query.conditions.is_empty() {
+                    write!(f, " where ")?;
+                    write_conditions(f, &query.conditions)?;
+                }
+
+                Ok(())
+            }
+            QueryType::InsertAlias(query) => {
+                write!(f, "insert aliases ")?;
+                write_aliases(f, &query.aliases)?;
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::InsertAnalytics(query) => {
+                write!(f, "insert analytics ")?;
+                write_algorithm(f, &query.algorithm)?;
+                write_analytics_ids(f, &query.ids)
+            }
+            QueryType::InsertEdges(query) => {
+                write!(f, "insert edges from ")?;
+                write_query_ids(f, &query.from)?;
+                write!(f, " to ")?;
+                write_query_ids(f, &query.to)?;
+
+                if query.ids != QueryIds::Ids(vec![]) {
+                    write!(f, " ids ")?;
+                    write_query_ids(f, &query.ids)?;
+                }
+
+                if query.each {
+                    write!(f, " each")?;
+                }
+
+                if query.acyclic {
+                    write!(f, " acyclic")?;
+                }
+
+                if query.values != QueryValues::Single(vec![]) {
+                    write!(f, " values ")?;
+                    write_query_values(f, &query.values)?;
+                }
+
+                Ok(())
+            }
+            QueryType::InsertIndex(query) => {
+                write!(f, "insert index ")?;
+                write_keys(f, &query.keys)?;
+
+                if query.ordered {
+                    write!(f, " ordered")?;
+                }
+
+                if query.unique {
+                    write!(f, " unique")?;
+                }
+
+                Ok(())
+            }
+            QueryType::InsertNodes(query) => {
+                write!(f, "insert nodes")?;
+
+                if query.count != 0 {
+                    write!(f, " count {}", query.count)?;
+                }
+
+                if !query.aliases.is_empty() {
+                    write!(f, " aliases ")?;
+                    write_aliases(f, &query.aliases)?;
+                }
+
+                if query.ids != QueryIds::Ids(vec![]) {
+                    write!(f, " ids ")?;
+                    write_query_ids(f, &query.ids)?;
+                }
+
+                if query.values != QueryValues::Single(vec![]) {
+                    write!(f, " values ")?;
+                    write_query_values(f, &query.values)?;
+                }
+
+                Ok(())
+            }
+            QueryType::InsertValues(query) => {
+                write!(f, "insert values ")?;
+                write_query_values(f, &query.values)?;
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::Match(query) => write_match(f, query),
+            QueryType::Remove(query) => {
+                write!(f, "remove ids ")?;
+                write_query_ids(f, &query.0)
+            }
+            QueryType::RemoveAliases(query) => {
+                write!(f, "remove aliases ")?;
+                write_aliases(f, &query.0)
+            }
+            QueryType::RemoveIndex(query) => {
+                write!(f, "remove index ")?;
+                write_keys(f, &query.0)
+            }
+            QueryType::RemoveValues(query) => {
+                write!(f, "remove values ")?;
+                write_keys(f, &query.0.keys)?;
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.0.ids)
+            }
+            QueryType::Search(query) => write_search(f, query),
+            QueryType::SelectAliases(query) => {
+                write!(f, "select aliases ids ")?;
+                write_query_ids(f, &query.0)
+            }
+            QueryType::SelectAllAliases(_) => write!(f, "select aliases"),
+            QueryType::SelectAnalytics(query) => {
+                write!(f, "select analytics ")?;
+                write_algorithm(f, &query.algorithm)?;
+                write_analytics_ids(f, &query.ids)
+            }
+            QueryType::SelectEdgeCount(query) => {
+                write!(f, "select edge_count")?;
+
+                if query.from {
+                    write!(f, " from")?;
+                }
+
+                if query.to {
+                    write!(f, " to")?;
+                }
+
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::SelectIndexes(_) => write!(f, "select indexes"),
+            QueryType::SelectKeys(query) => {
+                write!(f, "select keys ids ")?;
+                write_query_ids(f, &query.0)
+            }
+            QueryType::SelectKeyCount(query) => {
+                write!(f, "select key_count ids ")?;
+                write_query_ids(f, &query.0)
+            }
+            QueryType::SelectNodeCount(_) => write!(f, "select node_count"),
+            QueryType::SelectValues(query) if query.keys.is_empty() => {
+                write!(f, "select ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::SelectValues(query) => {
+                write!(f, "select values ")?;
+                write_keys(f, &query.keys)?;
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+            QueryType::UpdateValues(query) => {
+                write!(f, "insert update ")?;
+                write_list(f, &query.updates, write_update)?;
+                write!(f, " ids ")?;
+                write_query_ids(f, &query.ids)
+            }
+        }
+    }
+}
@@ -5,14 +5,14 @@ use crate::QueryMut;
 use crate::QueryResult;
 use crate::StorageData;
 
-/// Query to create a new index on
-/// a given key.
+/// Query to remove an index on
+/// a given key or keys.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
 #[derive(Clone, Debug, PartialEq)]
-pub struct RemoveIndexQuery(pub DbValue);
+pub struct RemoveIndexQuery(pub Vec<DbValue>);
 
 impl QueryMut for RemoveIndexQuery {
     fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
@@ -21,6 +21,7 @@ impl QueryMut for RemoveIndexQuery {
         Ok(QueryResult {
             result,
             elements: vec![],
+            cursor: None,
         })
     }
 }
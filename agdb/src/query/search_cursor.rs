@@ -0,0 +1,292 @@
+use crate::DbId;
+use crate::DbKeyValue;
+use crate::DbValue;
+use crate::utilities::serialize::Serialize;
+
+/// Deepest nesting of lists and maps in the values of a cursor key.
+/// It matches the default recursion limit of the JSON parsers so the
+/// cursors of any values received over the network can be read back.
+const MAX_DEPTH: usize = 128;
+
+const NONE: u8 = 0;
+const BYTES: u8 = 1;
+const I64: u8 = 2;
+const U64: u8 = 3;
+const F64: u8 = 4;
+const STRING: u8 = 5;
+const VEC_I64: u8 = 6;
+const VEC_U64: u8 = 7;
+const VEC_F64: u8 = 8;
+const VEC_STRING: u8 = 9;
+const BOOL: u8 = 10;
+const DATE_TIME: u8 = 11;
+const LIST: u8 = 12;
+const MAP: u8 = 13;
+
+/// Last element returned by a search recorded in its cursor along
+/// with the values of the keys the elements are ordered by (`None`
+/// for the keys the element does not have). The cursor text is the
+/// fingerprint of the query, the id and the hex encoded values
+/// separated by `:`.
+pub(crate) struct SearchCursor {
+    pub(crate) id: DbId,
+    pub(crate) key: Vec<Option<DbValue>>,
+}
+
+impl SearchCursor {
+    pub(crate) fn to_text(&self, fingerprint: u64) -> String {
+        let mut bytes = vec![];
+
+        for value in &self.key {
+            match value {
+                Some(value) => write_value(&mut bytes, value),
+                None => bytes.push(NONE),
+            }
+        }
+
+        let key = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+        format!("{fingerprint:x}:{}:{key}", self.id.0)
+    }
+
+    /// Parses the cursor `text` returning the fingerprint of the query
+    /// that created it and the cursor or `None` if it is malformed.
+    pub(crate) fn parse(text: &str) -> Option<(u64, Self)> {
+        let mut parts = text.split(':');
+        let (Some(fingerprint), Some(id), Some(key), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+
+        let fingerprint = u64::from_str_radix(fingerprint, 16).ok()?;
+        let id = DbId(id.parse().ok()?);
+
+        if key.len() % 2 != 0 || !key.is_ascii() {
+            return None;
+        }
+
+        let bytes = (0..key.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&key[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let mut reader = Reader { bytes: &bytes };
+        let mut values = vec![];
+
+        while !reader.bytes.is_empty() {
+            values.push(if reader.bytes[0] == NONE {
+                reader.take(1)?;
+                None
+            } else {
+                Some(reader.value(0)?)
+            });
+        }
+
+        Some((fingerprint, Self { id, key: values }))
+    }
+}
+
+fn write_len(bytes: &mut Vec<u8>, len: usize) {
+    bytes.extend(len.serialize());
+}
+
+fn write_value(bytes: &mut Vec<u8>, value: &DbValue) {
+    match value {
+        DbValue::Bytes(value) => {
+            bytes.push(BYTES);
+            write_len(bytes, value.len());
+            bytes.extend(value);
+        }
+        DbValue::I64(value) => {
+            bytes.push(I64);
+            bytes.extend(value.serialize());
+        }
+        DbValue::U64(value) => {
+            bytes.push(U64);
+            bytes.extend(value.serialize());
+        }
+        DbValue::F64(value) => {
+            bytes.push(F64);
+            bytes.extend(value.to_f64().serialize());
+        }
+        DbValue::String(value) => {
+            bytes.push(STRING);
+            bytes.extend(value.serialize());
+        }
+        DbValue::VecI64(values) => {
+            bytes.push(VEC_I64);
+            write_len(bytes, values.len());
+            values.iter().for_each(|v| bytes.extend(v.serialize()));
+        }
+        DbValue::VecU64(values) => {
+            bytes.push(VEC_U64);
+            write_len(bytes, values.len());
+            values.iter().for_each(|v| bytes.extend(v.serialize()));
+        }
+        DbValue::VecF64(values) => {
+            bytes.push(VEC_F64);
+            write_len(bytes, values.len());
+            values
+                .iter()
+                .for_each(|v| bytes.extend(v.to_f64().serialize()));
+        }
+        DbValue::VecString(values) => {
+            bytes.push(VEC_STRING);
+            write_len(bytes, values.len());
+            values.iter().for_each(|v| bytes.extend(v.serialize()));
+        }
+        DbValue::Bool(value) => {
+            bytes.push(BOOL);
+            bytes.extend(value.serialize());
+        }
+        DbValue::DateTime(value) => {
+            bytes.push(DATE_TIME);
+            bytes.extend(value.serialize());
+        }
+        DbValue::List(values) => {
+            bytes.push(LIST);
+            write_len(bytes, values.len());
+            values.iter().for_each(|v| write_value(bytes, v));
+        }
+        DbValue::Map(values) => {
+            bytes.push(MAP);
+            write_len(bytes, values.len());
+            values.iter().for_each(|kv| {
+                write_value(bytes, &kv.key);
+                write_value(bytes, &kv.value);
+            });
+        }
+    }
+}
+
+/// Reads the values written by `write_value()` checking every
+/// length against the remaining bytes as the cursors come from
+/// the clients.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn read<T: Serialize>(&mut self, len: usize) -> Option<T> {
+        T::deserialize(self.take(len)?).ok()
+    }
+
+    /// Reads a length of a sequence whose elements take
+    /// at least `min_size` bytes each.
+    fn len(&mut self, min_size: usize) -> Option<usize> {
+        let len = self.read::<usize>(8)?;
+        (len.checked_mul(min_size)? <= self.bytes.len()).then_some(len)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.len(1)?;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+
+    fn values<T>(
+        &mut self,
+        min_size: usize,
+        mut value: impl FnMut(&mut Self) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        (0..self.len(min_size)?).map(|_| value(self)).collect()
+    }
+
+    fn value(&mut self, depth: usize) -> Option<DbValue> {
+        if MAX_DEPTH < depth {
+            return None;
+        }
+
+        Some(match self.take(1)?[0] {
+            BYTES => {
+                let len = self.len(1)?;
+                DbValue::Bytes(self.take(len)?.to_vec())
+            }
+            I64 => DbValue::I64(self.read(8)?),
+            U64 => DbValue::U64(self.read(8)?),
+            F64 => DbValue::F64(self.read::<f64>(8)?.into()),
+            STRING => DbValue::String(self.string()?),
+            VEC_I64 => DbValue::VecI64(self.values(8, |r| r.read(8))?),
+            VEC_U64 => DbValue::VecU64(self.values(8, |r| r.read(8))?),
+            VEC_F64 => DbValue::VecF64(self.values(8, |r| r.read::<f64>(8).map(Into::into))?),
+            VEC_STRING => DbValue::VecString(self.values(8, Self::string)?),
+            BOOL => DbValue::Bool(self.read(1)?),
+            DATE_TIME => DbValue::DateTime(self.read(8)?),
+            LIST => DbValue::List(self.values(1, |r| r.value(depth + 1))?),
+            MAP => DbValue::Map(self.values(2, |r| {
+                Some(DbKeyValue {
+                    key: r.value(depth + 1)?,
+                    value: r.value(depth + 1)?,
+                })
+            })?),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let key = vec![
+            Some(DbValue::from(vec![1_u8, 2])),
+            None,
+            Some((-1_i64).into()),
+            Some(2_u64.into()),
+            Some(2.5.into()),
+            Some("a:b".into()),
+            Some(vec![1_i64, 2].into()),
+            Some(vec![1_u64].into()),
+            Some(vec![1.5].into()),
+            Some(vec!["a", "b"].into()),
+            Some(true.into()),
+            Some(DbValue::DateTime(-5)),
+            Some(DbValue::List(vec![1.into(), DbValue::List(vec![])])),
+            Some(DbValue::Map(vec![("k", "v").into()])),
+        ];
+        let cursor = SearchCursor {
+            id: DbId(-3),
+            key: key.clone(),
+        };
+
+        let (fingerprint, parsed) = SearchCursor::parse(&cursor.to_text(0xabc)).unwrap();
+
+        assert_eq!(fingerprint, 0xabc);
+        assert_eq!(parsed.id, DbId(-3));
+        assert_eq!(parsed.key, key);
+    }
+
+    #[test]
+    fn malformed() {
+        let text = SearchCursor {
+            id: DbId(1),
+            key: vec![Some("abc".into())],
+        }
+        .to_text(1);
+
+        assert!(SearchCursor::parse(&text).is_some());
+        assert!(SearchCursor::parse("1:1").is_none());
+        assert!(SearchCursor::parse("1:1::").is_none());
+        assert!(SearchCursor::parse("x:1:").is_none());
+        assert!(SearchCursor::parse("1:x:").is_none());
+        assert!(SearchCursor::parse("1:1:0").is_none());
+        assert!(SearchCursor::parse("1:1:zz").is_none());
+        assert!(SearchCursor::parse("1:1:0e").is_none());
+        assert!(SearchCursor::parse(&text[..text.len() - 2]).is_none());
+        assert!(SearchCursor::parse("1:1:05ffffffffffffffff").is_none());
+        assert!(
+            SearchCursor::parse(&format!("1:1:{}", "0c0100000000000000".repeat(200))).is_none()
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Comparison;
 use crate::DbElement;
 use crate::DbError;
 use crate::DbErrorType;
@@ -7,12 +8,22 @@ use crate::DbValue;
 use crate::Query;
 use crate::QueryCondition;
 use crate::QueryConditionData;
+use crate::QueryConditionLogic;
+use crate::QueryConditionModifier;
 use crate::QueryId;
 use crate::QueryResult;
 use crate::StorageData;
+use crate::db::cursor_element_not_found;
 use crate::db::db_key_order::DbKeyOrder;
+use crate::query::query_condition::KeyValueComparison;
+use crate::query::query_condition::condition_patterns;
+use crate::query::search_cursor::SearchCursor;
 use crate::query_builder::search::SearchQueryBuilder;
+use crate::utilities::regex::PatternScope;
 use std::cmp::Ordering;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 
 /// Search algorithm to be used
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -33,12 +44,67 @@ pub enum SearchQueryAlgorithm {
     DepthFirst,
 
     /// Bypasses the graph traversal and inspects only the index specified
-    /// as the first condition (key).
+    /// as the first condition (key). Ordered indexes support range
+    /// comparisons and are walked in the index order when the result is
+    /// ordered by the indexed key, applying `offset` and `limit` as they go.
     Index,
 
     /// Examines all elements in the database disregarding the graph structure
     /// or any relationship between the elements.
     Elements,
+
+    /// Finds up to `k` (0 = all) simple paths between the origin and the
+    /// destination from the shortest (or lightest if weighted) one. Each
+    /// element is returned with the `path` it belongs to so elements shared
+    /// by multiple paths are repeated. The `offset` and `limit` apply to
+    /// the paths rather than the elements.
+    ShortestPaths(u64),
+
+    /// Finds all simple paths between the origin and the destination of
+    /// at most given number of edges (0 = unlimited). Each element is
+    /// returned with the `path` it belongs to so elements shared by
+    /// multiple paths are repeated. The `offset` and `limit` apply to
+    /// the paths rather than the elements.
+    AllPaths(u64),
+
+    /// Orders the nodes reachable from the origin (or reaching the
+    /// destination) so that every edge leads from an earlier node to
+    /// a later one (dependencies come last). Edges are not returned.
+    /// Fails with `DbErrorType::Cycle` listing the elements of a cycle
+    /// if there is any among the reachable nodes.
+    Topological,
+}
+
+/// Direction in which the search follows the edges.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SearchQueryDirection {
+    /// Follows the edges in their direction: from the origin along
+    /// the outgoing edges (from->to) or towards the destination along
+    /// the incoming edges when only the destination is set.
+    #[default]
+    Forward,
+
+    /// Follows the edges against their direction, i.e. the opposite
+    /// of `Forward`.
+    Reverse,
+
+    /// Follows both outgoing and incoming edges treating them as
+    /// undirected.
+    Both,
+}
+
+impl SearchQueryDirection {
+    pub(crate) fn reversed(self) -> Self {
+        match self {
+            SearchQueryDirection::Forward => SearchQueryDirection::Reverse,
+            SearchQueryDirection::Reverse => SearchQueryDirection::Forward,
+            SearchQueryDirection::Both => SearchQueryDirection::Both,
+        }
+    }
 }
 
 /// Query to search for ids in the database following the graph.
@@ -52,6 +118,11 @@ pub struct SearchQuery {
     /// searches that unconditionally use A*.
     pub algorithm: SearchQueryAlgorithm,
 
+    /// Direction in which the edges are followed by the graph
+    /// traversal and the path searches. Defaults to `Forward`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub direction: SearchQueryDirection,
+
     /// Starting element of the search.
     pub origin: QueryId,
 
@@ -73,13 +144,42 @@ pub struct SearchQuery {
     /// Set of conditions every element must satisfy to be included in the
     /// result. Some conditions also influence the search path as well.
     pub conditions: Vec<QueryCondition>,
+
+    /// Key of a numeric value of nodes and edges whose sum the path
+    /// search minimises. Elements without the key weigh nothing.
+    /// Each element of the found path is returned with the `cost`
+    /// of the path up to and including that element.
+    pub weight: Option<DbValue>,
+
+    /// Continuation cursor returned in `QueryResult::cursor` by a previous
+    /// run of the same query. It records the last element returned by that
+    /// run along with the values it was ordered by (the index values for
+    /// index searches) and the search resumes right after it skipping
+    /// further `offset` elements. Index and ordered searches resume from
+    /// the recorded values so the database can be modified in between.
+    /// Unordered graph traversals are walked again up to the recorded
+    /// element and fail if it is no longer reached. Fails if the cursor
+    /// was created by a different query. Not supported by path searches.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub after: Option<String>,
 }
 
 impl Query for SearchQuery {
     fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let _patterns = self.pattern_scope()?;
+        let cursor = self.search_cursor()?;
+
+        if self.is_paths_search() {
+            return self.process_paths(db);
+        }
+
+        if self.weight.is_some() {
+            return self.process_weighted(db);
+        }
+
         let mut result = QueryResult::default();
 
-        for id in self.search(db)? {
+        for id in self.search_after(db, cursor.as_ref())? {
             result.elements.push(DbElement {
                 id,
                 from: db.from_id(id)?,
@@ -90,6 +190,13 @@ impl Query for SearchQuery {
 
         result.result = result.elements.len() as u64;
 
+        if let (true, Some(last)) = (
+            self.limit != 0 && result.result == self.limit,
+            result.elements.last(),
+        ) {
+            result.cursor = Some(self.cursor(db, last.id)?);
+        }
+
         Ok(result)
     }
 }
@@ -98,79 +205,514 @@ impl SearchQuery {
     pub(crate) fn search<Store: StorageData>(
         &self,
         db: &DbImpl<Store>,
+    ) -> Result<Vec<DbId>, DbError> {
+        let _patterns = self.pattern_scope()?;
+        self.search_after(db, self.search_cursor()?.as_ref())
+    }
+
+    fn search_after<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+        cursor: Option<&SearchCursor>,
     ) -> Result<Vec<DbId>, DbError> {
         if self.algorithm == SearchQueryAlgorithm::Index {
-            let condition = self.conditions.first().ok_or_else(|| {
-                DbError::query(
-                    DbErrorType::NotEnoughData,
-                    "Index condition is required for index search",
-                )
-            })?;
-
-            if let QueryConditionData::KeyValue(kvc) = &condition.data {
-                let ids = db.search_index(&kvc.key, kvc.value.value())?;
-                return Ok(ids);
-            } else {
-                return Err(DbError::query(
-                    DbErrorType::NotAllowed,
-                    "Index condition must be key value",
-                ));
-            }
+            return self.search_index(db, cursor);
+        }
+
+        if self.algorithm == SearchQueryAlgorithm::Topological {
+            return self.search_topological(db, cursor);
+        }
+
+        if self.is_paths_search() {
+            return Ok(self
+                .search_paths(db)?
+                .into_iter()
+                .flatten()
+                .map(|(id, _)| id)
+                .collect());
         }
 
         if self.algorithm == SearchQueryAlgorithm::Elements {
-            if self.order_by.is_empty() {
-                db.search_from(
-                    DbId(0),
-                    self.algorithm,
-                    self.limit,
-                    self.offset,
-                    &self.conditions,
-                )
-            } else {
-                let mut ids = db.search_from(DbId(0), self.algorithm, 0, 0, &self.conditions)?;
-                self.sort(&mut ids, db)?;
-                self.slice(ids)
-            }
+            self.search_graph(db, DbId(0), self.direction, cursor)
         } else if self.destination == QueryId::Id(DbId(0)) {
             let origin = db.db_id(&self.origin)?;
-
-            if self.order_by.is_empty() {
-                db.search_from(
-                    origin,
-                    self.algorithm,
-                    self.limit,
-                    self.offset,
-                    &self.conditions,
-                )
-            } else {
-                let mut ids = db.search_from(origin, self.algorithm, 0, 0, &self.conditions)?;
-                self.sort(&mut ids, db)?;
-                self.slice(ids)
-            }
+            self.search_graph(db, origin, self.direction, cursor)
         } else if self.origin == QueryId::Id(DbId(0)) {
+            // Searching towards the destination follows the edges
+            // in reverse (to<-from) unless the direction says otherwise.
             let destination = db.db_id(&self.destination)?;
-
-            if self.order_by.is_empty() {
-                db.search_to(
-                    destination,
-                    self.algorithm,
-                    self.limit,
-                    self.offset,
-                    &self.conditions,
-                )
-            } else {
-                let mut ids = db.search_to(destination, self.algorithm, 0, 0, &self.conditions)?;
-                self.sort(&mut ids, db)?;
-                self.slice(ids)
-            }
+            self.search_graph(db, destination, self.direction.reversed(), cursor)
+        } else if self.weight.is_some() {
+            let mut ids = self
+                .search_weighted(db)?
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<DbId>>();
+            self.sort(&mut ids, db)?;
+            self.slice(ids, db, cursor)
         } else {
             let origin = db.db_id(&self.origin)?;
             let destination = db.db_id(&self.destination)?;
-            let mut ids = db.search_from_to(origin, destination, &self.conditions)?;
+            let mut ids =
+                db.search_from_to(origin, destination, self.direction, &self.conditions)?;
             self.sort(&mut ids, db)?;
-            self.slice(ids)
+            self.slice(ids, db, cursor)
+        }
+    }
+
+    /// Traverses the graph from the `from` element. Unordered searches
+    /// resuming after the `cursor` traverse the graph again skipping
+    /// the elements up to the last returned one.
+    fn search_graph<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+        from: DbId,
+        direction: SearchQueryDirection,
+        cursor: Option<&SearchCursor>,
+    ) -> Result<Vec<DbId>, DbError> {
+        if !self.order_by.is_empty() {
+            let mut ids =
+                db.search_from(from, self.algorithm, direction, 0, 0, &self.conditions)?;
+            self.sort(&mut ids, db)?;
+            return self.slice(ids, db, cursor);
+        }
+
+        match cursor {
+            Some(cursor) => db.search_from_after(
+                from,
+                self.algorithm,
+                direction,
+                cursor.id,
+                self.limit,
+                self.offset,
+                &self.conditions,
+            ),
+            None => db.search_from(
+                from,
+                self.algorithm,
+                direction,
+                self.limit,
+                self.offset,
+                &self.conditions,
+            ),
+        }
+    }
+
+    fn search_cursor(&self) -> Result<Option<SearchCursor>, DbError> {
+        let Some(cursor) = &self.after else {
+            return Ok(None);
+        };
+
+        if self.is_paths_search() || self.weight.is_some() {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                "Cursor is not supported by path searches",
+            ));
+        }
+
+        let (fingerprint, search_cursor) =
+            SearchCursor::parse(cursor).ok_or_else(|| self.invalid_cursor())?;
+
+        if fingerprint != self.fingerprint() {
+            return Err(DbError::query(
+                DbErrorType::InvalidCursor,
+                format!("Cursor '{cursor}' was created by a different query"),
+            ));
+        }
+
+        Ok(Some(search_cursor))
+    }
+
+    fn invalid_cursor(&self) -> DbError {
+        DbError::query(
+            DbErrorType::InvalidCursor,
+            format!(
+                "Invalid cursor '{}'",
+                self.after.as_deref().unwrap_or_default()
+            ),
+        )
+    }
+
+    fn cursor<Store: StorageData>(&self, db: &DbImpl<Store>, id: DbId) -> Result<String, DbError> {
+        let key = Self::order_values(db, id, &self.cursor_keys(db)?);
+        Ok(SearchCursor { id, key }.to_text(self.fingerprint()))
+    }
+
+    /// Keys whose values of the last returned element recorded in the
+    /// cursor (along with its id) determine where the next run of the
+    /// query resumes. Index searches walking the index in its order use
+    /// the index keys and the others use the `order_by` keys.
+    fn cursor_keys<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<Vec<DbValue>, DbError> {
+        if self.algorithm == SearchQueryAlgorithm::Index {
+            let (keys, leading) = match self.conditions.first().map(|c| &c.data) {
+                Some(QueryConditionData::KeyValue(kvc)) => (vec![kvc.key.clone()], 0),
+                Some(QueryConditionData::Where(group)) => {
+                    let (keys, values) = Self::composite_index_condition(db, group)?;
+                    (keys, values.len())
+                }
+                _ => (vec![], 0),
+            };
+
+            if !keys.is_empty() && self.index_order(&keys, leading).is_some() {
+                return Ok(keys);
+            }
+        }
+
+        Ok(self.order_keys())
+    }
+
+    /// Hash of the query without the paging parameters
+    /// identifying the cursors it created.
+    fn fingerprint(&self) -> u64 {
+        let mut query = self.clone();
+        query.limit = 0;
+        query.offset = 0;
+        query.after = None;
+
+        let mut hasher = DefaultHasher::new();
+        format!("{query:?}").hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Compiles the regular expressions used by the conditions
+    /// once for the whole query execution.
+    fn pattern_scope(&self) -> Result<PatternScope, DbError> {
+        let mut result = vec![];
+        condition_patterns(&self.conditions, &mut result);
+        PatternScope::new(result)
+    }
+
+    fn is_paths_search(&self) -> bool {
+        matches!(
+            self.algorithm,
+            SearchQueryAlgorithm::ShortestPaths(_) | SearchQueryAlgorithm::AllPaths(_)
+        )
+    }
+
+    fn process_paths<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+    ) -> Result<QueryResult, DbError> {
+        let mut result = QueryResult::default();
+
+        for (path, elements) in self.search_paths(db)?.into_iter().enumerate() {
+            for (id, cost) in elements {
+                let mut values = vec![("path", self.offset + path as u64).into()];
+
+                if self.weight.is_some() {
+                    values.push(("cost", cost).into());
+                }
+
+                result.elements.push(DbElement {
+                    id,
+                    from: db.from_id(id)?,
+                    to: db.to_id(id)?,
+                    values,
+                });
+            }
+        }
+
+        result.result = result.elements.len() as u64;
+
+        Ok(result)
+    }
+
+    fn search_paths<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+    ) -> Result<Vec<Vec<(DbId, f64)>>, DbError> {
+        if self.origin == QueryId::Id(DbId(0)) || self.destination == QueryId::Id(DbId(0)) {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                "Paths search requires both origin and destination",
+            ));
+        }
+
+        if !self.order_by.is_empty() {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                "Paths search does not support ordering",
+            ));
+        }
+
+        let (max_paths, max_depth) = match self.algorithm {
+            SearchQueryAlgorithm::ShortestPaths(k) => (k, 0),
+            SearchQueryAlgorithm::AllPaths(depth) => (0, depth),
+            _ => (0, 0),
+        };
+        let max_paths = match (max_paths, self.limit) {
+            (_, 0) => max_paths,
+            (0, _) => self.offset + self.limit,
+            (_, _) => max_paths.min(self.offset + self.limit),
+        };
+
+        let origin = db.db_id(&self.origin)?;
+        let destination = db.db_id(&self.destination)?;
+        let paths = db.search_paths(
+            origin,
+            destination,
+            self.direction,
+            max_paths,
+            max_depth,
+            self.weight.as_ref(),
+            &self.conditions,
+        )?;
+        let paths = paths.into_iter().skip(self.offset as usize);
+
+        Ok(if self.limit == 0 {
+            paths.collect()
+        } else {
+            paths.take(self.limit as usize).collect()
+        })
+    }
+
+    fn process_weighted<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+    ) -> Result<QueryResult, DbError> {
+        let costs = self.search_weighted(db)?;
+        let mut ids = costs.iter().map(|(id, _)| *id).collect::<Vec<DbId>>();
+        self.sort(&mut ids, db)?;
+        let mut result = QueryResult::default();
+
+        for id in self.slice(ids, db, None)? {
+            let cost = costs
+                .iter()
+                .find_map(|(i, cost)| (*i == id).then_some(*cost))
+                .unwrap_or_default();
+            result.elements.push(DbElement {
+                id,
+                from: db.from_id(id)?,
+                to: db.to_id(id)?,
+                values: vec![("cost", cost).into()],
+            });
+        }
+
+        result.result = result.elements.len() as u64;
+
+        Ok(result)
+    }
+
+    fn search_weighted<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+    ) -> Result<Vec<(DbId, f64)>, DbError> {
+        let (Some(key), false, false) = (
+            &self.weight,
+            self.origin == QueryId::Id(DbId(0)),
+            self.destination == QueryId::Id(DbId(0)),
+        ) else {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                "Weighted search requires both origin and destination",
+            ));
+        };
+
+        let origin = db.db_id(&self.origin)?;
+        let destination = db.db_id(&self.destination)?;
+        db.search_from_to_weighted(origin, destination, self.direction, key, &self.conditions)
+    }
+
+    fn search_topological<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+        cursor: Option<&SearchCursor>,
+    ) -> Result<Vec<DbId>, DbError> {
+        let (from, direction) = match (
+            self.origin == QueryId::Id(DbId(0)),
+            self.destination == QueryId::Id(DbId(0)),
+        ) {
+            (false, true) => (db.db_id(&self.origin)?, self.direction),
+            (true, false) => (db.db_id(&self.destination)?, self.direction.reversed()),
+            _ => {
+                return Err(DbError::query(
+                    DbErrorType::NotAllowed,
+                    "Topological search requires either origin or destination",
+                ));
+            }
+        };
+
+        let mut ids = db.search_topological(from, direction, &self.conditions)?;
+        self.sort(&mut ids, db)?;
+        self.slice(ids, db, cursor)
+    }
+
+    fn search_index<Store: StorageData>(
+        &self,
+        db: &DbImpl<Store>,
+        cursor: Option<&SearchCursor>,
+    ) -> Result<Vec<DbId>, DbError> {
+        let condition = self.conditions.first().ok_or_else(|| {
+            DbError::query(
+                DbErrorType::NotEnoughData,
+                "Index condition is required for index search",
+            )
+        })?;
+        let conditions = &self.conditions[1..];
+
+        match &condition.data {
+            QueryConditionData::KeyValue(kvc) => {
+                let keys = std::slice::from_ref(&kvc.key);
+
+                if let Some(reverse) = self.index_order(keys, 0) {
+                    let after = self.index_cursor(cursor, keys.len())?;
+                    db.search_index(
+                        kvc,
+                        reverse,
+                        self.limit,
+                        self.offset,
+                        after.as_ref().map(|(values, id)| (&values[0], *id)),
+                        conditions,
+                    )
+                } else {
+                    let mut ids = db.search_index(kvc, false, 0, 0, None, conditions)?;
+                    self.sort(&mut ids, db)?;
+                    self.slice(ids, db, cursor)
+                }
+            }
+            QueryConditionData::Where(group) => {
+                let (keys, values) = Self::composite_index_condition(db, group)?;
+
+                if let Some(reverse) = self.index_order(&keys, values.len()) {
+                    let after = self.index_cursor(cursor, keys.len())?;
+                    db.search_composite_index(
+                        &keys,
+                        &values,
+                        reverse,
+                        self.limit,
+                        self.offset,
+                        after.as_ref().map(|(values, id)| (values.as_slice(), *id)),
+                        conditions,
+                    )
+                } else {
+                    let mut ids =
+                        db.search_composite_index(&keys, &values, false, 0, 0, None, conditions)?;
+                    self.sort(&mut ids, db)?;
+                    self.slice(ids, db, cursor)
+                }
+            }
+            _ => Err(DbError::query(
+                DbErrorType::NotAllowed,
+                "Index condition must be key value",
+            )),
+        }
+    }
+
+    /// Returns whether the result can be taken from the index over
+    /// `keys` (looked up by the values of the `leading` keys) in the
+    /// index order and whether in reverse or `None` if it needs to be
+    /// sorted. The index is ordered by its keys in sequence so ordering
+    /// by the keys that follow the looked up values (all in the same
+    /// direction) matches the index order.
+    fn index_order(&self, keys: &[DbValue], leading: usize) -> Option<bool> {
+        let remaining_keys = &keys[leading..];
+        let asc = self
+            .order_by
+            .iter()
+            .all(|o| matches!(o, DbKeyOrder::Asc(_)));
+        let desc = self
+            .order_by
+            .iter()
+            .all(|o| matches!(o, DbKeyOrder::Desc(_)));
+        let in_index_order = self.order_by.len() <= remaining_keys.len()
+            && self
+                .order_by
+                .iter()
+                .zip(remaining_keys)
+                .all(|(o, key)| match o {
+                    DbKeyOrder::Asc(k) | DbKeyOrder::Desc(k) => k == key,
+                });
+
+        (in_index_order && (asc || desc)).then_some(!asc)
+    }
+
+    /// Returns the index values and the id of the last element
+    /// recorded in the `cursor` to resume the index search after.
+    fn index_cursor(
+        &self,
+        cursor: Option<&SearchCursor>,
+        len: usize,
+    ) -> Result<Option<(Vec<DbValue>, DbId)>, DbError> {
+        cursor
+            .map(|cursor| {
+                let values = cursor
+                    .key
+                    .iter()
+                    .cloned()
+                    .collect::<Option<Vec<DbValue>>>()
+                    .filter(|values| values.len() == len)
+                    .ok_or_else(|| self.invalid_cursor())?;
+                Ok((values, cursor.id))
+            })
+            .transpose()
+    }
+
+    /// Composite index condition is a nested group of key value
+    /// conditions with `Equal` comparisons of the leading keys of
+    /// the index optionally accompanied by a `Keys` condition listing
+    /// the rest of its keys, all joined by `and`. The conditions and
+    /// the keys can be in any order as the index is looked up by the
+    /// keys. Returns the index keys and the values in their order.
+    fn composite_index_condition<Store: StorageData>(
+        db: &DbImpl<Store>,
+        conditions: &[QueryCondition],
+    ) -> Result<(Vec<DbValue>, Vec<DbValue>), DbError> {
+        let mut leading = vec![];
+        let mut values = vec![];
+        let mut rest = vec![];
+
+        for condition in conditions {
+            if condition.logic != QueryConditionLogic::And
+                || condition.modifier != QueryConditionModifier::None
+            {
+                return Err(Self::composite_index_condition_error());
+            }
+
+            match &condition.data {
+                QueryConditionData::KeyValue(KeyValueComparison {
+                    key,
+                    value: Comparison::Equal(value),
+                }) => {
+                    leading.push(key.clone());
+                    values.push(value.clone());
+                }
+                QueryConditionData::Keys(keys) => rest.extend(keys.iter().cloned()),
+                _ => return Err(Self::composite_index_condition_error()),
+            }
+        }
+
+        let all_keys = [leading.as_slice(), rest.as_slice()].concat();
+
+        if let Some(key) = all_keys
+            .iter()
+            .enumerate()
+            .find_map(|(i, key)| all_keys[..i].contains(key).then_some(key))
+        {
+            return Err(DbError::query(
+                DbErrorType::NotAllowed,
+                format!("Composite index condition lists the key '{key}' more than once"),
+            ));
         }
+
+        let keys = db.composite_index_keys(&leading, &rest)?;
+        let values = keys[..leading.len()]
+            .iter()
+            .filter_map(|key| {
+                leading
+                    .iter()
+                    .position(|k| k == key)
+                    .map(|pos| values[pos].clone())
+            })
+            .collect();
+
+        Ok((keys, values))
+    }
+
+    fn composite_index_condition_error() -> DbError {
+        DbError::query(
+            DbErrorType::NotAllowed,
+            "Composite index condition must consist of key value equality conditions optionally accompanied by keys condition all joined by 'and'",
+        )
     }
 
     fn sort<Store: StorageData>(
@@ -178,64 +720,137 @@ impl SearchQuery {
         ids: &mut [DbId],
         db: &DbImpl<Store>,
     ) -> Result<(), DbError> {
-        let keys = self
-            .order_by
+        if self.order_by.is_empty() {
+            return Ok(());
+        }
+
+        let keys = self.order_keys();
+        let mut sorted = ids
+            .iter()
+            .map(|id| (Self::order_values(db, *id, &keys), *id))
+            .collect::<Vec<(Vec<Option<DbValue>>, DbId)>>();
+        sorted.sort_by(|left, right| self.compare_order_values(&left.0, &right.0));
+
+        for (id, (_, sorted_id)) in ids.iter_mut().zip(sorted) {
+            *id = sorted_id;
+        }
+
+        Ok(())
+    }
+
+    fn order_keys(&self) -> Vec<DbValue> {
+        self.order_by
             .iter()
             .map(|key_order| match key_order {
                 DbKeyOrder::Asc(key) | DbKeyOrder::Desc(key) => key.clone(),
             })
-            .collect::<Vec<DbValue>>();
-
-        ids.sort_by(|left, right| {
-            let left_values = db.values_by_keys(*left, &keys).unwrap_or_default();
-            let right_values = db.values_by_keys(*right, &keys).unwrap_or_default();
-
-            for (key_order, key) in self.order_by.iter().zip(&keys) {
-                let left_kv = left_values.iter().find(|kv| kv.key == *key);
-                let right_kv = right_values.iter().find(|kv| kv.key == *key);
-
-                let ordering = match (left_kv, right_kv) {
-                    (None, None) => Ordering::Equal,
-                    (None, Some(_)) => Ordering::Greater,
-                    (Some(_), None) => Ordering::Less,
-                    (Some(l), Some(r)) => match key_order {
-                        DbKeyOrder::Asc(_) => l.value.cmp(&r.value),
-                        DbKeyOrder::Desc(_) => l.value.cmp(&r.value).reverse(),
-                    },
-                };
-
-                if ordering != Ordering::Equal {
-                    return ordering;
-                }
-            }
+            .collect()
+    }
 
-            Ordering::Equal
-        });
+    /// Values of the `keys` of the element `id` in the order of
+    /// the `keys` with `None` for the keys the element does not have.
+    fn order_values<Store: StorageData>(
+        db: &DbImpl<Store>,
+        id: DbId,
+        keys: &[DbValue],
+    ) -> Vec<Option<DbValue>> {
+        if keys.is_empty() {
+            return vec![];
+        }
 
-        Ok(())
+        let values = db.values_by_keys(id, keys).unwrap_or_default();
+
+        keys.iter()
+            .map(|key| {
+                values
+                    .iter()
+                    .find(|kv| kv.key == *key)
+                    .map(|kv| kv.value.clone())
+            })
+            .collect()
     }
 
-    fn slice(&self, mut ids: Vec<DbId>) -> Result<Vec<DbId>, DbError> {
-        Ok(match (self.limit, self.offset) {
-            (0, 0) => ids,
-            (0, _) => ids[self.offset as usize..].to_vec(),
-            (_, 0) => {
-                ids.truncate(self.limit as usize);
-                ids
+    fn compare_order_values(
+        &self,
+        left: &[Option<DbValue>],
+        right: &[Option<DbValue>],
+    ) -> Ordering {
+        for (key_order, (left, right)) in self.order_by.iter().zip(left.iter().zip(right)) {
+            let ordering = match (left, right) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(l), Some(r)) => match key_order {
+                    DbKeyOrder::Asc(_) => l.sort_cmp(r),
+                    DbKeyOrder::Desc(_) => l.sort_cmp(r).reverse(),
+                },
+            };
+
+            if ordering != Ordering::Equal {
+                return ordering;
             }
-            (_, _) => ids[self.offset as usize..(self.offset + self.limit) as usize].to_vec(),
+        }
+
+        Ordering::Equal
+    }
+
+    fn slice<Store: StorageData>(
+        &self,
+        ids: Vec<DbId>,
+        db: &DbImpl<Store>,
+        cursor: Option<&SearchCursor>,
+    ) -> Result<Vec<DbId>, DbError> {
+        let start = match cursor {
+            Some(cursor) => self.position_after(&ids, db, cursor)?,
+            None => 0,
+        };
+        let ids = ids.into_iter().skip(start + self.offset as usize);
+
+        Ok(if self.limit == 0 {
+            ids.collect()
+        } else {
+            ids.take(self.limit as usize).collect()
         })
     }
 
+    /// Returns the position in the sorted `ids` following the last
+    /// element returned by the previous run of the query. The element
+    /// is looked up among the elements with the same order values to
+    /// keep the order of the ties. If it is no longer there (it was
+    /// removed or its values changed) the search resumes with the first
+    /// element ordered after its recorded values. Unordered results
+    /// must contain the element.
+    fn position_after<Store: StorageData>(
+        &self,
+        ids: &[DbId],
+        db: &DbImpl<Store>,
+        cursor: &SearchCursor,
+    ) -> Result<usize, DbError> {
+        let keys = self.order_keys();
+        let ordering =
+            |id: &DbId| self.compare_order_values(&Self::order_values(db, *id, &keys), &cursor.key);
+        let start = ids.partition_point(|id| ordering(id).is_lt());
+        let end = start + ids[start..].partition_point(|id| ordering(id).is_eq());
+
+        match ids[start..end].iter().position(|id| *id == cursor.id) {
+            Some(pos) => Ok(start + pos + 1),
+            None if self.order_by.is_empty() => Err(cursor_element_not_found(cursor.id)),
+            None => Ok(end),
+        }
+    }
+
     pub(crate) fn new() -> Self {
         Self {
             algorithm: SearchQueryAlgorithm::BreadthFirst,
+            direction: SearchQueryDirection::Forward,
             origin: QueryId::Id(DbId(0)),
             destination: QueryId::Id(DbId(0)),
             limit: 0,
             offset: 0,
             order_by: vec![],
             conditions: vec![],
+            weight: None,
+            after: None,
         }
     }
 }
@@ -262,12 +877,15 @@ mod tests {
             "{:?}",
             SearchQuery {
                 algorithm: SearchQueryAlgorithm::BreadthFirst,
+                direction: SearchQueryDirection::Forward,
                 origin: QueryId::from(0),
                 destination: QueryId::from(0),
                 limit: 0,
                 offset: 0,
                 order_by: vec![],
-                conditions: vec![]
+                conditions: vec![],
+                weight: None,
+                after: None,
             }
         );
     }
@@ -277,12 +895,15 @@ mod tests {
     fn derived_from_clone() {
         let left = SearchQuery {
             algorithm: SearchQueryAlgorithm::BreadthFirst,
+            direction: SearchQueryDirection::Forward,
             origin: QueryId::from(0),
             destination: QueryId::from(0),
             limit: 0,
             offset: 0,
             order_by: vec![],
             conditions: vec![],
+            weight: None,
+            after: None,
         };
         let right = left.clone();
         assert_eq!(left, right);
@@ -293,21 +914,27 @@ mod tests {
         assert_eq!(
             SearchQuery {
                 algorithm: SearchQueryAlgorithm::BreadthFirst,
+                direction: SearchQueryDirection::Forward,
                 origin: QueryId::from(0),
                 destination: QueryId::from(0),
                 limit: 0,
                 offset: 0,
                 order_by: vec![],
-                conditions: vec![]
+                conditions: vec![],
+                weight: None,
+                after: None,
             },
             SearchQuery {
                 algorithm: SearchQueryAlgorithm::BreadthFirst,
+                direction: SearchQueryDirection::Forward,
                 origin: QueryId::from(0),
                 destination: QueryId::from(0),
                 limit: 0,
                 offset: 0,
                 order_by: vec![],
-                conditions: vec![]
+                conditions: vec![],
+                weight: None,
+                after: None,
             }
         );
     }
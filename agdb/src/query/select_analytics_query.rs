@@ -0,0 +1,167 @@
+use crate::DbElement;
+use crate::DbError;
+use crate::DbId;
+use crate::DbImpl;
+use crate::DbValue;
+use crate::Query;
+use crate::QueryIds;
+use crate::QueryResult;
+use crate::SearchQuery;
+use crate::StorageData;
+use crate::query_builder::search::SearchQueryBuilder;
+
+/// Graph algorithm computing a score of each node
+/// of the graph (or subgraph).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnalyticsAlgorithm {
+    /// Groups the nodes connected by edges regardless of their
+    /// direction. The `String("component")` property is the `u64`
+    /// number of the component counted from 0 in order of the nodes.
+    WeaklyConnectedComponents,
+
+    /// Groups the nodes that can all reach each other following the
+    /// edges. The `String("component")` property is the `u64` number
+    /// of the component counted from 0 in order of the nodes.
+    StronglyConnectedComponents,
+
+    /// Ranks the nodes by the rank of the nodes linking to them. The
+    /// `String("page_rank")` property is the `f64` rank. The ranks of
+    /// all nodes add up to 1.
+    PageRank(PageRankOptions),
+
+    /// Counts the edges of the nodes. The `String("degree")` property
+    /// is the `u64` number of incoming and outgoing edges.
+    DegreeCentrality,
+
+    /// Counts the shortest paths between other nodes passing through
+    /// the nodes. The `String("betweenness")` property is the `f64`
+    /// (unnormalized) centrality.
+    BetweennessCentrality,
+}
+
+/// Parameters of the `AnalyticsAlgorithm::PageRank`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageRankOptions {
+    /// Probability of following an edge rather than jumping to
+    /// a random node. Commonly `0.85`.
+    pub damping: f64,
+
+    /// Number of iterations of the computation.
+    pub iterations: u64,
+}
+
+/// Query to compute graph analytics over the nodes of the
+/// database. If the `ids` are empty the whole graph is
+/// analysed, otherwise only the subgraph of the given nodes
+/// and edges between them. Any edge ids are ignored.
+/// All of the ids must exist in the database.
+///
+/// The result is the number of nodes. Each element
+/// carries its score in a property named after the
+/// algorithm (see `AnalyticsAlgorithm`).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectAnalyticsQuery {
+    /// Ids of the nodes to analyse. If empty
+    /// all nodes in the database are analysed.
+    pub ids: QueryIds,
+
+    /// Algorithm to compute.
+    pub algorithm: AnalyticsAlgorithm,
+}
+
+impl Query for SelectAnalyticsQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let mut result = QueryResult::default();
+        let key = self.algorithm.key();
+
+        for (id, value) in analytics(db, &self.ids, self.algorithm)? {
+            result.elements.push(DbElement {
+                id,
+                from: db.from_id(id)?,
+                to: db.to_id(id)?,
+                values: vec![(key, value).into()],
+            });
+        }
+
+        result.result = result.elements.len() as u64;
+
+        Ok(result)
+    }
+}
+
+impl Query for &SelectAnalyticsQuery {
+    fn process<Store: StorageData>(&self, db: &DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
+
+impl SearchQueryBuilder for SelectAnalyticsQuery {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        if let QueryIds::Search(search) = &mut self.ids {
+            search
+        } else {
+            panic!("Expected search query");
+        }
+    }
+}
+
+impl AnalyticsAlgorithm {
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            AnalyticsAlgorithm::WeaklyConnectedComponents
+            | AnalyticsAlgorithm::StronglyConnectedComponents => "component",
+            AnalyticsAlgorithm::PageRank(_) => "page_rank",
+            AnalyticsAlgorithm::DegreeCentrality => "degree",
+            AnalyticsAlgorithm::BetweennessCentrality => "betweenness",
+        }
+    }
+}
+
+pub(crate) fn analytics<Store: StorageData>(
+    db: &DbImpl<Store>,
+    ids: &QueryIds,
+    algorithm: AnalyticsAlgorithm,
+) -> Result<Vec<(DbId, DbValue)>, DbError> {
+    let db_ids = match ids {
+        QueryIds::Ids(ids) if ids.is_empty() => None,
+        QueryIds::Ids(ids) => {
+            let mut db_ids = Vec::with_capacity(ids.len());
+
+            for query_id in ids {
+                db_ids.push(db.db_id(query_id)?);
+            }
+
+            Some(db_ids)
+        }
+        QueryIds::Search(search_query) => Some(search_query.search(db)?),
+    };
+
+    Ok(db.analytics(db_ids.as_deref(), algorithm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn missing_search() {
+        SelectAnalyticsQuery {
+            ids: QueryIds::Ids(vec![]),
+            algorithm: AnalyticsAlgorithm::DegreeCentrality,
+        }
+        .search_mut();
+    }
+}
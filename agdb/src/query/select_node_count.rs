@@ -19,6 +19,7 @@ impl Query for SelectNodeCountQuery {
         Ok(QueryResult {
             result: db.node_count()?,
             elements: vec![],
+            cursor: None,
         })
     }
 }
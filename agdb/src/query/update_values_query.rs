@@ -0,0 +1,414 @@
+use crate::DbElement;
+use crate::DbError;
+use crate::DbErrorType;
+use crate::DbId;
+use crate::DbImpl;
+use crate::DbKeyValue;
+use crate::DbValue;
+use crate::QueryIds;
+use crate::QueryMut;
+use crate::QueryResult;
+use crate::SearchQuery;
+use crate::StorageData;
+use crate::query_builder::search::SearchQueryBuilder;
+use std::cmp::Ordering;
+
+/// Update operation applied to the current value of a key.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueUpdate {
+    /// Adds the number to the current value (`I64`, `U64`, `F64`
+    /// or `DateTime`). A negative number decrements the value. Sets
+    /// the number if the key does not exist.
+    Increment(DbValue),
+
+    /// Appends the value to the current vector or list (or the
+    /// vector of the same type to the vector), to the current string
+    /// or to the current bytes. Creates a vector (or list) of the value
+    /// if the key does not exist.
+    Append(DbValue),
+
+    /// Removes all occurrences of the value from the current
+    /// vector or list. Does nothing if the key does not exist.
+    RemoveItem(DbValue),
+
+    /// Sets the value only if the key does not exist.
+    SetIfAbsent(DbValue),
+
+    /// Sets the value if it is smaller than the current value or
+    /// the key does not exist. Numbers of different types are compared
+    /// by their numeric value.
+    Min(DbValue),
+
+    /// Sets the value if it is greater than the current value or
+    /// the key does not exist. Numbers of different types are compared
+    /// by their numeric value.
+    Max(DbValue),
+}
+
+/// Update of a single property identified by `key`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyValueUpdate {
+    /// Property key
+    pub key: DbValue,
+
+    /// Operation to apply to the current value
+    pub update: ValueUpdate,
+}
+
+/// Convenience wrapper for passing one or more
+/// updates to the query builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+pub struct KeyValueUpdates(pub Vec<KeyValueUpdate>);
+
+/// Query to atomically update properties of existing elements
+/// based on their current values (e.g. incrementing counters or
+/// appending to lists) without reading them first. All `ids`
+/// must exist in the database. The `updates` are applied in order
+/// to each element and the indexes are kept in sync.
+///
+/// The result will be number of changed values and the elements
+/// with the resulting values of the updated keys (the keys that
+/// do not exist after the update are omitted).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "derive", derive(agdb::DbSerialize))]
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpdateValuesQuery {
+    /// Ids whose properties should be updated
+    pub ids: QueryIds,
+
+    /// Updates to apply to each element
+    pub updates: Vec<KeyValueUpdate>,
+}
+
+impl QueryMut for UpdateValuesQuery {
+    fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
+        let mut result = QueryResult::default();
+
+        let db_ids = match &self.ids {
+            QueryIds::Ids(ids) => ids
+                .iter()
+                .map(|id| db.db_id(id))
+                .collect::<Result<Vec<DbId>, DbError>>()?,
+            QueryIds::Search(search_query) => search_query.search(db)?,
+        };
+
+        let mut keys = Vec::<&DbValue>::new();
+
+        for update in &self.updates {
+            if !keys.contains(&&update.key) {
+                keys.push(&update.key);
+            }
+        }
+
+        for db_id in db_ids {
+            for update in &self.updates {
+                let current = db.value(db_id, &update.key)?;
+
+                if let Some(value) = update.apply(current.as_ref())? {
+                    db.insert_or_replace_key_value(
+                        db_id,
+                        &DbKeyValue {
+                            key: update.key.clone(),
+                            value,
+                        },
+                    )?;
+                    result.result += 1;
+                }
+            }
+
+            let mut values = Vec::with_capacity(keys.len());
+
+            for key in &keys {
+                if let Some(value) = db.value(db_id, key)? {
+                    values.push(DbKeyValue {
+                        key: (*key).clone(),
+                        value,
+                    });
+                }
+            }
+
+            result.elements.push(DbElement {
+                id: db_id,
+                from: db.from_id(db_id)?,
+                to: db.to_id(db_id)?,
+                values,
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+impl QueryMut for &UpdateValuesQuery {
+    fn process<Store: StorageData>(&self, db: &mut DbImpl<Store>) -> Result<QueryResult, DbError> {
+        (*self).process(db)
+    }
+}
+
+impl SearchQueryBuilder for UpdateValuesQuery {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        if let QueryIds::Search(search) = &mut self.ids {
+            search
+        } else {
+            panic!("Expected search query");
+        }
+    }
+}
+
+impl From<Vec<KeyValueUpdate>> for KeyValueUpdates {
+    fn from(updates: Vec<KeyValueUpdate>) -> Self {
+        Self(updates)
+    }
+}
+
+impl From<&[KeyValueUpdate]> for KeyValueUpdates {
+    fn from(updates: &[KeyValueUpdate]) -> Self {
+        Self(updates.to_vec())
+    }
+}
+
+impl<const N: usize> From<[KeyValueUpdate; N]> for KeyValueUpdates {
+    fn from(updates: [KeyValueUpdate; N]) -> Self {
+        Self(updates.to_vec())
+    }
+}
+
+impl From<KeyValueUpdate> for KeyValueUpdates {
+    fn from(update: KeyValueUpdate) -> Self {
+        Self(vec![update])
+    }
+}
+
+impl KeyValueUpdate {
+    /// Adds `value` to the current value of the `key`.
+    pub fn increment<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::Increment(value.into()),
+        }
+    }
+
+    /// Appends `value` to the current value of the `key`.
+    pub fn append<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::Append(value.into()),
+        }
+    }
+
+    /// Removes all occurrences of `value` from the current value of the `key`.
+    pub fn remove_item<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::RemoveItem(value.into()),
+        }
+    }
+
+    /// Sets `value` of the `key` if it does not exist.
+    pub fn set_if_absent<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::SetIfAbsent(value.into()),
+        }
+    }
+
+    /// Sets `value` of the `key` if it is smaller than the current value.
+    pub fn min<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::Min(value.into()),
+        }
+    }
+
+    /// Sets `value` of the `key` if it is greater than the current value.
+    pub fn max<K: Into<DbValue>, T: Into<DbValue>>(key: K, value: T) -> Self {
+        Self {
+            key: key.into(),
+            update: ValueUpdate::Max(value.into()),
+        }
+    }
+
+    /// Returns the new value or `None` if the value should not change.
+    fn apply(&self, current: Option<&DbValue>) -> Result<Option<DbValue>, DbError> {
+        match &self.update {
+            ValueUpdate::Increment(value) => self.increment_value(current, value).map(Some),
+            ValueUpdate::Append(value) => self.append_value(current, value).map(Some),
+            ValueUpdate::RemoveItem(value) => self.remove_item_value(current, value),
+            ValueUpdate::SetIfAbsent(value) => Ok(current.is_none().then(|| value.clone())),
+            ValueUpdate::Min(value) => Ok(current
+                .is_none_or(|current| value.numeric_cmp(current) == Ordering::Less)
+                .then(|| value.clone())),
+            ValueUpdate::Max(value) => Ok(current
+                .is_none_or(|current| value.numeric_cmp(current) == Ordering::Greater)
+                .then(|| value.clone())),
+        }
+    }
+
+    fn increment_value(
+        &self,
+        current: Option<&DbValue>,
+        value: &DbValue,
+    ) -> Result<DbValue, DbError> {
+        let result = match (current, value) {
+            (None, DbValue::I64(_) | DbValue::U64(_) | DbValue::F64(_)) => Some(value.clone()),
+            (Some(DbValue::I64(current)), DbValue::I64(value)) => {
+                current.checked_add(*value).map(DbValue::I64)
+            }
+            (Some(DbValue::I64(current)), DbValue::U64(value)) => i64::try_from(*value)
+                .ok()
+                .and_then(|value| current.checked_add(value))
+                .map(DbValue::I64),
+            (Some(DbValue::U64(current)), DbValue::U64(value)) => {
+                current.checked_add(*value).map(DbValue::U64)
+            }
+            (Some(DbValue::U64(current)), DbValue::I64(value)) => if *value < 0 {
+                current.checked_sub(value.unsigned_abs())
+            } else {
+                current.checked_add(value.unsigned_abs())
+            }
+            .map(DbValue::U64),
+            (Some(DbValue::F64(current)), DbValue::I64(value)) => {
+                Some(DbValue::F64((current.to_f64() + *value as f64).into()))
+            }
+            (Some(DbValue::F64(current)), DbValue::U64(value)) => {
+                Some(DbValue::F64((current.to_f64() + *value as f64).into()))
+            }
+            (Some(DbValue::F64(current)), DbValue::F64(value)) => {
+                Some(DbValue::F64((current.to_f64() + value.to_f64()).into()))
+            }
+            (Some(DbValue::DateTime(current)), DbValue::I64(value)) => {
+                current.checked_add(*value).map(DbValue::DateTime)
+            }
+            (current, value) => return Err(self.type_error("increment", current, value)),
+        };
+
+        result.ok_or_else(|| {
+            DbError::query(
+                DbErrorType::OutOfBounds,
+                format!("Increment of '{}' by '{value}' overflows", self.key),
+            )
+        })
+    }
+
+    fn append_value(&self, current: Option<&DbValue>, value: &DbValue) -> Result<DbValue, DbError> {
+        Ok(match (current, value) {
+            (None, DbValue::I64(value)) => DbValue::VecI64(vec![*value]),
+            (None, DbValue::U64(value)) => DbValue::VecU64(vec![*value]),
+            (None, DbValue::F64(value)) => DbValue::VecF64(vec![*value]),
+            (None, DbValue::String(value)) => DbValue::VecString(vec![value.clone()]),
+            (
+                None,
+                DbValue::Bytes(_)
+                | DbValue::VecI64(_)
+                | DbValue::VecU64(_)
+                | DbValue::VecF64(_)
+                | DbValue::VecString(_),
+            ) => value.clone(),
+            (None, value) => DbValue::List(vec![value.clone()]),
+            (Some(DbValue::VecI64(current)), DbValue::I64(value)) => {
+                DbValue::VecI64([current.as_slice(), &[*value]].concat())
+            }
+            (Some(DbValue::VecI64(current)), DbValue::VecI64(value)) => {
+                DbValue::VecI64([current.as_slice(), value].concat())
+            }
+            (Some(DbValue::VecU64(current)), DbValue::U64(value)) => {
+                DbValue::VecU64([current.as_slice(), &[*value]].concat())
+            }
+            (Some(DbValue::VecU64(current)), DbValue::VecU64(value)) => {
+                DbValue::VecU64([current.as_slice(), value].concat())
+            }
+            (Some(DbValue::VecF64(current)), DbValue::F64(value)) => {
+                DbValue::VecF64([current.as_slice(), &[*value]].concat())
+            }
+            (Some(DbValue::VecF64(current)), DbValue::VecF64(value)) => {
+                DbValue::VecF64([current.as_slice(), value].concat())
+            }
+            (Some(DbValue::VecString(current)), DbValue::String(value)) => {
+                DbValue::VecString([current.as_slice(), std::slice::from_ref(value)].concat())
+            }
+            (Some(DbValue::VecString(current)), DbValue::VecString(value)) => {
+                DbValue::VecString([current.as_slice(), value].concat())
+            }
+            (Some(DbValue::String(current)), DbValue::String(value)) => {
+                DbValue::String(format!("{current}{value}"))
+            }
+            (Some(DbValue::Bytes(current)), DbValue::Bytes(value)) => {
+                DbValue::Bytes([current.as_slice(), value].concat())
+            }
+            (Some(DbValue::List(current)), value) => {
+                DbValue::List([current.as_slice(), std::slice::from_ref(value)].concat())
+            }
+            (current, value) => return Err(self.type_error("append", current, value)),
+        })
+    }
+
+    fn remove_item_value(
+        &self,
+        current: Option<&DbValue>,
+        value: &DbValue,
+    ) -> Result<Option<DbValue>, DbError> {
+        fn retain<T: Clone + PartialEq>(current: &[T], value: &T) -> Option<Vec<T>> {
+            current
+                .contains(value)
+                .then(|| current.iter().filter(|v| *v != value).cloned().collect())
+        }
+
+        Ok(match (current, value) {
+            (None, _) => None,
+            (Some(DbValue::VecI64(current)), DbValue::I64(value)) => {
+                retain(current, value).map(DbValue::VecI64)
+            }
+            (Some(DbValue::VecU64(current)), DbValue::U64(value)) => {
+                retain(current, value).map(DbValue::VecU64)
+            }
+            (Some(DbValue::VecF64(current)), DbValue::F64(value)) => {
+                retain(current, value).map(DbValue::VecF64)
+            }
+            (Some(DbValue::VecString(current)), DbValue::String(value)) => {
+                retain(current, value).map(DbValue::VecString)
+            }
+            (Some(DbValue::List(current)), value) => retain(current, value).map(DbValue::List),
+            (current, value) => return Err(self.type_error("remove item", current, value)),
+        })
+    }
+
+    fn type_error(&self, operation: &str, current: Option<&DbValue>, value: &DbValue) -> DbError {
+        DbError::query(
+            DbErrorType::TypeError,
+            format!(
+                "Cannot {operation} '{}' ({}) with '{value}' ({})",
+                self.key,
+                current.map_or("none", type_name),
+                type_name(value)
+            ),
+        )
+    }
+}
+
+fn type_name(value: &DbValue) -> &'static str {
+    match value {
+        DbValue::Bytes(_) => "bytes",
+        DbValue::I64(_) => "i64",
+        DbValue::U64(_) => "u64",
+        DbValue::F64(_) => "f64",
+        DbValue::String(_) => "string",
+        DbValue::VecI64(_) => "vec<i64>",
+        DbValue::VecU64(_) => "vec<u64>",
+        DbValue::VecF64(_) => "vec<f64>",
+        DbValue::VecString(_) => "vec<string>",
+        DbValue::Bool(_) => "bool",
+        DbValue::DateTime(_) => "datetime",
+        DbValue::List(_) => "list",
+        DbValue::Map(_) => "map",
+    }
+}
@@ -1,9 +1,13 @@
+pub mod expect;
 pub mod insert;
 pub mod insert_aliases;
+pub mod insert_analytics;
 pub mod insert_edge;
 pub mod insert_index;
 pub mod insert_nodes;
+pub mod insert_update;
 pub mod insert_values;
+pub mod match_;
 pub mod remove;
 pub mod remove_aliases;
 pub mod remove_ids;
@@ -11,7 +15,9 @@ pub mod remove_index;
 pub mod remove_values;
 pub mod search;
 pub mod select;
+pub mod select_aggregate;
 pub mod select_aliases;
+pub mod select_analytics;
 pub mod select_edge_count;
 pub mod select_ids;
 pub mod select_indexes;
@@ -21,10 +27,15 @@ pub mod select_node_count;
 pub mod select_values;
 pub mod where_;
 
+use self::expect::Expect;
 use self::insert::Insert;
+use self::match_::Match;
 use self::remove::Remove;
 use self::search::Search;
 use self::select::Select;
+use crate::ExpectQuery;
+use crate::MatchQuery;
+use crate::QueryIds;
 use crate::SearchQuery;
 
 /// The starting point of all queries.
@@ -34,7 +45,9 @@ use crate::SearchQuery;
 /// ```
 /// use agdb::QueryBuilder;
 ///
+/// QueryBuilder::expect();
 /// QueryBuilder::insert();
+/// QueryBuilder::match_();
 /// QueryBuilder::remove();
 /// QueryBuilder::search();
 /// QueryBuilder::select();
@@ -45,6 +58,26 @@ pub struct QueryBuilder;
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl QueryBuilder {
+    /// Checks that the elements satisfy conditions failing
+    /// with `DbErrorType::PreconditionFailed` otherwise. Use it
+    /// in a transaction before mutable queries to apply them only
+    /// if the data has not changed (compare-and-set):
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::expect().ids(1).where_().key("version").value(7).query();
+    /// QueryBuilder::expect().ids(QueryBuilder::search().from(1).query()).where_().keys("k").query();
+    /// ```
+    pub fn expect() -> Expect {
+        Expect(ExpectQuery {
+            ids: QueryIds::Ids(vec![]),
+            conditions: vec![],
+        })
+    }
+
     /// Allows inserting data into the database:
     ///
     /// Options:
@@ -69,6 +102,22 @@ impl QueryBuilder {
         Insert {}
     }
 
+    /// Finds occurrences of a path pattern of node
+    /// and edge variables in the graph:
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::match_().node("a");
+    /// QueryBuilder::match_().node("a").where_().keys("k").query();
+    /// QueryBuilder::match_().node("a").edge("e").node("b");
+    /// ```
+    pub fn match_() -> Match {
+        Match(MatchQuery::default())
+    }
+
     /// Allows removing data from the database:
     ///
     /// Options:
@@ -0,0 +1,65 @@
+use crate::ExpectQuery;
+use crate::QueryIds;
+use crate::SearchQuery;
+use crate::query_builder::search::SearchQueryBuilder;
+use crate::query_builder::where_::Where;
+
+/// Expect builder to set ids of the elements
+/// to check.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct Expect(pub ExpectQuery);
+
+/// Expect builder that lets you set the conditions
+/// or create an actual query object.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct ExpectIds(pub ExpectQuery, pub SearchQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl Expect {
+    /// An id or list of ids or search query of the elements
+    /// that must satisfy the conditions.
+    pub fn ids<T: Into<QueryIds>>(mut self, ids: T) -> ExpectIds {
+        self.0.ids = ids.into();
+
+        ExpectIds(self.0, SearchQuery::new())
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl ExpectIds {
+    /// Returns the built `ExpectQuery` object.
+    pub fn query(mut self) -> ExpectQuery {
+        self.take_conditions();
+        self.0
+    }
+
+    /// Starts the condition builder for the conditions the elements
+    /// must satisfy. Finish the conditions with `query()` to return
+    /// to this builder.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::expect().ids(1).where_().key("version").value(7).query().query();
+    /// QueryBuilder::expect().ids([1, 2]).where_().keys("k").and().not().key("locked").value(true).query().query();
+    /// ```
+    pub fn where_(self) -> Where<ExpectIds> {
+        Where::new(self)
+    }
+
+    fn take_conditions(&mut self) {
+        self.0
+            .conditions
+            .extend(std::mem::take(&mut self.1.conditions));
+    }
+}
+
+impl SearchQueryBuilder for ExpectIds {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        &mut self.1
+    }
+}
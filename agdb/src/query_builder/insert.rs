@@ -1,18 +1,24 @@
+use crate::AnalyticsAlgorithm;
 use crate::DbType;
-use crate::DbValue;
 use crate::InsertAliasesQuery;
+use crate::InsertAnalyticsQuery;
 use crate::InsertEdgesQuery;
 use crate::InsertNodesQuery;
 use crate::InsertValuesQuery;
 use crate::QueryIds;
+use crate::UpdateValuesQuery;
+use crate::db::db_value::DbValues;
 use crate::query::query_aliases::QueryAliases;
 use crate::query::query_values::MultiValues;
 use crate::query::query_values::QueryValues;
 use crate::query::query_values::SingleValues;
+use crate::query::update_values_query::KeyValueUpdates;
 use crate::query_builder::insert_aliases::InsertAliases;
+use crate::query_builder::insert_analytics::InsertAnalytics;
 use crate::query_builder::insert_edge::InsertEdges;
 use crate::query_builder::insert_index::InsertIndex;
 use crate::query_builder::insert_nodes::InsertNodes;
+use crate::query_builder::insert_update::InsertUpdate;
 use crate::query_builder::insert_values::InsertValues;
 use crate::query_builder::insert_values::InsertValuesIds;
 
@@ -41,6 +47,26 @@ impl Insert {
         })
     }
 
+    /// Inserts graph analytics computed by the `algorithm` as
+    /// properties of the analysed nodes. If no ids are given
+    /// the whole graph will be analysed:
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{AnalyticsAlgorithm, QueryBuilder};
+    ///
+    /// QueryBuilder::insert().analytics(AnalyticsAlgorithm::WeaklyConnectedComponents).query();
+    /// QueryBuilder::insert().analytics(AnalyticsAlgorithm::WeaklyConnectedComponents).ids([1, 2]);
+    /// QueryBuilder::insert().analytics(AnalyticsAlgorithm::WeaklyConnectedComponents).search().from(1);
+    /// ```
+    pub fn analytics(self, algorithm: AnalyticsAlgorithm) -> InsertAnalytics {
+        InsertAnalytics(InsertAnalyticsQuery {
+            ids: QueryIds::Ids(vec![]),
+            algorithm,
+        })
+    }
+
     /// Inserts edges into the database:
     ///
     /// Options:
@@ -62,6 +88,7 @@ impl Insert {
             ids: QueryIds::Ids(vec![]),
             values: QueryValues::Single(vec![]),
             each: false,
+            acyclic: false,
         })
     }
 
@@ -103,9 +130,21 @@ impl Insert {
         })
     }
 
-    /// Key to index on all elements in the database.
-    pub fn index<T: Into<DbValue>>(self, key: T) -> InsertIndex {
-        InsertIndex(key.into())
+    /// Key to index on all elements in the database. Passing
+    /// multiple keys creates a composite index over the ordered
+    /// list of keys containing the elements that have all of them.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().index("k").query();
+    /// QueryBuilder::insert().index("k").ordered().query();
+    /// QueryBuilder::insert().index(["k1", "k2"]).query();
+    /// ```
+    pub fn index<T: Into<DbValues>>(self, keys: T) -> InsertIndex {
+        InsertIndex(Into::<DbValues>::into(keys).0)
     }
 
     /// Inserts nodes into the database:
@@ -154,6 +193,27 @@ impl Insert {
         })
     }
 
+    /// Updates values of existing elements based on their current
+    /// values, e.g. incrementing a counter or appending to a list,
+    /// applying the `updates` in order to every element identified by
+    /// ids in the next step:
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{KeyValueUpdate, QueryBuilder};
+    ///
+    /// QueryBuilder::insert().update(KeyValueUpdate::increment("k", 1)).ids(1);
+    /// QueryBuilder::insert().update([KeyValueUpdate::append("tags", "a"), KeyValueUpdate::max("k", 10)]).ids([1, 2]);
+    /// QueryBuilder::insert().update(KeyValueUpdate::set_if_absent("k", 1)).search().from(1);
+    /// ```
+    pub fn update<T: Into<KeyValueUpdates>>(self, updates: T) -> InsertUpdate {
+        InsertUpdate(UpdateValuesQuery {
+            ids: QueryIds::Ids(vec![]),
+            updates: Into::<KeyValueUpdates>::into(updates).0,
+        })
+    }
+
     /// Inserts or updates list of `key_values` into the database.
     /// The list represents a list of key-value pairs to be inserted
     /// into every database elements identified by ids in the next step.
@@ -0,0 +1,47 @@
+use crate::InsertAnalyticsQuery;
+use crate::QueryIds;
+use crate::SearchQuery;
+use crate::query_builder::search::Search;
+
+/// Insert analytics builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertAnalytics(pub InsertAnalyticsQuery);
+
+/// Final builder that lets you create
+/// an actual query object.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertAnalyticsIds(pub InsertAnalyticsQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertAnalytics {
+    /// An id or list of ids or search query of the nodes to analyse.
+    /// All ids specified must exist in the database.
+    pub fn ids<T: Into<QueryIds>>(mut self, ids: T) -> InsertAnalyticsIds {
+        self.0.ids = ids.into();
+
+        InsertAnalyticsIds(self.0)
+    }
+
+    /// Analyses the nodes returned from the search query.
+    /// Equivalent to `ids(QueryBuilder::search()/* ... */)`.
+    pub fn search(mut self) -> Search<InsertAnalyticsQuery> {
+        self.0.ids = QueryIds::Search(SearchQuery::new());
+        Search(self.0)
+    }
+
+    /// Returns the built `InsertAnalyticsQuery` object
+    /// analysing the whole graph.
+    pub fn query(self) -> InsertAnalyticsQuery {
+        self.0
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertAnalyticsIds {
+    /// Returns the built `InsertAnalyticsQuery` object.
+    pub fn query(self) -> InsertAnalyticsQuery {
+        self.0
+    }
+}
@@ -102,6 +102,23 @@ impl InsertEdgesIds {
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl InsertEdgesEach {
+    /// Rejects the query if any of the new edges would
+    /// create a cycle in the graph.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().edges().from(1).to(2).each().acyclic().query();
+    /// QueryBuilder::insert().edges().from(1).to(2).each().acyclic().values([[("k", 1).into()]]);
+    /// ```
+    pub fn acyclic(mut self) -> InsertEdgesEach {
+        self.0.acyclic = true;
+
+        InsertEdgesEach(self.0)
+    }
+
     /// Returns the built `InsertEdgesQuery` object.
     pub fn query(self) -> InsertEdgesQuery {
         self.0
@@ -146,6 +163,25 @@ impl InsertEdgesFrom {
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl InsertEdgesFromTo {
+    /// Rejects the query if any of the new edges would create
+    /// a cycle in the graph, including the cycles created by the
+    /// edges inserted by the same query.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().edges().from(1).to(2).acyclic().query();
+    /// QueryBuilder::insert().edges().from(1).to(2).acyclic().each();
+    /// QueryBuilder::insert().edges().from(1).to(2).acyclic().values_uniform([("k", 1).into()]);
+    /// ```
+    pub fn acyclic(mut self) -> InsertEdgesFromTo {
+        self.0.acyclic = true;
+
+        InsertEdgesFromTo(self.0)
+    }
+
     /// A modifier to create edges from each origin (from) to each destination (to)
     /// even if the number of origins and destinations is the same. This modifier is assumed
     /// and thus not needed if they are already asymmetric.
@@ -4,12 +4,99 @@ use crate::InsertIndexQuery;
 /// Final step in the insert index query builder.
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
 #[cfg_attr(feature = "api", type_def(inherent))]
-pub struct InsertIndex(pub DbValue);
+pub struct InsertIndex(pub Vec<DbValue>);
+
+/// Final step in the insert ordered index query builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertIndexOrdered(pub InsertIndexQuery);
+
+/// Final step in the insert unique index query builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertIndexUnique(pub InsertIndexQuery);
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl InsertIndex {
+    /// Makes the index ordered. Ordered index keeps the values
+    /// sorted allowing range searches (e.g. `LessThan`, `GreaterThan`,
+    /// `StartsWith`) and returning the results in the order of the values.
+    /// Ordered composite index additionally allows searching by the values
+    /// of its leading keys only.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().index("k").ordered().query();
+    /// QueryBuilder::insert().index(["k1", "k2"]).ordered().query();
+    /// ```
+    pub fn ordered(self) -> InsertIndexOrdered {
+        InsertIndexOrdered(InsertIndexQuery {
+            keys: self.0,
+            ordered: true,
+            unique: false,
+        })
+    }
+
+    /// Returns the built `InsertIndexQuery`.
+    pub fn query(self) -> InsertIndexQuery {
+        InsertIndexQuery {
+            keys: self.0,
+            ordered: false,
+            unique: false,
+        }
+    }
+
+    /// Makes the index unique. Inserting a value (or a combination of
+    /// values of a composite index) already held by another element
+    /// will fail with `DbErrorType::UniqueViolation`. The index cannot be
+    /// created if the existing data already contain duplicates.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().index("k").unique().query();
+    /// QueryBuilder::insert().index(["k1", "k2"]).unique().query();
+    /// ```
+    pub fn unique(self) -> InsertIndexUnique {
+        InsertIndexUnique(InsertIndexQuery {
+            keys: self.0,
+            ordered: false,
+            unique: true,
+        })
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertIndexOrdered {
+    /// Returns the built `InsertIndexQuery`.
+    pub fn query(self) -> InsertIndexQuery {
+        self.0
+    }
+
+    /// Makes the ordered index unique.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::insert().index("k").ordered().unique().query();
+    /// ```
+    pub fn unique(mut self) -> InsertIndexUnique {
+        self.0.unique = true;
+        InsertIndexUnique(self.0)
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertIndexUnique {
     /// Returns the built `InsertIndexQuery`.
     pub fn query(self) -> InsertIndexQuery {
-        InsertIndexQuery(self.0)
+        self.0
     }
 }
@@ -0,0 +1,41 @@
+use crate::QueryIds;
+use crate::SearchQuery;
+use crate::UpdateValuesQuery;
+use crate::query_builder::search::Search;
+
+/// Insert update builder to set ids whose values
+/// should be updated.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertUpdate(pub UpdateValuesQuery);
+
+/// Final builder that lets you create
+/// an actual query object.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct InsertUpdateIds(pub UpdateValuesQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertUpdate {
+    /// An id or list of ids or search query whose values should be updated.
+    pub fn ids<T: Into<QueryIds>>(mut self, ids: T) -> InsertUpdateIds {
+        self.0.ids = ids.into();
+
+        InsertUpdateIds(self.0)
+    }
+
+    /// Updates values of elements found using the search query.
+    /// Equivalent to `ids(QueryIds::Search(search)/*...*/)`.
+    pub fn search(mut self) -> Search<UpdateValuesQuery> {
+        self.0.ids = QueryIds::Search(SearchQuery::new());
+        Search(self.0)
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl InsertUpdateIds {
+    /// Returns the built `UpdateValuesQuery` object.
+    pub fn query(self) -> UpdateValuesQuery {
+        self.0
+    }
+}
@@ -0,0 +1,159 @@
+use crate::MatchQuery;
+use crate::MatchStep;
+use crate::MatchVariable;
+use crate::SearchQuery;
+use crate::SearchQueryDirection;
+use crate::query_builder::search::SearchQueryBuilder;
+use crate::query_builder::where_::Where;
+
+/// Match builder query.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct Match(pub MatchQuery);
+
+/// Match builder after a node variable that lets you
+/// set its conditions, continue the pattern with an edge
+/// or finish the query.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct MatchNode(pub MatchQuery, pub SearchQuery);
+
+/// Match builder after an edge variable that lets you
+/// set its conditions and direction and continue the
+/// pattern with a node.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct MatchEdge(pub MatchQuery, pub SearchQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl Match {
+    /// Starts the pattern with a node variable `name`.
+    pub fn node<T: Into<String>>(mut self, name: T) -> MatchNode {
+        self.0.node.name = name.into();
+
+        MatchNode(self.0, SearchQuery::new())
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl MatchNode {
+    /// Continues the pattern with an edge variable `name`
+    /// following the outgoing edges of the node by default.
+    pub fn edge<T: Into<String>>(mut self, name: T) -> MatchEdge {
+        self.take_conditions();
+        self.0.steps.push(MatchStep {
+            direction: SearchQueryDirection::Forward,
+            edge: MatchVariable {
+                name: name.into(),
+                conditions: vec![],
+            },
+            node: MatchVariable::default(),
+        });
+
+        MatchEdge(self.0, SearchQuery::new())
+    }
+
+    /// Sets the maximum number of matches to return.
+    pub fn limit(mut self, value: u64) -> MatchNode {
+        self.0.limit = value;
+        self
+    }
+
+    /// Sets the number of matches to skip.
+    pub fn offset(mut self, value: u64) -> MatchNode {
+        self.0.offset = value;
+        self
+    }
+
+    /// Returns the built `MatchQuery` object.
+    pub fn query(mut self) -> MatchQuery {
+        self.take_conditions();
+        self.0
+    }
+
+    /// Starts the condition builder for the node variable.
+    /// Finish the conditions with `query()` to continue
+    /// building the pattern.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::match_().node("a").where_().keys("k").query().query();
+    /// QueryBuilder::match_().node("a").where_().ids("a").query().edge("e");
+    /// ```
+    pub fn where_(self) -> Where<MatchNode> {
+        Where::new(self)
+    }
+
+    fn take_conditions(&mut self) {
+        let conditions = std::mem::take(&mut self.1.conditions);
+
+        match self.0.steps.last_mut() {
+            Some(step) => step.node.conditions.extend(conditions),
+            None => self.0.node.conditions.extend(conditions),
+        }
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl MatchEdge {
+    /// Sets the direction of the edge relative to the previous node:
+    /// `Forward` (default) follows the outgoing edges, `Reverse`
+    /// the incoming edges and `Both` any of them.
+    pub fn direction(mut self, direction: SearchQueryDirection) -> MatchEdge {
+        if let Some(step) = self.0.steps.last_mut() {
+            step.direction = direction;
+        }
+
+        self
+    }
+
+    /// Continues the pattern with a node variable `name`
+    /// the edge leads to.
+    pub fn node<T: Into<String>>(mut self, name: T) -> MatchNode {
+        self.take_conditions();
+
+        if let Some(step) = self.0.steps.last_mut() {
+            step.node.name = name.into();
+        }
+
+        MatchNode(self.0, SearchQuery::new())
+    }
+
+    /// Starts the condition builder for the edge variable.
+    /// Finish the conditions with `query()` to continue
+    /// building the pattern.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::match_().node("a").edge("e").where_().keys("k").query().node("b");
+    /// ```
+    pub fn where_(self) -> Where<MatchEdge> {
+        Where::new(self)
+    }
+
+    fn take_conditions(&mut self) {
+        let conditions = std::mem::take(&mut self.1.conditions);
+
+        if let Some(step) = self.0.steps.last_mut() {
+            step.edge.conditions.extend(conditions);
+        }
+    }
+}
+
+impl SearchQueryBuilder for MatchNode {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        &mut self.1
+    }
+}
+
+impl SearchQueryBuilder for MatchEdge {
+    fn search_mut(&mut self) -> &mut SearchQuery {
+        &mut self.1
+    }
+}
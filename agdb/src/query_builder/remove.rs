@@ -1,4 +1,3 @@
-use crate::DbValue;
 use crate::QueryIds;
 use crate::RemoveAliasesQuery;
 use crate::RemoveQuery;
@@ -38,9 +37,19 @@ impl Remove {
         RemoveIds(RemoveQuery(ids.into()))
     }
 
-    /// Index to be removed from the database.
-    pub fn index<T: Into<DbValue>>(self, key: T) -> RemoveIndex {
-        RemoveIndex(key.into())
+    /// Index to be removed from the database. Composite index
+    /// is identified by the list of its keys.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::remove().index("k").query();
+    /// QueryBuilder::remove().index(["k1", "k2"]).query();
+    /// ```
+    pub fn index<T: Into<DbValues>>(self, keys: T) -> RemoveIndex {
+        RemoveIndex(Into::<DbValues>::into(keys).0)
     }
 
     /// Remove the elements found using the search query.
@@ -8,7 +8,9 @@ use crate::QueryConditionModifier;
 use crate::QueryId;
 use crate::SearchQuery;
 use crate::SearchQueryAlgorithm;
+use crate::SearchQueryDirection;
 use crate::db::db_key_order::DbKeyOrders;
+use crate::db::db_value::DbValues;
 use crate::query::query_condition::KeyValueComparison;
 
 #[cfg(feature = "api")]
@@ -44,7 +46,7 @@ pub struct SearchTo<T: SearchQueryBuilder>(pub T);
 #[cfg_attr(feature = "api", derive(agdb::TypeDef))]
 #[cfg_attr(feature = "api", type_def(inherent))]
 pub struct SearchIndex<T: SearchQueryBuilder> {
-    pub index: DbValue,
+    pub index: Vec<DbValue>,
     pub query: T,
 }
 
@@ -75,8 +77,38 @@ pub struct SelectOffset<T: SearchQueryBuilder>(pub T);
 #[cfg_attr(feature = "api", type_def(inherent))]
 pub struct SearchAlgorithm<T: SearchQueryBuilder>(pub T);
 
+/// Search builder query that lets you choose the origin of
+/// the paths search.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SearchPaths<T: SearchQueryBuilder>(pub T);
+
+/// Search builder query that lets you choose the destination of
+/// the paths search.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SearchPathsFrom<T: SearchQueryBuilder>(pub T);
+
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl<T: SearchQueryBuilder> Search<T> {
+    /// Resumes the search after the last element returned by
+    /// a previous run of the same query using the cursor from its
+    /// result (`QueryResult::cursor`). The cursor is valid only
+    /// as long as the database is not modified.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().after("cursor").from(1).limit(10);
+    /// QueryBuilder::search().after("cursor").index("k").value(1).limit(10);
+    /// ```
+    pub fn after<C: Into<String>>(mut self, cursor: C) -> Search<T> {
+        self.0.search_mut().after = Some(cursor.into());
+        self
+    }
+
     /// Use breadth-first (BFS) search algorithm. This option is redundant as
     /// BFS is the default. BFS means each level of the graph is examined in full
     /// before advancing to the next level. E.g. all edges coming from a node,
@@ -114,6 +146,25 @@ impl<T: SearchQueryBuilder> Search<T> {
         SearchAlgorithm(self.0)
     }
 
+    /// Orders the nodes reachable from the origin so that every edge leads
+    /// from an earlier node to a later one, e.g. for dependency graphs.
+    /// Searching to a destination orders the nodes reaching it instead
+    /// with the destination first. Edges are not returned and the search
+    /// fails with `DbErrorType::Cycle` if the nodes form a cycle.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().topological().from(1);
+    /// QueryBuilder::search().topological().to(1);
+    /// ```
+    pub fn topological(mut self) -> SearchAlgorithm<T> {
+        self.0.search_mut().algorithm = SearchQueryAlgorithm::Topological;
+        SearchAlgorithm(self.0)
+    }
+
     /// Searches all elements (nodes & edges) in the database disregarding the graph
     /// structure or any relationships between elements. This performs linear search
     /// through the entire database which may be prohibitively expensive. Consider
@@ -137,8 +188,49 @@ impl<T: SearchQueryBuilder> Search<T> {
         SearchTo(self.0)
     }
 
-    /// Searches an index specified by `key`. This is to provide fast lookup of
-    /// specific elements with particular key-value pair.
+    /// Searches for up to `k` (0 = all) simple paths between two nodes
+    /// starting with the shortest one. Combine with `weighted_by()` to find
+    /// the lightest paths instead. Every element is returned with the `path`
+    /// (0-based) it belongs to and `limit()` & `offset()` count paths.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().shortest_paths(3).from(1).to(2);
+    /// ```
+    pub fn shortest_paths(mut self, k: u64) -> SearchPaths<T> {
+        self.0.search_mut().algorithm = SearchQueryAlgorithm::ShortestPaths(k);
+        SearchPaths(self.0)
+    }
+
+    /// Searches for all simple paths between two nodes of at most
+    /// `max_depth` edges (0 = unlimited). Every element is returned with
+    /// the `path` (0-based) it belongs to and `limit()` & `offset()` count
+    /// paths. Without a limit on depth this can be very expensive on
+    /// densely connected graphs.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().all_paths(5).from(1).to(2);
+    /// ```
+    pub fn all_paths(mut self, max_depth: u64) -> SearchPaths<T> {
+        self.0.search_mut().algorithm = SearchQueryAlgorithm::AllPaths(max_depth);
+        SearchPaths(self.0)
+    }
+
+    /// Searches an index specified by `key` or by `keys` of a composite
+    /// index. This is to provide fast lookup of specific elements with
+    /// particular key-value pair(s). The composite index is found by its
+    /// set of keys so they can be given in any order. A manually built
+    /// composite index condition must be a `where_()` group of `Equal`
+    /// key value conditions of the leading keys of the index optionally
+    /// accompanied by a `keys()` condition listing the rest of its keys,
+    /// all joined by `and()`.
     ///
     /// Options:
     ///
@@ -146,10 +238,11 @@ impl<T: SearchQueryBuilder> Search<T> {
     /// use agdb::QueryBuilder;
     ///
     /// QueryBuilder::search().index("k").value(1);
+    /// QueryBuilder::search().index(["k1", "k2"]).values([1, 2]);
     /// ```
-    pub fn index<K: Into<DbValue>>(self, key: K) -> SearchIndex<T> {
+    pub fn index<K: Into<DbValues>>(self, keys: K) -> SearchIndex<T> {
         SearchIndex {
-            index: key.into(),
+            index: Into::<DbValues>::into(keys).0,
             query: self.0,
         }
     }
@@ -232,6 +325,44 @@ impl<T: SearchQueryBuilder> SearchAlgorithm<T> {
     }
 }
 
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl<T: SearchQueryBuilder> SearchPaths<T> {
+    /// Sets the origin of the paths.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().shortest_paths(3).from(1).to(2);
+    /// ```
+    pub fn from<I: Into<QueryId>>(mut self, id: I) -> SearchPathsFrom<T> {
+        self.0.search_mut().origin = id.into();
+        SearchPathsFrom(self.0)
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl<T: SearchQueryBuilder> SearchPathsFrom<T> {
+    /// Sets the destination of the paths.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().shortest_paths(3).from(1).to(2).query();
+    /// QueryBuilder::search().shortest_paths(3).from(1).to(2).weighted_by("weight");
+    /// QueryBuilder::search().all_paths(5).from(1).to(2).offset(5);
+    /// QueryBuilder::search().all_paths(5).from(1).to(2).limit(10);
+    /// QueryBuilder::search().all_paths(5).from(1).to(2).where_();
+    /// ```
+    pub fn to<I: Into<QueryId>>(mut self, id: I) -> SearchTo<T> {
+        self.0.search_mut().destination = id.into();
+        SearchTo(self.0)
+    }
+}
+
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl<T: SearchQueryBuilder> SearchFrom<T> {
     /// Sets the limit to number of ids returned. If during the search
@@ -289,6 +420,25 @@ impl<T: SearchQueryBuilder> SearchFrom<T> {
         SearchOrderBy(self.0)
     }
 
+    /// Sets the direction in which the edges are followed. The default
+    /// `Forward` follows the outgoing edges (from->to), `Reverse` the
+    /// incoming ones and `Both` treats the edges as undirected.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{QueryBuilder, SearchQueryDirection};
+    ///
+    /// QueryBuilder::search().from(1).direction(SearchQueryDirection::Both).query();
+    /// QueryBuilder::search().from(1).direction(SearchQueryDirection::Both).to(2);
+    /// QueryBuilder::search().from(1).direction(SearchQueryDirection::Both).limit(5);
+    /// QueryBuilder::search().from(1).direction(SearchQueryDirection::Both).where_();
+    /// ```
+    pub fn direction(mut self, direction: SearchQueryDirection) -> SearchFrom<T> {
+        self.0.search_mut().direction = direction;
+        self
+    }
+
     /// Returns the built query object.
     pub fn query(self) -> T {
         self.0
@@ -471,6 +621,45 @@ impl<T: SearchQueryBuilder> SearchTo<T> {
         SearchOrderBy(self.0)
     }
 
+    /// Makes the path search minimise the sum of the numeric values
+    /// of `key` of the nodes and edges along the path instead of the
+    /// number of elements. Elements without the `key` weigh nothing.
+    /// Negative or non-numeric weights fail the search. Each returned
+    /// element carries the `cost` of the path up to and including it.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().from(1).to(2).weighted_by("weight").query();
+    /// QueryBuilder::search().from(1).to(2).weighted_by("weight").where_().edge();
+    /// ```
+    pub fn weighted_by<K: Into<DbValue>>(mut self, key: K) -> SearchTo<T> {
+        self.0.search_mut().weight = Some(key.into());
+        self
+    }
+
+    /// Sets the direction in which the edges are followed. For a reverse
+    /// search (only destination set) the default `Forward` follows the
+    /// incoming edges (to<-from) and `Reverse` the outgoing ones. For
+    /// a path search `Reverse` finds the path against the edges. `Both`
+    /// treats the edges as undirected.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{QueryBuilder, SearchQueryDirection};
+    ///
+    /// QueryBuilder::search().to(1).direction(SearchQueryDirection::Reverse).query();
+    /// QueryBuilder::search().from(1).to(2).direction(SearchQueryDirection::Both).query();
+    /// QueryBuilder::search().from(1).to(2).direction(SearchQueryDirection::Both).where_();
+    /// ```
+    pub fn direction(mut self, direction: SearchQueryDirection) -> SearchTo<T> {
+        self.0.search_mut().direction = direction;
+        self
+    }
+
     /// Returns the built `SearchQuery` object.
     pub fn query(self) -> T {
         self.0
@@ -591,25 +780,178 @@ impl<T: SearchQueryBuilder> SelectOffset<T> {
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl<T: SearchQueryBuilder> SearchIndex<T> {
-    /// Sets the value to be searched in the index.
-    pub fn value<V: Into<DbValue>>(mut self, value: V) -> SearchIndexValue<T> {
-        self.query.search_mut().algorithm = SearchQueryAlgorithm::Index;
-        self.query.search_mut().conditions.push(QueryCondition {
-            data: QueryConditionData::KeyValue(KeyValueComparison {
-                key: self.index,
-                value: Comparison::Equal(value.into()),
-            }),
+    /// Sets the value to be searched in the index. Plain values are
+    /// matched for equality. Ordered indexes (see `insert().index().ordered()`)
+    /// additionally support range comparisons such as `LessThan`,
+    /// `GreaterThanOrEqual` or `StartsWith`. For composite indexes
+    /// the value is matched for equality against the first key.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{QueryBuilder, Comparison};
+    ///
+    /// QueryBuilder::search().index("k").value(1).query();
+    /// QueryBuilder::search().index("k").value(Comparison::GreaterThan(1.into())).query();
+    /// QueryBuilder::search().index("k").value(Comparison::StartsWith("a".into())).query();
+    /// ```
+    pub fn value<V: Into<Comparison>>(self, value: V) -> SearchIndexValue<T> {
+        if let [key] = self.index.as_slice() {
+            let condition = QueryConditionData::KeyValue(KeyValueComparison {
+                key: key.clone(),
+                value: value.into(),
+            });
+            return Self::index_condition(self.query, condition);
+        }
+
+        let mut conditions = vec![Self::and(QueryConditionData::KeyValue(
+            KeyValueComparison {
+                key: self.index[0].clone(),
+                value: value.into(),
+            },
+        ))];
+
+        if self.index.len() > 1 {
+            conditions.push(Self::and(QueryConditionData::Keys(
+                self.index[1..].to_vec(),
+            )));
+        }
+
+        Self::index_condition(self.query, QueryConditionData::Where(conditions))
+    }
+
+    /// Sets the values of the leading keys of a composite index to be
+    /// searched for equality. All of the index keys must be given unless
+    /// the index is ordered (see `insert().index().ordered()`) in which
+    /// case a prefix of the keys finds all elements matching it.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().index(["k1", "k2"]).values([1, 2]).query();
+    /// QueryBuilder::search().index(["k1", "k2"]).values([1]).query();
+    /// ```
+    pub fn values<V: Into<DbValues>>(self, values: V) -> SearchIndexValue<T> {
+        let values = Into::<DbValues>::into(values).0;
+
+        if let ([key], [value]) = (self.index.as_slice(), values.as_slice()) {
+            let condition = QueryConditionData::KeyValue(KeyValueComparison {
+                key: key.clone(),
+                value: Comparison::Equal(value.clone()),
+            });
+            return Self::index_condition(self.query, condition);
+        }
+
+        let mut conditions: Vec<QueryCondition> = self
+            .index
+            .iter()
+            .zip(values.iter())
+            .map(|(key, value)| {
+                Self::and(QueryConditionData::KeyValue(KeyValueComparison {
+                    key: key.clone(),
+                    value: Comparison::Equal(value.clone()),
+                }))
+            })
+            .collect();
+
+        if self.index.len() > values.len() {
+            conditions.push(Self::and(QueryConditionData::Keys(
+                self.index[values.len()..].to_vec(),
+            )));
+        }
+
+        Self::index_condition(self.query, QueryConditionData::Where(conditions))
+    }
+
+    fn and(data: QueryConditionData) -> QueryCondition {
+        QueryCondition {
+            data,
             logic: QueryConditionLogic::And,
             modifier: QueryConditionModifier::None,
-        });
-        SearchIndexValue(self.query)
+        }
+    }
+
+    fn index_condition(mut query: T, data: QueryConditionData) -> SearchIndexValue<T> {
+        query.search_mut().algorithm = SearchQueryAlgorithm::Index;
+        query.search_mut().conditions.insert(0, Self::and(data));
+        SearchIndexValue(query)
     }
 }
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl<T: SearchQueryBuilder> SearchIndexValue<T> {
+    /// Sets the limit to number of ids returned. When the result is not
+    /// ordered or is ordered only by the indexed key the ordered index is
+    /// walked directly and the search ends once the `limit + offset` is hit.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().index("k").value(1).limit(10).query();
+    /// QueryBuilder::search().index("k").value(1).limit(10).where_();
+    /// ```
+    pub fn limit(mut self, value: u64) -> SelectLimit<T> {
+        self.0.search_mut().limit = value;
+        SelectLimit(self.0)
+    }
+
+    /// Sets the offset to the ids returned. The `offset` ids will be
+    /// skipped in the result.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().index("k").value(1).offset(10).query();
+    /// QueryBuilder::search().index("k").value(1).offset(10).limit(5);
+    /// QueryBuilder::search().index("k").value(1).offset(10).where_();
+    /// ```
+    pub fn offset(mut self, value: u64) -> SelectOffset<T> {
+        self.0.search_mut().offset = value;
+        SelectOffset(self.0)
+    }
+
+    /// Orders the result by `keys`. Ordering by the indexed key alone
+    /// (ascending or descending) uses the order of the ordered index
+    /// without sorting the result.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{QueryBuilder, DbKeyOrder};
+    ///
+    /// QueryBuilder::search().index("k").value(1).order_by([DbKeyOrder::Desc("k".into())]).query();
+    /// QueryBuilder::search().index("k").value(1).order_by([DbKeyOrder::Desc("k".into())]).offset(10);
+    /// QueryBuilder::search().index("k").value(1).order_by([DbKeyOrder::Desc("k".into())]).limit(5);
+    /// QueryBuilder::search().index("k").value(1).order_by([DbKeyOrder::Desc("k".into())]).where_();
+    /// ```
+    pub fn order_by<K: Into<DbKeyOrders>>(mut self, keys: K) -> SearchOrderBy<T> {
+        self.0.search_mut().order_by = Into::<DbKeyOrders>::into(keys).0;
+        SearchOrderBy(self.0)
+    }
+
     /// Returns the built q object.
     pub fn query(self) -> T {
         self.0
     }
+
+    /// Starts the condition builder. The conditions are applied to
+    /// the elements found in the index.
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::QueryBuilder;
+    ///
+    /// QueryBuilder::search().index("k").value(1).where_().key("k2").value(2);
+    /// QueryBuilder::search().index("k").value(1).where_().node();
+    /// ```
+    pub fn where_(self) -> Where<T> {
+        Where::new(self.0)
+    }
 }
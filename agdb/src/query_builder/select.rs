@@ -1,13 +1,20 @@
+use crate::AggregateFunction;
+use crate::AggregateQuery;
+use crate::AnalyticsAlgorithm;
 use crate::DbType;
+use crate::DbValue;
 use crate::QueryIds;
 use crate::SelectAliasesQuery;
+use crate::SelectAnalyticsQuery;
 use crate::SelectEdgeCountQuery;
 use crate::SelectKeyCountQuery;
 use crate::SelectKeysQuery;
 use crate::SelectValuesQuery;
 use crate::db::db_value::DbValues;
 use crate::query_builder::search::Search;
+use crate::query_builder::select_aggregate::SelectAggregate;
 use crate::query_builder::select_aliases::SelectAliases;
+use crate::query_builder::select_analytics::SelectAnalytics;
 use crate::query_builder::select_edge_count::SelectEdgeCount;
 use crate::query_builder::select_ids::SelectIds;
 use crate::query_builder::select_indexes::SelectIndexes;
@@ -24,6 +31,37 @@ pub struct Select {}
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl Select {
+    /// Select aggregates computed over the values of the `key`.
+    /// All aggregate functions are computed unless narrowed down
+    /// with `functions()`. The result has an element per group (a
+    /// single one without `group_by()`) with a property per
+    /// function named after it (e.g. `String("sum")`):
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{AggregateFunction, QueryBuilder};
+    ///
+    /// QueryBuilder::select().aggregate("k").ids([1, 2]);
+    /// QueryBuilder::select().aggregate("k").search().from(1);
+    /// QueryBuilder::select().aggregate("k").functions([AggregateFunction::Sum, AggregateFunction::Avg]).ids([1, 2]);
+    /// QueryBuilder::select().aggregate("k").group_by("g").search().from(1);
+    /// ```
+    pub fn aggregate<T: Into<DbValue>>(self, key: T) -> SelectAggregate {
+        SelectAggregate(AggregateQuery {
+            ids: QueryIds::Ids(vec![]),
+            key: key.into(),
+            functions: vec![
+                AggregateFunction::Count,
+                AggregateFunction::Sum,
+                AggregateFunction::Min,
+                AggregateFunction::Max,
+                AggregateFunction::Avg,
+            ],
+            group_by: None,
+        })
+    }
+
     /// Select aliases. If no ids are given all aliases
     /// in the database will be selected. Each element
     /// of the result will have a property `String("alias")`
@@ -34,6 +72,27 @@ impl Select {
         SelectAliases(SelectAliasesQuery(QueryIds::Ids(vec![])))
     }
 
+    /// Select graph analytics computed by the `algorithm`. If no
+    /// ids are given the whole graph will be analysed. Each element
+    /// of the result will have a property named after the algorithm
+    /// (e.g. `String("page_rank")`) holding its score:
+    ///
+    /// Options:
+    ///
+    /// ```
+    /// use agdb::{AnalyticsAlgorithm, QueryBuilder};
+    ///
+    /// QueryBuilder::select().analytics(AnalyticsAlgorithm::DegreeCentrality).query();
+    /// QueryBuilder::select().analytics(AnalyticsAlgorithm::DegreeCentrality).ids([1, 2]);
+    /// QueryBuilder::select().analytics(AnalyticsAlgorithm::DegreeCentrality).search().from(1);
+    /// ```
+    pub fn analytics(self, algorithm: AnalyticsAlgorithm) -> SelectAnalytics {
+        SelectAnalytics(SelectAnalyticsQuery {
+            ids: QueryIds::Ids(vec![]),
+            algorithm,
+        })
+    }
+
     /// Select number of outgoing and incoming edges.
     ///
     /// The aggregated edge count is returned in `QueryResult::result`. Each element of
@@ -0,0 +1,57 @@
+use crate::AggregateQuery;
+use crate::DbValue;
+use crate::QueryIds;
+use crate::SearchQuery;
+use crate::query::aggregate_query::AggregateFunctions;
+use crate::query_builder::search::Search;
+
+/// Select aggregate builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SelectAggregate(pub AggregateQuery);
+
+/// Final builder that lets you create
+/// an actual query object.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SelectAggregateIds(pub AggregateQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl SelectAggregate {
+    /// Aggregate functions to compute. All of them
+    /// are computed by default.
+    pub fn functions<T: Into<AggregateFunctions>>(mut self, functions: T) -> SelectAggregate {
+        self.0.functions = Into::<AggregateFunctions>::into(functions).0;
+        self
+    }
+
+    /// Groups the elements by the values of the `key`
+    /// computing the aggregates for each group.
+    pub fn group_by<T: Into<DbValue>>(mut self, key: T) -> SelectAggregate {
+        self.0.group_by = Some(key.into());
+        self
+    }
+
+    /// An id or list of ids or search query of the elements to aggregate.
+    /// All ids specified must exist in the database.
+    pub fn ids<T: Into<QueryIds>>(mut self, ids: T) -> SelectAggregateIds {
+        self.0.ids = ids.into();
+
+        SelectAggregateIds(self.0)
+    }
+
+    /// Aggregate the elements returned from the search query.
+    /// Equivalent to `ids(QueryBuilder::search()/* ... */)`.
+    pub fn search(mut self) -> Search<AggregateQuery> {
+        self.0.ids = QueryIds::Search(SearchQuery::new());
+        Search(self.0)
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl SelectAggregateIds {
+    /// Returns the built `AggregateQuery` object.
+    pub fn query(self) -> AggregateQuery {
+        self.0
+    }
+}
@@ -0,0 +1,47 @@
+use crate::QueryIds;
+use crate::SearchQuery;
+use crate::SelectAnalyticsQuery;
+use crate::query_builder::search::Search;
+
+/// Select analytics builder.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SelectAnalytics(pub SelectAnalyticsQuery);
+
+/// Final builder that lets you create
+/// an actual query object.
+#[cfg_attr(feature = "api", derive(agdb::TypeDef))]
+#[cfg_attr(feature = "api", type_def(inherent))]
+pub struct SelectAnalyticsIds(pub SelectAnalyticsQuery);
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl SelectAnalytics {
+    /// An id or list of ids or search query of the nodes to analyse.
+    /// All ids specified must exist in the database.
+    pub fn ids<T: Into<QueryIds>>(mut self, ids: T) -> SelectAnalyticsIds {
+        self.0.ids = ids.into();
+
+        SelectAnalyticsIds(self.0)
+    }
+
+    /// Analyse the nodes returned from the search query.
+    /// Equivalent to `ids(QueryBuilder::search()/* ... */)`.
+    pub fn search(mut self) -> Search<SelectAnalyticsQuery> {
+        self.0.ids = QueryIds::Search(SearchQuery::new());
+        Search(self.0)
+    }
+
+    /// Returns the built `SelectAnalyticsQuery` object
+    /// analysing the whole graph.
+    pub fn query(self) -> SelectAnalyticsQuery {
+        self.0
+    }
+}
+
+#[cfg_attr(feature = "api", agdb::impl_def())]
+impl SelectAnalyticsIds {
+    /// Returns the built `SelectAnalyticsQuery` object.
+    pub fn query(self) -> SelectAnalyticsQuery {
+        self.0
+    }
+}
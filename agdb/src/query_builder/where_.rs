@@ -1,7 +1,11 @@
+use crate::AdjacentCondition;
+use crate::AdjacentQuantifier;
 use crate::Comparison;
 use crate::DbType;
 use crate::DbValue;
 use crate::QueryIds;
+use crate::SearchQueryAlgorithm;
+use crate::SearchQueryDirection;
 use crate::db::db_value::DbValues;
 use crate::query::query_condition::CountComparison;
 use crate::query::query_condition::KeyValueComparison;
@@ -345,6 +349,75 @@ impl<T: SearchQueryBuilder> Where<T> {
         self
     }
 
+    /// Starts a sub-condition evaluated against the elements adjacent to the
+    /// current element: edges of a node or nodes of an edge in the `direction`.
+    /// The sub-condition is closed with `end_where()` like with `where_()` and
+    /// the adjacent elements are evaluated as if they were one step further
+    /// from the search origin. The condition passes if the number of the adjacent
+    /// elements passing the sub-condition satisfies the `quantifier`. Modifiers
+    /// apply to the whole condition so it can also steer the traversal
+    /// (e.g. with `beyond()`). The sub-condition only decides whether each
+    /// adjacent element passes as the adjacent elements are not being traversed.
+    /// Therefore `beyond()` and `not_beyond()` within it do not steer the search
+    /// (and always pass) while `distance()` filters by the adjacent element's
+    /// distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agdb::{AdjacentQuantifier, CountComparison, QueryBuilder, SearchQueryDirection};
+    ///
+    /// // Nodes with at least one outgoing edge with `k` key
+    /// QueryBuilder::search()
+    ///   .from(1)
+    ///   .where_()
+    ///   .node()
+    ///   .and()
+    ///   .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+    ///   .keys("k")
+    ///   .end_where()
+    ///   .query();
+    ///
+    /// // Only follow nodes with exactly 2 edges pointing to them
+    /// // from nodes with `k` key
+    /// QueryBuilder::search()
+    ///   .from(1)
+    ///   .where_()
+    ///   .beyond()
+    ///   .adjacent(
+    ///     SearchQueryDirection::Reverse,
+    ///     AdjacentQuantifier::Count(CountComparison::Equal(2)),
+    ///   )
+    ///   .adjacent(SearchQueryDirection::Reverse, AdjacentQuantifier::Any)
+    ///   .keys("k")
+    ///   .end_where()
+    ///   .end_where()
+    ///   .query();
+    /// ```
+    pub fn adjacent(
+        mut self,
+        direction: SearchQueryDirection,
+        quantifier: AdjacentQuantifier,
+    ) -> Self {
+        self.add_condition(QueryCondition {
+            logic: self.logic,
+            modifier: self.modifier,
+            data: QueryConditionData::Adjacent(AdjacentCondition {
+                direction,
+                quantifier,
+                conditions: vec![],
+            }),
+        });
+        self.conditions.push(vec![]);
+
+        Self {
+            logic: QueryConditionLogic::And,
+            modifier: QueryConditionModifier::None,
+            conditions: self.conditions,
+            query: self.query,
+        }
+    }
+
     /// Starts a sub-condition (it semantically represents an open bracket). The
     /// conditions in a sub-condition are collapsed into single condition when
     /// evaluated and passed to the previous level. Any condition modifiers can still
@@ -413,7 +486,9 @@ impl<T: SearchQueryBuilder> Where<T> {
             if let Some(QueryCondition {
                 logic: _,
                 modifier: _,
-                data: QueryConditionData::Where(conditions),
+                data:
+                    QueryConditionData::Where(conditions)
+                    | QueryConditionData::Adjacent(AdjacentCondition { conditions, .. }),
             }) = current_conditions.last_mut()
             {
                 *conditions = last_conditions;
@@ -427,6 +502,35 @@ impl<T: SearchQueryBuilder> Where<T> {
 
 #[cfg_attr(feature = "api", agdb::impl_def())]
 impl<T: SearchQueryBuilder> WhereKey<T> {
+    /// Compares the value of the `key` to the value of another key
+    /// of the same element given as the value of the `comparison`
+    /// (e.g. `Comparison::GreaterThan("budget".into())`). Elements
+    /// missing either of the keys do not pass the condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use agdb::{QueryBuilder, Comparison};
+    ///
+    /// // Includes only elements whose `spent` is greater than their `budget`
+    /// QueryBuilder::search().from(1).where_().key("spent").compare_key(Comparison::GreaterThan("budget".into())).query();
+    /// ```
+    pub fn compare_key<Comp: Into<Comparison>>(
+        mut self,
+        comparison: Comp,
+    ) -> WhereLogicOperator<T> {
+        let condition = QueryCondition {
+            logic: self.where_.logic,
+            modifier: self.where_.modifier,
+            data: QueryConditionData::KeyKey(KeyValueComparison {
+                key: self.key,
+                value: comparison.into(),
+            }),
+        };
+        self.where_.add_condition(condition);
+        WhereLogicOperator(self.where_)
+    }
+
     /// Sets the value of the `key` condition to `comparison`. Taking comparison method. If
     /// a value is provided without a method it will default to `Comparison::Equal`).
     pub fn value<Comp: Into<Comparison>>(mut self, comparison: Comp) -> WhereLogicOperator<T> {
@@ -482,8 +586,16 @@ impl<T: SearchQueryBuilder> WhereLogicOperator<T> {
     pub fn query(mut self) -> T {
         while self.0.collapse_conditions() {}
 
-        if !self.0.query.search_mut().conditions.is_empty() {
-            let existing_conditions = std::mem::take(&mut self.0.query.search_mut().conditions);
+        let search = self.0.query.search_mut();
+
+        if !search.conditions.is_empty() {
+            let mut existing_conditions = std::mem::take(&mut search.conditions);
+
+            // The index search expects its condition to be the first one.
+            if search.algorithm == SearchQueryAlgorithm::Index {
+                self.0.conditions[0].insert(0, existing_conditions.remove(0));
+            }
+
             self.0.conditions[0].extend(existing_conditions);
         }
 
@@ -0,0 +1,49 @@
+use crate::DbError;
+use crate::DbImpl;
+use crate::QueryResult;
+use crate::StorageData;
+use crate::Transaction;
+use crate::query::Query;
+
+/// The `Snapshot` is a read-only point-in-time view of
+/// a [`DbImpl`] created with [`DbImpl::snapshot()`]. It
+/// does not borrow the database so it can be sent to other
+/// threads and queried while the database is being written
+/// to. Changes made to the database after the snapshot was
+/// taken are not visible through it.
+pub struct Snapshot<Store: StorageData> {
+    db: DbImpl<Store>,
+}
+
+impl<Store: StorageData> Snapshot<Store> {
+    /// Executes immutable queries against the snapshot.
+    /// See [`DbImpl::exec()`].
+    pub fn exec<T: Query>(&self, query: T) -> Result<QueryResult, DbError> {
+        self.db.exec(query)
+    }
+
+    /// Returns the size of the database in bytes
+    /// at the time the snapshot was taken.
+    pub fn size(&self) -> u64 {
+        self.db.size()
+    }
+
+    /// Executes immutable transaction against the snapshot.
+    /// See [`DbImpl::transaction()`].
+    pub fn transaction<T, E>(
+        &self,
+        f: impl FnOnce(&Transaction<Store>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.db.transaction(f)
+    }
+
+    pub(crate) fn new(db: DbImpl<Store>) -> Self {
+        Self { db }
+    }
+}
+
+impl<Store: StorageData> std::fmt::Debug for Snapshot<Store> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("agdb::Snapshot").finish_non_exhaustive()
+    }
+}
@@ -1,11 +1,15 @@
 pub mod any_storage;
+#[cfg(feature = "encryption")]
+pub mod encrypted_storage;
 pub mod file_storage;
 pub mod file_storage_memory_mapped;
 pub mod memory_storage;
 
+mod shared_data;
 mod storage_records;
 mod write_ahead_log;
 
+use self::shared_data::SharedData;
 use self::storage_records::StorageRecord;
 use self::storage_records::StorageRecords;
 use crate::DbError;
@@ -14,10 +18,19 @@ use crate::collections::vec::VecValue;
 use crate::storage::storage_records::STORAGE_RECORD_SIZE;
 use crate::utilities::serialize::Serialize;
 use crate::utilities::serialize::SerializeStatic;
+use crate::utilities::stable_hash::StableHash;
 use std::borrow::Cow;
 
-const CURRENT_VERSION: u64 = 1;
+const CURRENT_VERSION: u64 = 3;
+const CHECKSUM_VERSION: u64 = 2;
+const CHECKSUM_SHIFT: u64 = 48;
+const SIZE_MASK: u64 = (1 << CHECKSUM_SHIFT) - 1;
 const CHUNK_SIZE: u64 = 1024 * 1024;
+const VALUE_CHUNK_SIZE: u64 = 8;
+// Record header without the value checksum used prior to version 3
+// and still used by the version record at the start of the storage.
+const LEGACY_RECORD_SIZE: u64 = 16;
+const VERSION_RECORD_SIZE: u64 = LEGACY_RECORD_SIZE + 8;
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
 pub struct StorageIndex(pub u64);
@@ -117,10 +130,11 @@ pub trait StorageData: Sized {
 
 #[derive(Debug)]
 pub(crate) struct Storage<D: StorageData> {
-    data: D,
+    data: SharedData<D>,
     records: StorageRecords,
     transactions: u64,
     version: u64,
+    compression_threshold: Option<u64>,
 }
 
 impl<D: StorageData> Storage<D> {
@@ -130,10 +144,11 @@ impl<D: StorageData> Storage<D> {
 
     pub fn with_data(data: D) -> Result<Self, DbError> {
         let mut s = Self {
-            data,
+            data: SharedData::new(data),
             records: StorageRecords::new(),
             transactions: 0,
             version: 0,
+            compression_threshold: None,
         };
 
         s.read_records()?;
@@ -149,12 +164,17 @@ impl<D: StorageData> Storage<D> {
         self.end_transaction(id)
     }
 
+    pub fn compression_threshold(&self) -> Option<u64> {
+        self.compression_threshold
+    }
+
     pub fn copy(&self, name: &str) -> Result<Self, DbError> {
         Ok(Self {
-            data: self.data.copy(name)?,
+            data: SharedData::new(self.data.copy(name)?),
             records: self.records.clone(),
             transactions: 0,
             version: self.version,
+            compression_threshold: self.compression_threshold,
         })
     }
 
@@ -173,7 +193,8 @@ impl<D: StorageData> Storage<D> {
 
     pub fn insert_bytes(&mut self, bytes: &[u8]) -> Result<StorageIndex, DbError> {
         if let Some((free_pos, free_size)) = self.records.take_free(bytes.len() as u64) {
-            let record = self.records.new_record(free_pos, bytes.len() as u64);
+            let mut record = self.records.new_record(free_pos, bytes.len() as u64);
+            self.set_checksum(&mut record, value_checksum(0, bytes));
             let id = self.transaction();
             self.write_record(&record)?;
             self.data.write(record.value_start(), bytes)?;
@@ -190,7 +211,8 @@ impl<D: StorageData> Storage<D> {
         }
 
         let len = self.len();
-        let record = self.new_record(len, bytes.len() as u64);
+        let mut record = self.new_record(len, bytes.len() as u64);
+        self.set_checksum(&mut record, value_checksum(0, bytes));
 
         let id = self.transaction();
         self.write_record(&record)?;
@@ -210,11 +232,14 @@ impl<D: StorageData> Storage<D> {
 
         let id = self.transaction();
         self.ensure_size(&mut record, offset, bytes.len() as u64)?;
-        let pos = record.value_start() + offset;
-        self.data.write(pos, bytes)?;
+        self.write_value(&mut record, offset, bytes)?;
         self.commit(id)
     }
 
+    pub fn is_snapshot(&self) -> bool {
+        self.data.is_snapshot()
+    }
+
     pub fn len(&self) -> u64 {
         self.data.len()
     }
@@ -231,8 +256,8 @@ impl<D: StorageData> Storage<D> {
             .to_vec();
         let id = self.transaction();
         self.insert_bytes_at(index, offset_to, &bytes)?;
-        let record = self.record(index.0)?;
-        self.erase_bytes(record.value_start(), offset_from, offset_to, size)?;
+        let mut record = self.record(index.0)?;
+        self.erase_bytes(&mut record, offset_from, offset_to, size)?;
         self.commit(id)
     }
 
@@ -302,7 +327,7 @@ impl<D: StorageData> Storage<D> {
 
     pub fn optimize_storage(&mut self) -> Result<(), DbError> {
         let id = self.transaction();
-        let mut current_pos = Self::current_version_record().end();
+        let mut current_pos = VERSION_RECORD_SIZE;
 
         for record in self.records.records() {
             current_pos = self.shrink_index(record, current_pos)?;
@@ -313,6 +338,88 @@ impl<D: StorageData> Storage<D> {
         self.commit(id)
     }
 
+    /// Loads the records of `data` like [`Storage::with_data()`] but skips
+    /// damaged record headers and values instead of failing. The records
+    /// following a damaged header are recovered only if the header checksums
+    /// allow locating the next intact record. Returns the storage together
+    /// with the descriptions of the skipped regions. The storage of an older
+    /// version is upgraded to the current version first skipping everything
+    /// from the first damaged record header as the older versions do not
+    /// allow locating the next intact record. The storage is meant to be
+    /// only read from.
+    pub fn salvage(data: D) -> Result<(Self, Vec<String>), DbError> {
+        let mut s = Self {
+            data: SharedData::new(data),
+            records: StorageRecords::new(),
+            transactions: 0,
+            version: 0,
+            compression_threshold: None,
+        };
+        let mut issues = vec![];
+
+        s.version = s.read_version()?;
+        Self::validate_version(s.version)?;
+
+        if s.version != CURRENT_VERSION {
+            s.upgrade_legacy_records(Some(&mut issues))?;
+            s.version = CURRENT_VERSION;
+        }
+
+        let end = s.len();
+        let mut pos = VERSION_RECORD_SIZE;
+
+        while pos < end {
+            match s.read_salvaged_record(pos, end) {
+                Ok(record) => {
+                    if record.index != 0 && s.records.record(record.index).is_ok() {
+                        issues.push(format!(
+                            "Record '{}' at position {pos} is a duplicate",
+                            record.index
+                        ));
+                    } else if let Some(e) = (record.index != 0)
+                        .then(|| s.read_value(&record).err())
+                        .flatten()
+                    {
+                        issues.push(format!(
+                            "Record '{}' at position {pos} skipped: {}",
+                            record.index, e.description
+                        ));
+                    } else {
+                        s.records.set_record(record);
+                    }
+
+                    pos = record.end();
+                }
+                Err(e) => {
+                    let next = s.find_salvaged_record(pos + 1, end).unwrap_or(end);
+                    issues.push(format!("Bytes {pos}..{next} skipped: {}", e.description));
+                    pos = next;
+                }
+            }
+        }
+
+        s.records.rebuild_free_index();
+
+        Ok((s, issues))
+    }
+
+    /// Returns a read-only view of the storage as it is now. Subsequent
+    /// writes to this storage are not visible through the snapshot.
+    pub fn snapshot(&mut self) -> Result<Self, DbError> {
+        Ok(Self {
+            data: self.data.snapshot()?,
+            records: self.records.snapshot(),
+            transactions: 0,
+            version: self.version,
+            compression_threshold: None,
+        })
+    }
+
+    #[cfg(feature = "compression")]
+    pub fn set_compression_threshold(&mut self, threshold: Option<u64>) {
+        self.compression_threshold = threshold;
+    }
+
     pub fn transaction(&mut self) -> u64 {
         self.begin_transaction()
     }
@@ -334,6 +441,11 @@ impl<D: StorageData> Storage<D> {
         self.value_as_bytes_at_size(index, offset, size - std::cmp::min(size, offset))
     }
 
+    /// Reads `size` bytes of the value at `index` starting at `offset`.
+    /// The value checksum is checked only when the whole value is read.
+    /// Partial reads (e.g. of single elements of a vector) are not
+    /// checked as that would require reading the whole value. Use
+    /// [`Storage::verify()`] to check all of the values.
     pub fn value_as_bytes_at_size(
         &'_ self,
         index: StorageIndex,
@@ -342,9 +454,12 @@ impl<D: StorageData> Storage<D> {
     ) -> Result<StorageSlice<'_>, DbError> {
         let record = self.record(index.0)?;
         Self::validate_read_size(offset, size, record.size)?;
-        let pos = record.value_start() + offset;
 
-        self.data.read(pos, size)
+        if offset == 0 && size == record.size {
+            return self.read_value(&record);
+        }
+
+        self.data.read(record.value_start() + offset, size)
     }
 
     #[allow(dead_code)]
@@ -356,6 +471,45 @@ impl<D: StorageData> Storage<D> {
         Ok(self.record(index.0)?.size)
     }
 
+    pub fn verify(&self) -> Vec<String> {
+        let mut issues = vec![];
+        let len = self.len();
+
+        for record in self.records.records() {
+            match self.read_record(record.pos) {
+                Ok(stored) if stored.index != record.index || stored.size != record.size => {
+                    issues.push(format!(
+                        "Record '{}' header at position {} does not match (index: {}, size: {})",
+                        record.index, record.pos, stored.index, stored.size
+                    ));
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    issues.push(format!(
+                        "Record '{}' is unreadable: {}",
+                        record.index, e.description
+                    ));
+                    continue;
+                }
+            }
+
+            if len < record.end() {
+                issues.push(format!(
+                    "Record '{}' at position {} exceeds storage size ({len})",
+                    record.index, record.pos
+                ));
+            } else if let Err(e) = self.read_value(&record) {
+                issues.push(format!(
+                    "Record '{}' value is unreadable: {}",
+                    record.index, e.description
+                ));
+            }
+        }
+
+        issues
+    }
+
     #[allow(dead_code)]
     pub fn version(&self) -> u64 {
         self.version
@@ -435,6 +589,7 @@ impl<D: StorageData> Storage<D> {
             index: 0,
             pos,
             size,
+            checksum: 0,
         })
     }
 
@@ -450,10 +605,7 @@ impl<D: StorageData> Storage<D> {
         let remainder = (old_size + header_size + free_size) - new_size;
         record.size = new_size;
         self.records.set_size(record.index, new_size);
-        self.data.write(
-            record.pos + record.index.serialized_size(),
-            &record.size.serialize(),
-        )?;
+        self.write_record(record)?;
         self.data
             .write(old_end, &vec![0_u8; (new_size - old_size) as usize])?;
 
@@ -468,10 +620,7 @@ impl<D: StorageData> Storage<D> {
         let old_size = record.size;
         record.size = new_size;
         self.records.set_size(record.index, new_size);
-        self.data.write(
-            record.pos + record.index.serialized_size(),
-            &record.size.serialize(),
-        )?;
+        self.write_record(record)?;
         self.append(&vec![0_u8; (new_size - old_size) as usize])
     }
 
@@ -492,20 +641,22 @@ impl<D: StorageData> Storage<D> {
 
     fn erase_bytes(
         &mut self,
-        pos: u64,
+        record: &mut StorageRecord,
         offset_from: u64,
         offset_to: u64,
         size: u64,
     ) -> Result<(), DbError> {
         if offset_from < offset_to {
-            self.data.write(
-                pos + offset_from,
+            self.write_value(
+                record,
+                offset_from,
                 &vec![0_u8; std::cmp::min(size, offset_to - offset_from) as usize],
             )?;
         } else if offset_from > offset_to {
             let position = std::cmp::max(offset_to + size, offset_from);
-            self.data.write(
-                pos + position,
+            self.write_value(
+                record,
+                position,
                 &vec![0_u8; (offset_from + size - position) as usize],
             )?;
         }
@@ -513,22 +664,6 @@ impl<D: StorageData> Storage<D> {
         Ok(())
     }
 
-    fn extract_version(&mut self, record: &StorageRecord) -> Result<u64, DbError> {
-        if record.size < u64::serialized_size_static() {
-            return Err(DbError::storage(
-                DbErrorType::NotEnoughData,
-                format!(
-                    "Invalid version record size ({} < {})",
-                    record.size,
-                    u64::serialized_size_static()
-                ),
-            ));
-        }
-
-        let bytes = self.read_value(record)?.to_vec();
-        u64::deserialize(&bytes)
-    }
-
     fn is_at_end(&mut self, record: &StorageRecord) -> bool {
         self.len() == record.end()
     }
@@ -536,6 +671,7 @@ impl<D: StorageData> Storage<D> {
     fn move_to_end(&mut self, record: &mut StorageRecord, new_size: u64) -> Result<(), DbError> {
         let mut bytes = self.read_value(record)?.to_vec();
         bytes.resize(new_size as usize, 0_u8);
+        self.set_checksum(record, value_checksum(0, &bytes));
         let len = self.len();
         self.free_a_region(record.pos, record.size)?;
         self.update_record(record, len, new_size)?;
@@ -546,42 +682,112 @@ impl<D: StorageData> Storage<D> {
         self.records.new_record(pos, value_len)
     }
 
-    fn read_record(&mut self, pos: u64) -> Result<StorageRecord, DbError> {
+    fn read_record(&self, pos: u64) -> Result<StorageRecord, DbError> {
         let bytes = self.data.read(pos, STORAGE_RECORD_SIZE)?;
-        let index = u64::deserialize(&bytes)?;
+        let mut record = Self::read_legacy_header(&bytes, pos, true)?;
+        record.checksum = u64::deserialize(&bytes[LEGACY_RECORD_SIZE as usize..])?;
+
+        Ok(record)
+    }
+
+    fn read_legacy_header(
+        bytes: &[u8],
+        pos: u64,
+        checksum: bool,
+    ) -> Result<StorageRecord, DbError> {
+        let index = u64::deserialize(bytes)?;
         let size = u64::deserialize(&bytes[index.serialized_size() as usize..])?;
+        let record = StorageRecord {
+            index,
+            pos,
+            size: size & SIZE_MASK,
+            checksum: 0,
+        };
 
-        Ok(StorageRecord { index, pos, size })
+        if checksum && size >> CHECKSUM_SHIFT != record_checksum(&record) {
+            return Err(DbError::storage(
+                DbErrorType::Corrupted,
+                format!("Record header checksum mismatch at position {pos}"),
+            ));
+        }
+
+        Ok(record)
     }
 
-    fn read_records(&mut self) -> Result<(), DbError> {
-        if STORAGE_RECORD_SIZE <= self.len() {
-            let version_record = self.read_record(0)?;
+    fn read_legacy_record(
+        &self,
+        pos: u64,
+        end: u64,
+        checksum: bool,
+    ) -> Result<StorageRecord, DbError> {
+        if end < pos + LEGACY_RECORD_SIZE {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!("Record header at position {pos} exceeds storage size ({end})"),
+            ));
+        }
 
-            if version_record.index == 0 {
-                self.version = self.extract_version(&version_record)?;
-            }
+        let record =
+            Self::read_legacy_header(&self.data.read(pos, LEGACY_RECORD_SIZE)?, pos, checksum)?;
+
+        if end < pos + LEGACY_RECORD_SIZE + record.size {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!(
+                    "Record '{}' at position {pos} exceeds storage size ({end})",
+                    record.index
+                ),
+            ));
+        }
+
+        Ok(record)
+    }
+
+    // Finds the first position from `from` holding an intact record
+    // that is followed by another intact record (or the end).
+    fn find_salvaged_record(&self, from: u64, end: u64) -> Option<u64> {
+        (from..end).find(|pos| {
+            self.read_salvaged_record(*pos, end).is_ok_and(|record| {
+                record.end() == end || self.read_salvaged_record(record.end(), end).is_ok()
+            })
+        })
+    }
+
+    fn read_salvaged_record(&self, pos: u64, end: u64) -> Result<StorageRecord, DbError> {
+        if end < pos + STORAGE_RECORD_SIZE {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!("Record header at position {pos} exceeds storage size ({end})"),
+            ));
         }
 
+        let record = self.read_record(pos)?;
+
+        // every record occupies at least its header so no valid
+        // index can exceed the number of headers that fit the storage
+        if end < record.end() || end / STORAGE_RECORD_SIZE < record.index {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!(
+                    "Record '{}' at position {pos} exceeds storage size ({end})",
+                    record.index
+                ),
+            ));
+        }
+
+        Ok(record)
+    }
+
+    fn read_records(&mut self) -> Result<(), DbError> {
+        self.version = self.read_version()?;
         self.validate_or_update_version()?;
 
         let end = self.len();
-        let mut current_pos = Self::current_version_record().end();
+        let mut current_pos = VERSION_RECORD_SIZE;
 
         while current_pos < end {
             let record = self.read_record(current_pos)?;
-
-            if (end - current_pos + STORAGE_RECORD_SIZE) < record.size {
-                return Err(DbError::storage(
-                    DbErrorType::OutOfBounds,
-                    format!(
-                        "Invalid record size ({}) exceeds remaining storage ({})",
-                        record.size,
-                        end - current_pos
-                    ),
-                ));
-            }
-
+            Self::validate_record_size(&record, end)?;
             self.records.set_record(record);
             current_pos = record.end();
         }
@@ -591,8 +797,54 @@ impl<D: StorageData> Storage<D> {
         Ok(())
     }
 
-    fn read_value(&'_ mut self, record: &StorageRecord) -> Result<StorageSlice<'_>, DbError> {
-        self.data.read(record.value_start(), record.size)
+    fn read_value(&'_ self, record: &StorageRecord) -> Result<StorageSlice<'_>, DbError> {
+        let bytes = self.data.read(record.value_start(), record.size)?;
+
+        if value_checksum(0, &bytes) != record.checksum {
+            return Err(DbError::storage(
+                DbErrorType::Corrupted,
+                format!("Record value checksum mismatch at position {}", record.pos),
+            ));
+        }
+
+        Ok(bytes)
+    }
+
+    // Reads the version from the version record at the start of the
+    // storage. The version record keeps the legacy layout in all versions.
+    // A storage without it (version 0) starts directly with the records.
+    fn read_version(&self) -> Result<u64, DbError> {
+        let len = self.len();
+
+        if len < LEGACY_RECORD_SIZE {
+            return Ok(0);
+        }
+
+        let record = Self::read_legacy_header(&self.data.read(0, LEGACY_RECORD_SIZE)?, 0, false)?;
+
+        if record.index != 0 {
+            return Ok(0);
+        }
+
+        if record.size < u64::serialized_size_static() {
+            return Err(DbError::storage(
+                DbErrorType::NotEnoughData,
+                format!(
+                    "Invalid version record size ({} < {})",
+                    record.size,
+                    u64::serialized_size_static()
+                ),
+            ));
+        }
+
+        self.read_legacy_record(0, len, false)?;
+        let version = u64::deserialize(&self.data.read(LEGACY_RECORD_SIZE, record.size)?)?;
+
+        if (CHECKSUM_VERSION..=CURRENT_VERSION).contains(&version) {
+            self.read_legacy_record(0, len, true)?;
+        }
+
+        Ok(version)
     }
 
     fn record(&self, index: u64) -> Result<StorageRecord, DbError> {
@@ -603,25 +855,30 @@ impl<D: StorageData> Storage<D> {
         self.records.remove_index(index);
     }
 
+    fn set_checksum(&mut self, record: &mut StorageRecord, checksum: u64) {
+        record.checksum = checksum;
+        self.records.set_checksum(record.index, checksum);
+    }
+
     fn shrink_value(&mut self, record: &mut StorageRecord, new_size: u64) -> Result<(), DbError> {
         if self.is_at_end(record) {
+            self.update_checksum(
+                record,
+                new_size,
+                &vec![0_u8; (record.size - new_size) as usize],
+            )?;
             record.size = new_size;
             self.records.set_size(record.index, new_size);
-            self.data.write(
-                record.pos + record.size.serialized_size(),
-                &record.size.serialize(),
-            )?;
+            self.write_record(record)?;
             self.truncate(record.end())
         } else {
             let free_size = record.size - new_size;
 
             if free_size >= STORAGE_RECORD_SIZE {
+                self.update_checksum(record, new_size, &vec![0_u8; free_size as usize])?;
                 record.size = new_size;
                 self.records.set_size(record.index, new_size);
-                self.data.write(
-                    record.pos + record.index.serialized_size(),
-                    &record.size.serialize(),
-                )?;
+                self.write_record(record)?;
                 self.free_a_region(record.end(), free_size - STORAGE_RECORD_SIZE)
             } else {
                 self.move_to_end(record, new_size)
@@ -639,6 +896,95 @@ impl<D: StorageData> Storage<D> {
         Ok(())
     }
 
+    // Updates the checksum of the `record` value as if the `bytes` were
+    // written at `offset` rehashing only the chunks they overwrite.
+    fn update_checksum(
+        &mut self,
+        record: &mut StorageRecord,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<(), DbError> {
+        let start = offset - offset % VALUE_CHUNK_SIZE;
+        let end = std::cmp::min(
+            record.size,
+            (offset + bytes.len() as u64).next_multiple_of(VALUE_CHUNK_SIZE),
+        );
+        let mut chunks = self
+            .data
+            .read(record.value_start() + start, end - start)?
+            .to_vec();
+        let old_checksum = value_checksum(start / VALUE_CHUNK_SIZE, &chunks);
+        chunks[(offset - start) as usize..][..bytes.len()].copy_from_slice(bytes);
+        let new_checksum = value_checksum(start / VALUE_CHUNK_SIZE, &chunks);
+        self.set_checksum(record, record.checksum ^ old_checksum ^ new_checksum);
+
+        Ok(())
+    }
+
+    // Rewrites the records of the older versions (without the value
+    // checksums and possibly without the header checksums and the version
+    // record) to the current layout dropping the free regions. The records
+    // are written after the current end of the data first and then moved
+    // to the start. With `issues` the records from the first damaged one
+    // are skipped and reported instead of failing.
+    fn upgrade_legacy_records(
+        &mut self,
+        mut issues: Option<&mut Vec<String>>,
+    ) -> Result<(), DbError> {
+        let checksums = CHECKSUM_VERSION <= self.version;
+        let end = self.len();
+        let mut pos = if self.version == 0 {
+            0
+        } else {
+            VERSION_RECORD_SIZE
+        };
+        self.data.write(end, &version_record(CURRENT_VERSION))?;
+        let mut new_end = end + VERSION_RECORD_SIZE;
+
+        while pos < end {
+            let legacy = match self.read_legacy_record(pos, end, checksums) {
+                Ok(legacy) => legacy,
+                Err(e) => match issues.as_mut() {
+                    Some(issues) => {
+                        issues.push(format!("Bytes {pos}..{end} skipped: {}", e.description));
+                        break;
+                    }
+                    None => return Err(e),
+                },
+            };
+
+            if legacy.index != 0 {
+                let bytes = self
+                    .data
+                    .read(pos + LEGACY_RECORD_SIZE, legacy.size)?
+                    .to_vec();
+                let record = StorageRecord {
+                    index: legacy.index,
+                    pos: new_end - end,
+                    size: legacy.size,
+                    checksum: value_checksum(0, &bytes),
+                };
+                self.data.write(new_end, &record_header(&record))?;
+                self.data.write(new_end + STORAGE_RECORD_SIZE, &bytes)?;
+                new_end += STORAGE_RECORD_SIZE + record.size;
+            }
+
+            pos += LEGACY_RECORD_SIZE + legacy.size;
+        }
+
+        let len = new_end - end;
+        let mut offset = 0;
+
+        while offset < len {
+            let size = std::cmp::min(CHUNK_SIZE, len - offset);
+            let bytes = self.data.read(end + offset, size)?.to_vec();
+            self.data.write(offset, &bytes)?;
+            offset += size;
+        }
+
+        self.data.resize(len)
+    }
+
     fn update_record(
         &mut self,
         record: &mut StorageRecord,
@@ -652,59 +998,47 @@ impl<D: StorageData> Storage<D> {
         self.write_record(record)
     }
 
-    fn validate_or_update_version(&mut self) -> Result<(), DbError> {
-        if self.version > CURRENT_VERSION {
+    fn validate_version(version: u64) -> Result<(), DbError> {
+        if version > CURRENT_VERSION {
             return Err(DbError::storage(
                 DbErrorType::NotAllowed,
                 format!(
-                    "Storage version '{}' is higher than the current version '{CURRENT_VERSION}'",
-                    self.version
+                    "Storage version '{version}' is higher than the current version '{CURRENT_VERSION}'"
                 ),
             ));
         }
 
+        Ok(())
+    }
+
+    fn validate_or_update_version(&mut self) -> Result<(), DbError> {
+        Self::validate_version(self.version)?;
+
         if self.version == CURRENT_VERSION {
             return Ok(());
         }
 
-        self.version = CURRENT_VERSION;
-        let version_record = Self::current_version_record();
-        let version_record_size = version_record.end();
-        let len = self.data.len();
-        let mut pos = len;
-        let mut size;
-
         let transaction_id = self.transaction();
-        self.data.resize(len + version_record_size)?;
+        self.upgrade_legacy_records(None)?;
+        self.version = CURRENT_VERSION;
+        self.commit(transaction_id)
+    }
 
-        while 0 < pos {
-            if CHUNK_SIZE < pos {
-                size = CHUNK_SIZE;
-                pos -= size;
-            } else {
-                size = pos;
-                pos = 0;
-            }
-            let data = self.data.read(pos, size)?.to_vec();
-            self.data.write(pos + version_record_size, &data)?;
+    fn validate_record_size(record: &StorageRecord, end: u64) -> Result<(), DbError> {
+        if (end - record.pos + STORAGE_RECORD_SIZE) < record.size {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!(
+                    "Invalid record size ({}) exceeds remaining storage ({})",
+                    record.size,
+                    end - record.pos
+                ),
+            ));
         }
 
-        self.write_record(&version_record)?;
-        self.data
-            .write(version_record.value_start(), &self.version.serialize())?;
-        self.commit(transaction_id)?;
-
         Ok(())
     }
 
-    fn current_version_record() -> StorageRecord {
-        StorageRecord {
-            index: 0,
-            pos: 0,
-            size: u64::serialized_size_static(),
-        }
-    }
-
     fn validate_read_size(offset: u64, read_size: u64, value_size: u64) -> Result<(), DbError> {
         if offset > value_size {
             return Err(DbError::storage(
@@ -727,11 +1061,75 @@ impl<D: StorageData> Storage<D> {
     }
 
     fn write_record(&mut self, record: &StorageRecord) -> Result<(), DbError> {
-        let mut bytes = Vec::with_capacity(STORAGE_RECORD_SIZE as usize);
-        bytes.extend(record.index.serialize());
-        bytes.extend(record.size.serialize());
-        self.data.write(record.pos, &bytes)
+        self.data.write(record.pos, &record_header(record))
     }
+
+    fn write_value(
+        &mut self,
+        record: &mut StorageRecord,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<(), DbError> {
+        self.update_checksum(record, offset, bytes)?;
+        self.write_record(record)?;
+        self.data.write(record.value_start() + offset, bytes)
+    }
+}
+
+// 16-bit checksum of the record header kept in the otherwise
+// unused upper bits of the size field.
+fn record_checksum(record: &StorageRecord) -> u64 {
+    let mut hash = record.index.stable_hash();
+    u64::add_to_hash(&mut hash, record.pos);
+    u64::add_to_hash(&mut hash, record.size);
+    (hash ^ (hash >> 16) ^ (hash >> 32) ^ (hash >> 48)) & 0xFFFF
+}
+
+fn record_header(record: &StorageRecord) -> Vec<u8> {
+    let size = record.size | (record_checksum(record) << CHECKSUM_SHIFT);
+    let mut bytes = Vec::with_capacity(STORAGE_RECORD_SIZE as usize);
+    bytes.extend(record.index.serialize());
+    bytes.extend(size.serialize());
+    bytes.extend(record.checksum.serialize());
+    bytes
+}
+
+// Checksum of the value bytes starting at the chunk `first_chunk` of
+// the value: XOR of the hashes of the 8 byte chunks (the last one padded
+// with zeros) combined with their position in the value. Zero chunks do
+// not contribute so padding the value with zeros does not change it and
+// a write needs to rehash only the chunks it overwrites.
+fn value_checksum(first_chunk: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .chunks(VALUE_CHUNK_SIZE as usize)
+        .zip(first_chunk..)
+        .fold(0, |checksum, (chunk, position)| {
+            let mut data = [0_u8; VALUE_CHUNK_SIZE as usize];
+            data[..chunk.len()].copy_from_slice(chunk);
+            let value = u64::from_le_bytes(data);
+
+            if value == 0 {
+                return checksum;
+            }
+
+            let mut hash = position.stable_hash();
+            u64::add_to_hash(&mut hash, value);
+            u64::add_to_hash(&mut hash, position);
+            checksum ^ hash
+        })
+}
+
+fn version_record(version: u64) -> Vec<u8> {
+    let record = StorageRecord {
+        index: 0,
+        pos: 0,
+        size: u64::serialized_size_static(),
+        checksum: 0,
+    };
+    let mut bytes = record_header(&record);
+    bytes.truncate(LEGACY_RECORD_SIZE as usize);
+    bytes.extend(version.serialize());
+    bytes
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
 use crate::DbError;
+#[cfg(feature = "encryption")]
+use crate::Encrypted;
 use crate::FileStorage;
 use crate::FileStorageMemoryMapped;
 use crate::MemoryStorage;
@@ -13,6 +15,8 @@ pub enum AnyStorage {
     MemoryMapped(FileStorageMemoryMapped),
     Memory(MemoryStorage),
     File(FileStorage),
+    #[cfg(feature = "encryption")]
+    Encrypted(Encrypted<FileStorage>),
 }
 
 impl StorageData for AnyStorage {
@@ -21,6 +25,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.backup(name),
             AnyStorage::Memory(s) => s.backup(name),
             AnyStorage::File(s) => s.backup(name),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.backup(name),
         }
     }
 
@@ -29,6 +35,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => AnyStorage::MemoryMapped(s.copy(name)?),
             AnyStorage::Memory(s) => AnyStorage::Memory(s.copy(name)?),
             AnyStorage::File(s) => AnyStorage::File(s.copy(name)?),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => AnyStorage::Encrypted(s.copy(name)?),
         })
     }
 
@@ -37,6 +45,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.flush(),
             AnyStorage::Memory(s) => s.flush(),
             AnyStorage::File(s) => s.flush(),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.flush(),
         }
     }
 
@@ -45,6 +55,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.len(),
             AnyStorage::Memory(s) => s.len(),
             AnyStorage::File(s) => s.len(),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.len(),
         }
     }
 
@@ -53,6 +65,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.name(),
             AnyStorage::Memory(s) => s.name(),
             AnyStorage::File(s) => s.name(),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.name(),
         }
     }
 
@@ -65,6 +79,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.read(pos, value_len),
             AnyStorage::Memory(s) => s.read(pos, value_len),
             AnyStorage::File(s) => s.read(pos, value_len),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.read(pos, value_len),
         }
     }
 
@@ -73,6 +89,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.rename(new_name),
             AnyStorage::Memory(s) => s.rename(new_name),
             AnyStorage::File(s) => s.rename(new_name),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.rename(new_name),
         }
     }
 
@@ -81,6 +99,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.resize(new_len),
             AnyStorage::Memory(s) => s.resize(new_len),
             AnyStorage::File(s) => s.resize(new_len),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.resize(new_len),
         }
     }
 
@@ -89,6 +109,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.write(pos, bytes),
             AnyStorage::Memory(s) => s.write(pos, bytes),
             AnyStorage::File(s) => s.write(pos, bytes),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.write(pos, bytes),
         }
     }
 
@@ -97,6 +119,8 @@ impl StorageData for AnyStorage {
             AnyStorage::MemoryMapped(s) => s.is_empty(),
             AnyStorage::Memory(s) => s.is_empty(),
             AnyStorage::File(s) => s.is_empty(),
+            #[cfg(feature = "encryption")]
+            AnyStorage::Encrypted(s) => s.is_empty(),
         }
     }
 }
@@ -126,6 +150,8 @@ mod tests {
                 Self::MemoryMapped(_) => f.write_str("MemoryMapped"),
                 Self::Memory(_) => f.write_str("Memory"),
                 Self::File(_) => f.write_str("File"),
+                #[cfg(feature = "encryption")]
+                Self::Encrypted(_) => f.write_str("Encrypted"),
             }
         }
     }
@@ -158,6 +184,36 @@ mod tests {
         storage.resize(0).unwrap();
     }
 
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_storage() {
+        let test_file = TestFile::new("encrypted_storage.agdb");
+        let test_file_copy = TestFile::new("encrypted_storage_copy.agdb");
+        let test_file_rename = TestFile::new("encrypted_storage_rename.agdb");
+        let _test_file_dot = TestFile::new(".encrypted_storage.agdb");
+        let test_file_copy_dot = TestFile::new(".encrypted_storage_copy.agdb");
+        let _test_file_rename_dot = TestFile::new(".encrypted_storage_rename.agdb");
+        let test_file_backup = TestFile::new("encrypted_storage_backup.agdb");
+
+        let mut storage =
+            AnyStorage::Encrypted(Encrypted::with_key(&test_file.0, &[1; 32]).unwrap());
+        let _ = format!("{storage:?}");
+        storage.backup(&test_file_backup.0).unwrap();
+        assert!(std::path::Path::new(&test_file_backup.0).exists());
+        let other = storage.copy(&test_file_copy.0).unwrap();
+        assert_eq!(other.name(), test_file_copy.0);
+        assert!(std::path::Path::new(&test_file_copy.0).exists());
+        assert!(std::path::Path::new(&test_file_copy_dot.0).exists());
+        storage.flush().unwrap();
+        assert!(storage.is_empty());
+        assert_eq!(storage.len(), 0);
+        assert_eq!(storage.name(), test_file.0);
+        assert!(storage.read(0, 0).unwrap().is_empty());
+        storage.rename(&test_file_rename.0).unwrap();
+        storage.write(0, &[]).unwrap();
+        storage.resize(0).unwrap();
+    }
+
     #[test]
     fn mapped_storage() {
         let test_file = TestFile::new("mapped_storage.agdb");
@@ -0,0 +1,478 @@
+use super::StorageData;
+use super::StorageSlice;
+use crate::DbError;
+use crate::DbErrorType;
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::AeadCore;
+use chacha20poly1305::aead::OsRng;
+use chacha20poly1305::aead::Payload;
+
+const MAGIC: [u8; 8] = *b"agdbenc1";
+const BLOCK_SIZE: u64 = 4096;
+const NONCE_SIZE: u64 = 24;
+const TAG_SIZE: u64 = 16;
+const STORED_BLOCK_SIZE: u64 = NONCE_SIZE + BLOCK_SIZE + TAG_SIZE;
+const HEADER_SIZE: u64 = MAGIC.len() as u64 + NONCE_SIZE + size_of::<u64>() as u64 + TAG_SIZE;
+
+/// Encryption key of the [`Encrypted`] storage (256 bits).
+pub type EncryptionKey = [u8; 32];
+
+/// Storage wrapper implementing [`StorageData`] that transparently
+/// encrypts all data written to the wrapped storage `D`. The data is
+/// split into fixed-size blocks (4 KiB) each encrypted separately with
+/// XChaCha20-Poly1305 using a fresh random nonce on every write and
+/// authenticated together with its position so that any modification,
+/// truncation or reordering of the blocks is detected on read. The
+/// logical length of the data is kept in an encrypted header which also
+/// serves to validate the key when the storage is opened.
+///
+/// As the wrapped storage only ever sees the encrypted blocks the write
+/// ahead log of the [`FileStorage`](crate::FileStorage) as well as the
+/// files produced by [`StorageData::backup()`] and [`StorageData::copy()`]
+/// are encrypted too (with the same key). Every read decrypts the whole
+/// blocks it touches so it is slower than the plain storage.
+///
+/// The key must be supplied by the caller, i.e. [`StorageData::new()`] is
+/// not supported. Use [`Encrypted::with_key()`] or [`Encrypted::with_data()`]
+/// instead together with `DbImpl::with_data()` or `DbImpl::new_encrypted()`:
+///
+/// ```
+/// # let _test_file = agdb::test_utilities::test_file::TestFile::from("encrypted.agdb");
+/// use agdb::{DbImpl, Encrypted, FileStorage};
+///
+/// let key = [7_u8; 32];
+/// let db = DbImpl::<Encrypted<FileStorage>>::new_encrypted("encrypted.agdb", &key).unwrap();
+/// ```
+pub struct Encrypted<D: StorageData> {
+    cipher: XChaCha20Poly1305,
+    data: D,
+    len: u64,
+}
+
+impl<D: StorageData> Encrypted<D> {
+    /// Creates or opens the storage `name` of type `D`
+    /// encrypted with `key`.
+    pub fn with_key(name: &str, key: &EncryptionKey) -> Result<Self, DbError> {
+        Self::with_data(D::new(name)?, key)
+    }
+
+    /// Wraps the `data` encrypted with `key`. If the `data` are empty
+    /// they are initialized as a new encrypted storage. Otherwise
+    /// the `key` is validated against the stored header.
+    pub fn with_data(data: D, key: &EncryptionKey) -> Result<Self, DbError> {
+        let mut storage = Self {
+            cipher: XChaCha20Poly1305::new(key.into()),
+            data,
+            len: 0,
+        };
+
+        if storage.data.is_empty() {
+            storage.write_header()?;
+        } else {
+            storage.len = storage.read_header()?;
+        }
+
+        Ok(storage)
+    }
+
+    fn block_count(len: u64) -> u64 {
+        len.div_ceil(BLOCK_SIZE)
+    }
+
+    fn block_pos(block: u64) -> u64 {
+        HEADER_SIZE + block * STORED_BLOCK_SIZE
+    }
+
+    fn decrypt(&self, aad: &[u8], bytes: &[u8]) -> Option<Vec<u8>> {
+        let (nonce, msg) = bytes.split_at(NONCE_SIZE as usize);
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), Payload { msg, aad })
+            .ok()
+    }
+
+    fn encrypt(&self, aad: &[u8], msg: &[u8]) -> Result<Vec<u8>, DbError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = self
+            .cipher
+            .encrypt(&nonce, Payload { msg, aad })
+            .map_err(|_| DbError::storage(DbErrorType::NotAllowed, "Failed to encrypt data"))?;
+
+        Ok([nonce.as_slice(), &encrypted].concat())
+    }
+
+    fn read_block(&self, block: u64) -> Result<Vec<u8>, DbError> {
+        if Self::block_count(self.len) <= block {
+            return Ok(vec![0; BLOCK_SIZE as usize]);
+        }
+
+        let bytes = self.data.read(Self::block_pos(block), STORED_BLOCK_SIZE)?;
+
+        self.decrypt(&block.to_le_bytes(), &bytes).ok_or_else(|| {
+            DbError::storage(
+                DbErrorType::Corrupted,
+                format!("Encrypted block '{block}' failed authentication"),
+            )
+        })
+    }
+
+    fn read_header(&self) -> Result<u64, DbError> {
+        if self.data.len() < HEADER_SIZE {
+            return Err(DbError::storage(
+                DbErrorType::Corrupted,
+                format!(
+                    "Encrypted storage header is incomplete ({} < {HEADER_SIZE})",
+                    self.data.len()
+                ),
+            ));
+        }
+
+        let bytes = self.data.read(0, HEADER_SIZE)?;
+        let (magic, header) = bytes.split_at(MAGIC.len());
+
+        if magic != MAGIC {
+            return Err(DbError::storage(
+                DbErrorType::NotAllowed,
+                "Storage is not encrypted",
+            ));
+        }
+
+        let len = self.decrypt(&MAGIC, header).ok_or_else(|| {
+            DbError::storage(
+                DbErrorType::NotAllowed,
+                "Invalid encryption key or corrupted storage header",
+            )
+        })?;
+
+        Ok(u64::from_le_bytes(len.try_into().unwrap_or_default()))
+    }
+
+    fn write_block(&mut self, block: u64, bytes: &[u8]) -> Result<(), DbError> {
+        let encrypted = self.encrypt(&block.to_le_bytes(), bytes)?;
+        self.data.write(Self::block_pos(block), &encrypted)
+    }
+
+    fn write_header(&mut self) -> Result<(), DbError> {
+        let header = self.encrypt(&MAGIC, &self.len.to_le_bytes())?;
+        self.data.write(0, &[MAGIC.as_slice(), &header].concat())
+    }
+
+    // Blocks past the end are kept zeroed so that growing
+    // the storage never exposes previously written data.
+    fn write_zero_blocks(&mut self, from: u64, to: u64) -> Result<(), DbError> {
+        let zeroes = vec![0; BLOCK_SIZE as usize];
+
+        for block in from..to {
+            self.write_block(block, &zeroes)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D: StorageData> StorageData for Encrypted<D> {
+    fn backup(&self, name: &str) -> Result<(), DbError> {
+        self.data.backup(name)
+    }
+
+    fn copy(&self, name: &str) -> Result<Self, DbError> {
+        Ok(Self {
+            cipher: self.cipher.clone(),
+            data: self.data.copy(name)?,
+            len: self.len,
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), DbError> {
+        self.data.flush()
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn name(&self) -> &str {
+        self.data.name()
+    }
+
+    fn new(_name: &str) -> Result<Self, DbError> {
+        Err(DbError::storage(
+            DbErrorType::NotAllowed,
+            "Encrypted storage requires a key, use Encrypted::with_key()",
+        ))
+    }
+
+    fn read(&'_ self, pos: u64, value_len: u64) -> Result<StorageSlice<'_>, DbError> {
+        let end = pos + value_len;
+
+        if self.len < end {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!("Read end ({end}) exceeds storage size ({})", self.len),
+            ));
+        }
+
+        let mut buffer = Vec::with_capacity(value_len as usize);
+        let mut current = pos;
+
+        while current < end {
+            let block = self.read_block(current / BLOCK_SIZE)?;
+            let offset = current % BLOCK_SIZE;
+            let size = (BLOCK_SIZE - offset).min(end - current);
+            buffer.extend_from_slice(&block[offset as usize..(offset + size) as usize]);
+            current += size;
+        }
+
+        Ok(StorageSlice::from(buffer))
+    }
+
+    fn rename(&mut self, new_name: &str) -> Result<(), DbError> {
+        self.data.rename(new_name)
+    }
+
+    fn resize(&mut self, new_len: u64) -> Result<(), DbError> {
+        let block_count = Self::block_count(self.len);
+        let new_block_count = Self::block_count(new_len);
+
+        if new_len < self.len {
+            let tail = new_len % BLOCK_SIZE;
+
+            if tail != 0 {
+                let block = new_len / BLOCK_SIZE;
+                let mut bytes = self.read_block(block)?;
+                bytes[tail as usize..].fill(0);
+                self.write_block(block, &bytes)?;
+            }
+
+            self.data.resize(Self::block_pos(new_block_count))?;
+        } else {
+            self.write_zero_blocks(block_count, new_block_count)?;
+        }
+
+        self.len = new_len;
+        self.write_header()
+    }
+
+    fn write(&mut self, pos: u64, bytes: &[u8]) -> Result<(), DbError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let end = pos + bytes.len() as u64;
+        let first = pos / BLOCK_SIZE;
+        self.write_zero_blocks(Self::block_count(self.len), first)?;
+
+        let mut current = pos;
+
+        while current < end {
+            let block = current / BLOCK_SIZE;
+            let offset = current % BLOCK_SIZE;
+            let size = (BLOCK_SIZE - offset).min(end - current);
+            let source = (current - pos) as usize;
+            let mut plain = self.read_block(block)?;
+            plain[offset as usize..(offset + size) as usize]
+                .copy_from_slice(&bytes[source..source + size as usize]);
+            self.write_block(block, &plain)?;
+            current += size;
+        }
+
+        if self.len < end {
+            self.len = end;
+            self.write_header()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileStorage;
+    use crate::MemoryStorage;
+    use crate::test_utilities::test_file::TestFile;
+
+    const KEY: EncryptionKey = [1; 32];
+
+    fn storage() -> Encrypted<MemoryStorage> {
+        Encrypted::with_data(MemoryStorage::new("encrypted").unwrap(), &KEY).unwrap()
+    }
+
+    #[test]
+    fn new_requires_key() {
+        assert_eq!(
+            Encrypted::<MemoryStorage>::new("encrypted").err(),
+            Some(DbError::storage(
+                DbErrorType::NotAllowed,
+                "Encrypted storage requires a key, use Encrypted::with_key()"
+            ))
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let storage = storage();
+        assert!(storage.is_empty());
+        assert_eq!(storage.name(), "encrypted");
+        assert_eq!(storage.data.len(), HEADER_SIZE);
+    }
+
+    #[test]
+    fn write_and_read_across_blocks() {
+        let mut storage = storage();
+        let bytes = (0..10000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        storage.write(100, &bytes).unwrap();
+
+        assert_eq!(storage.len(), 10100);
+        assert_eq!(storage.read(0, 100).unwrap().to_vec(), vec![0; 100]);
+        assert_eq!(storage.read(100, 10000).unwrap().to_vec(), bytes);
+        assert_eq!(
+            storage.read(4000, 200).unwrap().to_vec(),
+            bytes[3900..4100].to_vec()
+        );
+        assert_eq!(storage.data.len(), HEADER_SIZE + 3 * STORED_BLOCK_SIZE);
+    }
+
+    #[test]
+    fn write_beyond_end() {
+        let mut storage = storage();
+        storage.write(0, &[1, 2, 3]).unwrap();
+        storage.write(9000, &[4]).unwrap();
+
+        assert_eq!(storage.len(), 9001);
+        assert_eq!(storage.read(0, 4).unwrap().to_vec(), vec![1, 2, 3, 0]);
+        assert_eq!(storage.read(5000, 10).unwrap().to_vec(), vec![0; 10]);
+        assert_eq!(storage.read(8999, 2).unwrap().to_vec(), vec![0, 4]);
+    }
+
+    #[test]
+    fn read_out_of_bounds() {
+        let mut storage = storage();
+        storage.write(0, &[1, 2, 3]).unwrap();
+
+        assert_eq!(
+            storage.read(2, 2).err(),
+            Some(DbError::storage(
+                DbErrorType::OutOfBounds,
+                "Read end (4) exceeds storage size (3)"
+            ))
+        );
+    }
+
+    #[test]
+    fn resize() {
+        let mut storage = storage();
+        storage.write(0, &[1; 5000]).unwrap();
+        storage.resize(10).unwrap();
+
+        assert_eq!(storage.len(), 10);
+        assert_eq!(storage.data.len(), HEADER_SIZE + STORED_BLOCK_SIZE);
+
+        storage.resize(6000).unwrap();
+
+        assert_eq!(
+            storage.read(0, 12).unwrap().to_vec(),
+            [[1; 10], [0; 10]].concat()[..12]
+        );
+        assert_eq!(storage.read(4096, 100).unwrap().to_vec(), vec![0; 100]);
+    }
+
+    #[test]
+    fn data_is_encrypted() {
+        let mut storage = storage();
+        let secret = b"very secret value";
+        storage.write(0, secret).unwrap();
+
+        let raw = storage.data.read(0, storage.data.len()).unwrap();
+        assert!(!raw.windows(secret.len()).any(|window| window == secret));
+    }
+
+    #[test]
+    fn reopen() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage =
+                Encrypted::<FileStorage>::with_key(test_file.file_name(), &KEY).unwrap();
+            storage.write(0, b"Hello, World!").unwrap();
+            storage.flush().unwrap();
+        }
+
+        let storage = Encrypted::<FileStorage>::with_key(test_file.file_name(), &KEY).unwrap();
+        assert_eq!(storage.read(0, 13).unwrap().to_vec(), b"Hello, World!");
+    }
+
+    #[test]
+    fn wrong_key() {
+        let mut storage = storage();
+        storage.write(0, b"Hello").unwrap();
+        let raw = storage.data.read(0, storage.data.len()).unwrap().to_vec();
+
+        assert_eq!(
+            Encrypted::with_data(MemoryStorage::from_buffer("encrypted", raw), &[2; 32]).err(),
+            Some(DbError::storage(
+                DbErrorType::NotAllowed,
+                "Invalid encryption key or corrupted storage header"
+            ))
+        );
+    }
+
+    #[test]
+    fn not_encrypted() {
+        assert_eq!(
+            Encrypted::with_data(MemoryStorage::from_buffer("plain", vec![0; 100]), &KEY).err(),
+            Some(DbError::storage(
+                DbErrorType::NotAllowed,
+                "Storage is not encrypted"
+            ))
+        );
+        assert_eq!(
+            Encrypted::with_data(MemoryStorage::from_buffer("plain", vec![0; 10]), &KEY).err(),
+            Some(DbError::storage(
+                DbErrorType::Corrupted,
+                format!("Encrypted storage header is incomplete (10 < {HEADER_SIZE})")
+            ))
+        );
+    }
+
+    #[test]
+    fn tampered_block() {
+        let mut storage = storage();
+        storage.write(0, b"Hello").unwrap();
+        let mut raw = storage.data.read(0, storage.data.len()).unwrap().to_vec();
+        raw[(HEADER_SIZE + NONCE_SIZE) as usize] ^= 1;
+        let storage =
+            Encrypted::with_data(MemoryStorage::from_buffer("encrypted", raw), &KEY).unwrap();
+
+        assert_eq!(
+            storage.read(0, 5).err(),
+            Some(DbError::storage(
+                DbErrorType::Corrupted,
+                "Encrypted block '0' failed authentication"
+            ))
+        );
+    }
+
+    #[test]
+    fn copy_backup_rename() {
+        let test_file = TestFile::new();
+        let copy_file = TestFile::new();
+        let backup_file = TestFile::new();
+        let renamed_file = TestFile::new();
+
+        let mut storage = Encrypted::<FileStorage>::with_key(test_file.file_name(), &KEY).unwrap();
+        storage.write(0, b"Hello").unwrap();
+        storage.backup(backup_file.file_name()).unwrap();
+        let copy = storage.copy(copy_file.file_name()).unwrap();
+        storage.rename(renamed_file.file_name()).unwrap();
+
+        assert_eq!(storage.name(), renamed_file.file_name());
+        assert_eq!(copy.read(0, 5).unwrap().to_vec(), b"Hello");
+
+        let backup = Encrypted::<FileStorage>::with_key(backup_file.file_name(), &KEY).unwrap();
+        assert_eq!(backup.read(0, 5).unwrap().to_vec(), b"Hello");
+        assert!(Encrypted::<FileStorage>::with_key(backup_file.file_name(), &[2; 32]).is_err());
+    }
+}
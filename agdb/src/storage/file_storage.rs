@@ -46,8 +46,11 @@ impl FileStorage {
         Ok(())
     }
 
+    // The log holds the bytes each write replaced so the records are
+    // applied newest first. When a region was written more than once
+    // the oldest record holding its original bytes is applied last.
     fn apply_wal(file: &mut File, wal: &mut WriteAheadLog) -> Result<(), DbError> {
-        for record in wal.records()? {
+        for record in wal.records()?.into_iter().rev() {
             Self::apply_wal_record(file, record)?;
         }
 
@@ -174,8 +177,10 @@ mod tests {
     use crate::DbErrorType;
     use crate::storage::Storage;
     use crate::storage::StorageIndex;
+    use crate::storage::memory_storage::MemoryStorage;
     use crate::storage::storage_records::STORAGE_RECORD_SIZE;
     use crate::storage::storage_records::StorageRecord;
+    use crate::storage::value_checksum;
     use crate::test_utilities::test_file::TestFile;
     use crate::utilities::serialize::Serialize;
     use crate::utilities::serialize::SerializeStatic;
@@ -188,26 +193,31 @@ mod tests {
                 index: 0,
                 pos: 0,
                 size: 8,
+                checksum: 0,
             },
             StorageRecord {
                 index: 1,
                 pos: 24,
                 size: 48,
+                checksum: 0,
             },
             StorageRecord {
                 index: 4,
                 pos: 88,
                 size: 321654876,
+                checksum: 0,
             },
             StorageRecord {
                 index: 1,
                 pos: 104,
                 size: 0,
+                checksum: 0,
             },
             StorageRecord {
                 index: 2,
                 pos: 120,
                 size: 16,
+                checksum: 0,
             },
         ];
 
@@ -949,8 +959,15 @@ mod tests {
             index3 = storage.insert(&value3).unwrap();
         }
 
+        let mut bytes = value1.serialize();
+        bytes[..8].copy_from_slice(&2_u64.serialize());
         let mut wal = WriteAheadLog::new(test_file.file_name()).unwrap();
-        wal.insert(u64::serialized_size_static() * 5, &2_u64.serialize())
+        wal.insert(
+            u64::serialized_size_static() * 5,
+            &value_checksum(0, &bytes).serialize(),
+        )
+        .unwrap();
+        wal.insert(u64::serialized_size_static() * 6, &2_u64.serialize())
             .unwrap();
 
         let storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
@@ -972,8 +989,8 @@ mod tests {
         storage.optimize_storage().unwrap();
 
         let actual_size = std::fs::metadata(test_file.file_name()).unwrap().len();
-        let expected_size =
-            (u64::serialized_size_static() * 2) * 3 + i64::serialized_size_static() * 3;
+        let expected_size = (u64::serialized_size_static() * 2 + u64::serialized_size_static())
+            + (STORAGE_RECORD_SIZE + i64::serialized_size_static()) * 2;
 
         assert_eq!(actual_size, expected_size);
         assert_eq!(storage.value(index1), Ok(1_i64));
@@ -1337,7 +1354,7 @@ mod tests {
                 .write(true)
                 .open(TestFile::hidden_filename(test_file.file_name()))
                 .unwrap();
-            file.set_len((std::mem::size_of::<u64>() * 4 + 6) as u64)
+            file.set_len((std::mem::size_of::<u64>() * 7 + 6) as u64)
                 .unwrap();
         }
 
@@ -1364,7 +1381,7 @@ mod tests {
                 .write(true)
                 .open(TestFile::hidden_filename(test_file.file_name()))
                 .unwrap();
-            file.set_len((std::mem::size_of::<u64>() * 3 + 5) as u64)
+            file.set_len((std::mem::size_of::<u64>() * 5 + 5) as u64)
                 .unwrap();
         }
 
@@ -1372,6 +1389,237 @@ mod tests {
         assert_eq!(storage.len(), 5);
     }
 
+    #[test]
+    fn corrupted_wal_record_checksum_is_truncated() {
+        let test_file = TestFile::new();
+
+        {
+            let mut wal = WriteAheadLog::new(test_file.file_name()).unwrap();
+            wal.insert(0, "Hello".as_bytes()).unwrap();
+            wal.insert(5, "World".as_bytes()).unwrap();
+        }
+
+        // flip the last byte of the second record's value
+        {
+            let wal_file = TestFile::hidden_filename(test_file.file_name());
+            let mut bytes = std::fs::read(&wal_file).unwrap();
+            let last = bytes.len() - 1;
+            bytes[last] ^= 1;
+            std::fs::write(wal_file, bytes).unwrap();
+        }
+
+        let storage = FileStorage::new(test_file.file_name()).unwrap();
+        assert_eq!(storage.len(), 5);
+    }
+
+    #[test]
+    fn wal_without_checksums_is_applied() {
+        let test_file = TestFile::new();
+        std::fs::write(test_file.file_name(), "Hello, World!").unwrap();
+
+        let mut bytes = 7_u64.serialize();
+        bytes.extend(6_u64.serialize());
+        bytes.extend("Worlds".as_bytes());
+        std::fs::write(TestFile::hidden_filename(test_file.file_name()), bytes).unwrap();
+
+        let storage = FileStorage::new(test_file.file_name()).unwrap();
+        assert_eq!(storage.read(0, 13).unwrap(), "Hello, Worlds".as_bytes());
+    }
+
+    #[test]
+    fn wal_with_overlapping_records_is_applied_newest_first() {
+        let test_file = TestFile::new();
+        std::fs::write(test_file.file_name(), "Mello, World!??").unwrap();
+
+        // log of writes of "Jello" at 0, "!!" at 13 (past the end),
+        // "Mello" at 0 and "??" at 13 over the original "Hello, World!"
+        {
+            let mut wal = WriteAheadLog::new(test_file.file_name()).unwrap();
+            wal.insert(0, "Hello".as_bytes()).unwrap();
+            wal.insert(13, &[]).unwrap();
+            wal.insert(0, "Jello".as_bytes()).unwrap();
+            wal.insert(13, "!!".as_bytes()).unwrap();
+        }
+
+        let storage = FileStorage::new(test_file.file_name()).unwrap();
+        assert_eq!(storage.len(), 13);
+        assert_eq!(storage.read(0, 13).unwrap(), "Hello, World!".as_bytes());
+    }
+
+    #[test]
+    fn verify() {
+        let test_file = TestFile::new();
+        let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+        let index1 = storage.insert(&1_i64).unwrap();
+        let index2 = storage.insert(&"Hello, World!".to_string()).unwrap();
+
+        assert_eq!(storage.verify(), Vec::<String>::new());
+
+        let record1 = storage.record(index1.0).unwrap();
+        let record2 = storage.record(index2.0).unwrap();
+        storage.data.write(record1.pos, &5_u64.serialize()).unwrap();
+        storage.data.resize(record2.end() - 1).unwrap();
+
+        assert_eq!(
+            storage.verify(),
+            vec![
+                "Record '1' is unreadable: Record header checksum mismatch at position 24"
+                    .to_string(),
+                "Record '2' at position 56 exceeds storage size (100)".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn corrupted_record_value() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+            storage.insert(&1_i64).unwrap();
+            storage.insert(&2_i64).unwrap();
+        }
+
+        // flip a bit in the value of the first record leaving its header intact
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            bytes[50] ^= 4;
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        let storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+
+        assert_eq!(
+            storage.verify(),
+            vec![
+                "Record '1' value is unreadable: Record value checksum mismatch at position 24"
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            storage.value::<i64>(StorageIndex(1)).unwrap_err(),
+            DbError::storage(
+                DbErrorType::Corrupted,
+                "Record value checksum mismatch at position 24"
+            )
+        );
+        assert_eq!(storage.value::<i64>(StorageIndex(2)).unwrap(), 2);
+    }
+
+    #[test]
+    fn salvage_corrupted_record_value() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+            storage.insert(&1_i64).unwrap();
+            storage.insert(&2_i64).unwrap();
+        }
+
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            bytes[50] ^= 4;
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        let (storage, issues) =
+            Storage::<MemoryStorage>::salvage(MemoryStorage::new(test_file.file_name()).unwrap())
+                .unwrap();
+
+        assert_eq!(
+            issues,
+            vec![
+                "Record '1' at position 24 skipped: Record value checksum mismatch at position 24"
+            ]
+        );
+        assert!(storage.value::<i64>(StorageIndex(1)).is_err());
+        assert_eq!(storage.value::<i64>(StorageIndex(2)).unwrap(), 2);
+    }
+
+    #[test]
+    fn corrupted_record_header() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+            storage.insert(&1_i64).unwrap();
+        }
+
+        // flip a bit in the size of the first record after the version record
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            bytes[32] ^= 4;
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        assert_eq!(
+            Storage::<FileStorage>::new(test_file.file_name()).unwrap_err(),
+            DbError::storage(
+                DbErrorType::Corrupted,
+                "Record header checksum mismatch at position 24"
+            )
+        );
+    }
+
+    #[test]
+    fn salvage_corrupted_record_header() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+            storage.insert(&1_i64).unwrap();
+            storage.insert(&2_i64).unwrap();
+            storage.insert(&3_i64).unwrap();
+        }
+
+        // flip a bit in the size of the second record
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            bytes[64] ^= 4;
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        let (storage, issues) =
+            Storage::<MemoryStorage>::salvage(MemoryStorage::new(test_file.file_name()).unwrap())
+                .unwrap();
+
+        assert_eq!(
+            issues,
+            vec!["Bytes 56..88 skipped: Record header checksum mismatch at position 56"]
+        );
+        assert_eq!(storage.value::<i64>(StorageIndex(1)).unwrap(), 1);
+        assert!(storage.value::<i64>(StorageIndex(2)).is_err());
+        assert_eq!(storage.value::<i64>(StorageIndex(3)).unwrap(), 3);
+    }
+
+    #[test]
+    fn salvage_truncated_record() {
+        let test_file = TestFile::new();
+
+        {
+            let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
+            storage.insert(&1_i64).unwrap();
+            storage.insert(&2_i64).unwrap();
+        }
+
+        {
+            let mut bytes = std::fs::read(test_file.file_name()).unwrap();
+            bytes.truncate(bytes.len() - 4);
+            std::fs::write(test_file.file_name(), bytes).unwrap();
+        }
+
+        let (storage, issues) =
+            Storage::<MemoryStorage>::salvage(MemoryStorage::new(test_file.file_name()).unwrap())
+                .unwrap();
+
+        assert_eq!(
+            issues,
+            vec!["Bytes 56..84 skipped: Record '2' at position 56 exceeds storage size (84)"]
+        );
+        assert_eq!(storage.value::<i64>(StorageIndex(1)).unwrap(), 1);
+        assert!(storage.value::<i64>(StorageIndex(2)).is_err());
+    }
+
     #[test]
     fn load_with_version_info() {
         let test_file = TestFile::new();
@@ -1399,8 +1647,8 @@ mod tests {
 
         assert_eq!(vec, vec![1, 2, 3, 4, 5]);
         assert_eq!(val, -20);
-        assert_eq!(storage.len(), 149);
-        assert_eq!(storage.version(), 1);
+        assert_eq!(storage.len(), 128);
+        assert_eq!(storage.version(), 3);
     }
 
     #[test]
@@ -1432,8 +1680,8 @@ mod tests {
 
         assert_eq!(vec, vec![1, 2, 3, 4, 5]);
         assert_eq!(val, -20);
-        assert_eq!(storage.len(), 112);
-        assert_eq!(storage.version(), 1);
+        assert_eq!(storage.len(), 128);
+        assert_eq!(storage.version(), 3);
     }
 
     #[test]
@@ -1463,8 +1711,8 @@ mod tests {
 
         assert_eq!(vec, vec![1, 2, 3, 4, 5]);
         assert_eq!(val, -20);
-        assert_eq!(storage.len(), 112);
-        assert_eq!(storage.version(), 1);
+        assert_eq!(storage.len(), 128);
+        assert_eq!(storage.version(), 3);
     }
 
     #[test]
@@ -1473,14 +1721,14 @@ mod tests {
 
         let mut with_version_record = 0_u64.serialize();
         with_version_record.extend(8_u64.serialize());
-        with_version_record.extend(2_u64.serialize());
+        with_version_record.extend(4_u64.serialize());
         std::fs::write(test_file.file_name(), with_version_record).unwrap();
 
         assert_eq!(
             Storage::<FileStorage>::new(test_file.file_name()).unwrap_err(),
             DbError::storage(
                 DbErrorType::NotAllowed,
-                "Storage version '2' is higher than the current version '1'"
+                "Storage version '4' is higher than the current version '3'"
             )
         );
     }
@@ -1517,7 +1765,7 @@ mod tests {
         let vec = storage.value::<Vec<u64>>(StorageIndex(1)).unwrap();
 
         assert_eq!(vec, value1);
-        assert_eq!(storage.version(), 1);
+        assert_eq!(storage.version(), 3);
     }
 
     #[test]
@@ -1665,16 +1913,16 @@ mod tests {
 
         assert_eq!(storage.records.free_size(), 40);
 
-        // Every free needs space for the header (16 bytes).
-        // Free region after remove is 40 bytes + HEADER_SIZE (16) = 56 bytes.
-        // Request is for 4 bytes + HEADER_SIZE (16) = 20 bytes.
+        // Every free needs space for the header (24 bytes).
+        // Free region after remove is 40 bytes + HEADER_SIZE (24) = 64 bytes.
+        // Request is for 4 bytes + HEADER_SIZE (24) = 28 bytes.
         // Free region would need to be split - remainder of 36 bytes.
-        // 36 bytes ≥ HEADER_SIZE (16) → can split, new free region of 20 bytes + HEADER_SIZE (16).
+        // 36 bytes ≥ HEADER_SIZE (24) → can split, new free region of 12 bytes + HEADER_SIZE (24).
         let index4 = storage.insert_bytes(&[0u8; 4]).unwrap();
 
         assert_eq!(storage.len(), size_after_remove);
         assert_eq!(storage.value_as_bytes(index4).unwrap().len(), 4);
-        assert_eq!(storage.records.free_size(), 20);
+        assert_eq!(storage.records.free_size(), 12);
     }
 
     #[test]
@@ -1701,7 +1949,7 @@ mod tests {
 
         assert_eq!(storage.len(), size_after_remove);
         assert_eq!(storage.value_as_bytes(index4).unwrap().len(), 4);
-        assert_eq!(storage.records.free_size(), 20);
+        assert_eq!(storage.records.free_size(), 12);
     }
 
     #[test]
@@ -1720,7 +1968,7 @@ mod tests {
 
         assert_eq!(
             storage.records.free_size(),
-            2 * i64::serialized_size_static() + 16
+            2 * i64::serialized_size_static() + STORAGE_RECORD_SIZE
         );
     }
 
@@ -1740,7 +1988,7 @@ mod tests {
 
         assert_eq!(
             storage.records.free_size(),
-            2 * i64::serialized_size_static() + 16
+            2 * i64::serialized_size_static() + STORAGE_RECORD_SIZE
         );
     }
 
@@ -1761,7 +2009,7 @@ mod tests {
 
         assert_eq!(
             storage.records.free_size(),
-            3 * i64::serialized_size_static() + 2 * 16
+            3 * i64::serialized_size_static() + 2 * STORAGE_RECORD_SIZE
         );
     }
 
@@ -1816,7 +2064,7 @@ mod tests {
         assert_eq!(storage.value_size(index1).unwrap(), 16);
         assert_eq!(storage.value::<i64>(index2), Ok(42_i64));
         assert_eq!(storage.value::<i64>(index3), Ok(43_i64));
-        assert_eq!(storage.records.free_size(), 16);
+        assert_eq!(storage.records.free_size(), 8);
     }
 
     #[test]
@@ -1828,9 +2076,7 @@ mod tests {
 
         {
             let mut storage = Storage::<FileStorage>::new(test_file.file_name()).unwrap();
-            index_big = storage
-                .insert(&vec![1_i64, 2_i64, 3_i64, 4_i64, 5_i64])
-                .unwrap();
+            index_big = storage.insert(&vec![1_i64, 2_i64, 3_i64, 4_i64]).unwrap();
             index_next = storage.insert(&99_i64).unwrap();
 
             storage.replace(index_big, &42_i64).unwrap();
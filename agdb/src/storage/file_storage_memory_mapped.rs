@@ -78,6 +78,8 @@ mod tests {
     use crate::DbErrorType;
     use crate::storage::Storage;
     use crate::storage::StorageIndex;
+    use crate::storage::storage_records::STORAGE_RECORD_SIZE;
+    use crate::storage::value_checksum;
     use crate::storage::write_ahead_log::WriteAheadLog;
     use crate::test_utilities::test_file::TestFile;
     use crate::utilities::serialize::Serialize;
@@ -808,8 +810,15 @@ mod tests {
             index3 = storage.insert(&value3).unwrap();
         }
 
+        let mut bytes = value1.serialize();
+        bytes[..8].copy_from_slice(&2_u64.serialize());
         let mut wal = WriteAheadLog::new(test_file.file_name()).unwrap();
-        wal.insert(u64::serialized_size_static() * 5, &2_u64.serialize())
+        wal.insert(
+            u64::serialized_size_static() * 5,
+            &value_checksum(0, &bytes).serialize(),
+        )
+        .unwrap();
+        wal.insert(u64::serialized_size_static() * 6, &2_u64.serialize())
             .unwrap();
 
         let storage = Storage::<FileStorageMemoryMapped>::new(test_file.file_name()).unwrap();
@@ -831,8 +840,8 @@ mod tests {
         storage.optimize_storage().unwrap();
 
         let actual_size = std::fs::metadata(test_file.file_name()).unwrap().len();
-        let expected_size =
-            (u64::serialized_size_static() * 2) * 3 + i64::serialized_size_static() * 3;
+        let expected_size = (u64::serialized_size_static() * 2 + u64::serialized_size_static())
+            + (STORAGE_RECORD_SIZE + i64::serialized_size_static()) * 2;
 
         assert_eq!(actual_size, expected_size);
         assert_eq!(storage.value(index1), Ok(1_i64));
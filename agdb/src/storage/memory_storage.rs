@@ -90,6 +90,7 @@ mod tests {
     use crate::DbErrorType;
     use crate::storage::Storage;
     use crate::storage::StorageIndex;
+    use crate::storage::storage_records::STORAGE_RECORD_SIZE;
     use crate::utilities::serialize::Serialize;
     use crate::utilities::serialize::SerializeStatic;
 
@@ -709,8 +710,8 @@ mod tests {
         storage.remove(index2).unwrap();
         storage.optimize_storage().unwrap();
 
-        let expected_size =
-            (u64::serialized_size_static() * 2) * 3 + i64::serialized_size_static() * 3;
+        let expected_size = (u64::serialized_size_static() * 2 + u64::serialized_size_static())
+            + (STORAGE_RECORD_SIZE + i64::serialized_size_static()) * 2;
 
         assert_eq!(storage.len(), expected_size);
         assert_eq!(storage.value(index1), Ok(1_i64));
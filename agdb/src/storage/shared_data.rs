@@ -0,0 +1,436 @@
+use super::StorageData;
+use super::StorageSlice;
+use crate::DbError;
+use crate::DbErrorType;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+const PAGE_SIZE: u64 = 4096;
+
+/// Storage data that can be shared with read-only snapshots. The data
+/// is owned exclusively and read directly until the first snapshot is
+/// taken. Afterwards it is shared behind a lock and every write first
+/// preserves the original content of the pages it overwrites for each
+/// live snapshot so that the snapshots keep reading the data as it
+/// was when they were taken. The preserved pages of a snapshot are
+/// released when it is dropped and once all snapshots are dropped
+/// the data becomes exclusive again on the next write.
+pub(crate) struct SharedData<D: StorageData> {
+    name: String,
+    state: State<D>,
+}
+
+enum State<D: StorageData> {
+    Exclusive(D),
+    Shared(Arc<RwLock<Versions<D>>>),
+    Snapshot {
+        versions: Arc<RwLock<Versions<D>>>,
+        id: u64,
+        len: u64,
+    },
+    Moved,
+}
+
+struct Versions<D: StorageData> {
+    data: D,
+    snapshots: HashMap<u64, PreservedPages>,
+    next_id: u64,
+}
+
+struct PreservedPages {
+    len: u64,
+    pages: HashMap<u64, Vec<u8>>,
+}
+
+impl<D: StorageData> SharedData<D> {
+    pub(crate) fn new(data: D) -> Self {
+        Self {
+            name: data.name().to_string(),
+            state: State::Exclusive(data),
+        }
+    }
+
+    pub(crate) fn backup(&self, name: &str) -> Result<(), DbError> {
+        match &self.state {
+            State::Exclusive(data) => data.backup(name),
+            State::Shared(versions) => read(versions).data.backup(name),
+            State::Snapshot { .. } | State::Moved => Err(read_only()),
+        }
+    }
+
+    pub(crate) fn copy(&self, name: &str) -> Result<D, DbError> {
+        match &self.state {
+            State::Exclusive(data) => data.copy(name),
+            State::Shared(versions) => read(versions).data.copy(name),
+            State::Snapshot { .. } | State::Moved => Err(read_only()),
+        }
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<(), DbError> {
+        self.unshare();
+
+        match &mut self.state {
+            State::Exclusive(data) => data.flush(),
+            State::Shared(versions) => write(versions).data.flush(),
+            State::Snapshot { .. } | State::Moved => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_snapshot(&self) -> bool {
+        matches!(self.state, State::Snapshot { .. })
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        match &self.state {
+            State::Exclusive(data) => data.len(),
+            State::Shared(versions) => read(versions).data.len(),
+            State::Snapshot { len, .. } => *len,
+            State::Moved => 0,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn read(&'_ self, pos: u64, value_len: u64) -> Result<StorageSlice<'_>, DbError> {
+        match &self.state {
+            State::Exclusive(data) => data.read(pos, value_len),
+            State::Shared(versions) => Ok(StorageSlice::from(
+                read(versions).data.read(pos, value_len)?.into_owned(),
+            )),
+            State::Snapshot { versions, id, len } => {
+                if *len < pos + value_len {
+                    return Err(DbError::storage(
+                        DbErrorType::OutOfBounds,
+                        format!(
+                            "Snapshot read end ({}) exceeds its size ({len})",
+                            pos + value_len
+                        ),
+                    ));
+                }
+
+                Ok(StorageSlice::from(
+                    read(versions).read_snapshot(*id, pos, value_len)?,
+                ))
+            }
+            State::Moved => Err(read_only()),
+        }
+    }
+
+    pub(crate) fn rename(&mut self, new_name: &str) -> Result<(), DbError> {
+        match &mut self.state {
+            State::Exclusive(data) => data.rename(new_name)?,
+            State::Shared(versions) => write(versions).data.rename(new_name)?,
+            State::Snapshot { .. } | State::Moved => return Err(read_only()),
+        }
+
+        self.name = new_name.to_string();
+
+        Ok(())
+    }
+
+    pub(crate) fn resize(&mut self, new_len: u64) -> Result<(), DbError> {
+        self.unshare();
+
+        match &mut self.state {
+            State::Exclusive(data) => data.resize(new_len),
+            State::Shared(versions) => {
+                let mut versions = write(versions);
+                let len = versions.data.len();
+
+                if new_len < len {
+                    versions.preserve(new_len, len - new_len)?;
+                }
+
+                versions.data.resize(new_len)
+            }
+            State::Snapshot { .. } | State::Moved => Err(read_only()),
+        }
+    }
+
+    /// Returns a read-only view of the data as it is now.
+    pub(crate) fn snapshot(&mut self) -> Result<Self, DbError> {
+        let versions = self.share()?;
+        let mut guard = write(&versions);
+        let id = guard.next_id;
+        let len = guard.data.len();
+        guard.next_id += 1;
+        guard.snapshots.insert(
+            id,
+            PreservedPages {
+                len,
+                pages: HashMap::new(),
+            },
+        );
+        drop(guard);
+
+        Ok(Self {
+            name: self.name.clone(),
+            state: State::Snapshot { versions, id, len },
+        })
+    }
+
+    pub(crate) fn write(&mut self, pos: u64, bytes: &[u8]) -> Result<(), DbError> {
+        self.unshare();
+
+        match &mut self.state {
+            State::Exclusive(data) => data.write(pos, bytes),
+            State::Shared(versions) => {
+                let mut versions = write(versions);
+                versions.preserve(pos, bytes.len() as u64)?;
+                versions.data.write(pos, bytes)
+            }
+            State::Snapshot { .. } | State::Moved => Err(read_only()),
+        }
+    }
+
+    fn share(&mut self) -> Result<Arc<RwLock<Versions<D>>>, DbError> {
+        self.state = match std::mem::replace(&mut self.state, State::Moved) {
+            State::Exclusive(data) => State::Shared(Arc::new(RwLock::new(Versions {
+                data,
+                snapshots: HashMap::new(),
+                next_id: 0,
+            }))),
+            state => state,
+        };
+
+        match &self.state {
+            State::Shared(versions) => Ok(versions.clone()),
+            State::Exclusive(_) | State::Snapshot { .. } | State::Moved => Err(read_only()),
+        }
+    }
+
+    // Takes the data back once there are no snapshots
+    // so that it can be read again without the lock.
+    fn unshare(&mut self) {
+        self.state = match std::mem::replace(&mut self.state, State::Moved) {
+            State::Shared(versions) => match Arc::try_unwrap(versions) {
+                Ok(versions) => State::Exclusive(
+                    versions
+                        .into_inner()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .data,
+                ),
+                Err(versions) => State::Shared(versions),
+            },
+            state => state,
+        };
+    }
+}
+
+impl<D: StorageData> Drop for SharedData<D> {
+    fn drop(&mut self) {
+        if let State::Snapshot { versions, id, .. } = &self.state {
+            write(versions).snapshots.remove(id);
+        }
+    }
+}
+
+impl<D: StorageData> Versions<D> {
+    // Copies the pages overlapping `pos..pos + len` that have not
+    // been preserved yet for each snapshot they are visible to.
+    fn preserve(&mut self, pos: u64, len: u64) -> Result<(), DbError> {
+        for preserved in self.snapshots.values_mut() {
+            let end = std::cmp::min(pos + len, preserved.len);
+
+            if end <= pos {
+                continue;
+            }
+
+            for page in pos / PAGE_SIZE..=(end - 1) / PAGE_SIZE {
+                if let Entry::Vacant(entry) = preserved.pages.entry(page) {
+                    let start = page * PAGE_SIZE;
+                    let size = std::cmp::min(PAGE_SIZE, preserved.len - start);
+                    entry.insert(self.data.read(start, size)?.to_vec());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_snapshot(&self, id: u64, pos: u64, value_len: u64) -> Result<Vec<u8>, DbError> {
+        let preserved = self.snapshots.get(&id).ok_or_else(|| {
+            DbError::storage(DbErrorType::NotFound, format!("Snapshot '{id}' not found"))
+        })?;
+
+        if preserved.pages.is_empty() {
+            return Ok(self.data.read(pos, value_len)?.to_vec());
+        }
+
+        let end = pos + value_len;
+        let mut bytes = Vec::with_capacity(value_len as usize);
+        let mut current = pos;
+
+        while current < end {
+            let page = current / PAGE_SIZE;
+            let page_start = page * PAGE_SIZE;
+            let read_end = std::cmp::min(page_start + PAGE_SIZE, end);
+
+            if let Some(data) = preserved.pages.get(&page) {
+                bytes.extend_from_slice(
+                    &data[(current - page_start) as usize..(read_end - page_start) as usize],
+                );
+            } else {
+                bytes.extend_from_slice(&self.data.read(current, read_end - current)?);
+            }
+
+            current = read_end;
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn read<D: StorageData>(
+    versions: &RwLock<Versions<D>>,
+) -> std::sync::RwLockReadGuard<'_, Versions<D>> {
+    versions.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<D: StorageData>(
+    versions: &RwLock<Versions<D>>,
+) -> std::sync::RwLockWriteGuard<'_, Versions<D>> {
+    versions.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn read_only() -> DbError {
+    DbError::storage(DbErrorType::NotAllowed, "Snapshot is read-only")
+}
+
+impl<D: StorageData> std::fmt::Debug for SharedData<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedData")
+            .field("name", &self.name)
+            .field("snapshot", &self.is_snapshot())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::memory_storage::MemoryStorage;
+
+    fn data(len: u64) -> SharedData<MemoryStorage> {
+        let bytes = (0..len).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+        SharedData::new(MemoryStorage::from_buffer("test", bytes))
+    }
+
+    fn versions(data: &SharedData<MemoryStorage>) -> &RwLock<Versions<MemoryStorage>> {
+        match &data.state {
+            State::Shared(versions) | State::Snapshot { versions, .. } => versions,
+            State::Exclusive(_) | State::Moved => panic!("data is not shared"),
+        }
+    }
+
+    #[test]
+    fn exclusive_without_snapshots() {
+        let mut data = data(10);
+
+        assert!(matches!(
+            data.read(0, 10).unwrap(),
+            StorageSlice::Borrowed(_)
+        ));
+
+        let snapshot = data.snapshot().unwrap();
+        let other = data.snapshot().unwrap();
+
+        assert!(!data.is_snapshot());
+        assert!(snapshot.is_snapshot());
+        assert!(matches!(data.read(0, 10).unwrap(), StorageSlice::Owned(_)));
+        assert_eq!(read(versions(&data)).snapshots.len(), 2);
+
+        drop(snapshot);
+        drop(other);
+        assert!(read(versions(&data)).snapshots.is_empty());
+
+        data.write(0, &[1]).unwrap();
+        assert!(matches!(data.state, State::Exclusive(_)));
+        assert!(matches!(
+            data.read(0, 10).unwrap(),
+            StorageSlice::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn snapshot_reads_across_preserved_pages() {
+        let mut data = data(PAGE_SIZE * 3);
+        let original = data.read(0, PAGE_SIZE * 3).unwrap().to_vec();
+        let snapshot = data.snapshot().unwrap();
+
+        data.write(PAGE_SIZE - 2, &[0; 4]).unwrap();
+        data.write(PAGE_SIZE * 3, &[0; 10]).unwrap();
+
+        assert_eq!(snapshot.read(0, PAGE_SIZE * 3).unwrap().to_vec(), original);
+        assert_eq!(
+            snapshot.read(PAGE_SIZE - 5, 10).unwrap().to_vec(),
+            original[(PAGE_SIZE - 5) as usize..(PAGE_SIZE + 5) as usize]
+        );
+        assert_eq!(data.read(PAGE_SIZE - 2, 4).unwrap().to_vec(), vec![0; 4]);
+        assert_eq!(data.len(), PAGE_SIZE * 3 + 10);
+        assert_eq!(snapshot.len(), PAGE_SIZE * 3);
+    }
+
+    #[test]
+    fn snapshot_after_shrink() {
+        let mut data = data(PAGE_SIZE + 100);
+        let original = data.read(0, PAGE_SIZE + 100).unwrap().to_vec();
+        let snapshot = data.snapshot().unwrap();
+
+        data.resize(10).unwrap();
+        data.resize(PAGE_SIZE * 2).unwrap();
+
+        assert_eq!(
+            snapshot.read(0, PAGE_SIZE + 100).unwrap().to_vec(),
+            original
+        );
+        assert_eq!(data.read(10, 10).unwrap().to_vec(), vec![0; 10]);
+    }
+
+    #[test]
+    fn snapshot_out_of_bounds() {
+        let mut data = data(10);
+        let snapshot = data.snapshot().unwrap();
+        data.write(10, &[1, 2]).unwrap();
+
+        assert_eq!(
+            snapshot.read(5, 6).unwrap_err().description,
+            "Snapshot read end (11) exceeds its size (10)"
+        );
+    }
+
+    #[test]
+    fn snapshot_is_read_only() {
+        let mut data = data(10);
+        let mut snapshot = data.snapshot().unwrap();
+
+        assert_eq!(snapshot.write(0, &[1]).unwrap_err(), read_only());
+        assert_eq!(snapshot.resize(0).unwrap_err(), read_only());
+        assert_eq!(snapshot.rename("x").unwrap_err(), read_only());
+        assert_eq!(snapshot.backup("x").unwrap_err(), read_only());
+        assert!(snapshot.copy("x").is_err());
+        assert!(snapshot.snapshot().is_err());
+    }
+
+    #[test]
+    fn dropped_snapshot_releases_pages() {
+        let mut data = data(PAGE_SIZE);
+        let snapshot = data.snapshot().unwrap();
+        let other = data.snapshot().unwrap();
+        data.write(0, &[0; 10]).unwrap();
+        drop(snapshot);
+
+        {
+            let versions = read(versions(&data));
+            assert_eq!(versions.snapshots.len(), 1);
+            assert_eq!(versions.snapshots.values().next().unwrap().pages.len(), 1);
+        }
+
+        drop(other);
+    }
+}
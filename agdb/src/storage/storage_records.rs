@@ -2,14 +2,19 @@ use crate::DbError;
 use crate::DbErrorType;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::sync::Arc;
 
-pub(crate) const STORAGE_RECORD_SIZE: u64 = 16; // 8 bytes for index + 8 bytes for size
+pub(crate) const STORAGE_RECORD_SIZE: u64 = 24; // 8 bytes for index + 8 bytes for size + 8 bytes for value checksum
+const RECORDS_PAGE_SIZE: usize = 1024;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct StorageRecord {
     pub index: u64,
     pub pos: u64,
     pub size: u64,
+    pub checksum: u64,
 }
 
 impl StorageRecord {
@@ -22,9 +27,18 @@ impl StorageRecord {
     }
 }
 
+/// Records split into fixed size pages that are shared by the clones
+/// of the table. A shared page is copied only when one of its records
+/// is modified so cloning the table copies just the page pointers.
+#[derive(Debug, Clone, Default)]
+struct RecordTable {
+    pages: Vec<Arc<Vec<StorageRecord>>>,
+    len: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct StorageRecords {
-    records: Vec<StorageRecord>,
+    records: RecordTable,
     free_pos_size: BTreeMap<u64, u64>,
     free_size_pos: BTreeMap<u64, BTreeSet<u64>>,
     free_size: u64,
@@ -32,8 +46,11 @@ pub struct StorageRecords {
 
 impl StorageRecords {
     pub fn new() -> Self {
+        let mut records = RecordTable::default();
+        records.push(StorageRecord::default());
+
         Self {
-            records: vec![StorageRecord::default()],
+            records,
             free_pos_size: BTreeMap::new(),
             free_size_pos: BTreeMap::new(),
             free_size: 0,
@@ -46,13 +63,19 @@ impl StorageRecords {
         if self.records[0].index != 0 {
             let index = self.records[0].index;
             self.records[0].index = self.records[index as usize].index;
-            record = StorageRecord { index, pos, size };
+            record = StorageRecord {
+                index,
+                pos,
+                size,
+                checksum: 0,
+            };
             self.records[index as usize] = record;
         } else {
             record = StorageRecord {
                 index: self.records.len() as u64,
                 pos,
                 size,
+                checksum: 0,
             };
             self.records.push(record);
         }
@@ -69,7 +92,7 @@ impl StorageRecords {
     pub fn records(&self) -> Vec<StorageRecord> {
         let mut res = Vec::with_capacity(self.records.len());
 
-        for record in &self.records {
+        for record in self.records.iter() {
             if self.is_valid(record) {
                 res.push(*record);
             }
@@ -80,6 +103,12 @@ impl StorageRecords {
         res
     }
 
+    pub fn set_checksum(&mut self, index: u64, checksum: u64) {
+        if let Some(i) = self.records.get_mut(index as usize) {
+            i.checksum = checksum;
+        }
+    }
+
     pub fn set_pos(&mut self, index: u64, pos: u64) {
         if let Some(i) = self.records.get_mut(index as usize) {
             i.pos = pos;
@@ -100,6 +129,18 @@ impl StorageRecords {
         }
     }
 
+    /// Returns a read-only copy of the records sharing the pages with
+    /// this table. The free regions are not copied as they are needed
+    /// only for writing.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            records: self.records.clone(),
+            free_pos_size: BTreeMap::new(),
+            free_size_pos: BTreeMap::new(),
+            free_size: 0,
+        }
+    }
+
     pub fn rebuild_free_index(&mut self) {
         for index in 1..self.records.len() {
             if self.records[index].index == 0 {
@@ -216,3 +257,58 @@ impl StorageRecords {
         record.index != 0 && self.records[record.index as usize].index == record.index
     }
 }
+
+impl RecordTable {
+    fn get(&self, index: usize) -> Option<&StorageRecord> {
+        self.pages
+            .get(index / RECORDS_PAGE_SIZE)
+            .and_then(|page| page.get(index % RECORDS_PAGE_SIZE))
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut StorageRecord> {
+        self.pages
+            .get_mut(index / RECORDS_PAGE_SIZE)
+            .and_then(|page| Arc::make_mut(page).get_mut(index % RECORDS_PAGE_SIZE))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &StorageRecord> {
+        self.pages.iter().flat_map(|page| page.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, record: StorageRecord) {
+        if self.len.is_multiple_of(RECORDS_PAGE_SIZE) {
+            self.pages
+                .push(Arc::new(Vec::with_capacity(RECORDS_PAGE_SIZE)));
+        }
+
+        if let Some(page) = self.pages.last_mut() {
+            Arc::make_mut(page).push(record);
+        }
+
+        self.len += 1;
+    }
+
+    fn resize(&mut self, len: usize, record: StorageRecord) {
+        while self.len < len {
+            self.push(record);
+        }
+    }
+}
+
+impl Index<usize> for RecordTable {
+    type Output = StorageRecord;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.pages[index / RECORDS_PAGE_SIZE][index % RECORDS_PAGE_SIZE]
+    }
+}
+
+impl IndexMut<usize> for RecordTable {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut Arc::make_mut(&mut self.pages[index / RECORDS_PAGE_SIZE])[index % RECORDS_PAGE_SIZE]
+    }
+}
@@ -1,6 +1,8 @@
 use crate::DbError;
+use crate::DbErrorType;
 use crate::utilities::serialize::Serialize;
 use crate::utilities::serialize::SerializeStatic;
+use crate::utilities::stable_hash::StableHash;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::Read;
@@ -8,6 +10,10 @@ use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
 
+// Marks the log as containing checksummed records. Logs written
+// by older versions start directly with the first record instead.
+const WAL_MAGIC: u64 = u64::from_le_bytes(*b"agdbwal1");
+
 #[derive(Debug)]
 pub struct WriteAheadLog {
     file: File,
@@ -24,9 +30,14 @@ impl WriteAheadLog {
     }
 
     pub fn insert(&mut self, pos: u64, value: &[u8]) -> Result<(), DbError> {
-        self.file.seek(SeekFrom::End(0))?;
+        if self.file.seek(SeekFrom::End(0))? == 0 {
+            self.file.write_all(&WAL_MAGIC.serialize())?;
+        }
+
         self.file.write_all(&pos.serialize())?;
         self.file.write_all(&(value.len() as u64).serialize())?;
+        self.file
+            .write_all(&Self::checksum(pos, value).serialize())?;
         self.file.write_all(value)?;
 
         Ok(())
@@ -50,10 +61,10 @@ impl WriteAheadLog {
     pub fn records(&mut self) -> Result<Vec<WriteAheadLogRecord>, DbError> {
         let mut records = Vec::<WriteAheadLogRecord>::new();
         let size = self.file.seek(SeekFrom::End(0))?;
-        self.file.rewind()?;
+        let checksums = self.has_checksums()?;
 
         while self.file.stream_position()? < size {
-            records.push(Self::read_record(&mut self.file)?);
+            records.push(Self::read_record(&mut self.file, checksums)?);
         }
 
         Ok(records)
@@ -76,6 +87,28 @@ impl WriteAheadLog {
         name
     }
 
+    fn checksum(pos: u64, value: &[u8]) -> u64 {
+        let mut hash = pos.stable_hash();
+        u64::add_to_hash(&mut hash, value.stable_hash());
+        hash
+    }
+
+    // Positions the file after the log header (if any) and
+    // returns whether the records carry checksums.
+    fn has_checksums(&mut self) -> Result<bool, DbError> {
+        self.file.rewind()?;
+
+        if Self::read_exact(&mut self.file, u64::serialized_size_static())
+            .and_then(|bytes| u64::deserialize(&bytes))
+            .is_ok_and(|magic| magic == WAL_MAGIC)
+        {
+            return Ok(true);
+        }
+
+        self.file.rewind()?;
+        Ok(false)
+    }
+
     fn read_exact(file: &mut File, size: u64) -> Result<Vec<u8>, DbError> {
         let mut buffer = vec![0_u8; size as usize];
         file.read_exact(&mut buffer)?;
@@ -83,41 +116,49 @@ impl WriteAheadLog {
         Ok(buffer)
     }
 
-    fn read_record(file: &mut File) -> Result<WriteAheadLogRecord, DbError> {
+    fn read_record(file: &mut File, checksums: bool) -> Result<WriteAheadLogRecord, DbError> {
         let pos = u64::deserialize(&Self::read_exact(file, u64::serialized_size_static())?)?;
         let size = u64::deserialize(&Self::read_exact(file, u64::serialized_size_static())?)?;
+        let checksum = if checksums {
+            Some(u64::deserialize(&Self::read_exact(
+                file,
+                u64::serialized_size_static(),
+            )?)?)
+        } else {
+            None
+        };
+        let file_size = file.metadata()?.len();
 
-        Ok(WriteAheadLogRecord {
-            pos,
-            value: Self::read_exact(file, size)?,
-        })
-    }
+        if file.stream_position()? + size > file_size {
+            return Err(DbError::storage(
+                DbErrorType::OutOfBounds,
+                format!("WAL record value size ({size}) exceeds the log size ({file_size})"),
+            ));
+        }
 
-    fn skip_record(file: &mut File) -> Result<(), DbError> {
-        file.seek(SeekFrom::Current(u64::serialized_size_static() as i64))?;
-        let value_size = u64::deserialize(&Self::read_exact(file, u64::serialized_size_static())?)?;
-        file.seek(SeekFrom::Current(value_size as i64))?;
-        Ok(())
+        let value = Self::read_exact(file, size)?;
+
+        if checksum.is_some_and(|checksum| checksum != Self::checksum(pos, &value)) {
+            return Err(DbError::storage(
+                DbErrorType::Corrupted,
+                format!("WAL record checksum mismatch at position {pos}"),
+            ));
+        }
+
+        Ok(WriteAheadLogRecord { pos, value })
     }
 
     fn repair(&mut self) -> Result<(), DbError> {
         let size = self.file.seek(SeekFrom::End(0))?;
-        self.file.rewind()?;
-        let mut pos = 0_u64;
+        let checksums = self.has_checksums()?;
+        let mut pos = self.file.stream_position()?;
 
         while pos < size {
-            if Self::skip_record(&mut self.file).is_err() {
+            if Self::read_record(&mut self.file, checksums).is_err() {
                 self.file.set_len(pos)?;
                 return Ok(());
             } else {
-                let new_pos = self.file.stream_position()?;
-
-                if new_pos > size {
-                    self.file.set_len(pos)?;
-                    return Ok(());
-                } else {
-                    pos = new_pos;
-                }
+                pos = self.file.stream_position()?;
             }
         }
 
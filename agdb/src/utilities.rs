@@ -1,2 +1,4 @@
+pub mod compression;
+pub mod regex;
 pub mod serialize;
 pub mod stable_hash;
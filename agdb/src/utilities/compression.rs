@@ -0,0 +1,76 @@
+use crate::DbError;
+use crate::DbErrorType;
+
+/// Compresses `bytes` if they are at least `threshold` bytes long
+/// and the compression actually saves space. Returns `None` if the
+/// value should be stored as is.
+#[cfg(feature = "compression")]
+pub(crate) fn compress(bytes: &[u8], threshold: Option<u64>) -> Option<Vec<u8>> {
+    if (bytes.len() as u64) < threshold? {
+        return None;
+    }
+
+    let compressed = lz4_flex::compress_prepend_size(bytes);
+    (compressed.len() < bytes.len()).then_some(compressed)
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn compress(_bytes: &[u8], _threshold: Option<u64>) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(feature = "compression")]
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Vec<u8>, DbError> {
+    lz4_flex::decompress_size_prepended(bytes).map_err(|e| {
+        DbError::db(
+            DbErrorType::Corrupted,
+            format!("Compressed value cannot be decompressed: {e}"),
+        )
+    })
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn decompress(_bytes: &[u8]) -> Result<Vec<u8>, DbError> {
+    Err(DbError::db(
+        DbErrorType::NotAllowed,
+        "Reading compressed values requires the 'compression' feature",
+    ))
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn below_threshold() {
+        assert_eq!(compress(&[1_u8; 100], Some(101)), None);
+    }
+
+    #[test]
+    fn disabled() {
+        assert_eq!(compress(&[1_u8; 100], None), None);
+    }
+
+    #[test]
+    fn incompressible() {
+        let bytes = (0..64_u8).collect::<Vec<u8>>();
+        assert_eq!(compress(&bytes, Some(0)), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = [7_u8; 1000];
+        let compressed = compress(&bytes, Some(1000)).unwrap();
+
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(decompress(&compressed).unwrap(), bytes);
+    }
+
+    #[test]
+    fn corrupted() {
+        assert_eq!(
+            decompress(&[100, 0, 0, 0, 240]).unwrap_err().ty,
+            DbErrorType::Corrupted
+        );
+    }
+}
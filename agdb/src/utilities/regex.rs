@@ -0,0 +1,173 @@
+use crate::DbError;
+use crate::DbErrorType;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static PATTERNS: RefCell<HashMap<String, Pattern>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(feature = "regex")]
+type Pattern = regex::Regex;
+
+#[cfg(not(feature = "regex"))]
+type Pattern = ();
+
+/// Patterns compiled for a single query execution. While the scope
+/// exists the compiled patterns are used by `is_match()` on the
+/// current thread. Dropping the scope removes the patterns it
+/// has compiled leaving those of the enclosing scope (if any).
+pub(crate) struct PatternScope {
+    compiled: Vec<String>,
+}
+
+impl PatternScope {
+    /// Compiles the `patterns` not yet compiled by an enclosing scope.
+    /// Fails if any of the patterns is invalid.
+    pub(crate) fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<Self, DbError> {
+        let mut compiled = Vec::<(String, Pattern)>::new();
+
+        for pattern in patterns {
+            if !PATTERNS.with_borrow(|patterns| patterns.contains_key(pattern))
+                && !compiled.iter().any(|(p, _)| p == pattern)
+            {
+                compiled.push((pattern.to_string(), compile(pattern)?));
+            }
+        }
+
+        let compiled = PATTERNS.with_borrow_mut(|patterns| {
+            compiled
+                .into_iter()
+                .map(|(pattern, regex)| {
+                    patterns.insert(pattern.clone(), regex);
+                    pattern
+                })
+                .collect()
+        });
+
+        Ok(Self { compiled })
+    }
+}
+
+impl Drop for PatternScope {
+    fn drop(&mut self) {
+        PATTERNS.with_borrow_mut(|patterns| {
+            for pattern in &self.compiled {
+                patterns.remove(pattern);
+            }
+        });
+    }
+}
+
+/// Returns `true` if the `pattern` matches anywhere in the `text`. The
+/// pattern is expected to be compiled by the `PatternScope` of the query
+/// being executed. Otherwise it is compiled for this call only (and
+/// does not match if it is invalid) and debug builds panic to reveal
+/// the missing scope.
+pub(crate) fn is_match(pattern: &str, text: &str) -> bool {
+    PATTERNS.with_borrow(|patterns| {
+        debug_assert!(
+            patterns.contains_key(pattern),
+            "Pattern '{pattern}' is not compiled by any PatternScope"
+        );
+
+        match patterns.get(pattern) {
+            Some(regex) => regex_match(regex, text),
+            None => compile(pattern).is_ok_and(|regex| regex_match(&regex, text)),
+        }
+    })
+}
+
+#[cfg(feature = "regex")]
+fn compile(pattern: &str) -> Result<Pattern, DbError> {
+    regex::Regex::new(pattern).map_err(|e| {
+        let reason = match e {
+            regex::Error::Syntax(message) => message
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string(),
+            e => e.to_string(),
+        };
+        DbError::query(
+            DbErrorType::InvalidPattern,
+            format!("Invalid pattern '{pattern}': {reason}"),
+        )
+    })
+}
+
+#[cfg(not(feature = "regex"))]
+fn compile(pattern: &str) -> Result<Pattern, DbError> {
+    Err(DbError::query(
+        DbErrorType::InvalidPattern,
+        format!("Invalid pattern '{pattern}': regular expressions require the 'regex' feature"),
+    ))
+}
+
+#[cfg(feature = "regex")]
+fn regex_match(regex: &Pattern, text: &str) -> bool {
+    regex.is_match(text)
+}
+
+#[cfg(not(feature = "regex"))]
+fn regex_match(_regex: &Pattern, _text: &str) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn pattern_scope() {
+        {
+            let _scope = PatternScope::new(["^a+$", "^a+$"]).unwrap();
+            assert!(PATTERNS.with_borrow(|patterns| patterns.contains_key("^a+$")));
+            assert!(is_match("^a+$", "aaa"));
+            assert!(!is_match("^a+$", "aab"));
+
+            {
+                let _inner = PatternScope::new(["^a+$", "b"]).unwrap();
+                assert!(PATTERNS.with_borrow(|patterns| patterns.len() == 2));
+            }
+
+            assert!(PATTERNS.with_borrow(|patterns| patterns.len() == 1));
+            assert!(is_match("^a+$", "aaa"));
+        }
+
+        assert!(PATTERNS.with_borrow(|patterns| patterns.is_empty()));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "Pattern '^a+$' is not compiled by any PatternScope")]
+    fn match_without_scope() {
+        is_match("^a+$", "aaa");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn invalid() {
+        let _scope = PatternScope::new(["a"]).unwrap();
+        assert_eq!(
+            PatternScope::new(["b", "(ab"]).err().unwrap().description,
+            "Invalid pattern '(ab': unclosed group"
+        );
+        assert_eq!(
+            PatternScope::new(["[b-a]"]).err().unwrap().description,
+            "Invalid pattern '[b-a]': invalid character class range, the start must be <= the end"
+        );
+        assert!(PATTERNS.with_borrow(|patterns| patterns.len() == 1));
+    }
+
+    #[cfg(not(feature = "regex"))]
+    #[test]
+    fn regex_feature_disabled() {
+        assert_eq!(
+            PatternScope::new(["a"]).err().unwrap().description,
+            "Invalid pattern 'a': regular expressions require the 'regex' feature"
+        );
+    }
+}
@@ -150,6 +150,32 @@ impl Serialize for bool {
     }
 }
 
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self) -> Vec<u8> {
+        match self {
+            Some(value) => {
+                let mut bytes = Vec::with_capacity(self.serialized_size() as usize);
+                bytes.push(1);
+                bytes.extend(value.serialize());
+                bytes
+            }
+            None => vec![0],
+        }
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, DbError> {
+        if bool::deserialize(bytes)? {
+            Ok(Some(T::deserialize(&bytes[1..])?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn serialized_size(&self) -> u64 {
+        1 + self.as_ref().map_or(0, |value| value.serialized_size())
+    }
+}
+
 impl<T: Serialize> Serialize for Vec<T> {
     fn serialize(&self) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(self.serialized_size() as usize);
@@ -491,6 +517,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn option() {
+        let some = Some("hello".to_string());
+        let none: Option<String> = None;
+
+        assert_eq!(some.serialized_size(), 1 + 8 + 5);
+        assert_eq!(none.serialized_size(), 1);
+        assert_eq!(Option::<String>::deserialize(&some.serialize()), Ok(some));
+        assert_eq!(Option::<String>::deserialize(&none.serialize()), Ok(none));
+        assert!(Option::<String>::deserialize(&[]).is_err());
+    }
+
     #[test]
     fn vec_u64() {
         let original = vec![1_u64, 2_u64, 3_u64];
@@ -0,0 +1,228 @@
+mod test_db;
+
+use agdb::AggregateFunction;
+use agdb::DbElement;
+use agdb::DbId;
+use agdb::QueryBuilder;
+use test_db::TestDb;
+
+fn orders_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().aliases("orders").query(), 1);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("customer", "alice").into(), ("total", 10).into()],
+                [("customer", "bob").into(), ("total", 5).into()],
+                [("customer", "alice").into(), ("total", 20).into()],
+                [("customer", "carol").into(), ("note", "no total").into()],
+            ])
+            .query(),
+        4,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("orders")
+            .to([2, 3, 4, 5])
+            .query(),
+        4,
+    );
+    db
+}
+
+#[test]
+fn aggregate_ids() {
+    let db = orders_db();
+    db.exec_elements(
+        QueryBuilder::select()
+            .aggregate("total")
+            .ids([2, 3, 4])
+            .query(),
+        &[DbElement {
+            id: DbId(0),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![
+                ("count", 3_u64).into(),
+                ("sum", 35).into(),
+                ("min", 5).into(),
+                ("max", 20).into(),
+                ("avg", 35.0 / 3.0).into(),
+            ],
+        }],
+    );
+}
+
+#[test]
+fn aggregate_search() {
+    let db = orders_db();
+    db.exec_elements(
+        QueryBuilder::select()
+            .aggregate("total")
+            .functions([AggregateFunction::Count, AggregateFunction::Sum])
+            .search()
+            .from("orders")
+            .where_()
+            .node()
+            .query(),
+        &[DbElement {
+            id: DbId(0),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![("count", 3_u64).into(), ("sum", 35).into()],
+        }],
+    );
+}
+
+#[test]
+fn aggregate_group_by() {
+    let db = orders_db();
+    db.exec_count_elements(
+        QueryBuilder::select()
+            .aggregate("total")
+            .functions([AggregateFunction::Sum, AggregateFunction::Avg])
+            .group_by("customer")
+            .search()
+            .from("orders")
+            .query(),
+        2,
+        &[
+            DbElement {
+                id: DbId(0),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![
+                    ("customer", "alice").into(),
+                    ("sum", 30).into(),
+                    ("avg", 15.0).into(),
+                ],
+            },
+            DbElement {
+                id: DbId(0),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![
+                    ("customer", "bob").into(),
+                    ("sum", 5).into(),
+                    ("avg", 5.0).into(),
+                ],
+            },
+        ],
+    );
+}
+
+#[test]
+fn aggregate_empty() {
+    let db = orders_db();
+    db.exec_elements(
+        QueryBuilder::select().aggregate("total").ids(5).query(),
+        &[DbElement {
+            id: DbId(0),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![("count", 0_u64).into()],
+        }],
+    );
+}
+
+#[test]
+fn aggregate_mixed_numbers() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("k", 1).into()],
+                [("k", 2_u64).into()],
+                [("k", 1.5).into()],
+            ])
+            .query(),
+        3,
+    );
+    db.exec_elements(
+        QueryBuilder::select()
+            .aggregate("k")
+            .functions([
+                AggregateFunction::Sum,
+                AggregateFunction::Min,
+                AggregateFunction::Max,
+            ])
+            .ids([1, 2, 3])
+            .query(),
+        &[DbElement {
+            id: DbId(0),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![
+                ("sum", 4.5).into(),
+                ("min", 1).into(),
+                ("max", 2_u64).into(),
+            ],
+        }],
+    );
+}
+
+#[test]
+fn aggregate_min_max_strings() {
+    let db = orders_db();
+    db.exec_elements(
+        QueryBuilder::select()
+            .aggregate("customer")
+            .functions([AggregateFunction::Min, AggregateFunction::Max])
+            .ids([2, 3, 4, 5])
+            .query(),
+        &[DbElement {
+            id: DbId(0),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![("min", "alice").into(), ("max", "carol").into()],
+        }],
+    );
+}
+
+#[test]
+fn aggregate_not_numeric() {
+    let db = orders_db();
+    db.exec_error(
+        QueryBuilder::select()
+            .aggregate("customer")
+            .functions(AggregateFunction::Sum)
+            .ids([2, 3])
+            .query(),
+        "Value 'alice' of key 'customer' is not numeric",
+    );
+}
+
+#[test]
+fn aggregate_overflow() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("k", u64::MAX).into()], [("k", 1_u64).into()]])
+            .query(),
+        2,
+    );
+    db.exec_error(
+        QueryBuilder::select()
+            .aggregate("k")
+            .functions(AggregateFunction::Sum)
+            .ids([1, 2])
+            .query(),
+        "Sum of values of key 'k' overflows",
+    );
+}
+
+#[test]
+fn aggregate_missing_id() {
+    let db = orders_db();
+    db.exec_error(
+        QueryBuilder::select()
+            .aggregate("total")
+            .ids("missing")
+            .query(),
+        "Alias 'missing' not found",
+    );
+}
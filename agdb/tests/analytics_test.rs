@@ -0,0 +1,221 @@
+mod test_db;
+
+use agdb::AnalyticsAlgorithm;
+use agdb::DbId;
+use agdb::DbValue;
+use agdb::PageRankOptions;
+use agdb::QueryBuilder;
+use test_db::TestDb;
+
+fn graph_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(5).query(), 5);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 2, 3, 3])
+            .to([2, 3, 1, 4])
+            .query(),
+        4,
+    );
+    db
+}
+
+fn scores(db: &TestDb, algorithm: AnalyticsAlgorithm) -> Vec<(DbId, DbValue)> {
+    db.exec_result(QueryBuilder::select().analytics(algorithm).query())
+        .elements
+        .into_iter()
+        .map(|e| (e.id, e.values[0].value.clone()))
+        .collect()
+}
+
+#[test]
+fn select_weakly_connected_components() {
+    let db = graph_db();
+
+    assert_eq!(
+        scores(&db, AnalyticsAlgorithm::WeaklyConnectedComponents),
+        vec![
+            (DbId(1), 0_u64.into()),
+            (DbId(2), 0_u64.into()),
+            (DbId(3), 0_u64.into()),
+            (DbId(4), 0_u64.into()),
+            (DbId(5), 1_u64.into()),
+        ]
+    );
+}
+
+#[test]
+fn select_strongly_connected_components() {
+    let db = graph_db();
+
+    assert_eq!(
+        scores(&db, AnalyticsAlgorithm::StronglyConnectedComponents),
+        vec![
+            (DbId(1), 0_u64.into()),
+            (DbId(2), 0_u64.into()),
+            (DbId(3), 0_u64.into()),
+            (DbId(4), 1_u64.into()),
+            (DbId(5), 2_u64.into()),
+        ]
+    );
+}
+
+#[test]
+fn select_page_rank() {
+    let db = graph_db();
+    let ranks = scores(
+        &db,
+        AnalyticsAlgorithm::PageRank(PageRankOptions {
+            damping: 0.85,
+            iterations: 50,
+        }),
+    )
+    .into_iter()
+    .map(|(_, rank)| rank.to_f64().unwrap().to_f64())
+    .collect::<Vec<f64>>();
+
+    assert_eq!(ranks.len(), 5);
+    assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(ranks[0] > ranks[4]);
+}
+
+#[test]
+fn select_degree_centrality() {
+    let db = graph_db();
+
+    assert_eq!(
+        scores(&db, AnalyticsAlgorithm::DegreeCentrality),
+        vec![
+            (DbId(1), 2_u64.into()),
+            (DbId(2), 2_u64.into()),
+            (DbId(3), 3_u64.into()),
+            (DbId(4), 1_u64.into()),
+            (DbId(5), 0_u64.into()),
+        ]
+    );
+}
+
+#[test]
+fn select_betweenness_centrality() {
+    let db = graph_db();
+
+    assert_eq!(
+        scores(&db, AnalyticsAlgorithm::BetweennessCentrality),
+        vec![
+            (DbId(1), 1.0.into()),
+            (DbId(2), 2.0.into()),
+            (DbId(3), 3.0.into()),
+            (DbId(4), 0.0.into()),
+            (DbId(5), 0.0.into()),
+        ]
+    );
+}
+
+#[test]
+fn select_analytics_ids() {
+    let db = graph_db();
+    let result = db.exec_result(
+        QueryBuilder::select()
+            .analytics(AnalyticsAlgorithm::DegreeCentrality)
+            .ids([1, 3, -6])
+            .query(),
+    );
+
+    assert_eq!(result.result, 2);
+    assert_eq!(
+        result
+            .elements
+            .iter()
+            .map(|e| (e.id, e.values.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (DbId(1), vec![("degree", 1_u64).into()]),
+            (DbId(3), vec![("degree", 1_u64).into()]),
+        ]
+    );
+}
+
+#[test]
+fn select_analytics_search() {
+    let db = graph_db();
+
+    db.exec_ids(
+        QueryBuilder::select()
+            .analytics(AnalyticsAlgorithm::WeaklyConnectedComponents)
+            .search()
+            .from(4)
+            .query(),
+        &[4],
+    );
+}
+
+#[test]
+fn select_analytics_missing_id() {
+    let db = graph_db();
+
+    db.exec_error(
+        QueryBuilder::select()
+            .analytics(AnalyticsAlgorithm::DegreeCentrality)
+            .ids(100)
+            .query(),
+        "Id '100' not found",
+    );
+}
+
+#[test]
+fn insert_analytics() {
+    let mut db = graph_db();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .analytics(AnalyticsAlgorithm::DegreeCentrality)
+            .query(),
+        5,
+    );
+
+    let result = db.exec_result(QueryBuilder::select().values("degree").ids([3, 5]).query());
+
+    assert_eq!(result.elements[0].values, vec![("degree", 3_u64).into()]);
+    assert_eq!(result.elements[1].values, vec![("degree", 0_u64).into()]);
+}
+
+#[test]
+fn insert_analytics_replaces_values() {
+    let mut db = graph_db();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values_uniform([("component", 10).into()])
+            .ids([1, 5])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .analytics(AnalyticsAlgorithm::WeaklyConnectedComponents)
+            .search()
+            .from(1)
+            .where_()
+            .node()
+            .query(),
+        4,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .key("component")
+            .value(0_u64)
+            .query(),
+        &[1, 2, 3, 4],
+    );
+    db.exec_elements(
+        QueryBuilder::select().values("component").ids(5).query(),
+        &[agdb::DbElement {
+            id: DbId(5),
+            from: DbId::default(),
+            to: DbId::default(),
+            values: vec![("component", 10).into()],
+        }],
+    );
+}
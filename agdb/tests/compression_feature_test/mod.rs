@@ -0,0 +1,158 @@
+use crate::test_db::test_file::TestFile;
+use agdb::Comparison;
+use agdb::DbFile;
+use agdb::DbId;
+use agdb::DbMemory;
+use agdb::QueryBuilder;
+
+fn text() -> String {
+    "the quick brown fox jumps over the lazy dog ".repeat(100)
+}
+
+fn insert_document(db: &mut DbFile) {
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases("doc")
+            .values([[
+                ("text", text()).into(),
+                ("blob", vec![7_u8; 4096]).into(),
+                ("title", "fox").into(),
+            ]])
+            .query(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn compressed_values_are_smaller() {
+    let plain_file = TestFile::new();
+    let compressed_file = TestFile::new();
+    let mut plain = DbFile::new(plain_file.file_name()).unwrap();
+    let mut compressed = DbFile::new(compressed_file.file_name()).unwrap();
+    compressed.set_compression_threshold(Some(64));
+
+    insert_document(&mut plain);
+    insert_document(&mut compressed);
+
+    assert!(compressed.size() + 4096 < plain.size());
+}
+
+#[test]
+fn select_compressed_values() {
+    let test_file = TestFile::new();
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.set_compression_threshold(Some(64));
+    insert_document(&mut db);
+
+    let result = db
+        .exec(
+            QueryBuilder::select()
+                .values(["text", "blob"])
+                .ids("doc")
+                .query(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        result.elements[0].values,
+        vec![("text", text()).into(), ("blob", vec![7_u8; 4096]).into()]
+    );
+}
+
+#[test]
+fn search_compressed_values() {
+    let test_file = TestFile::new();
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.set_compression_threshold(Some(64));
+    insert_document(&mut db);
+
+    let result = db
+        .exec(
+            QueryBuilder::search()
+                .from("doc")
+                .where_()
+                .key("text")
+                .value(Comparison::Equal(text().into()))
+                .and()
+                .key("text")
+                .value(Comparison::Contains("lazy dog".into()))
+                .query(),
+        )
+        .unwrap();
+
+    assert_eq!(result.ids(), vec![DbId(1)]);
+}
+
+#[test]
+fn compressed_index_values() {
+    let mut db = DbMemory::new("test").unwrap();
+    db.set_compression_threshold(Some(64));
+    db.exec_mut(QueryBuilder::insert().index("text").query())
+        .unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("text", text()).into()], [("text", "short").into()]])
+            .query(),
+    )
+    .unwrap();
+
+    let result = db
+        .exec(QueryBuilder::search().index("text").value(text()).query())
+        .unwrap();
+
+    assert_eq!(result.ids(), vec![DbId(1)]);
+}
+
+#[test]
+fn reopen_without_threshold() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = DbFile::new(test_file.file_name()).unwrap();
+        db.set_compression_threshold(Some(64));
+        insert_document(&mut db);
+    }
+
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("text2", text()).into()]])
+            .ids("doc")
+            .query(),
+    )
+    .unwrap();
+    let result = db
+        .exec(
+            QueryBuilder::select()
+                .values(["text", "blob", "text2"])
+                .ids("doc")
+                .query(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        result.elements[0].values,
+        vec![
+            ("text", text()).into(),
+            ("blob", vec![7_u8; 4096]).into(),
+            ("text2", text()).into()
+        ]
+    );
+}
+
+#[test]
+fn disable_compression() {
+    let plain_file = TestFile::new();
+    let test_file = TestFile::new();
+    let mut plain = DbFile::new(plain_file.file_name()).unwrap();
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.set_compression_threshold(Some(64));
+    db.set_compression_threshold(None);
+
+    insert_document(&mut plain);
+    insert_document(&mut db);
+
+    assert_eq!(db.size(), plain.size());
+}
@@ -1,7 +1,11 @@
+#[cfg(feature = "compression")]
+mod compression_feature_test;
 #[cfg(feature = "derive")]
 mod derive_feature_test;
 #[cfg(feature = "derive")]
 mod efficient_agdb;
+#[cfg(feature = "encryption")]
+mod encryption_feature_test;
 #[cfg(feature = "openapi")]
 mod openapi_feature_test;
 #[cfg(feature = "derive")]
@@ -30,6 +34,9 @@ use test_db::test_file::TestFile;
 #[test]
 fn public_types() {
     use agdb::AgdbSerialize;
+    use agdb::AggregateFunction;
+    use agdb::AggregateQuery;
+    use agdb::AnalyticsAlgorithm;
     use agdb::Comparison;
     use agdb::CountComparison;
     use agdb::Db;
@@ -41,23 +48,31 @@ fn public_types() {
     use agdb::DbFileTransactionMut;
     use agdb::DbId;
     use agdb::DbImpl;
+    use agdb::DbIssue;
+    use agdb::DbIssueType;
     use agdb::DbKeyOrder;
     use agdb::DbKeyValue;
     use agdb::DbMemory;
     use agdb::DbMemoryTransaction;
     use agdb::DbMemoryTransactionMut;
+    use agdb::DbSalvageReport;
     use agdb::DbTransaction;
     use agdb::DbTransactionMut;
     use agdb::DbType;
     use agdb::DbValue;
+    use agdb::DbVerifyReport;
+    use agdb::ExpectQuery;
     use agdb::FileStorage;
     use agdb::FileStorageMemoryMapped;
     use agdb::InsertAliasesQuery;
+    use agdb::InsertAnalyticsQuery;
     use agdb::InsertEdgesQuery;
     use agdb::InsertIndexQuery;
     use agdb::InsertNodesQuery;
     use agdb::InsertValuesQuery;
+    use agdb::KeyValueUpdate;
     use agdb::MemoryStorage;
+    use agdb::PageRankOptions;
     use agdb::Query;
     use agdb::QueryBuilder;
     use agdb::QueryCondition;
@@ -76,6 +91,7 @@ fn public_types() {
     use agdb::SearchQueryAlgorithm;
     use agdb::SelectAliasesQuery;
     use agdb::SelectAllAliasesQuery;
+    use agdb::SelectAnalyticsQuery;
     use agdb::SelectEdgeCountQuery;
     use agdb::SelectIndexesQuery;
     use agdb::SelectKeyCountQuery;
@@ -87,6 +103,8 @@ fn public_types() {
     use agdb::StorageSlice;
     use agdb::Transaction;
     use agdb::TransactionMut;
+    use agdb::UpdateValuesQuery;
+    use agdb::ValueUpdate;
 }
 
 #[test]
@@ -659,6 +677,45 @@ fn convert_db_before_0_11_0() {
     assert_eq!(result.elements[2].values, vec![("tag", "label").into()]);
 }
 
+#[test]
+fn convert_db_before_0_14_0() {
+    let test_file = TestFile::new();
+    std::fs::copy("tests/test_db_prior_0_14_0.agdb", test_file.file_name()).unwrap();
+
+    {
+        let mut db = Db::new(test_file.file_name()).unwrap();
+        db.exec_mut(
+            QueryBuilder::insert()
+                .nodes()
+                .values([[("username", "user3").into(), ("age", 25).into()]])
+                .query(),
+        )
+        .unwrap();
+        db.exec_mut(QueryBuilder::insert().index("created").ordered().query())
+            .unwrap();
+    }
+
+    let db = Db::new(test_file.file_name()).unwrap();
+    let result = db.exec(QueryBuilder::select().indexes().query()).unwrap();
+    assert_eq!(
+        result.elements[0].values,
+        vec![
+            ("username", 3_u64).into(),
+            ("age", 3_u64).into(),
+            ("created", 0_u64).into()
+        ]
+    );
+    let result = db
+        .exec(
+            QueryBuilder::search()
+                .index("username")
+                .value("user2")
+                .query(),
+        )
+        .unwrap();
+    assert_eq!(result.ids(), vec![DbId(2)]);
+}
+
 #[test]
 fn db_any() {
     let test_file1 = TestFile::new();
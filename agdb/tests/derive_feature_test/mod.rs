@@ -522,7 +522,8 @@ fn insert_element_no_id_new_element() {
                 from: DbId::default(),
                 to: DbId::default(),
                 values: vec![],
-            }]
+            }],
+            cursor: None,
         }
     );
     db.exec_elements(
@@ -947,6 +948,81 @@ fn derive_db_type_flatten_nested_struct() {
     assert_eq!(flattened, retrieved);
 }
 
+#[test]
+fn derive_db_type_nested_struct() {
+    #[derive(DbType, DbTypeMarker, Clone, PartialEq, Debug)]
+    struct Address {
+        city: String,
+        zip: Option<u64>,
+    }
+
+    #[derive(DbType, PartialEq, Debug)]
+    struct Person {
+        db_id: Option<DbId>,
+        name: String,
+        address: Address,
+        previous: Vec<Address>,
+    }
+
+    let mut person = Person {
+        db_id: None,
+        name: "John".into(),
+        address: Address {
+            city: "Prague".into(),
+            zip: Some(11000),
+        },
+        previous: vec![Address {
+            city: "Brno".into(),
+            zip: None,
+        }],
+    };
+
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().element(&person).query(), 3);
+
+    let retrieved: Person = db
+        .exec_result(QueryBuilder::select().elements::<Person>().ids(1).query())
+        .try_into()
+        .unwrap();
+
+    person.db_id = Some(DbId(1));
+    assert_eq!(person, retrieved);
+
+    assert_eq!(
+        db.exec_result(
+            QueryBuilder::search()
+                .elements()
+                .where_()
+                .key("address.city")
+                .value("Prague")
+                .query()
+        )
+        .result,
+        1
+    );
+    assert_eq!(
+        db.exec_result(
+            QueryBuilder::select()
+                .values(["previous.0.city"])
+                .ids(1)
+                .query()
+        )
+        .elements[0]
+            .values,
+        vec![("previous.0.city", "Brno").into()]
+    );
+    assert_eq!(
+        DbValue::from(&person.address),
+        DbValue::Map(vec![("city", "Prague").into(), ("zip", 11000_u64).into()])
+    );
+    assert_eq!(
+        Address::try_from(DbValue::from("Prague"))
+            .unwrap_err()
+            .description,
+        "Cannot convert 'string' to 'map'."
+    );
+}
+
 #[test]
 fn derive_db_type_skip_field() {
     #[derive(DbType, PartialEq, Debug)]
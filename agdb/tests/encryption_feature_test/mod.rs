@@ -0,0 +1,177 @@
+use crate::test_db::test_file::TestFile;
+use agdb::DbAny;
+use agdb::DbError;
+use agdb::DbErrorType;
+use agdb::DbId;
+use agdb::DbImpl;
+use agdb::Encrypted;
+use agdb::FileStorage;
+use agdb::QueryBuilder;
+use agdb::StorageData;
+
+type DbEncrypted = DbImpl<Encrypted<FileStorage>>;
+
+const KEY: [u8; 32] = [42; 32];
+
+fn insert_secret(db: &mut DbEncrypted) {
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases("secret")
+            .values([[("password", "correct horse battery staple").into()]])
+            .query(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn reopen_with_key() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+        insert_secret(&mut db);
+    }
+
+    let db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+    let result = db
+        .exec(QueryBuilder::select().ids("secret").query())
+        .unwrap();
+
+    assert_eq!(result.ids(), vec![DbId(1)]);
+    assert_eq!(
+        result.elements[0].values,
+        vec![("password", "correct horse battery staple").into()]
+    );
+}
+
+#[test]
+fn wrong_key() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+        insert_secret(&mut db);
+    }
+
+    let error = DbEncrypted::new_encrypted(test_file.file_name(), &[1; 32]).unwrap_err();
+
+    assert_eq!(error.ty, DbErrorType::DbCreate);
+    assert_eq!(
+        error.cause,
+        Some(Box::new(DbError::storage(
+            DbErrorType::NotAllowed,
+            "Invalid encryption key or corrupted storage header"
+        )))
+    );
+}
+
+#[test]
+fn plain_db_cannot_be_opened() {
+    let test_file = TestFile::new();
+
+    {
+        agdb::DbFile::new(test_file.file_name()).unwrap();
+    }
+
+    assert!(DbEncrypted::new_encrypted(test_file.file_name(), &KEY).is_err());
+}
+
+#[test]
+fn file_contains_no_plaintext() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+        insert_secret(&mut db);
+    }
+
+    let bytes = std::fs::read(test_file.file_name()).unwrap();
+
+    for secret in [b"secret".as_slice(), b"password", b"battery staple"] {
+        assert!(!bytes.windows(secret.len()).any(|window| window == secret));
+    }
+}
+
+#[test]
+fn rollback() {
+    let test_file = TestFile::new();
+    let mut db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+    insert_secret(&mut db);
+
+    db.transaction_mut(|t| -> Result<(), DbError> {
+        t.exec_mut(QueryBuilder::remove().ids("secret").query())?;
+        t.exec(QueryBuilder::select().ids("secret").query())?;
+        Ok(())
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids("secret").query())
+            .unwrap()
+            .result,
+        1
+    );
+}
+
+#[test]
+fn backup_copy_rename() {
+    let test_file = TestFile::new();
+    let backup_file = TestFile::new();
+    let copy_file = TestFile::new();
+    let renamed_file = TestFile::new();
+
+    let mut db = DbEncrypted::new_encrypted(test_file.file_name(), &KEY).unwrap();
+    insert_secret(&mut db);
+    db.backup(backup_file.file_name()).unwrap();
+    let copy = db.copy(copy_file.file_name()).unwrap();
+    db.rename(renamed_file.file_name()).unwrap();
+
+    assert_eq!(db.filename(), renamed_file.file_name());
+    assert_eq!(
+        copy.exec(QueryBuilder::select().ids("secret").query())
+            .unwrap()
+            .result,
+        1
+    );
+
+    let backup = DbEncrypted::new_encrypted(backup_file.file_name(), &KEY).unwrap();
+    assert_eq!(
+        backup
+            .exec(QueryBuilder::select().ids("secret").query())
+            .unwrap()
+            .result,
+        1
+    );
+}
+
+#[test]
+fn db_any() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = DbAny::new_encrypted(test_file.file_name(), &KEY).unwrap();
+        db.exec_mut(QueryBuilder::insert().nodes().aliases("root").query())
+            .unwrap();
+    }
+
+    let db = DbAny::new_encrypted(test_file.file_name(), &KEY).unwrap();
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids("root").query())
+            .unwrap()
+            .result,
+        1
+    );
+    assert!(DbAny::new_encrypted(test_file.file_name(), &[0; 32]).is_err());
+}
+
+#[test]
+fn storage_new_is_not_supported() {
+    assert_eq!(
+        Encrypted::<FileStorage>::new("encrypted_storage_new.agdb")
+            .err()
+            .unwrap()
+            .ty,
+        DbErrorType::NotAllowed
+    );
+}
@@ -0,0 +1,203 @@
+mod test_db;
+
+use agdb::DbElement;
+use agdb::DbError;
+use agdb::DbErrorType;
+use agdb::DbId;
+use agdb::QueryBuilder;
+use test_db::TestDb;
+
+fn versioned_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["doc1", "doc2"])
+            .values([
+                [("version", 7).into(), ("text", "a").into()],
+                [("version", 3).into(), ("text", "b").into()],
+            ])
+            .query(),
+        2,
+    );
+    db
+}
+
+#[test]
+fn expect_ids() {
+    let db = versioned_db();
+    db.exec_elements(
+        QueryBuilder::expect()
+            .ids("doc1")
+            .where_()
+            .key("version")
+            .value(7)
+            .query()
+            .query(),
+        &[DbElement {
+            id: DbId(1),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![],
+        }],
+    );
+    db.exec(
+        QueryBuilder::expect()
+            .ids(["doc1", "doc2"])
+            .where_()
+            .keys("text")
+            .query()
+            .query(),
+        2,
+    );
+    db.exec(QueryBuilder::expect().ids([1, 2]).query(), 2);
+}
+
+#[test]
+fn expect_search() {
+    let db = versioned_db();
+    db.exec(
+        QueryBuilder::expect()
+            .ids(QueryBuilder::search().elements().query())
+            .where_()
+            .key("version")
+            .value(agdb::Comparison::GreaterThan(1.into()))
+            .query()
+            .query(),
+        2,
+    );
+    db.exec_error(
+        QueryBuilder::expect()
+            .ids(QueryBuilder::search().elements().query())
+            .where_()
+            .key("version")
+            .value(7)
+            .query()
+            .query(),
+        "Precondition failed for id '2'",
+    );
+}
+
+#[test]
+fn expect_failed() {
+    let db = versioned_db();
+    db.exec_error(
+        QueryBuilder::expect()
+            .ids("doc1")
+            .where_()
+            .key("version")
+            .value(6)
+            .query()
+            .query(),
+        "Precondition failed for id '1'",
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn expect_matches() {
+    let db = versioned_db();
+    db.exec(
+        QueryBuilder::expect()
+            .ids(["doc1", "doc2"])
+            .where_()
+            .key("text")
+            .value(agdb::Comparison::Matches("^[ab]$".into()))
+            .query()
+            .query(),
+        2,
+    );
+    db.exec_error(
+        QueryBuilder::expect()
+            .ids(["doc1", "doc2"])
+            .where_()
+            .key("text")
+            .value(agdb::Comparison::Matches("^a$".into()))
+            .query()
+            .query(),
+        "Precondition failed for id '2'",
+    );
+    db.exec_error(
+        QueryBuilder::expect()
+            .ids("doc1")
+            .where_()
+            .key("text")
+            .value(agdb::Comparison::Matches("(a".into()))
+            .query()
+            .query(),
+        "Invalid pattern '(a': unclosed group",
+    );
+}
+
+#[test]
+fn expect_missing_id() {
+    let db = versioned_db();
+    db.exec_error(
+        QueryBuilder::expect()
+            .ids("doc3")
+            .where_()
+            .keys("version")
+            .query()
+            .query(),
+        "Alias 'doc3' not found",
+    );
+}
+
+#[test]
+fn compare_and_set_in_transaction() {
+    let mut db = versioned_db();
+    db.transaction_mut(|t| -> Result<(), DbError> {
+        t.exec(
+            QueryBuilder::expect()
+                .ids("doc1")
+                .where_()
+                .key("version")
+                .value(7)
+                .query()
+                .query(),
+        )?;
+        t.exec_mut(
+            QueryBuilder::insert()
+                .values_uniform([("version", 8).into(), ("text", "c").into()])
+                .ids("doc1")
+                .query(),
+        )?;
+        Ok(())
+    });
+    db.transaction_mut_error(
+        |t| -> Result<(), DbError> {
+            t.exec_mut(
+                QueryBuilder::insert()
+                    .values_uniform([("text", "d").into()])
+                    .ids("doc1")
+                    .query(),
+            )?;
+            t.exec(
+                QueryBuilder::expect()
+                    .ids("doc1")
+                    .where_()
+                    .key("version")
+                    .value(7)
+                    .query()
+                    .query(),
+            )?;
+            Ok(())
+        },
+        DbError::query(
+            DbErrorType::PreconditionFailed,
+            "Precondition failed for id '1'",
+        ),
+    );
+    db.exec_elements(
+        QueryBuilder::select()
+            .values(["version", "text"])
+            .ids("doc1")
+            .query(),
+        &[DbElement {
+            id: DbId(1),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![("version", 8).into(), ("text", "c").into()],
+        }],
+    );
+}
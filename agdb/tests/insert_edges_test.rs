@@ -452,3 +452,75 @@ fn insert_or_update_node_id() {
         "The ids for insert or update must all refer to edges - node id '1' found",
     );
 }
+
+#[test]
+fn insert_edges_acyclic() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(3).query(), 3);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 2])
+            .to([2, 3])
+            .acyclic()
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from(1)
+            .to(3)
+            .acyclic()
+            .query(),
+        1,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .edges()
+            .from(3)
+            .to(1)
+            .acyclic()
+            .query(),
+        "Edge from '3' to '1' would create a cycle: 1 -> -6 -> 3 -> 1",
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .edges()
+            .from(2)
+            .to(2)
+            .acyclic()
+            .query(),
+        "Edge from '2' to '2' would create a cycle: 2 -> 2",
+    );
+    db.exec_mut(QueryBuilder::insert().edges().from(3).to(1).query(), 1);
+}
+
+#[test]
+fn insert_edges_acyclic_within_query_rollback() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(3).query(), 3);
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 2, 3])
+            .to([2, 3, 1])
+            .acyclic()
+            .query(),
+        "Edge from '3' to '1' would create a cycle: 1 -> -4 -> 2 -> -5 -> 3 -> 1",
+    );
+    db.exec(
+        QueryBuilder::select().edge_count().ids([1, 2, 3]).query(),
+        0,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 2])
+            .to([2, 1])
+            .each()
+            .acyclic()
+            .query(),
+        "Edge from '1' to '1' would create a cycle: 1 -> 1",
+    );
+}
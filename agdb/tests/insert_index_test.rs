@@ -137,3 +137,310 @@ fn update_indexed_value_rollback() {
         1,
     );
 }
+
+#[test]
+fn insert_composite_index_with_existing_data() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("tenant", "t1").into(), ("email", "a@b.c").into()],
+                vec![("tenant", "t1").into()],
+                vec![("tenant", "t2").into(), ("email", "a@b.c").into()],
+            ])
+            .query(),
+        3,
+    );
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 2);
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t2", "a@b.c"])
+            .query(),
+        &[3],
+    );
+}
+
+#[test]
+fn insert_existing_composite_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(QueryBuilder::insert().index("tenant").query(), 0);
+    db.exec_mut(QueryBuilder::insert().index(["email", "tenant"]).query(), 0);
+    db.exec_mut_error(
+        QueryBuilder::insert().index(["tenant", "email"]).query(),
+        "Index 'tenant, email' already exists",
+    );
+}
+
+#[test]
+fn insert_composite_index_duplicate_keys() {
+    let mut db = TestDb::new();
+    db.exec_mut_error(
+        QueryBuilder::insert().index(["tenant", "tenant"]).query(),
+        "Index keys 'tenant, tenant' must be unique",
+    );
+}
+
+#[test]
+fn insert_index_without_keys() {
+    let mut db = TestDb::new();
+    db.exec_mut_error(
+        QueryBuilder::insert().index(Vec::<String>::new()).query(),
+        "Index requires at least one key",
+    );
+}
+
+#[test]
+fn update_composite_indexed_value() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("tenant", "t1").into(), ("email", "a@b.c").into()]])
+            .query(),
+        1,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("email", "x@y.z").into()]])
+            .ids(1)
+            .query(),
+        1,
+    );
+    db.exec(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        0,
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "x@y.z"])
+            .query(),
+        &[1],
+    );
+}
+
+#[test]
+fn update_composite_indexed_value_rollback() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("tenant", "t1").into(), ("email", "a@b.c").into()]])
+            .query(),
+        1,
+    );
+    db.transaction_mut_error(
+        |t| -> Result<(), DbError> {
+            t.exec_mut(
+                QueryBuilder::insert()
+                    .values([[("tenant", "t2").into()]])
+                    .ids(1)
+                    .query(),
+            )?;
+            Err(DbError::db(DbErrorType::NotAllowed, "error"))
+        },
+        DbError::db(DbErrorType::NotAllowed, "error"),
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        &[1],
+    );
+    db.exec(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t2", "a@b.c"])
+            .query(),
+        0,
+    );
+}
+
+#[test]
+fn insert_unique_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("email").unique().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "a@b.c").into()], [("email", "x@y.z").into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "q@r.s").into()], [("email", "a@b.c").into()]])
+            .query(),
+        "Value 'a@b.c' of unique index 'email' already exists (id: 1)",
+    );
+    db.exec(QueryBuilder::select().node_count().query(), 2);
+    db.exec(
+        QueryBuilder::search().index("email").value("q@r.s").query(),
+        0,
+    );
+}
+
+#[test]
+fn insert_unique_index_violation_type() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("email").unique().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "a@b.c").into()]])
+            .query(),
+        1,
+    );
+    db.transaction_mut_error(
+        |t| {
+            t.exec_mut(
+                QueryBuilder::insert()
+                    .nodes()
+                    .values([[("email", "a@b.c").into()]])
+                    .query(),
+            )
+        },
+        DbError::db(
+            DbErrorType::UniqueViolation,
+            "Value 'a@b.c' of unique index 'email' already exists (id: 1)",
+        ),
+    );
+}
+
+#[test]
+fn update_unique_indexed_value() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index("email")
+            .ordered()
+            .unique()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "a@b.c").into()], [("email", "x@y.z").into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("email", "a@b.c").into()]])
+            .ids(1)
+            .query(),
+        1,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .values([[("email", "a@b.c").into()]])
+            .ids(2)
+            .query(),
+        "Value 'a@b.c' of unique index 'email' already exists (id: 1)",
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("email", "q@r.s").into()]])
+            .ids(1)
+            .query(),
+        1,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("email", "a@b.c").into()]])
+            .ids(2)
+            .query(),
+        1,
+    );
+}
+
+#[test]
+fn insert_unique_composite_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index(["tenant", "email"])
+            .unique()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("tenant", "t1").into(), ("email", "a@b.c").into()],
+                [("tenant", "t2").into(), ("email", "a@b.c").into()],
+            ])
+            .query(),
+        2,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .values([[("tenant", "t1").into()]])
+            .ids(2)
+            .query(),
+        "Value 't1, a@b.c' of unique index 'tenant, email' already exists (id: 1)",
+    );
+}
+
+#[test]
+fn insert_unique_index_over_duplicates() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("email", "a@b.c").into()],
+                [("email", "x@y.z").into()],
+                [("email", "a@b.c").into()],
+                [("email", "x@y.z").into()],
+                [("email", "a@b.c").into()],
+                [("email", "q@r.s").into()],
+            ])
+            .query(),
+        6,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert().index("email").unique().query(),
+        "Unique index 'email' cannot be created over duplicate values: 'a@b.c' (ids: 1, 3, 5), 'x@y.z' (ids: 2, 4)",
+    );
+    db.exec(QueryBuilder::select().indexes().query(), 0);
+}
+
+#[test]
+fn remove_unique_index_rollback() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("email").unique().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "a@b.c").into()]])
+            .query(),
+        1,
+    );
+    db.transaction_mut_error(
+        |t| -> Result<(), DbError> {
+            t.exec_mut(QueryBuilder::remove().index("email").query())?;
+            Err(DbError::db(DbErrorType::NotAllowed, "error"))
+        },
+        DbError::db(DbErrorType::NotAllowed, "error"),
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("email", "a@b.c").into()]])
+            .query(),
+        "Value 'a@b.c' of unique index 'email' already exists (id: 1)",
+    );
+}
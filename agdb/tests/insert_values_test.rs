@@ -1,9 +1,12 @@
 mod test_db;
 
+use agdb::Comparison;
 use agdb::DbElement;
 use agdb::DbError;
 use agdb::DbErrorType;
 use agdb::DbId;
+use agdb::DbKeyOrder;
+use agdb::DbValue;
 use agdb::QueryBuilder;
 use test_db::TestDb;
 
@@ -380,3 +383,140 @@ fn overwrite_empty_value() {
         1,
     );
 }
+
+#[test]
+fn insert_values_bool_and_date_time() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("created").ordered().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [
+                    ("active", true).into(),
+                    ("created", DbValue::DateTime(1_000)).into(),
+                ],
+                [
+                    ("active", false).into(),
+                    ("created", DbValue::DateTime(-1_000)).into(),
+                ],
+            ])
+            .query(),
+        2,
+    );
+    db.exec_elements(
+        QueryBuilder::select().ids([1, 2]).query(),
+        &[
+            DbElement {
+                id: DbId(1),
+                from: DbId::default(),
+                to: DbId::default(),
+                values: vec![
+                    ("active", true).into(),
+                    ("created", DbValue::DateTime(1_000)).into(),
+                ],
+            },
+            DbElement {
+                id: DbId(2),
+                from: DbId::default(),
+                to: DbId::default(),
+                values: vec![
+                    ("active", false).into(),
+                    ("created", DbValue::DateTime(-1_000)).into(),
+                ],
+            },
+        ],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("active")
+            .value(true)
+            .query(),
+        &[1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::LessThan(DbValue::DateTime(0)))
+            .query(),
+        &[2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .order_by(DbKeyOrder::Asc("created".into()))
+            .query(),
+        &[2, 1],
+    );
+}
+
+#[test]
+fn migrate_legacy_values() {
+    let created = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(10);
+    let mut legacy_created = vec![0_u8; 13];
+    legacy_created[0] = 10;
+    legacy_created[12] = 1;
+    let legacy_history = agdb::AgdbSerialize::serialize(&vec![
+        DbValue::Bytes(legacy_created.clone()),
+        DbValue::Bytes(legacy_created.clone()),
+    ]);
+    let mut db = agdb::DbMemory::new("test").unwrap();
+    db.exec_mut(QueryBuilder::insert().index("active").query())
+        .unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values(vec![
+                vec![
+                    ("active", 1_u64).into(),
+                    ("created", legacy_created.clone()).into(),
+                    ("count", 1_u64).into(),
+                    ("history", legacy_history).into(),
+                ],
+                vec![
+                    ("active", 2_u64).into(),
+                    ("created", vec![1_u8, 2, 3]).into(),
+                    ("count", 0_u64).into(),
+                ],
+            ])
+            .query(),
+    )
+    .unwrap();
+    let search_active = QueryBuilder::search().index("active").value(true).query();
+
+    assert!(db.exec(&search_active).unwrap().ids().is_empty());
+    assert_eq!(
+        db.migrate_legacy_values(&["active".into(), "created".into(), "history".into()])
+            .unwrap(),
+        3
+    );
+    assert_eq!(db.exec(&search_active).unwrap().ids(), vec![DbId(1)]);
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids([1, 2]).query())
+            .unwrap()
+            .elements
+            .into_iter()
+            .map(|e| e.values)
+            .collect::<Vec<_>>(),
+        vec![
+            vec![
+                ("active", true).into(),
+                ("created", created).into(),
+                ("count", 1_u64).into(),
+                ("history", vec![created, created]).into(),
+            ],
+            vec![
+                ("active", 2_u64).into(),
+                ("created", vec![1_u8, 2, 3]).into(),
+                ("count", 0_u64).into(),
+            ],
+        ]
+    );
+    assert_eq!(
+        db.migrate_legacy_values(&["active".into(), "created".into(), "history".into()])
+            .unwrap(),
+        0
+    );
+}
@@ -0,0 +1,303 @@
+mod test_db;
+
+use agdb::Comparison;
+use agdb::DbElement;
+use agdb::DbId;
+use agdb::QueryBuilder;
+use agdb::SearchQueryDirection;
+use test_db::TestDb;
+
+fn companies_db() -> TestDb {
+    let mut db = TestDb::new();
+    //1, 2, 3, 4, 5, 6
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("type", "person").into(), ("name", "alice").into()],
+                [("type", "person").into(), ("name", "bob").into()],
+                [("type", "company").into(), ("name", "acme").into()],
+                [("type", "company").into(), ("name", "globex").into()],
+                [("type", "fund").into(), ("size", 100).into()],
+                [("type", "fund").into(), ("size", 10).into()],
+            ])
+            .query(),
+        6,
+    );
+    //-7, -8, -9, -10, -11
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 2, 5, 6, 5])
+            .to([3, 4, 3, 4, 4])
+            .values([
+                [("type", "works_at").into()],
+                [("type", "works_at").into()],
+                [("type", "invested_in").into()],
+                [("type", "invested_in").into()],
+                [("type", "invested_in").into()],
+            ])
+            .query(),
+        5,
+    );
+    db
+}
+
+#[test]
+fn match_pattern() {
+    let db = companies_db();
+    let query = QueryBuilder::match_()
+        .node("person")
+        .where_()
+        .key("type")
+        .value("person")
+        .query()
+        .edge("works_at")
+        .where_()
+        .key("type")
+        .value("works_at")
+        .query()
+        .node("company")
+        .edge("invested_in")
+        .direction(SearchQueryDirection::Reverse)
+        .where_()
+        .key("type")
+        .value("invested_in")
+        .query()
+        .node("fund")
+        .where_()
+        .key("size")
+        .value(Comparison::GreaterThan(50.into()))
+        .query()
+        .query();
+
+    db.exec_ids(query, &[1, -7, 3, -9, 5, 2, -8, 4, -11, 5]);
+}
+
+#[test]
+fn match_elements() {
+    let db = companies_db();
+    db.exec_elements(
+        QueryBuilder::match_()
+            .node("person")
+            .where_()
+            .ids(2)
+            .query()
+            .edge("works_at")
+            .node("company")
+            .query(),
+        &[
+            DbElement {
+                id: DbId(2),
+                from: DbId(-8),
+                to: DbId(0),
+                values: vec![("match", 0_u64).into(), ("variable", "person").into()],
+            },
+            DbElement {
+                id: DbId(-8),
+                from: DbId(2),
+                to: DbId(4),
+                values: vec![("match", 0_u64).into(), ("variable", "works_at").into()],
+            },
+            DbElement {
+                id: DbId(4),
+                from: DbId(0),
+                to: DbId(-11),
+                values: vec![("match", 0_u64).into(), ("variable", "company").into()],
+            },
+        ],
+    );
+}
+
+#[test]
+fn match_single_node() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("fund")
+            .where_()
+            .key("type")
+            .value("fund")
+            .query()
+            .query(),
+        &[5, 6],
+    );
+}
+
+#[test]
+fn match_offset_limit() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("fund")
+            .where_()
+            .key("type")
+            .value("fund")
+            .query()
+            .edge("invested_in")
+            .node("company")
+            .offset(1)
+            .limit(1)
+            .query(),
+        &[5, -9, 3],
+    );
+    db.exec_elements(
+        QueryBuilder::match_()
+            .node("fund")
+            .where_()
+            .key("type")
+            .value("fund")
+            .query()
+            .edge("invested_in")
+            .node("company")
+            .offset(2)
+            .limit(1)
+            .query(),
+        &[
+            DbElement {
+                id: DbId(6),
+                from: DbId(-10),
+                to: DbId(0),
+                values: vec![("match", 2_u64).into(), ("variable", "fund").into()],
+            },
+            DbElement {
+                id: DbId(-10),
+                from: DbId(6),
+                to: DbId(4),
+                values: vec![("match", 2_u64).into(), ("variable", "invested_in").into()],
+            },
+            DbElement {
+                id: DbId(4),
+                from: DbId(0),
+                to: DbId(-11),
+                values: vec![("match", 2_u64).into(), ("variable", "company").into()],
+            },
+        ],
+    );
+}
+
+#[test]
+fn match_both_directions_edges_bound_once() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("a")
+            .where_()
+            .ids(1)
+            .query()
+            .edge("e1")
+            .direction(SearchQueryDirection::Both)
+            .node("b")
+            .edge("e2")
+            .direction(SearchQueryDirection::Both)
+            .node("c")
+            .query(),
+        &[1, -7, 3, -9, 5],
+    );
+}
+
+#[test]
+fn match_cycle() {
+    let mut db = companies_db();
+    db.exec_mut(QueryBuilder::insert().edges().from(3).to(1).query(), 1);
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("a")
+            .where_()
+            .key("name")
+            .value(Comparison::StartsWith("a".into()))
+            .query()
+            .edge("e1")
+            .node("b")
+            .edge("e2")
+            .node("c")
+            .where_()
+            .ids(1)
+            .query()
+            .query(),
+        &[1, -7, 3, -12, 1],
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn match_regex() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("person")
+            .where_()
+            .key("name")
+            .value(Comparison::Matches("^(alice|bob)$".into()))
+            .query()
+            .edge("e")
+            .node("company")
+            .where_()
+            .key("name")
+            .value(Comparison::Matches("^g".into()))
+            .query()
+            .query(),
+        &[2, -8, 4],
+    );
+    db.exec_error(
+        QueryBuilder::match_()
+            .node("person")
+            .where_()
+            .key("name")
+            .value(Comparison::Matches("[a".into()))
+            .query()
+            .query(),
+        "Invalid pattern '[a': unclosed character class",
+    );
+}
+
+#[test]
+fn match_no_match() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("person")
+            .where_()
+            .key("type")
+            .value("person")
+            .query()
+            .edge("e")
+            .direction(SearchQueryDirection::Reverse)
+            .node("other")
+            .query(),
+        &[],
+    );
+}
+
+#[test]
+fn match_ids_or_condition() {
+    let db = companies_db();
+    db.exec_ids(
+        QueryBuilder::match_()
+            .node("a")
+            .where_()
+            .ids(1)
+            .or()
+            .key("type")
+            .value("fund")
+            .query()
+            .edge("e")
+            .node("b")
+            .query(),
+        &[1, -7, 3, 5, -11, 4, 5, -9, 3, 6, -10, 4],
+    );
+}
+
+#[test]
+fn match_ids_missing_alias() {
+    let db = companies_db();
+    db.exec_error(
+        QueryBuilder::match_()
+            .node("a")
+            .where_()
+            .ids("missing")
+            .query()
+            .query(),
+        "Alias 'missing' not found",
+    );
+}
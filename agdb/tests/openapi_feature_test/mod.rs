@@ -11,12 +11,27 @@ use utoipa::OpenApi;
     agdb::DbKeyOrder,
     agdb::DbValue,
     agdb::DbF64,
+    agdb::DbIssue,
+    agdb::DbIssueType,
+    agdb::DbSalvageReport,
+    agdb::DbVerifyReport,
     agdb::QueryType,
+    agdb::AggregateFunction,
+    agdb::AggregateQuery,
+    agdb::ExpectQuery,
+    agdb::AnalyticsAlgorithm,
+    agdb::PageRankOptions,
     agdb::InsertAliasesQuery,
+    agdb::InsertAnalyticsQuery,
     agdb::InsertEdgesQuery,
     agdb::InsertIndexQuery,
     agdb::InsertNodesQuery,
     agdb::InsertValuesQuery,
+    agdb::MatchQuery,
+    agdb::MatchStep,
+    agdb::MatchVariable,
+    agdb::AdjacentCondition,
+    agdb::AdjacentQuantifier,
     agdb::Comparison,
     agdb::CountComparison,
     agdb::QueryCondition,
@@ -35,12 +50,16 @@ use utoipa::OpenApi;
     agdb::SearchQueryAlgorithm,
     agdb::SelectAliasesQuery,
     agdb::SelectAllAliasesQuery,
+    agdb::SelectAnalyticsQuery,
     agdb::SelectEdgeCountQuery,
     agdb::SelectIndexesQuery,
     agdb::SelectKeyCountQuery,
     agdb::SelectKeysQuery,
     agdb::SelectNodeCountQuery,
     agdb::SelectValuesQuery,
+    agdb::KeyValueUpdate,
+    agdb::UpdateValuesQuery,
+    agdb::ValueUpdate,
 )))]
 pub(crate) struct Api;
 
@@ -0,0 +1,607 @@
+#![cfg(feature = "serde")]
+
+mod test_db;
+
+use agdb::AdjacentQuantifier;
+use agdb::AggregateFunction;
+use agdb::AnalyticsAlgorithm;
+use agdb::Comparison;
+use agdb::CountComparison;
+use agdb::DbKeyOrder;
+use agdb::DbValue;
+use agdb::KeyValueUpdate;
+use agdb::PageRankOptions;
+use agdb::QueryBuilder;
+use agdb::QueryConditionLogic;
+use agdb::QueryId;
+use agdb::QueryType;
+use agdb::SearchQueryDirection;
+use agdb::parse_queries;
+use agdb::parse_query;
+use test_db::TestDb;
+
+#[track_caller]
+fn round_trip<T: Into<QueryType>>(query: T) {
+    let query = query.into();
+    let text = query.to_string();
+    assert_eq!(parse_query(&text).unwrap(), query, "{text}");
+}
+
+#[track_caller]
+fn text<T: Into<QueryType>>(query: T, expected: &str) {
+    let query = query.into();
+    assert_eq!(query.to_string(), expected);
+    assert_eq!(parse_query(expected).unwrap(), query);
+}
+
+#[track_caller]
+fn error(text: &str, expected: &str) {
+    assert_eq!(parse_query(text).unwrap_err().description, expected);
+}
+
+#[test]
+fn insert_queries() {
+    text(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["a", "b"])
+            .values([[("k", 1).into()], [("k", 2).into()]])
+            .query(),
+        r#"insert nodes aliases ["a", "b"] values [{"k": 1}, {"k": 2}]"#,
+    );
+    text(
+        QueryBuilder::insert().nodes().count(2).query(),
+        "insert nodes count 2",
+    );
+    text(
+        QueryBuilder::insert()
+            .edges()
+            .from("a")
+            .to([2, 3])
+            .each()
+            .values_uniform([("k", "v").into()])
+            .query(),
+        r#"insert edges from "a" to [2, 3] each values {"k": "v"}"#,
+    );
+    round_trip(QueryBuilder::insert().edges().ids(-3).from(1).to(2).query());
+    round_trip(
+        QueryBuilder::insert()
+            .edges()
+            .from(1)
+            .to(2)
+            .acyclic()
+            .query(),
+    );
+    round_trip(QueryBuilder::insert().nodes().ids("a").count(1).query());
+    text(
+        QueryBuilder::insert().aliases("a").ids(1).query(),
+        r#"insert aliases ["a"] ids 1"#,
+    );
+    round_trip(
+        QueryBuilder::insert()
+            .values([[("k", 1).into()], [("k", 2).into()]])
+            .ids([1, 2])
+            .query(),
+    );
+    round_trip(
+        QueryBuilder::insert()
+            .values_uniform([("k", 1).into()])
+            .search()
+            .from(1)
+            .query(),
+    );
+    text(
+        QueryBuilder::insert().index("k").ordered().unique().query(),
+        r#"insert index ["k"] ordered unique"#,
+    );
+    round_trip(
+        QueryBuilder::insert()
+            .analytics(AnalyticsAlgorithm::PageRank(PageRankOptions {
+                damping: 0.85,
+                iterations: 20,
+            }))
+            .query(),
+    );
+    round_trip(
+        QueryBuilder::insert()
+            .analytics(AnalyticsAlgorithm::DegreeCentrality)
+            .ids([1, 2])
+            .query(),
+    );
+    text(
+        QueryBuilder::insert()
+            .update([
+                KeyValueUpdate::increment("visits", 1),
+                KeyValueUpdate::append("tags", "x"),
+            ])
+            .ids("a")
+            .query(),
+        r#"insert update [increment "visits" 1, append "tags" "x"] ids "a""#,
+    );
+    round_trip(
+        QueryBuilder::insert()
+            .update([
+                KeyValueUpdate::remove_item("tags", "x"),
+                KeyValueUpdate::set_if_absent("created", 1_u64),
+                KeyValueUpdate::min("low", 1.5),
+                KeyValueUpdate::max("high", -1),
+            ])
+            .search()
+            .from(1)
+            .query(),
+    );
+}
+
+#[test]
+fn expect_queries() {
+    text(
+        QueryBuilder::expect()
+            .ids("a")
+            .where_()
+            .key("version")
+            .value(7)
+            .query()
+            .query(),
+        r#"expect ids "a" where key "version" = 7"#,
+    );
+    text(
+        QueryBuilder::expect().ids([1, 2]).query(),
+        "expect ids [1, 2]",
+    );
+    round_trip(
+        QueryBuilder::expect()
+            .ids(QueryBuilder::search().from(1).query())
+            .where_()
+            .keys("k")
+            .or()
+            .not()
+            .key("locked")
+            .value(true)
+            .query()
+            .query(),
+    );
+}
+
+#[test]
+fn remove_queries() {
+    text(
+        QueryBuilder::remove().ids([1, -2]).query(),
+        "remove ids [1, -2]",
+    );
+    round_trip(QueryBuilder::remove().aliases(["a", "b"]).query());
+    round_trip(QueryBuilder::remove().index("k").query());
+    text(
+        QueryBuilder::remove().values("k").ids("a").query(),
+        r#"remove values ["k"] ids "a""#,
+    );
+    round_trip(
+        QueryBuilder::remove()
+            .search()
+            .from(1)
+            .where_()
+            .edge()
+            .query(),
+    );
+}
+
+#[test]
+fn select_queries() {
+    text(QueryBuilder::select().ids(1).query(), "select ids 1");
+    text(
+        QueryBuilder::select().values(["a", "b"]).ids(1).query(),
+        r#"select values ["a", "b"] ids 1"#,
+    );
+    text(QueryBuilder::select().aliases().query(), "select aliases");
+    text(
+        QueryBuilder::select().aliases().ids([1, 2]).query(),
+        "select aliases ids [1, 2]",
+    );
+    text(QueryBuilder::select().indexes().query(), "select indexes");
+    text(
+        QueryBuilder::select().node_count().query(),
+        "select node_count",
+    );
+    round_trip(QueryBuilder::select().keys().ids(1).query());
+    round_trip(QueryBuilder::select().key_count().ids(1).query());
+    text(
+        QueryBuilder::select().edge_count_from().ids(1).query(),
+        "select edge_count from ids 1",
+    );
+    round_trip(QueryBuilder::select().edge_count().ids(1).query());
+    round_trip(QueryBuilder::select().edge_count_to().ids(1).query());
+    round_trip(
+        QueryBuilder::select()
+            .analytics(AnalyticsAlgorithm::StronglyConnectedComponents)
+            .query(),
+    );
+    text(
+        QueryBuilder::select()
+            .aggregate("age")
+            .functions([AggregateFunction::Min, AggregateFunction::Max])
+            .group_by("city")
+            .ids([1, 2])
+            .query(),
+        r#"select aggregate "age" functions [min, max] group_by "city" ids [1, 2]"#,
+    );
+    round_trip(
+        QueryBuilder::select()
+            .aggregate("age")
+            .search()
+            .from(1)
+            .query(),
+    );
+}
+
+#[test]
+fn search_queries() {
+    text(
+        QueryBuilder::search()
+            .from("a")
+            .order_by([DbKeyOrder::Desc("age".into())])
+            .offset(2)
+            .limit(3)
+            .where_()
+            .key("age")
+            .value(Comparison::GreaterThan(18.into()))
+            .query(),
+        r#"search from "a" order_by [desc "age"] offset 2 limit 3 where key "age" > 18"#,
+    );
+    text(
+        QueryBuilder::search().depth_first().to(1).query(),
+        "search depth_first to 1",
+    );
+    round_trip(QueryBuilder::search().index("k").value(1).query());
+    round_trip(QueryBuilder::search().elements().query());
+    round_trip(
+        QueryBuilder::search()
+            .from(1)
+            .to(5)
+            .weighted_by("w")
+            .query(),
+    );
+    round_trip(
+        QueryBuilder::search()
+            .after("cursor")
+            .from(1)
+            .direction(SearchQueryDirection::Both)
+            .query(),
+    );
+}
+
+#[test]
+fn conditions() {
+    text(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .not()
+            .ids(2)
+            .or()
+            .beyond()
+            .where_()
+            .edge()
+            .and()
+            .distance(CountComparison::LessThan(3))
+            .end_where()
+            .and()
+            .not_beyond()
+            .keys("k")
+            .query(),
+        r#"search from 1 where not ids [2] or beyond (edge and distance < 3) and not_beyond keys ["k"]"#,
+    );
+    round_trip(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .node()
+            .and()
+            .edge_count(CountComparison::Equal(1))
+            .and()
+            .edge_count_from(CountComparison::GreaterThanOrEqual(2))
+            .and()
+            .edge_count_to(CountComparison::NotEqual(3))
+            .or()
+            .edge_count(CountComparison::LessThanOrEqual(4))
+            .or()
+            .edge_count(CountComparison::GreaterThan(5))
+            .query(),
+    );
+    round_trip(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .key("name")
+            .value(Comparison::Matches("^a.*".into()))
+            .and()
+            .key("name")
+            .value(Comparison::StartsWithIgnoreCase("A".into()))
+            .and()
+            .key("name")
+            .value(Comparison::ContainsIgnoreCase("b".into()))
+            .and()
+            .key("name")
+            .value(Comparison::EndsWithIgnoreCase("c".into()))
+            .and()
+            .key("name")
+            .value(Comparison::EqualIgnoreCase("abc".into()))
+            .and()
+            .key("name")
+            .value(Comparison::Contains("b".into()))
+            .and()
+            .key("name")
+            .value(Comparison::StartsWith("a".into()))
+            .and()
+            .key("name")
+            .value(Comparison::EndsWith("c".into()))
+            .and()
+            .key("name")
+            .value(Comparison::NotEqual("x".into()))
+            .and()
+            .key("n")
+            .value(Comparison::LessThanOrEqual(1.into()))
+            .and()
+            .key("n")
+            .value(Comparison::GreaterThanOrEqual(1.into()))
+            .query(),
+    );
+    text(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .key("a")
+            .compare_key(Comparison::LessThan("b".into()))
+            .query(),
+        r#"search from 1 where key "a" < key "b""#,
+    );
+    text(
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .adjacent(
+                SearchQueryDirection::Reverse,
+                AdjacentQuantifier::Count(CountComparison::Equal(2)),
+            )
+            .key("k")
+            .value(1)
+            .end_where()
+            .query(),
+        r#"search from 1 where adjacent reverse count = 2 (key "k" = 1)"#,
+    );
+
+    let mut query = QueryBuilder::search().from(1).where_().node().query();
+    query.conditions[0].logic = QueryConditionLogic::Or;
+    text(query, "search from 1 where or node");
+}
+
+#[test]
+fn match_queries() {
+    text(
+        QueryBuilder::match_()
+            .node("p")
+            .where_()
+            .key("type")
+            .value("person")
+            .query()
+            .edge("e")
+            .node("c")
+            .edge("f")
+            .direction(SearchQueryDirection::Reverse)
+            .where_()
+            .edge_count(CountComparison::Equal(1))
+            .query()
+            .node("x")
+            .edge("")
+            .direction(SearchQueryDirection::Both)
+            .node("some node")
+            .limit(2)
+            .offset(1)
+            .query(),
+        r#"match (p where key "type" = "person") -[e]-> (c) <-[f where edge_count = 1]- (x) -[]- ("some node") offset 1 limit 2"#,
+    );
+    round_trip(
+        QueryBuilder::match_()
+            .node("")
+            .where_()
+            .ids(1)
+            .query()
+            .edge("where")
+            .node("b")
+            .query(),
+    );
+}
+
+#[test]
+fn values() {
+    let values: Vec<DbValue> = vec![
+        1.into(),
+        (-1).into(),
+        1_u64.into(),
+        1.5.into(),
+        (-0.0).into(),
+        1e300.into(),
+        f64::INFINITY.into(),
+        f64::NEG_INFINITY.into(),
+        "text \"quoted\" \\ \n\t\u{1} ü".into(),
+        vec![0_u8, 15, 255].into(),
+        Vec::<u8>::new().into(),
+        vec![1_i64, -2].into(),
+        Vec::<i64>::new().into(),
+        vec![1_u64, 2].into(),
+        Vec::<u64>::new().into(),
+        vec![1.0, -2.5].into(),
+        Vec::<f64>::new().into(),
+        vec!["a", "b"].into(),
+        Vec::<String>::new().into(),
+        true.into(),
+        false.into(),
+        DbValue::DateTime(-1_000_000),
+        DbValue::List(vec![1.into(), "a".into(), DbValue::List(vec![])]),
+        DbValue::List(vec![1.into(), 2.into()]),
+        DbValue::Map(vec![
+            ("a", 1).into(),
+            ("b", DbValue::Map(vec![(1, true).into()])).into(),
+        ]),
+    ];
+
+    for value in values {
+        round_trip(
+            QueryBuilder::insert()
+                .values_uniform([(value.clone(), value).into()])
+                .ids(1)
+                .query(),
+        );
+    }
+
+    let query = parse_query("insert values {\"k\": nan} ids 1").unwrap();
+    let QueryType::InsertValues(query) = query else {
+        panic!("unexpected query");
+    };
+    let agdb::QueryValues::Single(values) = query.values else {
+        panic!("unexpected values");
+    };
+    assert!(values[0].value.to_f64().unwrap().to_f64().is_nan());
+}
+
+#[test]
+fn subqueries() {
+    round_trip(
+        QueryBuilder::insert()
+            .edges()
+            .from(QueryBuilder::search().from(1).where_().node().query())
+            .to(QueryBuilder::search().to(2).query())
+            .query(),
+    );
+    round_trip(
+        QueryBuilder::select()
+            .ids(
+                QueryBuilder::search()
+                    .from(1)
+                    .where_()
+                    .ids(QueryBuilder::search().from(2).query())
+                    .query(),
+            )
+            .query(),
+    );
+}
+
+#[test]
+fn multiple_queries() {
+    assert_eq!(
+        parse_queries("insert nodes count 1; select node_count;").unwrap(),
+        vec![
+            QueryBuilder::insert().nodes().count(1).query().into(),
+            QueryBuilder::select().node_count().query().into(),
+        ]
+    );
+    assert_eq!(parse_queries("").unwrap(), vec![]);
+}
+
+#[test]
+fn whitespace_and_optional_syntax() {
+    assert_eq!(
+        parse_query("  search\n from 1\twhere and ids 2 ; ").unwrap(),
+        QueryBuilder::search()
+            .from(1)
+            .where_()
+            .ids(2)
+            .query()
+            .into()
+    );
+    assert_eq!(
+        parse_query("select values \"k\" ids [\"a\", 2,]").unwrap(),
+        QueryBuilder::select()
+            .values("k")
+            .ids([QueryId::from("a"), QueryId::from(2)])
+            .query()
+            .into()
+    );
+    assert_eq!(
+        parse_query("select analytics page_rank(1, 10)").unwrap(),
+        QueryBuilder::select()
+            .analytics(AnalyticsAlgorithm::PageRank(PageRankOptions {
+                damping: 1.0,
+                iterations: 10,
+            }))
+            .query()
+            .into()
+    );
+}
+
+#[test]
+fn errors() {
+    error("", "Invalid query: expected keyword at position 0");
+    error("find ids 1", "Invalid query: unknown query at position 0");
+    error(
+        "select ids 1 2",
+        "Invalid query: unexpected token at position 13",
+    );
+    error(
+        "select ids",
+        "Invalid query: expected id or alias at position 10",
+    );
+    error(
+        "select values \"k",
+        "Invalid query: unterminated string at position 14",
+    );
+    error(
+        "select values \"\\q\" ids 1",
+        "Invalid query: invalid escape at position 15",
+    );
+    error(
+        "select ids 1 @",
+        "Invalid query: unexpected character '@' at position 13",
+    );
+    error(
+        "select ids 1x",
+        "Invalid query: invalid number at position 11",
+    );
+    error(
+        "select ids 0x1",
+        "Invalid query: odd number of hex digits at position 11",
+    );
+    error(
+        "select ids 99999999999999999999",
+        "Invalid query: number out of range at position 11",
+    );
+    error(
+        "select values i64[1, \"a\"] ids 1",
+        "Invalid query: mixed vector element types at position 18",
+    );
+    error(
+        "search from 1 where key \"k\" like 1",
+        "Invalid query: expected comparison at position 28",
+    );
+    error(
+        "search from 1 where (node",
+        "Invalid query: expected ')' at position 25",
+    );
+    error(
+        "match (a) -[e]> (b)",
+        "Invalid query: expected ']-' at position 13",
+    );
+}
+
+#[test]
+fn exec_parsed_queries() {
+    let mut db = TestDb::new();
+
+    for query in parse_queries(
+        r#"insert nodes aliases ["a", "b"] values [{"k": 1}, {"k": 2}];
+        insert edges from "a" to "b""#,
+    )
+    .unwrap()
+    {
+        match query {
+            QueryType::InsertNodes(query) => db.exec_mut(query, 2),
+            QueryType::InsertEdges(query) => db.exec_mut(query, 1),
+            _ => panic!("unexpected query"),
+        }
+    }
+
+    let QueryType::Search(query) = parse_query(r#"search from "a" where key "k" = 2"#).unwrap()
+    else {
+        panic!("unexpected query");
+    };
+
+    db.exec_ids(query, &[2]);
+}
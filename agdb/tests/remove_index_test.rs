@@ -1,5 +1,6 @@
 mod test_db;
 
+use agdb::Comparison;
 use agdb::DbError;
 use agdb::DbErrorType;
 use agdb::DbValue;
@@ -122,3 +123,120 @@ fn remove_indexed_key_rollback() {
     let result = db.exec_result(QueryBuilder::select().indexes().query());
     assert_eq!(result.elements[0].values[0].value, DbValue::from(3_u64));
 }
+
+#[test]
+fn remove_ordered_index_rollback() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("age").ordered().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("age", 30).into()],
+                [("age", 10).into()],
+                [("age", 20).into()],
+            ])
+            .query(),
+        3,
+    );
+    db.transaction_mut_error(
+        |t| -> Result<(), DbError> {
+            t.exec_mut(QueryBuilder::remove().index("age").query())?;
+            Err(DbError::db(DbErrorType::NotAllowed, "error"))
+        },
+        DbError::db(DbErrorType::NotAllowed, "error"),
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("age")
+            .value(Comparison::GreaterThan(10.into()))
+            .query(),
+        &[3, 1],
+    );
+}
+
+#[test]
+fn remove_composite_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("tenant").query(), 0);
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("tenant", "t1").into(), ("email", "a@b.c").into()]])
+            .query(),
+        1,
+    );
+    db.exec_mut(QueryBuilder::remove().index(["tenant", "email"]).query(), 1);
+    db.exec(QueryBuilder::select().indexes().query(), 1);
+    db.exec_error(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        "Index 'tenant, email' not found",
+    );
+}
+
+#[test]
+fn remove_composite_index_rollback() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index(["tenant", "created"])
+            .ordered()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("tenant", "t1").into(), ("created", 20).into()],
+                [("tenant", "t1").into(), ("created", 10).into()],
+            ])
+            .query(),
+        2,
+    );
+    db.transaction_mut_error(
+        |t| -> Result<(), DbError> {
+            t.exec_mut(QueryBuilder::remove().index(["tenant", "created"]).query())?;
+            Err(DbError::db(DbErrorType::NotAllowed, "error"))
+        },
+        DbError::db(DbErrorType::NotAllowed, "error"),
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .values(["t1"])
+            .query(),
+        &[2, 1],
+    );
+}
+
+#[test]
+fn remove_node_with_composite_indexed_values() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("tenant", "t1").into(), ("email", "a@b.c").into()],
+                [("tenant", "t1").into(), ("email", "x@y.z").into()],
+            ])
+            .query(),
+        2,
+    );
+    db.exec_mut(QueryBuilder::remove().ids(1).query(), 1);
+    db.exec(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        0,
+    );
+
+    let result = db.exec_result(QueryBuilder::select().indexes().query());
+    assert_eq!(result.elements[0].values[0].value, DbValue::from(1_u64));
+}
@@ -0,0 +1,178 @@
+mod test_db;
+
+use agdb::Comparison;
+use agdb::Db;
+use agdb::DbElement;
+use agdb::DbError;
+use agdb::DbErrorType;
+use agdb::DbId;
+use agdb::DbIssueType;
+use agdb::QueryBuilder;
+use test_db::TestFile;
+
+fn create_db(filename: &str) {
+    let mut db = Db::new(filename).unwrap();
+    db.exec_mut(QueryBuilder::insert().index("name").query())
+        .unwrap();
+    db.exec_mut(QueryBuilder::insert().index("age").ordered().query())
+        .unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["users", "docs"])
+            .values([[("name", "users").into()], [("name", "docs").into()]])
+            .query(),
+    )
+    .unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values(
+                (0..10)
+                    .map(|age| vec![("age", age).into()])
+                    .collect::<Vec<_>>(),
+            )
+            .query(),
+    )
+    .unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("users")
+            .to((3..13).collect::<Vec<i64>>())
+            .values_uniform([("name", "owns").into()])
+            .query(),
+    )
+    .unwrap();
+    db.exec_mut(QueryBuilder::remove().ids([5, 7, -20]).query())
+        .unwrap();
+}
+
+fn header_pos(filename: &str, record: usize) -> usize {
+    let bytes = std::fs::read(filename).unwrap();
+    let mut pos = 24;
+
+    for _ in 0..record {
+        let size = u64::from_le_bytes(bytes[pos + 8..pos + 16].try_into().unwrap());
+        pos += 24 + (size & ((1 << 48) - 1)) as usize;
+    }
+
+    pos
+}
+
+#[test]
+fn salvage_intact() {
+    let source = TestFile::new();
+    let target = TestFile::new();
+    create_db(source.file_name());
+
+    let (db, report) = Db::salvage(source.file_name(), target.file_name()).unwrap();
+
+    assert!(report.is_complete());
+    assert_eq!(report.nodes, 10);
+    assert_eq!(report.edges, 7);
+    assert_eq!(report.aliases, 2);
+    assert_eq!(report.values, 17);
+    assert_eq!(report.indexes, 2);
+    assert!(db.verify().is_ok());
+
+    let result = db
+        .exec(
+            QueryBuilder::select()
+                .ids(QueryBuilder::search().from("users").where_().edge().query())
+                .query(),
+        )
+        .unwrap();
+    assert_eq!(
+        result.ids(),
+        vec![
+            DbId(-22),
+            DbId(-21),
+            DbId(-19),
+            DbId(-18),
+            DbId(-16),
+            DbId(-14),
+            DbId(-13)
+        ]
+    );
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids(-14).query())
+            .unwrap()
+            .elements,
+        vec![DbElement {
+            id: DbId(-14),
+            from: DbId(1),
+            to: DbId(4),
+            values: vec![("name", "owns").into()],
+        }]
+    );
+    assert_eq!(
+        db.exec(QueryBuilder::search().index("name").value("docs").query())
+            .unwrap()
+            .ids(),
+        vec![DbId(2)]
+    );
+    assert_eq!(
+        db.exec(
+            QueryBuilder::search()
+                .index("age")
+                .value(Comparison::GreaterThan(7.into()))
+                .query()
+        )
+        .unwrap()
+        .ids(),
+        vec![DbId(11), DbId(12)]
+    );
+}
+
+#[test]
+fn salvage_corrupted_record() {
+    let source = TestFile::new();
+    let target = TestFile::new();
+    create_db(source.file_name());
+
+    {
+        let pos = header_pos(source.file_name(), 5);
+        let mut bytes = std::fs::read(source.file_name()).unwrap();
+        bytes[pos + 8] ^= 4;
+        std::fs::write(source.file_name(), bytes).unwrap();
+    }
+
+    assert!(Db::new(source.file_name()).is_err());
+
+    let (db, report) = Db::salvage(source.file_name(), target.file_name()).unwrap();
+
+    assert!(!report.is_complete());
+    assert_eq!(report.issues[0].ty, DbIssueType::UnreadableRecord);
+    assert!(db.verify().is_ok());
+}
+
+#[test]
+fn salvage_missing_source() {
+    let source = TestFile::new();
+    let target = TestFile::new();
+
+    assert_eq!(
+        Db::salvage(source.file_name(), target.file_name()).unwrap_err(),
+        DbError::db(
+            DbErrorType::NotFound,
+            format!("Database file '{}' not found", source.file_name())
+        )
+    );
+}
+
+#[test]
+fn salvage_existing_target() {
+    let source = TestFile::new();
+    let target = TestFile::new();
+    create_db(source.file_name());
+    create_db(target.file_name());
+
+    assert_eq!(
+        Db::salvage(source.file_name(), target.file_name()).unwrap_err(),
+        DbError::db(
+            DbErrorType::NotAllowed,
+            format!("Salvage target '{}' already exists", target.file_name())
+        )
+    );
+}
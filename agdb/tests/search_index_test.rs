@@ -1,6 +1,9 @@
 mod test_db;
 
+use agdb::Comparison;
 use agdb::DbId;
+use agdb::DbKeyOrder;
+use agdb::DbValue;
 use agdb::QueryBuilder;
 use agdb::QueryCondition;
 use agdb::SearchQuery;
@@ -81,6 +84,236 @@ fn search_index_missing_value() {
     db.exec_ids(QueryBuilder::search().index("age").value(50).query(), &[]);
 }
 
+fn ordered_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("created").ordered().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("created", 30).into(), ("name", "c").into()],
+                vec![("created", 10).into(), ("name", "a").into()],
+                vec![("created", 50).into(), ("name", "e").into()],
+                vec![("created", 20).into(), ("name", "b").into()],
+                vec![("created", 40).into(), ("name", "d").into()],
+                vec![("name", "f").into()],
+            ])
+            .query(),
+        6,
+    );
+    db
+}
+
+#[test]
+fn search_ordered_index_equal() {
+    let db = ordered_db();
+    db.exec_ids(
+        QueryBuilder::search().index("created").value(20).query(),
+        &[4],
+    );
+}
+
+#[test]
+fn search_ordered_index_range() {
+    let db = ordered_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThan(20.into()))
+            .query(),
+        &[1, 5, 3],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::LessThanOrEqual(30.into()))
+            .query(),
+        &[2, 4, 1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::NotEqual(30.into()))
+            .query(),
+        &[2, 4, 5, 3],
+    );
+}
+
+#[test]
+fn search_ordered_index_starts_with() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("name").ordered().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("name", "bob").into()],
+                [("name", "alice").into()],
+                [("name", "albert").into()],
+                [("name", "alfred").into()],
+            ])
+            .query(),
+        4,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("name")
+            .value(Comparison::StartsWith("al".into()))
+            .query(),
+        &[3, 4, 2],
+    );
+}
+
+#[test]
+fn search_ordered_index_desc_limit_offset() {
+    let db = ordered_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThanOrEqual(10.into()))
+            .order_by(DbKeyOrder::Desc("created".into()))
+            .offset(1)
+            .limit(2)
+            .query(),
+        &[5, 1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThanOrEqual(10.into()))
+            .offset(3)
+            .query(),
+        &[5, 3],
+    );
+}
+
+#[test]
+fn search_ordered_index_order_by_other_key() {
+    let db = ordered_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::LessThan(40.into()))
+            .order_by(DbKeyOrder::Desc("name".into()))
+            .limit(2)
+            .query(),
+        &[1, 4],
+    );
+}
+
+#[test]
+fn search_ordered_index_with_conditions() {
+    let db = ordered_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThan(10.into()))
+            .limit(2)
+            .where_()
+            .key("name")
+            .value(Comparison::NotEqual("c".into()))
+            .query(),
+        &[4, 5],
+    );
+}
+
+#[test]
+fn search_ordered_index_after_update_and_remove() {
+    let mut db = ordered_db();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("created", 60).into()]])
+            .ids(2)
+            .query(),
+        1,
+    );
+    db.exec_mut(QueryBuilder::remove().ids(3).query(), 1);
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThan(20.into()))
+            .query(),
+        &[1, 5, 2],
+    );
+}
+
+#[test]
+fn search_ordered_index_numeric_types() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("price").ordered().query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("price", 30_u64).into()],
+                [("price", 10).into()],
+                [("price", 20.5).into()],
+                [("price", 20_u64).into()],
+                [("price", 20).into()],
+            ])
+            .query(),
+        5,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search().index("price").value(20.0).query(),
+        &[5, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("price")
+            .value(Comparison::GreaterThan(15_u64.into()))
+            .order_by(DbKeyOrder::Desc("price".into()))
+            .limit(3)
+            .query(),
+        &[1, 3, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index("price")
+            .value(Comparison::LessThan(25.into()))
+            .query(),
+        &[2, 5, 4, 3],
+    );
+}
+
+#[test]
+fn search_hash_index_numeric_types() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("price").query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("price", 20_u64).into()],
+                [("price", 20.5).into()],
+                [("price", 20.0).into()],
+            ])
+            .query(),
+        3,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search().index("price").value(20).query(),
+        &[1, 3],
+    );
+}
+
+#[test]
+fn search_hash_index_range() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("created").query(), 0);
+
+    db.exec_error(
+        QueryBuilder::search()
+            .index("created")
+            .value(Comparison::GreaterThan(20.into()))
+            .query(),
+        "Index 'created' is not ordered and supports only equality comparison",
+    );
+}
+
 #[test]
 fn missing_index() {
     let db = TestDb::new();
@@ -114,12 +347,15 @@ fn missing_condition() {
     let db = TestDb::new();
     let query = SearchQuery {
         algorithm: agdb::SearchQueryAlgorithm::Index,
+        direction: agdb::SearchQueryDirection::Forward,
         origin: agdb::QueryId::Id(DbId(0)),
         destination: agdb::QueryId::Id(DbId(0)),
         limit: 0,
         offset: 0,
         order_by: vec![],
         conditions: vec![],
+        weight: None,
+        after: None,
     };
 
     db.exec_error(query, "Index condition is required for index search");
@@ -130,6 +366,7 @@ fn wrong_condition() {
     let db = TestDb::new();
     let query = SearchQuery {
         algorithm: agdb::SearchQueryAlgorithm::Index,
+        direction: agdb::SearchQueryDirection::Forward,
         origin: agdb::QueryId::Id(DbId(0)),
         destination: agdb::QueryId::Id(DbId(0)),
         limit: 0,
@@ -140,7 +377,320 @@ fn wrong_condition() {
             modifier: agdb::QueryConditionModifier::None,
             data: agdb::QueryConditionData::Node,
         }],
+        weight: None,
+        after: None,
     };
 
     db.exec_error(query, "Index condition must be key value");
 }
+
+#[test]
+fn search_composite_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("tenant", "t1").into(), ("email", "a@b.c").into()],
+                vec![("tenant", "t2").into(), ("email", "a@b.c").into()],
+                vec![("tenant", "t1").into(), ("email", "x@y.z").into()],
+                vec![("email", "a@b.c").into()],
+            ])
+            .query(),
+        4,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        &[1],
+    );
+    db.exec(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t3", "a@b.c"])
+            .query(),
+        0,
+    );
+    db.exec_error(
+        QueryBuilder::search()
+            .index(["tenant", "email"])
+            .values(["t1"])
+            .query(),
+        "Index 'tenant, email' is not ordered and supports only lookups by all of its keys",
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["email", "tenant"])
+            .values(["a@b.c", "t1"])
+            .query(),
+        &[1],
+    );
+    db.exec_error(
+        QueryBuilder::search()
+            .index(["tenant", "name"])
+            .values(["t1", "a@b.c"])
+            .query(),
+        "Index 'tenant, name' not found",
+    );
+}
+
+fn composite_index_query(conditions: Vec<agdb::QueryConditionData>) -> SearchQuery {
+    SearchQuery {
+        algorithm: agdb::SearchQueryAlgorithm::Index,
+        direction: agdb::SearchQueryDirection::Forward,
+        origin: agdb::QueryId::Id(DbId(0)),
+        destination: agdb::QueryId::Id(DbId(0)),
+        limit: 0,
+        offset: 0,
+        order_by: vec![],
+        conditions: vec![QueryCondition {
+            logic: agdb::QueryConditionLogic::And,
+            modifier: agdb::QueryConditionModifier::None,
+            data: agdb::QueryConditionData::Where(
+                conditions
+                    .into_iter()
+                    .map(|data| QueryCondition {
+                        logic: agdb::QueryConditionLogic::And,
+                        modifier: agdb::QueryConditionModifier::None,
+                        data,
+                    })
+                    .collect(),
+            ),
+        }],
+        weight: None,
+        after: None,
+    }
+}
+
+#[test]
+fn search_composite_index_by_keys() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index(["tenant", "team", "email"])
+            .ordered()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![
+                    ("tenant", "t1").into(),
+                    ("team", "a").into(),
+                    ("email", "x@y.z").into(),
+                ],
+                vec![
+                    ("tenant", "t1").into(),
+                    ("team", "b").into(),
+                    ("email", "a@b.c").into(),
+                ],
+                vec![
+                    ("tenant", "t1").into(),
+                    ("team", "a").into(),
+                    ("email", "a@b.c").into(),
+                ],
+            ])
+            .query(),
+        3,
+    );
+
+    let equal = |key: &str, value: &str| {
+        agdb::QueryConditionData::KeyValue(agdb::KeyValueComparison {
+            key: key.into(),
+            value: Comparison::Equal(value.into()),
+        })
+    };
+    let keys = |keys: &[&str]| {
+        agdb::QueryConditionData::Keys(keys.iter().map(|key| (*key).into()).collect())
+    };
+
+    db.exec_ids(
+        composite_index_query(vec![equal("team", "a"), equal("tenant", "t1")]),
+        &[3, 1],
+    );
+    db.exec_ids(
+        composite_index_query(vec![
+            keys(&["email"]),
+            equal("team", "a"),
+            equal("tenant", "t1"),
+        ]),
+        &[3, 1],
+    );
+    db.exec_ids(
+        composite_index_query(vec![
+            equal("email", "a@b.c"),
+            equal("tenant", "t1"),
+            equal("team", "b"),
+        ]),
+        &[2],
+    );
+    db.exec_error(
+        composite_index_query(vec![equal("team", "a")]),
+        "Index 'team' not found",
+    );
+    db.exec_error(
+        composite_index_query(vec![equal("tenant", "t1"), keys(&["email"])]),
+        "Index 'tenant, email' not found",
+    );
+    db.exec_error(
+        composite_index_query(vec![equal("tenant", "t1"), keys(&["tenant", "team"])]),
+        "Composite index condition lists the key 'tenant' more than once",
+    );
+    db.exec_error(
+        composite_index_query(vec![
+            equal("tenant", "t1"),
+            agdb::QueryConditionData::KeyValue(agdb::KeyValueComparison {
+                key: "team".into(),
+                value: Comparison::GreaterThan("a".into()),
+            }),
+        ]),
+        "Composite index condition must consist of key value equality conditions optionally accompanied by keys condition all joined by 'and'",
+    );
+}
+
+#[test]
+fn search_ordered_composite_index_prefix() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index(["tenant", "created"])
+            .ordered()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("tenant", "t1").into(), ("created", 30).into()],
+                vec![("tenant", "t2").into(), ("created", 10).into()],
+                vec![("tenant", "t1").into(), ("created", 10).into()],
+                vec![("tenant", "t1").into(), ("created", 20).into()],
+            ])
+            .query(),
+        4,
+    );
+
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .values(["t1"])
+            .query(),
+        &[3, 4, 1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .value("t1")
+            .order_by(DbKeyOrder::Desc("created".into()))
+            .limit(2)
+            .query(),
+        &[1, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .values(["t1"])
+            .order_by(DbKeyOrder::Asc("tenant".into()))
+            .offset(1)
+            .query(),
+        &[4, 1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .values([DbValue::from("t1"), DbValue::from(20)])
+            .query(),
+        &[4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .values(["t1"])
+            .where_()
+            .key("created")
+            .value(Comparison::GreaterThan(10.into()))
+            .query(),
+        &[4, 1],
+    );
+}
+
+#[test]
+fn search_ordered_composite_index_after_cursor() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .index(["tenant", "created"])
+            .ordered()
+            .query(),
+        0,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("tenant", "t1").into(), ("created", 30).into()],
+                vec![("tenant", "t1").into(), ("created", 10).into()],
+                vec![("tenant", "t1").into(), ("created", 20).into()],
+            ])
+            .query(),
+        3,
+    );
+    let query = || {
+        QueryBuilder::search()
+            .index(["tenant", "created"])
+            .value("t1")
+            .order_by(DbKeyOrder::Desc("created".into()))
+            .limit(1)
+    };
+    let cursor = db.exec_result(query().query()).cursor;
+    db.exec_mut(QueryBuilder::remove().ids(1).query(), 1);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([vec![("tenant", "t1").into(), ("created", 40).into()]])
+            .query(),
+        1,
+    );
+
+    let mut query = query().query();
+    query.limit = 0;
+    query.after = cursor;
+    db.exec_ids(query, &[3, 2]);
+}
+
+#[test]
+fn search_hash_index_after_cursor() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("price").query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("price", 20).into()],
+                [("price", 20).into()],
+                [("price", 20).into()],
+            ])
+            .query(),
+        3,
+    );
+    let query = || QueryBuilder::search().index("price").value(20).limit(1);
+    let cursor = db.exec_result(query().query()).cursor;
+
+    let mut next = query().query();
+    next.limit = 0;
+    next.after = cursor;
+    db.exec_ids(next.clone(), &[2, 3]);
+
+    db.exec_mut(QueryBuilder::remove().ids(1).query(), 1);
+    db.exec_error(
+        next,
+        "Cursor is no longer valid as its last element (1) is no longer found",
+    );
+}
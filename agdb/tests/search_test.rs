@@ -1,9 +1,15 @@
 mod test_db;
 
+use agdb::Comparison;
+use agdb::CountComparison;
 use agdb::DbElement;
+use agdb::DbError;
 use agdb::DbId;
 use agdb::DbKeyOrder;
+use agdb::DbKeyValue;
 use agdb::QueryBuilder;
+use agdb::QueryResult;
+use agdb::SearchQueryDirection;
 use test_db::TestDb;
 
 #[test]
@@ -653,3 +659,797 @@ fn search_depth_first_to_offset_limit() {
         &[-17, 5, -16],
     );
 }
+
+fn weighted_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(4).query(), 4);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 1, 2, 3])
+            .to([4, 2, 3, 4])
+            .values([
+                [("weight", 10).into()],
+                [("weight", 1).into()],
+                [("weight", 2.5).into()],
+                [("weight", 3_u64).into()],
+            ])
+            .query(),
+        4,
+    );
+    db
+}
+
+#[test]
+fn search_from_to_weighted() {
+    let db = weighted_db();
+    db.exec_ids(QueryBuilder::search().from(1).to(4).query(), &[1, -5, 4]);
+
+    let result = db.exec_result(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .query(),
+    );
+    assert_eq!(
+        result.ids(),
+        vec![
+            DbId(1),
+            DbId(-6),
+            DbId(2),
+            DbId(-7),
+            DbId(3),
+            DbId(-8),
+            DbId(4)
+        ]
+    );
+    assert_eq!(
+        result
+            .elements
+            .iter()
+            .map(|e| e.values.clone())
+            .collect::<Vec<Vec<DbKeyValue>>>(),
+        [0.0, 1.0, 1.0, 3.5, 3.5, 6.5, 6.5]
+            .into_iter()
+            .map(|cost| vec![("cost", cost).into()])
+            .collect::<Vec<Vec<DbKeyValue>>>()
+    );
+}
+
+#[test]
+fn search_from_to_weighted_nodes() {
+    let mut db = weighted_db();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("weight", 10).into()]])
+            .ids(3)
+            .query(),
+        1,
+    );
+
+    let result = db.exec_result(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .query(),
+    );
+    assert_eq!(result.ids(), vec![DbId(1), DbId(-5), DbId(4)]);
+    assert_eq!(result.elements[2].values, vec![("cost", 10.0).into()]);
+}
+
+#[test]
+fn search_from_to_weighted_conditions() {
+    let db = weighted_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .where_()
+            .node()
+            .query(),
+        &[1, 2, 3, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .where_()
+            .not_beyond()
+            .ids(2)
+            .query(),
+        &[1, -5, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::select()
+            .ids(
+                QueryBuilder::search()
+                    .from(1)
+                    .to(4)
+                    .weighted_by("weight")
+                    .where_()
+                    .edge()
+                    .query(),
+            )
+            .query(),
+        &[-6, -7, -8],
+    );
+}
+
+#[test]
+fn search_from_to_weighted_invalid_weight() {
+    let mut db = weighted_db();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("weight", -1).into()]])
+            .ids(-7)
+            .query(),
+        1,
+    );
+    db.exec_error(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .query(),
+        "Weight '-1' of key 'weight' must be a non-negative number (id: -7)",
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("weight", "heavy").into()]])
+            .ids(-7)
+            .query(),
+        1,
+    );
+    db.exec_error(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .query(),
+        "Weight 'heavy' of key 'weight' is not numeric (id: -7)",
+    );
+}
+
+#[test]
+fn search_weighted_without_destination() {
+    let db = weighted_db();
+    db.exec_error(
+        QueryBuilder::search()
+            .elements()
+            .weighted_by("weight")
+            .query(),
+        "Weighted search requires both origin and destination",
+    );
+}
+
+fn path_values(result: &QueryResult) -> Vec<Vec<DbKeyValue>> {
+    result.elements.iter().map(|e| e.values.clone()).collect()
+}
+
+#[test]
+fn search_shortest_paths() {
+    let db = weighted_db();
+    let result = db.exec_result(
+        QueryBuilder::search()
+            .shortest_paths(0)
+            .from(1)
+            .to(4)
+            .query(),
+    );
+    assert_eq!(result.result, 10);
+    assert_eq!(
+        result.ids(),
+        [1, -5, 4, 1, -6, 2, -7, 3, -8, 4]
+            .into_iter()
+            .map(DbId)
+            .collect::<Vec<DbId>>()
+    );
+    assert_eq!(
+        path_values(&result),
+        [0, 0, 0, 1, 1, 1, 1, 1, 1, 1]
+            .into_iter()
+            .map(|path: u64| vec![("path", path).into()])
+            .collect::<Vec<Vec<DbKeyValue>>>()
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .shortest_paths(1)
+            .from(1)
+            .to(4)
+            .query(),
+        &[1, -5, 4],
+    );
+}
+
+#[test]
+fn search_shortest_paths_weighted() {
+    let db = weighted_db();
+    let result = db.exec_result(
+        QueryBuilder::search()
+            .shortest_paths(1)
+            .from(1)
+            .to(4)
+            .weighted_by("weight")
+            .query(),
+    );
+    assert_eq!(
+        result.ids(),
+        [1, -6, 2, -7, 3, -8, 4]
+            .into_iter()
+            .map(DbId)
+            .collect::<Vec<DbId>>()
+    );
+    assert_eq!(
+        path_values(&result),
+        [0.0, 1.0, 1.0, 3.5, 3.5, 6.5, 6.5]
+            .into_iter()
+            .map(|cost| vec![("path", 0_u64).into(), ("cost", cost).into()])
+            .collect::<Vec<Vec<DbKeyValue>>>()
+    );
+}
+
+#[test]
+fn search_all_paths_max_depth() {
+    let db = weighted_db();
+    db.exec_ids(
+        QueryBuilder::search().all_paths(1).from(1).to(4).query(),
+        &[1, -5, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search().all_paths(3).from(1).to(4).query(),
+        &[1, -5, 4, 1, -6, 2, -7, 3, -8, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search().all_paths(1).from(4).to(1).query(),
+        &[],
+    );
+}
+
+#[test]
+fn search_all_paths_limit_offset() {
+    let db = weighted_db();
+    let result = db.exec_result(
+        QueryBuilder::search()
+            .all_paths(0)
+            .from(1)
+            .to(4)
+            .offset(1)
+            .query(),
+    );
+    assert_eq!(
+        result.ids(),
+        [1, -6, 2, -7, 3, -8, 4]
+            .into_iter()
+            .map(DbId)
+            .collect::<Vec<DbId>>()
+    );
+    assert_eq!(result.elements[0].values, vec![("path", 1_u64).into()]);
+    db.exec_ids(
+        QueryBuilder::search()
+            .all_paths(0)
+            .from(1)
+            .to(4)
+            .limit(1)
+            .query(),
+        &[1, -5, 4],
+    );
+}
+
+#[test]
+fn search_all_paths_conditions() {
+    let db = weighted_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .all_paths(0)
+            .from(1)
+            .to(4)
+            .where_()
+            .node()
+            .query(),
+        &[1, 4, 1, 2, 3, 4],
+    );
+}
+
+#[test]
+fn search_paths_invalid() {
+    let db = weighted_db();
+    db.exec_error(
+        QueryBuilder::search().all_paths(0).from(1).to(0).query(),
+        "Paths search requires both origin and destination",
+    );
+    db.exec_error(
+        QueryBuilder::search()
+            .shortest_paths(2)
+            .from(1)
+            .to(4)
+            .order_by(DbKeyOrder::Asc("weight".into()))
+            .query(),
+        "Paths search does not support ordering",
+    );
+}
+
+fn undirected_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(4).query(), 4);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 3, 3])
+            .to([2, 2, 4])
+            .query(),
+        3,
+    );
+    db
+}
+
+#[test]
+fn search_from_direction() {
+    let db = undirected_db();
+    db.exec_ids(QueryBuilder::search().from(1).query(), &[1, -5, 2]);
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(2)
+            .direction(SearchQueryDirection::Reverse)
+            .query(),
+        &[2, -6, -5, 3, 1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .direction(SearchQueryDirection::Both)
+            .query(),
+        &[1, -5, 2, -6, 3, -7, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .depth_first()
+            .from(2)
+            .direction(SearchQueryDirection::Both)
+            .query(),
+        &[2, -6, 3, -7, 4, -5, 1],
+    );
+}
+
+#[test]
+fn search_to_direction() {
+    let db = undirected_db();
+    db.exec_ids(QueryBuilder::search().to(2).query(), &[2, -6, -5, 3, 1]);
+    db.exec_ids(
+        QueryBuilder::search()
+            .to(3)
+            .direction(SearchQueryDirection::Reverse)
+            .query(),
+        &[3, -7, -6, 4, 2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .to(4)
+            .direction(SearchQueryDirection::Both)
+            .query(),
+        &[4, -7, 3, -6, 2, -5, 1],
+    );
+}
+
+#[test]
+fn search_direction_both_distance() {
+    let db = undirected_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .direction(SearchQueryDirection::Both)
+            .where_()
+            .distance(CountComparison::LessThanOrEqual(4))
+            .query(),
+        &[1, -5, 2, -6, 3],
+    );
+}
+
+#[test]
+fn search_from_to_direction() {
+    let db = undirected_db();
+    db.exec_ids(QueryBuilder::search().from(1).to(4).query(), &[]);
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(2)
+            .to(3)
+            .direction(SearchQueryDirection::Reverse)
+            .query(),
+        &[2, -6, 3],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from(1)
+            .to(4)
+            .direction(SearchQueryDirection::Both)
+            .query(),
+        &[1, -5, 2, -6, 3, -7, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .all_paths(0)
+            .from(4)
+            .to(1)
+            .direction(SearchQueryDirection::Both)
+            .where_()
+            .node()
+            .query(),
+        &[4, 3, 2, 1],
+    );
+}
+
+fn dependency_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().nodes().count(5).query(), 5);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from([1, 1, 2, 3, 2])
+            .to([2, 3, 4, 4, 3])
+            .values_uniform([("k", 1).into()])
+            .query(),
+        5,
+    );
+    db
+}
+
+#[test]
+fn search_topological() {
+    let db = dependency_db();
+
+    db.exec_ids(
+        QueryBuilder::search().topological().from(1).query(),
+        &[1, 2, 3, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search().topological().from(3).query(),
+        &[3, 4],
+    );
+    db.exec_ids(
+        QueryBuilder::search().topological().to(4).query(),
+        &[4, 3, 2, 1],
+    );
+    db.exec_ids(QueryBuilder::search().topological().from(5).query(), &[5]);
+}
+
+#[test]
+fn search_topological_conditions() {
+    let db = dependency_db();
+
+    db.exec_ids(
+        QueryBuilder::search()
+            .topological()
+            .from(1)
+            .offset(1)
+            .limit(2)
+            .query(),
+        &[2, 3],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .topological()
+            .from(1)
+            .where_()
+            .distance(CountComparison::GreaterThan(2))
+            .query(),
+        &[4],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .topological()
+            .from(1)
+            .where_()
+            .not_beyond()
+            .ids([2, 3])
+            .query(),
+        &[1, 2, 3],
+    );
+}
+
+#[test]
+fn search_topological_cycle() {
+    let mut db = dependency_db();
+    db.exec_mut(QueryBuilder::insert().edges().from(4).to(2).query(), 1);
+
+    db.exec_error(
+        QueryBuilder::search().topological().from(1).query(),
+        "Cycle detected: 3 -> -9 -> 4 -> -11 -> 2 -> -10 -> 3",
+    );
+    db.exec_ids(QueryBuilder::search().topological().from(5).query(), &[5]);
+}
+
+#[test]
+fn search_topological_origin_and_destination() {
+    let db = dependency_db();
+
+    db.exec_error(
+        QueryBuilder::search().topological().from(1).to(4).query(),
+        "Topological search requires either origin or destination",
+    );
+}
+
+fn log_db() -> TestDb {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("time").ordered().query(), 0);
+    db.exec_mut(QueryBuilder::insert().nodes().aliases("log").query(), 1);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("time", 5).into()],
+                [("time", 3).into()],
+                [("time", 1).into()],
+                [("time", 4).into()],
+                [("time", 2).into()],
+            ])
+            .query(),
+        5,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("log")
+            .to([2, 3, 4, 5, 6])
+            .query(),
+        5,
+    );
+    db
+}
+
+#[test]
+fn search_after_cursor() {
+    let db = log_db();
+    let page1 = db.exec_result(
+        QueryBuilder::search()
+            .from("log")
+            .limit(4)
+            .where_()
+            .node()
+            .query(),
+    );
+    assert_eq!(page1.ids(), vec![DbId(1), DbId(6), DbId(5), DbId(4)]);
+
+    let page2 = db.exec_result(
+        QueryBuilder::search()
+            .after(page1.cursor.unwrap())
+            .from("log")
+            .limit(4)
+            .where_()
+            .node()
+            .query(),
+    );
+    assert_eq!(page2.ids(), vec![DbId(3), DbId(2)]);
+    assert_eq!(page2.cursor, None);
+}
+
+#[test]
+fn search_after_cursor_order_by() {
+    let db = log_db();
+    let mut ids = vec![];
+    let mut cursor = None;
+
+    loop {
+        let mut query = QueryBuilder::search()
+            .from("log")
+            .order_by(DbKeyOrder::Desc("time".into()))
+            .limit(2)
+            .where_()
+            .keys("time")
+            .query();
+        query.after = cursor;
+        let result = db.exec_result(query);
+        ids.extend(result.ids());
+        cursor = result.cursor;
+
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(ids, vec![DbId(2), DbId(5), DbId(3), DbId(6), DbId(4)]);
+}
+
+#[test]
+fn search_after_cursor_index() {
+    let db = log_db();
+    let page1 = db.exec_result(
+        QueryBuilder::search()
+            .index("time")
+            .value(Comparison::GreaterThan(1.into()))
+            .limit(2)
+            .query(),
+    );
+    assert_eq!(page1.ids(), vec![DbId(6), DbId(3)]);
+
+    let page2 = db.exec_result(
+        QueryBuilder::search()
+            .after(page1.cursor.unwrap())
+            .index("time")
+            .value(Comparison::GreaterThan(1.into()))
+            .limit(2)
+            .query(),
+    );
+    assert_eq!(page2.ids(), vec![DbId(5), DbId(2)]);
+    assert!(page2.cursor.is_some());
+}
+
+#[test]
+fn search_after_cursor_index_modified() {
+    let mut db = log_db();
+    let query = || {
+        QueryBuilder::search()
+            .index("time")
+            .value(Comparison::GreaterThan(1.into()))
+            .limit(2)
+    };
+    let cursor = db.exec_result(query().query()).cursor.unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("time", 0).into()], [("time", 3).into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut(QueryBuilder::remove().ids(3).query(), 1);
+    db.exec_mut(QueryBuilder::remove().ids(5).query(), 1);
+
+    let mut query = query().query();
+    query.after = Some(cursor);
+    db.exec_ids(query, &[13, 2]);
+}
+
+#[test]
+fn search_after_cursor_order_by_modified() {
+    let mut db = log_db();
+    let query = || {
+        QueryBuilder::search()
+            .from("log")
+            .order_by(DbKeyOrder::Desc("time".into()))
+            .limit(2)
+            .where_()
+            .keys("time")
+    };
+    let page1 = db.exec_result(query().query());
+    assert_eq!(page1.ids(), vec![DbId(2), DbId(5)]);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("time", 10).into()], [("time", 1).into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("log")
+            .to([12, 13])
+            .query(),
+        2,
+    );
+    db.exec_mut(QueryBuilder::remove().ids(5).query(), 1);
+
+    let mut query = query().query();
+    query.after = page1.cursor;
+    db.exec_ids(query, &[3, 6]);
+}
+
+#[test]
+fn search_after_cursor_order_by_ties() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("k", 2).into()],
+                [("k", 1).into()],
+                [("k", 1).into()],
+                [("k", 1).into()],
+            ])
+            .query(),
+        4,
+    );
+    let query = || {
+        QueryBuilder::search()
+            .elements()
+            .order_by(DbKeyOrder::Asc("k".into()))
+            .limit(2)
+    };
+    let page1 = db.exec_result(query().query());
+    assert_eq!(page1.ids(), vec![DbId(2), DbId(3)]);
+
+    let mut page2 = query().query();
+    page2.after = page1.cursor;
+    db.exec_ids(page2, &[4, 1]);
+}
+
+#[test]
+fn search_after_cursor_traversal_modified() {
+    let mut db = log_db();
+    let cursor = db
+        .exec_result(QueryBuilder::search().from("log").limit(2).query())
+        .cursor
+        .unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(1).query(), 1);
+    db.exec_ids(
+        QueryBuilder::search()
+            .after(cursor.clone())
+            .from("log")
+            .limit(2)
+            .query(),
+        &[-10, -9],
+    );
+
+    db.exec_mut(QueryBuilder::remove().ids(-11).query(), 1);
+    db.exec_error(
+        QueryBuilder::search()
+            .after(cursor)
+            .from("log")
+            .limit(2)
+            .query(),
+        "Cursor is no longer valid as its last element (-11) is no longer found",
+    );
+}
+
+#[test]
+fn search_after_cursor_read_only_transaction() {
+    let mut db = log_db();
+    let cursor = db
+        .exec_result(QueryBuilder::search().from("log").limit(2).query())
+        .cursor
+        .unwrap();
+    db.transaction_mut(|t| -> Result<(), DbError> {
+        t.exec(QueryBuilder::search().from("log").query())?;
+        Ok(())
+    });
+    db.exec_ids(
+        QueryBuilder::search()
+            .after(cursor)
+            .from("log")
+            .limit(2)
+            .query(),
+        &[-10, -9],
+    );
+}
+
+#[test]
+fn search_after_cursor_different_query() {
+    let db = log_db();
+    let cursor = db
+        .exec_result(QueryBuilder::search().from("log").limit(2).query())
+        .cursor
+        .unwrap();
+    db.exec_error(
+        QueryBuilder::search()
+            .after(cursor.clone())
+            .from(2)
+            .limit(2)
+            .query(),
+        &format!("Cursor '{cursor}' was created by a different query"),
+    );
+}
+
+#[test]
+fn search_after_invalid_cursor() {
+    let db = log_db();
+    db.exec_error(
+        QueryBuilder::search().after("xyz").from("log").query(),
+        "Invalid cursor 'xyz'",
+    );
+}
+
+#[test]
+fn search_after_cursor_path_search() {
+    let db = log_db();
+    db.exec_error(
+        QueryBuilder::search()
+            .after("1-2-3")
+            .shortest_paths(2)
+            .from("log")
+            .to(2)
+            .query(),
+        "Cursor is not supported by path searches",
+    );
+}
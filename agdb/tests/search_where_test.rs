@@ -1,12 +1,15 @@
 mod test_db;
 
+use agdb::AdjacentQuantifier;
 use agdb::Comparison;
 use agdb::CountComparison;
 use agdb::DbKeyOrder;
 use agdb::DbType;
+use agdb::DbValue;
 use agdb::KeyValueComparison;
 use agdb::QueryBuilder;
 use agdb::QueryConditionData;
+use agdb::SearchQueryDirection;
 use test_db::TestDb;
 
 #[track_caller]
@@ -524,6 +527,194 @@ fn search_from_where_key_value_contains() {
     );
 }
 
+#[test]
+fn search_where_key_value_numeric_types() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                [("amount", 5).into()],
+                [("amount", 3_u64).into()],
+                [("amount", 4.5).into()],
+                [("amount", -1).into()],
+                [("amount", f64::NAN).into()],
+            ])
+            .query(),
+        5,
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("amount")
+            .value(Comparison::GreaterThan(3_u64.into()))
+            .query(),
+        &[1, 3],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("amount")
+            .value(5.0)
+            .query(),
+        &[1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("amount")
+            .value(Comparison::NotEqual(3.into()))
+            .query(),
+        &[1, 3, 4, 5],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .order_by(DbKeyOrder::Asc("amount".into()))
+            .query(),
+        &[4, 2, 3, 1, 5],
+    );
+}
+
+#[test]
+fn search_where_key_key() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("spent", 120).into(), ("budget", 100).into()],
+                vec![("spent", 80).into(), ("budget", 100.0).into()],
+                vec![("spent", 100_u64).into(), ("budget", 100).into()],
+                vec![("spent", 150).into()],
+                vec![
+                    ("name", "Alice").into(),
+                    ("alias", "alice").into(),
+                    ("limits", DbValue::Map(vec![("budget", 90).into()])).into(),
+                ],
+            ])
+            .query(),
+        5,
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("spent")
+            .compare_key(Comparison::GreaterThan("budget".into()))
+            .query(),
+        &[1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("spent")
+            .compare_key(Comparison::LessThanOrEqual("budget".into()))
+            .query(),
+        &[2, 3],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .not()
+            .key("spent")
+            .compare_key("budget")
+            .query(),
+        &[1, 2, 4, 5],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("name")
+            .compare_key(Comparison::EqualIgnoreCase("alias".into()))
+            .query(),
+        &[5],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("limits.budget")
+            .compare_key(Comparison::LessThan("spent".into()))
+            .query(),
+        &[],
+    );
+}
+
+#[test]
+fn search_where_key_value_nested_path() {
+    let mut db = TestDb::new();
+    let address = |city: &str, zip: u64| {
+        DbValue::Map(vec![
+            ("city", city).into(),
+            ("zip", zip).into(),
+            ("tags", DbValue::List(vec!["home".into(), city.into()])).into(),
+        ])
+    };
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("address", address("Prague", 11000)).into()],
+                vec![("address", address("Brno", 60200)).into()],
+                vec![("address", "Prague").into()],
+            ])
+            .query(),
+        3,
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("address.city")
+            .value("Prague")
+            .query(),
+        &[1],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("address.zip")
+            .value(Comparison::GreaterThan(20000_u64.into()))
+            .query(),
+        &[2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("address.tags.1")
+            .value("Brno")
+            .query(),
+        &[2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .where_()
+            .key("address.tags")
+            .value(Comparison::Contains("home".into()))
+            .query(),
+        &[1, 2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .elements()
+            .order_by(DbKeyOrder::Desc("address.zip".into()))
+            .where_()
+            .keys("address")
+            .query(),
+        &[2, 1, 3],
+    );
+}
+
 #[test]
 fn search_path_with_distance() {
     let db = create_db();
@@ -644,6 +835,266 @@ fn search_where_ends_with() {
     );
 }
 
+#[test]
+fn search_where_ignore_case() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .key("name")
+            .value(Comparison::EqualIgnoreCase("BOOK".into()))
+            .query(),
+        &[7],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .key("content")
+            .value(Comparison::ContainsIgnoreCase("DOLOR".into()))
+            .query(),
+        &[7],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .key("content")
+            .value(Comparison::StartsWithIgnoreCase("APPLES".into()))
+            .query(),
+        &[8],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .key("name")
+            .value(Comparison::EndsWithIgnoreCase("LIST".into()))
+            .query(),
+        &[8],
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn search_where_matches() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .key("username")
+            .value(Comparison::Matches("^user_[1-3]$".into()))
+            .query(),
+        &[14, 13, 12],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .key("content")
+            .value(Comparison::Matches("(?i)^lorem\\b".into()))
+            .or()
+            .where_()
+            .key("content")
+            .value(Comparison::Matches("^or".into()))
+            .end_where()
+            .query(),
+        &[8, 7],
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn search_where_matches_invalid_pattern() {
+    let db = create_db();
+    db.exec_error(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .key("username")
+            .value(Comparison::Matches("user_(".into()))
+            .query(),
+        "Invalid pattern 'user_(': unclosed group",
+    );
+}
+
+#[cfg(not(feature = "regex"))]
+#[test]
+fn search_where_matches_without_regex_feature() {
+    let db = create_db();
+    db.exec_error(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .key("username")
+            .value(Comparison::Matches("^user_".into()))
+            .query(),
+        "Invalid pattern '^user_': regular expressions require the 'regex' feature",
+    );
+}
+
+#[test]
+fn search_where_adjacent_any() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .key("type")
+            .value(Comparison::Equal("owns".into()))
+            .end_where()
+            .query(),
+        &[15, 14],
+    );
+}
+
+#[test]
+fn search_where_adjacent_all() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .node()
+            .and()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::All)
+            .key("type")
+            .value(Comparison::Equal("owns".into()))
+            .end_where()
+            .query(),
+        &[16, 15, 14, 13, 8, 6, 7],
+    );
+}
+
+#[test]
+fn search_where_adjacent_count() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("root")
+            .where_()
+            .adjacent(
+                SearchQueryDirection::Both,
+                AdjacentQuantifier::Count(CountComparison::GreaterThan(2)),
+            )
+            .edge()
+            .end_where()
+            .query(),
+        &[3, 2],
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("docs")
+            .where_()
+            .adjacent(
+                SearchQueryDirection::Reverse,
+                AdjacentQuantifier::Count(CountComparison::Equal(1)),
+            )
+            .adjacent(SearchQueryDirection::Reverse, AdjacentQuantifier::Any)
+            .keys("username")
+            .end_where()
+            .end_where()
+            .query(),
+        &[8, 7, 6],
+    );
+}
+
+#[test]
+fn search_where_adjacent_edge() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .edge()
+            .and()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .key("name")
+            .value(Comparison::Equal("book".into()))
+            .end_where()
+            .or()
+            .adjacent(SearchQueryDirection::Reverse, AdjacentQuantifier::Any)
+            .key("id")
+            .value(Comparison::Equal(3.into()))
+            .end_where()
+            .query(),
+        &[-23, -22],
+    );
+}
+
+#[test]
+fn search_where_adjacent_not_beyond() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .not_beyond()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .key("type")
+            .value(Comparison::Equal("owns".into()))
+            .end_where()
+            .query(),
+        &[2, -21, -20, -19, -18, -17, 16, 15, 14, 13, 12, -22, 7],
+    );
+}
+
+#[test]
+fn search_where_adjacent_beyond() {
+    let db = create_db();
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .beyond()
+            .where_()
+            .node()
+            .or()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .key("active")
+            .value(1)
+            .end_where()
+            .end_where()
+            .and()
+            .keys("id")
+            .query(),
+        &[15, 14, 12],
+    );
+}
+
+#[test]
+fn search_where_adjacent_nested_beyond() {
+    let db = create_db();
+    let expected = [2, -21, -20, -19, -18, -17, 15, 14, 12, -24, -23, -22];
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .beyond()
+            .key("active")
+            .value(1)
+            .end_where()
+            .query(),
+        &expected,
+    );
+    db.exec_ids(
+        QueryBuilder::search()
+            .from("users")
+            .where_()
+            .adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Any)
+            .node()
+            .or()
+            .edge()
+            .end_where()
+            .query(),
+        &expected,
+    );
+}
+
 #[test]
 fn search_neighbor() {
     let neighbor_query = QueryBuilder::search()
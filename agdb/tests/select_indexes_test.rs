@@ -41,3 +41,26 @@ fn select_indexes() {
         }]
     );
 }
+
+#[test]
+fn select_composite_indexes() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index(["tenant", "email"]).query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([
+                vec![("tenant", "t1").into(), ("email", "a@b.c").into()],
+                vec![("tenant", "t1").into()],
+            ])
+            .query(),
+        2,
+    );
+
+    let result = db.exec_result(QueryBuilder::select().indexes().query());
+    assert_eq!(result.result, 1);
+    assert_eq!(
+        result.elements[0].values,
+        vec![(vec!["tenant".to_string(), "email".to_string()], 1_u64).into()]
+    );
+}
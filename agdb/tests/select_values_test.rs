@@ -2,6 +2,7 @@ mod test_db;
 
 use agdb::DbElement;
 use agdb::DbId;
+use agdb::DbValue;
 use agdb::QueryBuilder;
 use test_db::TestDb;
 
@@ -182,3 +183,44 @@ fn select_values_search_alt() {
         ],
     );
 }
+
+#[test]
+fn select_values_nested_path() {
+    let mut db = TestDb::new();
+    let address = DbValue::Map(vec![
+        ("city", "Prague").into(),
+        ("zip", 11000).into(),
+        ("tags", DbValue::List(vec!["old".into(), "big".into()])).into(),
+    ]);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases("alias1")
+            .values([[("name", "John").into(), ("address", address).into()]])
+            .query(),
+        1,
+    );
+    db.exec_elements(
+        QueryBuilder::select()
+            .values(["address.city", "name", "address.tags.1"])
+            .ids("alias1")
+            .query(),
+        &[DbElement {
+            id: DbId(1),
+            from: DbId::default(),
+            to: DbId::default(),
+            values: vec![
+                ("address.city", "Prague").into(),
+                ("name", "John").into(),
+                ("address.tags.1", "big").into(),
+            ],
+        }],
+    );
+    db.exec_error(
+        QueryBuilder::select()
+            .values(["address.country"])
+            .ids("alias1")
+            .query(),
+        "Key 'address.country' for id '1' not found",
+    );
+}
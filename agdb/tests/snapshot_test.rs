@@ -0,0 +1,313 @@
+mod test_db;
+
+use agdb::Db;
+use agdb::DbElement;
+use agdb::DbFile;
+use agdb::DbId;
+use agdb::DbMemory;
+use agdb::QueryBuilder;
+use std::sync::Arc;
+use test_db::TestFile;
+
+fn insert_users(db: &mut DbMemory) {
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["alice", "bob"])
+            .values([[("age", 30).into()], [("age", 40).into()]])
+            .query(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn snapshot_is_isolated_from_writes() {
+    let mut db = DbMemory::new("test").unwrap();
+    insert_users(&mut db);
+    let snapshot = db.snapshot().unwrap();
+
+    db.exec_mut(
+        QueryBuilder::insert()
+            .values([[("age", 31).into()]])
+            .ids("alice")
+            .query(),
+    )
+    .unwrap();
+    db.exec_mut(QueryBuilder::remove().ids("bob").query())
+        .unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().aliases("carol").query())
+        .unwrap();
+
+    assert_eq!(
+        snapshot
+            .exec(QueryBuilder::select().ids(["alice", "bob"]).query())
+            .unwrap()
+            .elements,
+        vec![
+            DbElement {
+                id: DbId(1),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![("age", 30).into()],
+            },
+            DbElement {
+                id: DbId(2),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![("age", 40).into()],
+            }
+        ]
+    );
+    assert!(
+        snapshot
+            .exec(QueryBuilder::select().ids("carol").query())
+            .is_err()
+    );
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids("alice").query())
+            .unwrap()
+            .elements[0]
+            .values,
+        vec![("age", 31).into()]
+    );
+    assert!(db.exec(QueryBuilder::select().ids("bob").query()).is_err());
+}
+
+#[test]
+fn multiple_snapshots() {
+    let mut db = DbMemory::new("test").unwrap();
+    let count = QueryBuilder::select().node_count().query();
+    db.exec_mut(QueryBuilder::insert().nodes().count(1).query())
+        .unwrap();
+    let first = db.snapshot().unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(1).query())
+        .unwrap();
+    let second = db.snapshot().unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(1).query())
+        .unwrap();
+
+    assert_eq!(first.exec(&count).unwrap().result, 1);
+    assert_eq!(second.exec(&count).unwrap().result, 2);
+    assert_eq!(db.exec(&count).unwrap().result, 3);
+}
+
+#[test]
+fn snapshot_transaction() {
+    let mut db = DbMemory::new("test").unwrap();
+    insert_users(&mut db);
+    let snapshot = db.snapshot().unwrap();
+
+    let ids = snapshot
+        .transaction(|t| -> Result<Vec<DbId>, agdb::DbError> {
+            let alice = t.exec(QueryBuilder::select().ids("alice").query())?;
+            let bob = t.exec(QueryBuilder::select().ids("bob").query())?;
+            Ok(vec![alice.ids()[0], bob.ids()[0]])
+        })
+        .unwrap();
+
+    assert_eq!(ids, vec![DbId(1), DbId(2)]);
+}
+
+#[test]
+fn snapshot_survives_optimize_storage() {
+    let test_file = TestFile::new();
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .count(100)
+            .values_uniform([("text", "x".repeat(100)).into()])
+            .query(),
+    )
+    .unwrap();
+    let snapshot = db.snapshot().unwrap();
+    let size = db.size();
+
+    db.exec_mut(
+        QueryBuilder::remove()
+            .ids((1..100).collect::<Vec<i64>>())
+            .query(),
+    )
+    .unwrap();
+    db.shrink_to_fit().unwrap();
+
+    assert!(db.size() < size);
+    assert_eq!(snapshot.size(), size);
+    assert_eq!(
+        snapshot
+            .exec(QueryBuilder::select().ids(50).query())
+            .unwrap()
+            .elements[0]
+            .values,
+        vec![("text", "x".repeat(100)).into()]
+    );
+    assert_eq!(
+        db.exec(QueryBuilder::select().node_count().query())
+            .unwrap()
+            .result,
+        1
+    );
+}
+
+#[test]
+fn snapshot_outlives_db() {
+    let mut db = DbMemory::new("test").unwrap();
+    insert_users(&mut db);
+    let snapshot = db.snapshot().unwrap();
+    drop(db);
+
+    assert_eq!(
+        snapshot
+            .exec(QueryBuilder::select().ids("bob").query())
+            .unwrap()
+            .ids(),
+        vec![DbId(2)]
+    );
+}
+
+#[test]
+fn writes_after_snapshot_dropped() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = Db::new(test_file.file_name()).unwrap();
+        db.exec_mut(QueryBuilder::insert().nodes().aliases("a").query())
+            .unwrap();
+        let snapshot = db.snapshot().unwrap();
+        db.exec_mut(QueryBuilder::insert().nodes().aliases("b").query())
+            .unwrap();
+        drop(snapshot);
+        db.exec_mut(QueryBuilder::insert().nodes().aliases("c").query())
+            .unwrap();
+    }
+
+    let db = Db::new(test_file.file_name()).unwrap();
+
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids(["a", "b", "c"]).query())
+            .unwrap()
+            .ids(),
+        vec![DbId(1), DbId(2), DbId(3)]
+    );
+    assert!(db.verify().is_ok());
+}
+
+#[test]
+fn concurrent_reads_during_writes() {
+    let mut db = DbMemory::new("test").unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases("root")
+            .values([[("count", 0).into()]])
+            .query(),
+    )
+    .unwrap();
+    let snapshot = Arc::new(db.snapshot().unwrap());
+
+    let readers = (0..4)
+        .map(|_| {
+            let snapshot = snapshot.clone();
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    let result = snapshot
+                        .exec(QueryBuilder::select().ids("root").query())
+                        .unwrap();
+                    assert_eq!(result.elements[0].values, vec![("count", 0).into()]);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for i in 1..100 {
+        db.exec_mut(
+            QueryBuilder::insert()
+                .values([[("count", i).into()]])
+                .ids("root")
+                .query(),
+        )
+        .unwrap();
+        db.exec_mut(QueryBuilder::insert().nodes().count(10).query())
+            .unwrap();
+    }
+
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    assert_eq!(
+        db.exec(QueryBuilder::select().ids("root").query())
+            .unwrap()
+            .elements[0]
+            .values,
+        vec![("count", 99).into()]
+    );
+}
+
+#[test]
+fn snapshot_per_read() {
+    let db = std::sync::RwLock::new(DbMemory::new("test").unwrap());
+    db.write()
+        .unwrap()
+        .exec_mut(QueryBuilder::insert().nodes().count(1).query())
+        .unwrap();
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    let snapshot = db.write().unwrap().snapshot().unwrap();
+                    let count = snapshot
+                        .exec(QueryBuilder::select().node_count().query())
+                        .unwrap()
+                        .result;
+                    assert!((1..=100).contains(&count));
+                }
+            });
+        }
+
+        for _ in 1..100 {
+            db.write()
+                .unwrap()
+                .exec_mut(QueryBuilder::insert().nodes().count(1).query())
+                .unwrap();
+        }
+    });
+
+    assert_eq!(
+        db.read()
+            .unwrap()
+            .exec(QueryBuilder::select().node_count().query())
+            .unwrap()
+            .result,
+        100
+    );
+}
+
+#[test]
+fn snapshot_of_many_records() {
+    let mut db = DbMemory::new("test").unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(3000).query())
+        .unwrap();
+    let snapshot = db.snapshot().unwrap();
+
+    db.exec_mut(
+        QueryBuilder::remove()
+            .ids((1..=3000).collect::<Vec<i64>>())
+            .query(),
+    )
+    .unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(100).query())
+        .unwrap();
+
+    let count = QueryBuilder::select().node_count().query();
+    assert_eq!(snapshot.exec(&count).unwrap().result, 3000);
+    assert_eq!(db.exec(&count).unwrap().result, 100);
+    assert_eq!(
+        snapshot
+            .exec(QueryBuilder::select().ids(3000).query())
+            .unwrap()
+            .ids(),
+        vec![DbId(3000)]
+    );
+}
@@ -0,0 +1,231 @@
+mod test_db;
+
+use agdb::DbElement;
+use agdb::DbId;
+use agdb::DbValue;
+use agdb::KeyValueUpdate;
+use agdb::QueryBuilder;
+use test_db::TestDb;
+
+#[test]
+fn update_increment() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("visits", 1).into()], [("visits", 10_u64).into()]])
+            .query(),
+        2,
+    );
+    let result = db.exec_mut_result(
+        QueryBuilder::insert()
+            .update([
+                KeyValueUpdate::increment("visits", 2),
+                KeyValueUpdate::increment("score", 1.5),
+            ])
+            .ids([1, 2])
+            .query(),
+    );
+    assert_eq!(result.result, 4);
+    assert_eq!(
+        result.elements,
+        vec![
+            DbElement {
+                id: DbId(1),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![("visits", 3).into(), ("score", 1.5).into()],
+            },
+            DbElement {
+                id: DbId(2),
+                from: DbId(0),
+                to: DbId(0),
+                values: vec![("visits", 12_u64).into(), ("score", 1.5).into()],
+            },
+        ]
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::increment("visits", -5))
+            .ids(2)
+            .query(),
+        1,
+    );
+    db.exec_elements(
+        QueryBuilder::select().values("visits").ids(2).query(),
+        &[DbElement {
+            id: DbId(2),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![("visits", 7_u64).into()],
+        }],
+    );
+}
+
+#[test]
+fn update_increment_errors() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("visits", i64::MAX).into(), ("name", "a").into()]])
+            .query(),
+        1,
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::increment("visits", 1))
+            .ids(1)
+            .query(),
+        "Increment of 'visits' by '1' overflows",
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::increment("name", 1))
+            .ids(1)
+            .query(),
+        "Cannot increment 'name' (string) with '1' (i64)",
+    );
+}
+
+#[test]
+fn update_append_and_remove_item() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("tags", vec!["a", "b", "a"]).into(), ("name", "x").into()]])
+            .query(),
+        1,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .update([
+                KeyValueUpdate::append("tags", "c"),
+                KeyValueUpdate::remove_item("tags", "a"),
+                KeyValueUpdate::remove_item("tags", "z"),
+                KeyValueUpdate::append("name", "y"),
+                KeyValueUpdate::append("ids", 1),
+            ])
+            .ids(1)
+            .query(),
+        4,
+    );
+    db.exec_elements(
+        QueryBuilder::select()
+            .values(["tags", "name", "ids"])
+            .ids(1)
+            .query(),
+        &[DbElement {
+            id: DbId(1),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![
+                ("tags", vec!["b", "c"]).into(),
+                ("name", "xy").into(),
+                ("ids", vec![1]).into(),
+            ],
+        }],
+    );
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::remove_item("name", "x"))
+            .ids(1)
+            .query(),
+        "Cannot remove item 'name' (string) with 'x' (string)",
+    );
+}
+
+#[test]
+fn update_set_if_absent_min_max() {
+    let mut db = TestDb::new();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("low", 5).into(), ("high", 5).into()]])
+            .query(),
+        1,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .update([
+                KeyValueUpdate::set_if_absent("low", 0),
+                KeyValueUpdate::set_if_absent("created", "now"),
+                KeyValueUpdate::min("low", 3_u64),
+                KeyValueUpdate::min("low", 4),
+                KeyValueUpdate::max("high", 2),
+                KeyValueUpdate::max("high", 7.5),
+            ])
+            .ids(1)
+            .query(),
+        3,
+    );
+    db.exec_elements(
+        QueryBuilder::select()
+            .values(["low", "high", "created"])
+            .ids(1)
+            .query(),
+        &[DbElement {
+            id: DbId(1),
+            from: DbId(0),
+            to: DbId(0),
+            values: vec![
+                ("low", 3_u64).into(),
+                ("high", 7.5).into(),
+                ("created", "now").into(),
+            ],
+        }],
+    );
+}
+
+#[test]
+fn update_search_keeps_index() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("count").query(), 0);
+    db.exec_mut(QueryBuilder::insert().nodes().aliases("root").query(), 1);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .values([[("count", 1).into()], [("count", 2).into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("root")
+            .to([2, 3])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::increment("count", 10))
+            .search()
+            .from("root")
+            .where_()
+            .node()
+            .and()
+            .key("count")
+            .value(agdb::Comparison::Equal(1.into()))
+            .query(),
+        1,
+    );
+    db.exec_ids(
+        QueryBuilder::search().index("count").value(11).query(),
+        &[2],
+    );
+    db.exec_ids(QueryBuilder::search().index("count").value(1).query(), &[]);
+}
+
+#[test]
+fn update_missing_id() {
+    let mut db = TestDb::new();
+    db.exec_mut_error(
+        QueryBuilder::insert()
+            .update(KeyValueUpdate::set_if_absent("key", DbValue::I64(1)))
+            .ids("missing")
+            .query(),
+        "Alias 'missing' not found",
+    );
+}
@@ -0,0 +1,101 @@
+mod test_db;
+
+use agdb::Db;
+use agdb::DbFile;
+use agdb::DbMemory;
+use agdb::QueryBuilder;
+use test_db::TestDb;
+use test_db::TestFile;
+
+#[test]
+fn verify_empty() {
+    let db = TestDb::new();
+    assert!(db.db.verify().is_ok());
+}
+
+#[test]
+fn verify_after_inserts_and_removals() {
+    let mut db = TestDb::new();
+    db.exec_mut(QueryBuilder::insert().index("name").query(), 0);
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["users", "docs"])
+            .values([[("name", "users").into()], [("name", "docs").into()]])
+            .query(),
+        2,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .count(10)
+            .values_uniform([("name", "user").into()])
+            .query(),
+        10,
+    );
+    db.exec_mut(
+        QueryBuilder::insert()
+            .edges()
+            .from("users")
+            .to((3..13).collect::<Vec<i64>>())
+            .values_uniform([("name", "owns").into()])
+            .query(),
+        10,
+    );
+    db.exec_mut_result(QueryBuilder::remove().ids([5, 7, -20]).query());
+    db.exec_mut_result(QueryBuilder::remove().aliases("docs").query());
+
+    assert_eq!(db.db.verify().issues, vec![]);
+}
+
+#[test]
+fn verify_storage_variants() {
+    let test_file = TestFile::new();
+    let mut db = DbFile::new(test_file.file_name()).unwrap();
+    db.exec_mut(
+        QueryBuilder::insert()
+            .nodes()
+            .aliases("root")
+            .values([[("key", 1).into()]])
+            .query(),
+    )
+    .unwrap();
+    assert!(db.verify().is_ok());
+
+    let mut db = DbMemory::new("memory").unwrap();
+    db.exec_mut(QueryBuilder::insert().nodes().count(2).query())
+        .unwrap();
+    db.exec_mut(QueryBuilder::insert().edges().from(1).to(2).query())
+        .unwrap();
+    assert!(db.verify().is_ok());
+}
+
+#[test]
+fn verify_reopened() {
+    let test_file = TestFile::new();
+
+    {
+        let mut db = Db::new(test_file.file_name()).unwrap();
+        db.exec_mut(
+            QueryBuilder::insert()
+                .nodes()
+                .aliases(["a", "b"])
+                .values([[("key", 1).into()], [("key", 2).into()]])
+                .query(),
+        )
+        .unwrap();
+        db.exec_mut(QueryBuilder::insert().edges().from("a").to("b").query())
+            .unwrap();
+    }
+
+    let db = Db::new(test_file.file_name()).unwrap();
+    assert!(db.verify().is_ok());
+}
+
+#[test]
+fn verify_converted_db() {
+    let test_file = TestFile::new();
+    std::fs::copy("tests/test_db_prior_0_14_0.agdb", test_file.file_name()).unwrap();
+    let db = Db::new(test_file.file_name()).unwrap();
+    assert!(db.verify().is_ok());
+}
@@ -1,20 +1,35 @@
 use std::sync::atomic::AtomicU16;
 use std::time::Duration;
 
+use agdb::AdjacentCondition;
+use agdb::AdjacentQuantifier;
+use agdb::AggregateFunction;
+use agdb::AggregateFunctions;
+use agdb::AggregateQuery;
+use agdb::AnalyticsAlgorithm;
 use agdb::Comparison;
 use agdb::CountComparison;
 use agdb::DbElement;
 use agdb::DbF64;
 use agdb::DbId;
+use agdb::DbIssue;
+use agdb::DbIssueType;
 use agdb::DbKeyOrder;
 use agdb::DbKeyOrders;
 use agdb::DbKeyValue;
 use agdb::DbValue;
 use agdb::DbValues;
+use agdb::DbVerifyReport;
+use agdb::Expect;
+use agdb::ExpectIds;
+use agdb::ExpectQuery;
 use agdb::Insert;
 use agdb::InsertAliases;
 use agdb::InsertAliasesIds;
 use agdb::InsertAliasesQuery;
+use agdb::InsertAnalytics;
+use agdb::InsertAnalyticsIds;
+use agdb::InsertAnalyticsQuery;
 use agdb::InsertEdges;
 use agdb::InsertEdgesEach;
 use agdb::InsertEdgesFrom;
@@ -23,18 +38,31 @@ use agdb::InsertEdgesIds;
 use agdb::InsertEdgesQuery;
 use agdb::InsertEdgesValues;
 use agdb::InsertIndex;
+use agdb::InsertIndexOrdered;
 use agdb::InsertIndexQuery;
+use agdb::InsertIndexUnique;
 use agdb::InsertNodes;
 use agdb::InsertNodesAliases;
 use agdb::InsertNodesCount;
 use agdb::InsertNodesIds;
 use agdb::InsertNodesQuery;
 use agdb::InsertNodesValues;
+use agdb::InsertUpdate;
+use agdb::InsertUpdateIds;
 use agdb::InsertValues;
 use agdb::InsertValuesIds;
 use agdb::InsertValuesQuery;
 use agdb::KeyValueComparison;
+use agdb::KeyValueUpdate;
+use agdb::KeyValueUpdates;
+use agdb::Match;
+use agdb::MatchEdge;
+use agdb::MatchNode;
+use agdb::MatchQuery;
+use agdb::MatchStep;
+use agdb::MatchVariable;
 use agdb::MultiValues;
+use agdb::PageRankOptions;
 use agdb::QueryAliases;
 use agdb::QueryBuilder;
 use agdb::QueryCondition;
@@ -62,15 +90,23 @@ use agdb::SearchFrom;
 use agdb::SearchIndexBuilder;
 use agdb::SearchIndexValue;
 use agdb::SearchOrderBy;
+use agdb::SearchPaths;
+use agdb::SearchPathsFrom;
 use agdb::SearchQuery;
 use agdb::SearchQueryAlgorithm;
 use agdb::SearchQueryBuilderDef;
+use agdb::SearchQueryDirection;
 use agdb::SearchTo;
 use agdb::Select;
+use agdb::SelectAggregate;
+use agdb::SelectAggregateIds;
 use agdb::SelectAliases;
 use agdb::SelectAliasesIds;
 use agdb::SelectAliasesQuery;
 use agdb::SelectAllAliasesQuery;
+use agdb::SelectAnalytics;
+use agdb::SelectAnalyticsIds;
+use agdb::SelectAnalyticsQuery;
 use agdb::SelectEdgeCount;
 use agdb::SelectEdgeCountIds;
 use agdb::SelectEdgeCountQuery;
@@ -91,6 +127,8 @@ use agdb::SelectValues;
 use agdb::SelectValuesIds;
 use agdb::SelectValuesQuery;
 use agdb::SingleValues;
+use agdb::UpdateValuesQuery;
+use agdb::ValueUpdate;
 use agdb::Where;
 use agdb::WhereKey;
 use agdb::WhereLogicOperator;
@@ -127,23 +165,38 @@ impl Api {
             DbElement::type_def(),
             DbF64::type_def(),
             DbId::type_def(),
+            DbIssue::type_def(),
+            DbIssueType::type_def(),
             DbKeyOrder::type_def(),
             DbKeyOrders::type_def(),
             DbKeyValue::type_def(),
             DbValue::type_def(),
             DbValues::type_def(),
+            DbVerifyReport::type_def(),
         ]
     }
 
     pub fn query_types() -> Vec<Type> {
         vec![
             QueryType::type_def(),
+            AggregateFunction::type_def(),
+            AggregateFunctions::type_def(),
+            AggregateQuery::type_def(),
+            ExpectQuery::type_def(),
+            AnalyticsAlgorithm::type_def(),
+            PageRankOptions::type_def(),
             QueryAliases::type_def(),
             InsertAliasesQuery::type_def(),
+            InsertAnalyticsQuery::type_def(),
             InsertEdgesQuery::type_def(),
             InsertIndexQuery::type_def(),
             InsertNodesQuery::type_def(),
             InsertValuesQuery::type_def(),
+            MatchQuery::type_def(),
+            MatchStep::type_def(),
+            MatchVariable::type_def(),
+            AdjacentCondition::type_def(),
+            AdjacentQuantifier::type_def(),
             Comparison::type_def(),
             CountComparison::type_def(),
             KeyValueComparison::type_def(),
@@ -163,23 +216,33 @@ impl Api {
             RemoveValuesQuery::type_def(),
             SearchQuery::type_def(),
             SearchQueryAlgorithm::type_def(),
+            SearchQueryDirection::type_def(),
             SelectAliasesQuery::type_def(),
             SelectAllAliasesQuery::type_def(),
+            SelectAnalyticsQuery::type_def(),
             SelectEdgeCountQuery::type_def(),
             SelectIndexesQuery::type_def(),
             SelectKeyCountQuery::type_def(),
             SelectKeysQuery::type_def(),
             SelectNodeCountQuery::type_def(),
             SelectValuesQuery::type_def(),
+            KeyValueUpdate::type_def(),
+            KeyValueUpdates::type_def(),
+            UpdateValuesQuery::type_def(),
+            ValueUpdate::type_def(),
         ]
     }
 
     pub fn query_builder_types() -> Vec<Type> {
         vec![
             QueryBuilder::type_def(),
+            Expect::type_def(),
+            ExpectIds::type_def(),
             Insert::type_def(),
             InsertAliases::type_def(),
             InsertAliasesIds::type_def(),
+            InsertAnalytics::type_def(),
+            InsertAnalyticsIds::type_def(),
             InsertEdges::type_def(),
             InsertEdgesEach::type_def(),
             InsertEdgesFrom::type_def(),
@@ -187,13 +250,20 @@ impl Api {
             InsertEdgesIds::type_def(),
             InsertEdgesValues::type_def(),
             InsertIndex::type_def(),
+            InsertIndexOrdered::type_def(),
+            InsertIndexUnique::type_def(),
             InsertNodes::type_def(),
             InsertNodesAliases::type_def(),
             InsertNodesCount::type_def(),
             InsertNodesIds::type_def(),
             InsertNodesValues::type_def(),
+            InsertUpdate::type_def(),
+            InsertUpdateIds::type_def(),
             InsertValues::type_def(),
             InsertValuesIds::type_def(),
+            Match::type_def(),
+            MatchEdge::type_def(),
+            MatchNode::type_def(),
             Remove::type_def(),
             RemoveAliases::type_def(),
             RemoveIds::type_def(),
@@ -206,12 +276,18 @@ impl Api {
             SearchTo::<SearchQuery>::type_def(),
             SearchIndexBuilder::<SearchQuery>::type_def(),
             SearchIndexValue::<SearchQuery>::type_def(),
+            SearchPaths::<SearchQuery>::type_def(),
+            SearchPathsFrom::<SearchQuery>::type_def(),
             SearchOrderBy::<SearchQuery>::type_def(),
             SelectLimit::<SearchQuery>::type_def(),
             SelectOffset::<SearchQuery>::type_def(),
             Select::type_def(),
+            SelectAggregate::type_def(),
+            SelectAggregateIds::type_def(),
             SelectAliases::type_def(),
             SelectAliasesIds::type_def(),
+            SelectAnalytics::type_def(),
+            SelectAnalyticsIds::type_def(),
             SelectEdgeCount::type_def(),
             SelectEdgeCountIds::type_def(),
             SelectIds::type_def(),
@@ -304,6 +380,7 @@ impl Api {
         defs.extend(crate::tests::routes::admin_db_user_add_test::test_defs());
         defs.extend(crate::tests::routes::admin_db_user_list_test::test_defs());
         defs.extend(crate::tests::routes::admin_db_user_remove_test::test_defs());
+        defs.extend(crate::tests::routes::admin_db_verify_test::test_defs());
         defs.extend(crate::tests::routes::admin_status_test::test_defs());
         defs.extend(crate::tests::routes::admin_user_add_test::test_defs());
         defs.extend(crate::tests::routes::admin_user_change_password_test::test_defs());
@@ -47,6 +47,7 @@ pub enum DbKind {
     Memory,
     Mapped,
     File,
+    Encrypted,
 }
 
 #[derive(
@@ -198,6 +199,7 @@ impl From<&str> for DbKind {
         match value {
             "mapped" => Self::Mapped,
             "file" => Self::File,
+            "encrypted" => Self::Encrypted,
             _ => Self::Memory,
         }
     }
@@ -266,6 +268,7 @@ impl Display for DbKind {
             DbKind::File => f.write_str("file"),
             DbKind::Mapped => f.write_str("mapped"),
             DbKind::Memory => f.write_str("memory"),
+            DbKind::Encrypted => f.write_str("encrypted"),
         }
     }
 }
@@ -575,6 +578,8 @@ mod tests {
         assert_eq!(db_type, DbKind::File);
         let db_type = DbKind::from("memory");
         assert_eq!(db_type, DbKind::Memory);
+        let db_type = DbKind::from("encrypted");
+        assert_eq!(db_type, DbKind::Encrypted);
 
         let db_value = DbValue::from(DbKind::Memory);
         let db_type: DbKind = db_value.try_into().unwrap();
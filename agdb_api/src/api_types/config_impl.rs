@@ -20,6 +20,7 @@ pub struct ConfigImpl {
     pub request_body_limit: u64,
     pub data_dir: String,
     pub pepper_path: String,
+    pub encryption_keys_dir: String,
     pub tls_certificate: String,
     pub tls_key: String,
     pub tls_root: String,
@@ -59,6 +60,10 @@ pub fn config_to_str(config: &ConfigImpl) -> String {
     ));
     buffer.push_str(&format!("data_dir: {}\n", config.data_dir));
     buffer.push_str(&format!("pepper_path: {}\n", config.pepper_path));
+    buffer.push_str(&format!(
+        "encryption_keys_dir: {}\n",
+        config.encryption_keys_dir
+    ));
     buffer.push_str(&format!("tls_certificate: {}\n", config.tls_certificate));
     buffer.push_str(&format!("tls_key: {}\n", config.tls_key));
     buffer.push_str(&format!("tls_root: {}\n", config.tls_root));
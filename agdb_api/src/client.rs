@@ -13,6 +13,7 @@ use crate::api_types::LogLevelFilter;
 use crate::api_types::ServerDatabase;
 use crate::api_types::UserCredentials;
 use crate::http_client::HttpClient;
+use agdb::DbVerifyReport;
 use agdb::QueryResult;
 use agdb::QueryType;
 
@@ -504,6 +505,27 @@ impl<T: AgdbApiClient> AgdbApi<T> {
             .await
     }
 
+    /// `GET /admin/db/{owner}/{db}/verify`
+    ///
+    /// Checks the database integrity (storage records, graph, aliases,
+    /// indexes and values) and returns the inconsistencies found.
+    ///
+    /// Returns `(200, DbVerifyReport)` on success.
+    ///
+    /// Common error responses: `401` unauthorized, `404` user or database not found.
+    pub async fn admin_db_verify(
+        &self,
+        owner: &str,
+        db: &str,
+    ) -> AgdbApiResult<(u16, DbVerifyReport)> {
+        self.client
+            .get(
+                &self.url(&format!("/admin/db/{owner}/{db}/verify")),
+                &self.token,
+            )
+            .await
+    }
+
     /// `POST /admin/shutdown`
     ///
     /// Requests server shutdown.
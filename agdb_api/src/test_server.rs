@@ -309,6 +309,7 @@ impl TestServerImpl {
             request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
             data_dir: SERVER_DATA_DIR.into(),
             pepper_path: String::new(),
+            encryption_keys_dir: "agdb_server_keys".to_string(),
             tls_certificate: String::new(),
             tls_key: String::new(),
             tls_root: String::new(),
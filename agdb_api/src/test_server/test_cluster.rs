@@ -130,6 +130,7 @@ pub async fn create_cluster(nodes: usize, tls: bool) -> Result<Vec<TestServerImp
             request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
             data_dir: super::SERVER_DATA_DIR.into(),
             pepper_path: String::new(),
+            encryption_keys_dir: "agdb_server_keys".to_string(),
             tls_certificate: tls_cert.clone(),
             tls_key: tls_key.clone(),
             tls_root: tls_root.clone(),
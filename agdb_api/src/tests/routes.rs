@@ -14,6 +14,7 @@ pub mod admin_db_rename_test;
 pub mod admin_db_user_add_test;
 pub mod admin_db_user_list_test;
 pub mod admin_db_user_remove_test;
+pub mod admin_db_verify_test;
 
 // Admin User Operations
 pub mod admin_status_test;
@@ -37,6 +37,7 @@ pub async fn read_write() -> Result<(), TestError> {
                 to: DbId::default(),
                 values: vec![],
             }],
+            cursor: None,
         },
         QueryResult {
             result: 1,
@@ -46,6 +47,7 @@ pub async fn read_write() -> Result<(), TestError> {
                 to: DbId::default(),
                 values: vec![("key", 1.1).into()],
             }],
+            cursor: None,
         },
     ];
     assert_eq!(results, expected);
@@ -79,6 +81,7 @@ pub async fn read_only() -> Result<(), TestError> {
             to: DbId::default(),
             values: vec![("key", 1.1).into()],
         }],
+        cursor: None,
     }];
     assert_eq!(results, expected);
     Ok(())
@@ -0,0 +1,95 @@
+use crate::DbKind;
+use crate::test_server::ADMIN;
+use crate::test_server::TestServer;
+use crate::test_server::next_db_name;
+use crate::test_server::next_user_name;
+use crate::test_server::test_error::TestError;
+use agdb::QueryBuilder;
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn verify() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.admin_db_add(owner, db, DbKind::Mapped).await?;
+    let queries = &[
+        QueryBuilder::insert().index("key").query().into(),
+        QueryBuilder::insert()
+            .nodes()
+            .aliases(["root", "node"])
+            .values([[("key", 1).into()], [("key", 2).into()]])
+            .query()
+            .into(),
+        QueryBuilder::insert()
+            .edges()
+            .from("root")
+            .to("node")
+            .query()
+            .into(),
+    ];
+    server.api.admin_db_exec_mut(owner, db, queries).await?;
+    let (status, report) = server.api.admin_db_verify(owner, db).await?;
+    assert_eq!(status, 200);
+    assert!(report.is_ok());
+    Ok(())
+}
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn db_not_found() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    let status = server
+        .api
+        .admin_db_verify(owner, "db")
+        .await
+        .unwrap_err()
+        .status;
+    assert_eq!(status, 404);
+    Ok(())
+}
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn non_admin() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.admin_db_add(owner, db, DbKind::Memory).await?;
+    server.api.user_login(owner, owner).await?;
+    let status = server
+        .api
+        .admin_db_verify(owner, db)
+        .await
+        .unwrap_err()
+        .status;
+    assert_eq!(status, 401);
+    Ok(())
+}
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn no_token() -> Result<(), TestError> {
+    let server = TestServer::new().await?;
+    let status = server
+        .api
+        .admin_db_verify("owner", "db")
+        .await
+        .unwrap_err()
+        .status;
+    assert_eq!(status, 401);
+    Ok(())
+}
+
+#[cfg(feature = "api")]
+pub fn test_defs() -> Vec<agdb::type_def::Type> {
+    vec![
+        __verify_type_def(),
+        __db_not_found_type_def(),
+        __non_admin_type_def(),
+        __no_token_type_def(),
+    ]
+}
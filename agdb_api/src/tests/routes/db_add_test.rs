@@ -4,6 +4,7 @@ use crate::test_server::TestServer;
 use crate::test_server::next_db_name;
 use crate::test_server::next_user_name;
 use crate::test_server::test_error::TestError;
+use agdb::QueryBuilder;
 use std::path::Path;
 
 #[cfg_attr(feature = "api", agdb::test_def())]
@@ -164,10 +165,50 @@ pub async fn no_token() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn add_encrypted() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    let status = server.api.db_add(owner, db, DbKind::Encrypted).await?;
+    assert_eq!(status, 201);
+    server
+        .api
+        .db_exec_mut(
+            owner,
+            db,
+            &[QueryBuilder::insert()
+                .nodes()
+                .values([[("secret", "classified information").into()]])
+                .query()
+                .into()],
+        )
+        .await?;
+    let list = server.api.db_list().await?.1;
+    assert_eq!(list[0].db_type, DbKind::Encrypted);
+    assert!(
+        Path::new(&server.dir)
+            .join("agdb_server_keys")
+            .join(owner)
+            .join(format!("{db}.key"))
+            .exists()
+    );
+    let content = String::from_utf8_lossy(&std::fs::read(
+        Path::new(&server.data_dir).join(owner).join(db),
+    )?)
+    .to_string();
+    assert!(!content.contains("classified information"));
+    Ok(())
+}
+
 #[cfg(feature = "api")]
 pub fn test_defs() -> Vec<agdb::type_def::Type> {
     vec![
         __add_type_def(),
+        __add_encrypted_type_def(),
         __add_same_name_with_previous_backup_after_delete_type_def(),
         __add_same_name_with_backup_after_remove_type_def(),
         __add_same_name_different_user_type_def(),
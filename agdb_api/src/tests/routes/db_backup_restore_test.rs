@@ -63,7 +63,8 @@ pub async fn backup() -> Result<(), TestError> {
                 from: DbId::default(),
                 to: DbId::default(),
                 values: vec![]
-            }]
+            }],
+            cursor: None,
         }]
     );
     Ok(())
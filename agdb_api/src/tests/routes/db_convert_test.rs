@@ -249,6 +249,142 @@ pub async fn no_token() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn file_to_encrypted_preserves_backup() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::File).await?;
+    server
+        .api
+        .db_exec_mut(
+            owner,
+            db,
+            &[QueryBuilder::insert()
+                .nodes()
+                .aliases(["root", "other"])
+                .query()
+                .into()],
+        )
+        .await?;
+    server.api.db_backup(owner, db).await?;
+    server
+        .api
+        .db_exec_mut(
+            owner,
+            db,
+            &[QueryBuilder::remove().ids("root").query().into()],
+        )
+        .await?;
+
+    server.api.db_convert(owner, db, DbKind::Encrypted).await?;
+    let list = server.api.db_list().await?.1;
+    assert_eq!(list[0].db_type, DbKind::Encrypted);
+    let results = server
+        .api
+        .db_exec(
+            owner,
+            db,
+            &[QueryBuilder::select().ids("other").query().into()],
+        )
+        .await?
+        .1;
+    assert_eq!(results[0].result, 1);
+
+    server.api.db_restore(owner, db).await?;
+
+    let results = server
+        .api
+        .db_exec(
+            owner,
+            db,
+            &[QueryBuilder::select().ids("root").query().into()],
+        )
+        .await?
+        .1;
+    assert_eq!(results[0].result, 1);
+    assert!(
+        Path::new(&server.dir)
+            .join("agdb_server_keys")
+            .join(owner)
+            .join(format!("{db}.key"))
+            .exists()
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn encrypted_to_memory_preserves_backup() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Encrypted).await?;
+    server
+        .api
+        .db_exec_mut(
+            owner,
+            db,
+            &[QueryBuilder::insert()
+                .nodes()
+                .aliases(["root", "other"])
+                .query()
+                .into()],
+        )
+        .await?;
+    server.api.db_backup(owner, db).await?;
+    server
+        .api
+        .db_exec_mut(
+            owner,
+            db,
+            &[QueryBuilder::remove().ids("root").query().into()],
+        )
+        .await?;
+
+    server.api.db_convert(owner, db, DbKind::Memory).await?;
+    let list = server.api.db_list().await?.1;
+    assert_eq!(list[0].db_type, DbKind::Memory);
+    let results = server
+        .api
+        .db_exec(
+            owner,
+            db,
+            &[QueryBuilder::select().ids("other").query().into()],
+        )
+        .await?
+        .1;
+    assert_eq!(results[0].result, 1);
+
+    server.api.db_restore(owner, db).await?;
+
+    let results = server
+        .api
+        .db_exec(
+            owner,
+            db,
+            &[QueryBuilder::select().ids("root").query().into()],
+        )
+        .await?
+        .1;
+    assert_eq!(results[0].result, 1);
+    assert!(
+        !Path::new(&server.dir)
+            .join("agdb_server_keys")
+            .join(owner)
+            .join(format!("{db}.key"))
+            .exists()
+    );
+
+    Ok(())
+}
+
 #[cfg(feature = "api")]
 pub fn test_defs() -> Vec<agdb::type_def::Type> {
     vec![
@@ -257,6 +393,8 @@ pub fn test_defs() -> Vec<agdb::type_def::Type> {
         __file_to_memory_type_def(),
         __memory_to_mapped_preserves_backup_and_audit_type_def(),
         __file_to_memory_preserves_backup_and_audit_type_def(),
+        __file_to_encrypted_preserves_backup_type_def(),
+        __encrypted_to_memory_preserves_backup_type_def(),
         __db_not_found_type_def(),
         __non_admin_type_def(),
         __no_token_type_def(),
@@ -197,10 +197,40 @@ pub async fn no_token() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn copy_encrypted() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    let db2 = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Encrypted).await?;
+    let queries = &[QueryBuilder::insert()
+        .nodes()
+        .aliases(["root"])
+        .query()
+        .into()];
+    server.api.db_exec_mut(owner, db, queries).await?;
+    let status = server.api.db_copy(owner, db, db2).await?;
+    assert_eq!(status, 201);
+    let keys_dir = Path::new(&server.dir).join("agdb_server_keys").join(owner);
+    assert_eq!(
+        std::fs::read(keys_dir.join(format!("{db}.key")))?,
+        std::fs::read(keys_dir.join(format!("{db2}.key")))?
+    );
+    let queries = &[QueryBuilder::select().ids("root").query().into()];
+    let results = server.api.db_exec(owner, db2, queries).await?.1;
+    assert_eq!(results[0].result, 1);
+    Ok(())
+}
+
 #[cfg(feature = "api")]
 pub fn test_defs() -> Vec<agdb::type_def::Type> {
     vec![
         __copy_type_def(),
+        __copy_encrypted_type_def(),
         __copy_from_different_user_type_def(),
         __copy_to_removed_type_def(),
         __target_exists_type_def(),
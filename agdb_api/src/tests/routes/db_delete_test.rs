@@ -125,6 +125,26 @@ pub async fn no_token() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn delete_encrypted() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Encrypted).await?;
+    let key_file = Path::new(&server.dir)
+        .join("agdb_server_keys")
+        .join(owner)
+        .join(format!("{db}.key"));
+    assert!(key_file.exists());
+    let status = server.api.db_delete(owner, db).await?;
+    assert_eq!(status, 204);
+    assert!(!key_file.exists());
+    Ok(())
+}
+
 #[cfg(feature = "api")]
 pub fn test_defs() -> Vec<agdb::type_def::Type> {
     vec![
@@ -132,6 +152,7 @@ pub fn test_defs() -> Vec<agdb::type_def::Type> {
         __delete_in_memory_type_def(),
         __delete_with_backup_type_def(),
         __delete_in_memory_with_backup_type_def(),
+        __delete_encrypted_type_def(),
         __db_not_found_type_def(),
         __non_owner_type_def(),
         __no_token_type_def(),
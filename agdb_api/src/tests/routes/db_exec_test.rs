@@ -4,11 +4,13 @@ use crate::test_server::ADMIN;
 use crate::test_server::TestServer;
 use crate::test_server::next_db_name;
 use crate::test_server::next_user_name;
+use crate::test_server::reqwest_client;
 use crate::test_server::test_error::TestError;
 use agdb::DbElement;
 use agdb::DbId;
 use agdb::QueryBuilder;
 use agdb::QueryResult;
+use reqwest::header::CONTENT_TYPE;
 
 #[cfg_attr(feature = "api", agdb::test_def())]
 pub async fn read_write() -> Result<(), TestError> {
@@ -39,6 +41,7 @@ pub async fn read_write() -> Result<(), TestError> {
                 to: DbId::default(),
                 values: vec![],
             }],
+            cursor: None,
         },
         QueryResult {
             result: 1,
@@ -48,6 +51,7 @@ pub async fn read_write() -> Result<(), TestError> {
                 to: DbId::default(),
                 values: vec![("key", 1.1).into()],
             }],
+            cursor: None,
         },
     ];
     assert_eq!(results, expected);
@@ -82,6 +86,7 @@ pub async fn read_only() -> Result<(), TestError> {
             to: DbId::default(),
             values: vec![("key", 1.1).into()],
         }],
+        cursor: None,
     }];
     assert_eq!(results, expected);
     Ok(())
@@ -238,7 +243,8 @@ pub async fn use_result_of_previous_query() -> Result<(), TestError> {
                     to: DbId(-4),
                     values: vec![("key", 1.1).into()]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
 
@@ -301,7 +307,8 @@ pub async fn use_result_in_subquery() -> Result<(), TestError> {
                     to: DbId(-4),
                     values: vec![("key", 1.1).into()]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
 
@@ -341,7 +348,8 @@ pub async fn use_result_in_condition() -> Result<(), TestError> {
                 from: DbId::default(),
                 to: DbId::default(),
                 values: vec![]
-            },]
+            },],
+            cursor: None,
         }
     );
 
@@ -393,7 +401,8 @@ pub async fn use_result_in_search() -> Result<(), TestError> {
                     to: DbId(-3),
                     values: vec![]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
     Ok(())
@@ -469,7 +478,8 @@ pub async fn use_result_in_insert_ids() -> Result<(), TestError> {
                     to: DbId(-5),
                     values: vec![]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
     Ok(())
@@ -546,7 +556,8 @@ pub async fn reentrant_queries() -> Result<(), TestError> {
                     to: DbId(-5),
                     values: vec![]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
     let (status, results) = server.api.db_exec_mut(owner, db, queries).await?;
@@ -574,7 +585,8 @@ pub async fn reentrant_queries() -> Result<(), TestError> {
                     to: DbId(-7),
                     values: vec![]
                 }
-            ]
+            ],
+            cursor: None,
         }
     );
     Ok(())
@@ -694,6 +706,105 @@ pub async fn query_error() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn text_queries() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Mapped).await?;
+    let client = reqwest_client();
+    let token = server.api.token.clone().unwrap_or_default();
+    let response = client
+        .post(server.full_url(&format!("/db/{owner}/{db}/exec_mut")))
+        .bearer_auth(&token)
+        .header(CONTENT_TYPE, "text/plain")
+        .body(r#"insert nodes aliases "root" values {"key": 1.1}; select ids "root""#)
+        .send()
+        .await?;
+    assert_eq!(response.status(), 200);
+    let results: Vec<QueryResult> = response.json().await?;
+    assert_eq!(
+        results[1],
+        QueryResult {
+            result: 1,
+            elements: vec![DbElement {
+                id: DbId(1),
+                from: DbId::default(),
+                to: DbId::default(),
+                values: vec![("key", 1.1).into()],
+            }],
+            cursor: None,
+        }
+    );
+    let response = client
+        .post(server.full_url(&format!("/db/{owner}/{db}/exec")))
+        .bearer_auth(&token)
+        .header(CONTENT_TYPE, "text/plain")
+        .body("select values \"key\" ids")
+        .send()
+        .await?;
+    assert_eq!(response.status(), 470);
+    assert_eq!(
+        response.text().await?,
+        "Invalid query: expected id or alias at position 23"
+    );
+    Ok(())
+}
+
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn precondition_failed() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Mapped).await?;
+    let queries = &[QueryBuilder::insert()
+        .nodes()
+        .aliases("doc")
+        .values([[("version", 1).into()]])
+        .query()
+        .into()];
+    server.api.db_exec_mut(owner, db, queries).await?;
+    let update = |version: i64| {
+        vec![
+            QueryBuilder::expect()
+                .ids("doc")
+                .where_()
+                .key("version")
+                .value(version)
+                .query()
+                .query()
+                .into(),
+            QueryBuilder::insert()
+                .values_uniform([("version", version + 1).into()])
+                .ids(":0")
+                .query()
+                .into(),
+        ]
+    };
+    server.api.db_exec_mut(owner, db, &update(1)).await?;
+    let error = server
+        .api
+        .db_exec_mut(owner, db, &update(1))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status, 471);
+    assert_eq!(error.description, "Precondition failed for id '1'");
+    let queries = &[QueryBuilder::select()
+        .values("version")
+        .ids("doc")
+        .query()
+        .into()];
+    let (_, results) = server.api.db_exec(owner, db, queries).await?;
+    assert_eq!(results[0].elements[0].values, vec![("version", 2).into()]);
+    Ok(())
+}
+
 #[cfg_attr(feature = "api", agdb::test_def())]
 pub async fn permission_denied() -> Result<(), TestError> {
     let mut server = TestServer::new().await?;
@@ -791,6 +902,8 @@ pub fn test_defs() -> Vec<agdb::type_def::Type> {
         __use_result_bad_query_type_def(),
         __use_result_out_of_bounds_type_def(),
         __query_error_type_def(),
+        __text_queries_type_def(),
+        __precondition_failed_type_def(),
         __permission_denied_type_def(),
         __db_not_found_type_def(),
         __someone_elses_db_type_def(),
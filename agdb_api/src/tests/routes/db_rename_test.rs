@@ -165,11 +165,30 @@ pub async fn no_token() -> Result<(), TestError> {
     Ok(())
 }
 
+#[cfg_attr(feature = "api", agdb::test_def())]
+pub async fn rename_encrypted() -> Result<(), TestError> {
+    let mut server = TestServer::new().await?;
+    let owner = &next_user_name();
+    let db = &next_db_name();
+    let db2 = &next_db_name();
+    server.api.user_login(ADMIN, ADMIN).await?;
+    server.api.admin_user_add(owner, owner).await?;
+    server.api.user_login(owner, owner).await?;
+    server.api.db_add(owner, db, DbKind::Encrypted).await?;
+    let status = server.api.db_rename(owner, db, db2).await?;
+    assert_eq!(status, 201);
+    let keys_dir = Path::new(&server.dir).join("agdb_server_keys").join(owner);
+    assert!(!keys_dir.join(format!("{db}.key")).exists());
+    assert!(keys_dir.join(format!("{db2}.key")).exists());
+    Ok(())
+}
+
 #[cfg(feature = "api")]
 pub fn test_defs() -> Vec<agdb::type_def::Type> {
     vec![
         __rename_type_def(),
         __rename_with_backup_type_def(),
+        __rename_encrypted_type_def(),
         __non_owner_type_def(),
         __invalid_type_def(),
         __db_not_found_type_def(),
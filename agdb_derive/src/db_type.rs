@@ -104,6 +104,35 @@ pub fn derive_impl(item: TokenStream, element_id: bool) -> TokenStream {
             }
         }
 
+        impl ::std::convert::From<#name> for ::agdb::DbValue {
+            #[track_caller]
+            fn from(value: #name) -> Self {
+                ::agdb::DbValue::Map(::agdb::DbType::to_db_values(&value))
+            }
+        }
+
+        impl ::std::convert::From<&#name> for ::agdb::DbValue {
+            #[track_caller]
+            fn from(value: &#name) -> Self {
+                ::agdb::DbValue::Map(::agdb::DbType::to_db_values(value))
+            }
+        }
+
+        impl ::std::convert::TryFrom<::agdb::DbValue> for #name {
+            type Error = ::agdb::DbError;
+
+            #[track_caller]
+            fn try_from(value: ::agdb::DbValue) -> ::std::result::Result<Self, Self::Error> {
+                use ::agdb::DbType;
+                #name::from_db_element(&::agdb::DbElement {
+                    id: ::agdb::DbId::default(),
+                    from: ::agdb::DbId::default(),
+                    to: ::agdb::DbId::default(),
+                    values: value.map()?.clone(),
+                })
+            }
+        }
+
         impl TryFrom<agdb::QueryResult> for #name {
             type Error = agdb::DbError;
 
@@ -25,6 +25,12 @@ pub(crate) fn compile_error(span: impl ToTokens, message: impl AsRef<str>) -> To
 /// or you must use `#[agdb(flatten)]` attribute to merge the fields in a flat list (transitively). Flattened
 /// types must themselves be derived from `agdb::DbType` (or implement `agdb::UserDbType`)
 ///
+/// The derive also implements the conversions from/to `agdb::DbValue::Map` so that
+/// the types derived from `agdb::DbType` can be nested as fields of other types without
+/// flattening. The nested values can then be searched and selected with a path of keys
+/// separated by `.`, e.g. `"address.city"`. Vectors of such types additionally require
+/// `agdb::DbTypeMarker` and are stored as `agdb::DbValue::List`.
+///
 /// NOTE: if the nested struct(s) have keys of the same name the value will
 /// be overwritten by the last encountered field of the same name (transitively).
 /// Use `#[agdb(rename = "new_name")]` to disambiguate.
@@ -78,6 +84,15 @@ pub(crate) fn compile_error(span: impl ToTokens, message: impl AsRef<str>) -> To
 ///     nested: NestedStruct,
 /// }
 /// ```
+///
+/// ## Nested
+/// ```ignore
+/// #[derive(DbType)]
+/// struct MyValue {
+///     num_value: i64,
+///     nested: NestedStruct, // NestedStruct derives DbType, stored as a map
+/// }
+/// ```
 #[proc_macro_derive(DbType, attributes(agdb))]
 pub fn user_db_type_derive(item: TokenStream) -> TokenStream {
     db_type::db_type_derive(item)
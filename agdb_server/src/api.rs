@@ -27,6 +27,7 @@ use utoipa::openapi::security::SecurityScheme;
         routes::admin::db::remove,
         routes::admin::db::restore,
         routes::admin::db::rollback,
+        routes::admin::db::verify,
         routes::admin::db::user::add,
         routes::admin::db::user::list,
         routes::admin::db::user::remove,
@@ -99,12 +100,26 @@ use utoipa::openapi::security::SecurityScheme;
         agdb::DbKeyOrder,
         agdb::DbValue,
         agdb::DbF64,
+        agdb::DbIssue,
+        agdb::DbIssueType,
+        agdb::DbVerifyReport,
         agdb::QueryType,
+        agdb::AggregateFunction,
+        agdb::AggregateQuery,
+        agdb::ExpectQuery,
+        agdb::AnalyticsAlgorithm,
+        agdb::PageRankOptions,
         agdb::InsertAliasesQuery,
+        agdb::InsertAnalyticsQuery,
         agdb::InsertEdgesQuery,
         agdb::InsertIndexQuery,
         agdb::InsertNodesQuery,
         agdb::InsertValuesQuery,
+        agdb::MatchQuery,
+        agdb::MatchStep,
+        agdb::MatchVariable,
+        agdb::AdjacentCondition,
+        agdb::AdjacentQuantifier,
         agdb::Comparison,
         agdb::CountComparison,
         agdb::KeyValueComparison,
@@ -122,14 +137,19 @@ use utoipa::openapi::security::SecurityScheme;
         agdb::RemoveValuesQuery,
         agdb::SearchQuery,
         agdb::SearchQueryAlgorithm,
+        agdb::SearchQueryDirection,
         agdb::SelectAliasesQuery,
         agdb::SelectAllAliasesQuery,
+        agdb::SelectAnalyticsQuery,
         agdb::SelectEdgeCountQuery,
         agdb::SelectIndexesQuery,
         agdb::SelectKeyCountQuery,
         agdb::SelectKeysQuery,
         agdb::SelectNodeCountQuery,
         agdb::SelectValuesQuery,
+        agdb::KeyValueUpdate,
+        agdb::UpdateValuesQuery,
+        agdb::ValueUpdate,
     )),
     modifiers(&BearerToken),
 )]
@@ -151,13 +171,20 @@ impl Modify for BearerToken {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use agdb::AdjacentQuantifier;
+    use agdb::AggregateFunction;
+    use agdb::AnalyticsAlgorithm;
     use agdb::Comparison;
     use agdb::CountComparison;
     use agdb::DbKeyOrder;
     use agdb::DbType;
+    use agdb::DbValue;
+    use agdb::KeyValueUpdate;
+    use agdb::PageRankOptions;
     use agdb::QueryBuilder;
     use agdb::QueryId;
     use agdb::QueryType;
+    use agdb::SearchQueryDirection;
     use std::fs::File;
     use std::io::Write;
 
@@ -195,11 +222,16 @@ mod tests {
     fn test_queries() {
         #[rustfmt::skip]
         let queries = queries![
+QueryBuilder::expect().ids(1).where_().key("version").value(7).query().query(),
+QueryBuilder::expect().ids(QueryBuilder::search().from("a").query()).where_().keys("k").query().query(),
 QueryBuilder::insert().aliases("a").ids(1).query(),
 QueryBuilder::insert().aliases("a").ids("b").query(),
 QueryBuilder::insert().aliases(["a", "b"]).ids([1, 2]).query(),
+QueryBuilder::insert().analytics(AnalyticsAlgorithm::WeaklyConnectedComponents).query(),
+QueryBuilder::insert().analytics(AnalyticsAlgorithm::PageRank(PageRankOptions { damping: 0.85, iterations: 20 })).search().from(1).query(),
 QueryBuilder::insert().edges().from(1).to(2).query(),
 QueryBuilder::insert().edges().from("a").to("b").query(),
+QueryBuilder::insert().edges().from("a").to("b").acyclic().query(),
 QueryBuilder::insert().edges().from("a").to([1, 2]).query(),
 QueryBuilder::insert().edges().from([1, 2]).to([2, 3]).query(),
 QueryBuilder::insert().edges().from([1, 2]).to([2, 3]).each().query(),
@@ -214,6 +246,10 @@ QueryBuilder::insert().edges().ids(-3).from(1).to(2).query(),
 QueryBuilder::insert().edges().ids([-3, -4]).from(1).to(2).query(),
 QueryBuilder::insert().edges().ids(QueryBuilder::search().from(1).where_().edge().query()).from(1).to(2).query(),
 QueryBuilder::insert().index("key").query(),
+QueryBuilder::insert().index("key").ordered().query(),
+QueryBuilder::insert().index(["k1", "k2"]).query(),
+QueryBuilder::insert().index("key").unique().query(),
+QueryBuilder::insert().index("key").ordered().unique().query(),
 QueryBuilder::insert().nodes().count(2).query(),
 QueryBuilder::insert().nodes().count(2).values_uniform([("k", "v").into(), (1, 10).into()]).query(),
 QueryBuilder::insert().nodes().aliases(["a", "b"]).query(),
@@ -236,6 +272,13 @@ QueryBuilder::insert().values([vec![("k", "v").into(), (1, 10).into()], vec![("k
 QueryBuilder::insert().values_uniform([("k", "v").into(), (1, 10).into()]).ids([1, 2]).query(),
 QueryBuilder::insert().values_uniform([("k", "v").into(), (1, 10).into()]).ids(QueryBuilder::search().from("a").query()).query(),
 QueryBuilder::insert().values_uniform([("k", "v").into(), (1, 10).into()]).search().from("a").query(),
+QueryBuilder::insert().values_uniform([("active", true).into(), ("created", DbValue::DateTime(1_700_000_000_000_000_000)).into()]).ids([1, 2]).query(),
+QueryBuilder::insert().values_uniform([("address", DbValue::Map(vec![("city", "Prague").into(), ("tags", DbValue::List(vec![1.into(), "x".into()])).into()])).into()]).ids(1).query(),
+QueryBuilder::insert().update(KeyValueUpdate::increment("visits", 1)).ids("a").query(),
+QueryBuilder::insert().update([KeyValueUpdate::append("tags", "x"), KeyValueUpdate::max("score", 10)]).search().from("a").where_().node().query(),
+QueryBuilder::match_().node("a").query(),
+QueryBuilder::match_().node("a").where_().ids("a").query().edge("e").where_().key("k").value(1).query().node("b").limit(10).query(),
+QueryBuilder::match_().node("a").edge("e").direction(SearchQueryDirection::Reverse).node("b").where_().keys("k").query().offset(1).query(),
 QueryBuilder::remove().aliases("a").query(),
 QueryBuilder::remove().aliases(["a", "b"]).query(),
 QueryBuilder::remove().ids(1).query(),
@@ -245,13 +288,20 @@ QueryBuilder::remove().ids(["a", "b"]).query(),
 QueryBuilder::remove().ids(QueryBuilder::search().from("a").query()).query(),
 QueryBuilder::remove().search().from("a").query(),
 QueryBuilder::remove().index("key").query(),
+QueryBuilder::remove().index(["k1", "k2"]).query(),
 QueryBuilder::remove().values(["k1", "k2"]).ids([1, 2]).query(),
 QueryBuilder::remove().values(["k1", "k2"]).ids(QueryBuilder::search().from("a").query()).query(),
 QueryBuilder::remove().values(["k1", "k2"]).search().from("a").query(),
+QueryBuilder::select().aggregate("k").ids([1, 2]).query(),
+QueryBuilder::select().aggregate("k").functions([AggregateFunction::Sum, AggregateFunction::Avg]).search().from(1).query(),
+QueryBuilder::select().aggregate("k").functions(AggregateFunction::Count).group_by("g").search().from(1).where_().node().query(),
 QueryBuilder::select().aliases().ids([1, 2]).query(),
 QueryBuilder::select().aliases().ids(QueryBuilder::search().from(1).query()).query(),
 QueryBuilder::select().aliases().search().from(1).query(),
 QueryBuilder::select().aliases().query(),
+QueryBuilder::select().analytics(AnalyticsAlgorithm::DegreeCentrality).query(),
+QueryBuilder::select().analytics(AnalyticsAlgorithm::StronglyConnectedComponents).ids([1, 2]).query(),
+QueryBuilder::select().analytics(AnalyticsAlgorithm::BetweennessCentrality).search().from(1).query(),
 QueryBuilder::select().edge_count().ids([1, 2]).query(),
 QueryBuilder::select().edge_count_from().ids([1, 2]).query(),
 QueryBuilder::select().edge_count_to().ids([1, 2]).query(),
@@ -283,11 +333,23 @@ QueryBuilder::select().values(["k", "k2"]).search().from(1).query(),
 QueryBuilder::search().from("a").query(),
 QueryBuilder::search().to(1).query(), 
 QueryBuilder::search().from("a").to("b").query(), 
+QueryBuilder::search().from("a").to("b").weighted_by("weight").query(),
+QueryBuilder::search().shortest_paths(3).from("a").to("b").query(),
+QueryBuilder::search().shortest_paths(3).from("a").to("b").weighted_by("weight").query(),
+QueryBuilder::search().all_paths(5).from("a").to("b").limit(10).query(),
 QueryBuilder::search().breadth_first().from("a").query(), 
 QueryBuilder::search().depth_first().to(1).query(),
 QueryBuilder::search().depth_first().from("a").query(),
+QueryBuilder::search().topological().from("a").query(),
+QueryBuilder::search().topological().to(1).where_().node().query(),
+QueryBuilder::search().from("a").direction(SearchQueryDirection::Both).query(),
+QueryBuilder::search().to(1).direction(SearchQueryDirection::Reverse).query(),
+QueryBuilder::search().from("a").to("b").direction(SearchQueryDirection::Both).query(),
 QueryBuilder::search().elements().query(),
 QueryBuilder::search().index("age").value(20).query(), 
+QueryBuilder::search().index("age").value(Comparison::GreaterThan(20.into())).order_by(DbKeyOrder::Desc("age".into())).offset(1).limit(5).query(),
+QueryBuilder::search().index("age").value(Comparison::LessThan(20.into())).where_().key("k").value(1).query(),
+QueryBuilder::search().index(["k1", "k2"]).values([1, 2]).query(),
 QueryBuilder::search().from(1).order_by([DbKeyOrder::Desc("age".into()), DbKeyOrder::Asc("name".into())]).query(),
 QueryBuilder::search().from(1).offset(10).query(),
 QueryBuilder::search().from(1).limit(5).query(),
@@ -295,6 +357,8 @@ QueryBuilder::search().from(1).order_by([DbKeyOrder::Desc("k".into())]).offset(1
 QueryBuilder::search().from(1).order_by([DbKeyOrder::Desc("k".into())]).limit(5).query(),
 QueryBuilder::search().from(1).order_by([DbKeyOrder::Desc("k".into())]).offset(10).limit(5).query(),
 QueryBuilder::search().from(1).offset(10).limit(5).query(),
+QueryBuilder::search().after("18f0c2a1-5d2e-14").from(1).limit(5).query(),
+QueryBuilder::search().after("18f0c2a1-5d2e-14").index("age").value(20).limit(5).query(),
 QueryBuilder::search().from(1).where_().distance(CountComparison::LessThan(3)).query(),
 QueryBuilder::search().from(1).where_().neighbor().query(),
 QueryBuilder::search().from(1).where_().edge().query(),
@@ -303,6 +367,7 @@ QueryBuilder::search().from(1).where_().edge_count_from(1).query(),
 QueryBuilder::search().from(1).where_().edge_count_to(CountComparison::NotEqual(1)).query(),
 QueryBuilder::search().from(1).where_().node().query(),
 QueryBuilder::search().from(1).where_().key("k").value(1).query(),
+QueryBuilder::search().from(1).where_().key("address.city").value("Prague").query(),
 QueryBuilder::search().from(1).where_().keys(["k1", "k2"]).query(),
 QueryBuilder::search().from(1).where_().not().keys(["k1", "k2"]).query(),
 QueryBuilder::search().from(1).where_().ids([1, 2]).query(),
@@ -316,6 +381,10 @@ QueryBuilder::search().from(1).where_().node().or().where_().edge().and().key("k
 QueryBuilder::search().from(1).where_().node().or().where_().edge().and().key("k").value(Comparison::Contains(([1, 2]).into())).end_where().query(),
 QueryBuilder::search().from(1).where_().node().or().where_().edge().and().key("k").value(Comparison::StartsWith(1.into())).end_where().query(),
 QueryBuilder::search().from(1).where_().node().or().where_().edge().and().key("k").value(Comparison::EndsWith(([1, 2]).into())).end_where().query(),
+QueryBuilder::search().from(1).where_().key("k").value(Comparison::EqualIgnoreCase("abc".into())).query(),
+QueryBuilder::search().from(1).where_().key("k").value(Comparison::Matches("^a.*c$".into())).query(),
+QueryBuilder::search().from(1).where_().key("spent").compare_key(Comparison::GreaterThan("budget".into())).query(),
+QueryBuilder::search().from(1).where_().node().and().adjacent(SearchQueryDirection::Forward, AdjacentQuantifier::Count(CountComparison::GreaterThan(1))).key("k").value(1).end_where().query(),
 QueryBuilder::search().from(1).order_by([DbKeyOrder::Asc("k".into())]).where_().node().query(),
 QueryBuilder::search().from(1).limit(1).where_().node().query(),
 QueryBuilder::search().from(1).offset(1).where_().node().query(),
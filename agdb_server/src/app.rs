@@ -128,6 +128,10 @@ pub(crate) fn app(
             "/admin/db/{owner}/{db}/rollback",
             routing::post(routes::admin::db::rollback),
         )
+        .route(
+            "/admin/db/{owner}/{db}/verify",
+            routing::get(routes::admin::db::verify),
+        )
         .route(
             "/admin/db/{owner}/{db}/user/list",
             routing::get(routes::admin::db::user::list),
@@ -402,6 +402,7 @@ mod tests {
                 sep = std::path::MAIN_SEPARATOR
             ),
             pepper_path: String::new(),
+            encryption_keys_dir: String::new(),
             tls_certificate: String::new(),
             tls_key: String::new(),
             tls_root: String::new(),
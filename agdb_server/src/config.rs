@@ -140,6 +140,7 @@ pub(crate) fn from_str(content: &str) -> Result<ConfigImpl, String> {
                 }
                 "data_dir" => config.data_dir = value.to_string(),
                 "pepper_path" => config.pepper_path = value.to_string(),
+                "encryption_keys_dir" => config.encryption_keys_dir = value.to_string(),
                 "tls_certificate" => config.tls_certificate = value.to_string(),
                 "tls_key" => config.tls_key = value.to_string(),
                 "tls_root" => config.tls_root = value.to_string(),
@@ -210,6 +211,7 @@ fn default_config() -> ConfigImpl {
         request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
         data_dir: "agdb_server_data".to_string(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: String::new(),
         tls_key: String::new(),
         tls_root: String::new(),
@@ -273,6 +275,7 @@ mod tests {
             request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
             data_dir: "agdb_server_data".to_string(),
             pepper_path: pepper_file.filename.to_string(),
+            encryption_keys_dir: String::new(),
             tls_certificate: String::new(),
             tls_key: String::new(),
             tls_root: String::new(),
@@ -312,6 +315,7 @@ mod tests {
             request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
             data_dir: "agdb_server_data".to_string(),
             pepper_path: "missing_file".to_string(),
+            encryption_keys_dir: String::new(),
             tls_certificate: String::new(),
             tls_key: String::new(),
             tls_root: String::new(),
@@ -350,6 +354,7 @@ mod tests {
             request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
             data_dir: "agdb_server_data".to_string(),
             pepper_path: pepper_file.filename.to_string(),
+            encryption_keys_dir: String::new(),
             tls_certificate: String::new(),
             tls_key: String::new(),
             tls_root: String::new(),
@@ -461,6 +466,7 @@ mod tests {
         assert_eq!(config.request_body_limit, DEFAULT_REQUEST_BODY_LIMIT);
         assert_eq!(config.data_dir, "agdb_server_data");
         assert_eq!(config.pepper_path, "");
+        assert_eq!(config.encryption_keys_dir, "");
         assert_eq!(config.tls_certificate, "");
         assert_eq!(config.tls_key, "");
         assert_eq!(config.tls_root, "");
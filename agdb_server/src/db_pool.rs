@@ -1,3 +1,4 @@
+mod encryption;
 mod user_db;
 
 use crate::config::Config;
@@ -7,6 +8,8 @@ use crate::server_db::ServerDb;
 use crate::server_error::ServerError;
 use crate::server_error::ServerResult;
 use crate::utilities::remove_file_if_exists;
+use agdb::DbVerifyReport;
+use agdb::EncryptionKey;
 use agdb::QueryResult;
 use agdb_api::DbAudit;
 use agdb_api::DbKind;
@@ -53,7 +56,8 @@ pub(crate) async fn new(config: Config, server_db: &ServerDb) -> ServerResult<Db
     for db in server_db.dbs().await? {
         let db_path = db_file(&db.owner, &db.db, &config);
         std::fs::create_dir_all(db_audit_dir(&db.owner, &config))?;
-        let server_db = UserDb::new(db_path.to_string_lossy().as_ref(), db.db_type)?;
+        let key = db_key(&db.owner, &db.db, db.db_type, &config)?;
+        let server_db = UserDb::new(db_path.to_string_lossy().as_ref(), db.db_type, key.as_ref())?;
         pool.write().await.insert(db.name(), server_db);
     }
 
@@ -76,11 +80,13 @@ impl DbPool {
 
         std::fs::create_dir_all(db_audit_dir(owner, &self.config))?;
 
-        let user_db = UserDb::new(&path, db_type).map_err(|mut e| {
-            e.status = ErrorCode::DbInvalid.into();
-            e.description = format!("{}: {}", ErrorCode::DbInvalid.as_str(), e.description);
-            e
-        })?;
+        let user_db = create_db_key(owner, db, db_type, &self.config)
+            .and_then(|key| UserDb::new(&path, db_type, key.as_ref()))
+            .map_err(|mut e| {
+                e.status = ErrorCode::DbInvalid.into();
+                e.description = format!("{}: {}", ErrorCode::DbInvalid.as_str(), e.description);
+                e
+            })?;
 
         let backup = if std::fs::exists(db_backup_file(owner, db, &self.config))? {
             SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
@@ -129,7 +135,7 @@ impl DbPool {
         let backup_audit_path = db_backup_audit_file(owner, db, &self.config);
         remove_file_if_exists(&backup_audit_path)?;
 
-        let db_guard = user_db.0.read().await;
+        let db_guard = user_db.db.read().await;
         db_guard.backup(backup_path.to_string_lossy().as_ref())?;
 
         if audit_path.exists() {
@@ -202,11 +208,14 @@ impl DbPool {
             .ok_or(ErrorCode::DbInvalid)?
             .to_string();
         let user_db = pool.get_mut(&name).ok_or(db_not_found(owner, db))?;
-        *user_db = UserDb::new(&db_path, DbKind::Memory)?;
-        if database.db_type != DbKind::Memory {
+        if database.db_type == DbKind::Memory {
+            *user_db = UserDb::new(&db_path, DbKind::Memory, None)?;
+        } else {
+            let key = db_key(owner, db, database.db_type, &self.config)?;
+            *user_db = UserDb::empty(&db_path)?;
             remove_file_if_exists(db_file(owner, db, &self.config))?;
             remove_file_if_exists(db_file(owner, &format!(".{db}"), &self.config))?;
-            *user_db = UserDb::new(&db_path, database.db_type)?;
+            *user_db = UserDb::new(&db_path, database.db_type, key.as_ref())?;
         }
 
         Ok(())
@@ -246,6 +255,8 @@ impl DbPool {
         target_type: DbKind,
     ) -> Result<(), ServerError> {
         let current_path = db_file(owner, db, &self.config);
+        let source_key = db_key(owner, db, db_type, &self.config)?;
+        let target_key = create_db_key(owner, db, target_type, &self.config)?;
         let source_backup_path = backup_path(owner, db, db_type, &self.config);
         let target_backup_path = backup_path(owner, db, target_type, &self.config);
         let backup_exists = source_backup_path.exists();
@@ -256,12 +267,29 @@ impl DbPool {
         }
         if db_type == DbKind::Memory {
             user_db
-                .0
+                .db
                 .read()
                 .await
                 .backup(current_path.to_string_lossy().as_ref())?;
         }
-        *user_db = UserDb::new(current_path.to_string_lossy().as_ref(), target_type)?;
+
+        if source_key == target_key {
+            *user_db = UserDb::new(
+                current_path.to_string_lossy().as_ref(),
+                target_type,
+                target_key.as_ref(),
+            )?;
+        } else {
+            self.reencrypt_db(
+                user_db,
+                owner,
+                db,
+                target_type,
+                source_key.as_ref(),
+                target_key.as_ref(),
+            )
+            .await?;
+        }
 
         if db_type != DbKind::Memory && target_type == DbKind::Memory {
             if backup_exists {
@@ -271,6 +299,59 @@ impl DbPool {
                 remove_file_if_exists(&target_backup_path)?;
             }
         }
+        if source_key.is_some() && target_key.is_none() {
+            remove_file_if_exists(db_key_file(owner, db, &self.config)?)?;
+        }
+
+        Ok(())
+    }
+
+    // The encrypted and plain databases differ in their file format so
+    // the database (and its backup) must be rewritten rather than just
+    // reopened: a snapshot of the database is written in the target format
+    // and then opened and moved in place of the current database file.
+    async fn reencrypt_db(
+        &self,
+        user_db: &mut UserDb,
+        owner: &str,
+        db: &str,
+        target_type: DbKind,
+        source_key: Option<&EncryptionKey>,
+        target_key: Option<&EncryptionKey>,
+    ) -> ServerResult {
+        let current_path = db_file(owner, db, &self.config);
+        let snapshot_path = db_backup_dir(owner, &self.config).join(format!("{db}.snapshot"));
+        let converted_path = db_backup_dir(owner, &self.config).join(format!("{db}.converted"));
+        let backup_path = db_backup_file(owner, db, &self.config);
+        std::fs::create_dir_all(db_backup_dir(owner, &self.config))?;
+
+        user_db
+            .db
+            .read()
+            .await
+            .backup(snapshot_path.to_string_lossy().as_ref())?;
+        encryption::reencrypt(&snapshot_path, source_key, &converted_path, target_key)?;
+        encryption::remove_db_files(&snapshot_path)?;
+
+        if backup_path.exists() {
+            encryption::reencrypt(&backup_path, source_key, &snapshot_path, target_key)?;
+            std::fs::rename(&snapshot_path, &backup_path)?;
+        }
+
+        *user_db = UserDb::new(
+            converted_path.to_string_lossy().as_ref(),
+            target_type,
+            target_key,
+        )?;
+        encryption::remove_db_files(&current_path)?;
+        user_db
+            .rename(current_path.to_string_lossy().as_ref())
+            .await?;
+
+        if target_type == DbKind::Memory {
+            encryption::remove_db_files(&converted_path)?;
+        }
+
         Ok(())
     }
 
@@ -296,12 +377,20 @@ impl DbPool {
         let target_audit_path = db_audit_file(new_owner, new_db, &self.config);
         remove_file_if_exists(&target_audit_path)?;
 
+        if let Some(key_file) = key_file(owner, db, &self.config)
+            && key_file.exists()
+        {
+            let target_key_file = db_key_file(new_owner, new_db, &self.config)?;
+            std::fs::create_dir_all(target_key_file.parent().unwrap_or(Path::new("")))?;
+            std::fs::copy(key_file, target_key_file)?;
+        }
+
         let target_db = DbName::new(new_owner, new_db);
         let cloned_db;
 
         {
             let user_db = self.db(owner, db).await?;
-            let db_guard = user_db.0.read().await;
+            let db_guard = user_db.db.read().await;
             cloned_db = db_guard.copy(target_file.to_string_lossy().as_ref())?;
 
             if audit_path.exists() {
@@ -312,7 +401,7 @@ impl DbPool {
         self.pool
             .write()
             .await
-            .insert(target_db, UserDb(Arc::new(RwLock::new(cloned_db))));
+            .insert(target_db, UserDb::from_db(cloned_db)?);
 
         Ok(())
     }
@@ -323,7 +412,13 @@ impl DbPool {
         remove_file_if_exists(db_file(owner, &format!(".{db}"), &self.config))?;
         remove_file_if_exists(db_backup_file(owner, db, &self.config))?;
         remove_file_if_exists(db_backup_audit_file(owner, db, &self.config))?;
-        remove_file_if_exists(db_audit_file(owner, db, &self.config))
+        remove_file_if_exists(db_audit_file(owner, db, &self.config))?;
+
+        if let Some(key_file) = key_file(owner, db, &self.config) {
+            remove_file_if_exists(key_file)?;
+        }
+
+        Ok(())
     }
 
     pub(crate) async fn exec(
@@ -374,6 +469,10 @@ impl DbPool {
         Ok(())
     }
 
+    pub(crate) async fn verify_db(&self, owner: &str, db: &str) -> ServerResult<DbVerifyReport> {
+        Ok(self.db(owner, db).await?.verify().await)
+    }
+
     pub(crate) async fn shrink_to_fit_db(&self, owner: &str, db: &str) -> ServerResult {
         let user_db = self.db(owner, db).await?;
         user_db.shrink_to_fit().await?;
@@ -401,6 +500,13 @@ impl DbPool {
             std::fs::remove_dir_all(&user_dir)?;
         }
 
+        if !self.config.encryption_keys_dir.is_empty() {
+            let keys_dir = Path::new(&self.config.encryption_keys_dir).join(username);
+            if std::fs::exists(&keys_dir)? {
+                std::fs::remove_dir_all(&keys_dir)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -483,6 +589,13 @@ impl DbPool {
             std::fs::create_dir_all(backups_dir)?;
             std::fs::rename(backup_audit_path, new_backup_audit_path)?;
         }
+        if let Some(key_file) = key_file(owner, db, &self.config)
+            && key_file.exists()
+        {
+            let new_key_file = db_key_file(new_owner, new_db, &self.config)?;
+            std::fs::create_dir_all(new_key_file.parent().unwrap_or(Path::new("")))?;
+            std::fs::rename(key_file, new_key_file)?;
+        }
         let audit_path = db_audit_file(owner, db, &self.config);
         if audit_path.exists() {
             let new_audit_path = db_audit_file(new_owner, new_db, &self.config);
@@ -513,12 +626,17 @@ impl DbPool {
             });
         }
 
+        let key = db_key(owner, db, db_type, &self.config)?;
         self.remove_db(owner, db).await?;
 
         let db_name = DbName::new(owner, db);
         let current_path = db_file(owner, db, &self.config);
         let result = self.do_restore(backup_path, &current_path, owner, db, db_type);
-        let user_db = UserDb::new(current_path.to_string_lossy().as_ref(), db_type)?;
+        let user_db = UserDb::new(
+            current_path.to_string_lossy().as_ref(),
+            db_type,
+            key.as_ref(),
+        )?;
         self.pool.write().await.insert(db_name, user_db);
         result
     }
@@ -556,12 +674,17 @@ impl DbPool {
             });
         }
 
+        let key = db_key(owner, db, db_type, &self.config)?;
         let db_name = DbName::new(owner, db);
         self.remove_db(owner, db).await?;
 
         let current_path = db_file(owner, db, &self.config);
         let result = self.do_rollback(backup_path, &current_path, owner, db, db_type);
-        let user_db = UserDb::new(current_path.to_string_lossy().as_ref(), db_type)?;
+        let user_db = UserDb::new(
+            current_path.to_string_lossy().as_ref(),
+            db_type,
+            key.as_ref(),
+        )?;
         self.pool.write().await.insert(db_name, user_db);
 
         match result {
@@ -678,3 +801,52 @@ fn db_audit_file(owner: &str, db: &str, config: &Config) -> PathBuf {
 fn db_file(owner: &str, db: &str, config: &Config) -> PathBuf {
     Path::new(&config.data_dir).join(owner).join(db)
 }
+
+fn key_file(owner: &str, db: &str, config: &Config) -> Option<PathBuf> {
+    if config.encryption_keys_dir.is_empty() {
+        return None;
+    }
+
+    Some(
+        Path::new(&config.encryption_keys_dir)
+            .join(owner)
+            .join(format!("{db}.key")),
+    )
+}
+
+fn db_key_file(owner: &str, db: &str, config: &Config) -> ServerResult<PathBuf> {
+    key_file(owner, db, config).ok_or_else(|| {
+        ServerError::new(
+            ErrorCode::DbInvalid.into(),
+            "encrypted databases require 'encryption_keys_dir' to be configured",
+        )
+    })
+}
+
+fn db_key(
+    owner: &str,
+    db: &str,
+    db_type: DbKind,
+    config: &Config,
+) -> ServerResult<Option<EncryptionKey>> {
+    if db_type != DbKind::Encrypted {
+        return Ok(None);
+    }
+
+    Ok(Some(encryption::db_key(&db_key_file(owner, db, config)?)?))
+}
+
+fn create_db_key(
+    owner: &str,
+    db: &str,
+    db_type: DbKind,
+    config: &Config,
+) -> ServerResult<Option<EncryptionKey>> {
+    if db_type != DbKind::Encrypted {
+        return Ok(None);
+    }
+
+    Ok(Some(encryption::create_db_key(&db_key_file(
+        owner, db, config,
+    )?)?))
+}
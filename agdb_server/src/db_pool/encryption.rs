@@ -0,0 +1,222 @@
+use crate::server_error::ServerError;
+use crate::server_error::ServerResult;
+use crate::utilities::remove_file_if_exists;
+use agdb::AnyStorage;
+use agdb::Encrypted;
+use agdb::EncryptionKey;
+use agdb::FileStorage;
+use agdb::StorageData;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+/// Reads the hex encoded database encryption key from `path`.
+pub(crate) fn db_key(path: &Path) -> ServerResult<EncryptionKey> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ServerError::from(format!(
+            "failed to read the encryption key '{}': {e}",
+            path.display()
+        ))
+    })?;
+
+    decode_key(content.trim()).ok_or_else(|| {
+        ServerError::from(format!(
+            "invalid encryption key '{}', expected {} hex characters",
+            path.display(),
+            size_of::<EncryptionKey>() * 2
+        ))
+    })
+}
+
+/// Reads the database encryption key from `path` or generates
+/// a new random key and stores it there if the file does not exist.
+/// On unix the new key file is readable and writable only by its owner.
+pub(crate) fn create_db_key(path: &Path) -> ServerResult<EncryptionKey> {
+    if std::fs::exists(path)? {
+        return db_key(path);
+    }
+
+    let mut key = EncryptionKey::default();
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| ServerError::from("failed to generate encryption key"))?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path)?.write_all(encode_key(&key).as_bytes())?;
+
+    Ok(key)
+}
+
+/// Writes the content of the database file `source` (encrypted with
+/// `source_key` if any) into a new file `target` encrypted with
+/// `target_key` (or unencrypted). The write ahead log files of both
+/// are removed as the databases are not open.
+pub(crate) fn reencrypt(
+    source: &Path,
+    source_key: Option<&EncryptionKey>,
+    target: &Path,
+    target_key: Option<&EncryptionKey>,
+) -> ServerResult {
+    remove_db_files(target)?;
+
+    {
+        let source_storage = storage(source, source_key)?;
+        let mut target_storage = storage(target, target_key)?;
+        target_storage.write(0, &source_storage.read(0, source_storage.len())?)?;
+        target_storage.flush()?;
+    }
+
+    remove_file_if_exists(wal_file(source))?;
+    remove_file_if_exists(wal_file(target))
+}
+
+pub(crate) fn remove_db_files(path: &Path) -> ServerResult {
+    remove_file_if_exists(path)?;
+    remove_file_if_exists(wal_file(path))
+}
+
+fn storage(path: &Path, key: Option<&EncryptionKey>) -> ServerResult<AnyStorage> {
+    let name = path.to_string_lossy();
+
+    Ok(match key {
+        Some(key) => AnyStorage::Encrypted(Encrypted::with_key(&name, key)?),
+        None => AnyStorage::File(FileStorage::new(&name)?),
+    })
+}
+
+fn wal_file(path: &Path) -> std::path::PathBuf {
+    path.with_file_name(format!(
+        ".{}",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+fn encode_key(key: &EncryptionKey) -> String {
+    key.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_key(hex: &str) -> Option<EncryptionKey> {
+    if hex.len() != size_of::<EncryptionKey>() * 2 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut key = EncryptionKey::default();
+
+    for (byte, chunk) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+    }
+
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    struct TestDir {
+        directory: PathBuf,
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.directory);
+        }
+    }
+
+    fn test_dir(test_name: &str) -> TestDir {
+        let directory = std::env::temp_dir().join(format!(
+            "agdb_encryption_{test_name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        TestDir { directory }
+    }
+
+    #[test]
+    fn key_roundtrip() {
+        let key = [0xab; 32];
+        let encoded = encode_key(&key);
+
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(decode_key(&encoded), Some(key));
+        assert_eq!(decode_key("abc"), None);
+        assert_eq!(decode_key(&"zz".repeat(32)), None);
+    }
+
+    #[test]
+    fn create_and_read_key() {
+        let dir = test_dir("create_and_read_key");
+        let path = dir.directory.join("user").join("db.key");
+
+        let key = create_db_key(&path).unwrap();
+
+        assert_eq!(db_key(&path).unwrap(), key);
+        assert_eq!(create_db_key(&path).unwrap(), key);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn created_key_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = test_dir("created_key_is_owner_only");
+        let path = dir.directory.join("db.key");
+
+        create_db_key(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn invalid_key() {
+        let dir = test_dir("invalid_key");
+        let path = dir.directory.join("db.key");
+        std::fs::write(&path, "0123").unwrap();
+
+        assert!(
+            db_key(&path)
+                .unwrap_err()
+                .description
+                .starts_with("invalid encryption key")
+        );
+        assert!(db_key(&dir.directory.join("missing.key")).is_err());
+    }
+
+    #[test]
+    fn reencrypt_roundtrip() {
+        let dir = test_dir("reencrypt_roundtrip");
+        let plain = dir.directory.join("plain");
+        let encrypted = dir.directory.join("encrypted");
+        let decrypted = dir.directory.join("decrypted");
+        let key = [1; 32];
+
+        {
+            let mut storage = FileStorage::new(&plain.to_string_lossy()).unwrap();
+            storage.write(0, b"Hello, World!").unwrap();
+            storage.flush().unwrap();
+        }
+
+        reencrypt(&plain, None, &encrypted, Some(&key)).unwrap();
+        reencrypt(&encrypted, Some(&key), &decrypted, None).unwrap();
+
+        assert_ne!(std::fs::read(&encrypted).unwrap(), b"Hello, World!");
+        assert_eq!(std::fs::read(&decrypted).unwrap(), b"Hello, World!");
+        assert!(!std::fs::exists(wal_file(&encrypted)).unwrap());
+        assert!(!std::fs::exists(wal_file(&decrypted)).unwrap());
+    }
+}
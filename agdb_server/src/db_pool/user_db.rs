@@ -1,10 +1,16 @@
 use crate::db_pool::ErrorCode;
 use crate::db_pool::ServerError;
 use crate::server_error::ServerResult;
+use agdb::AnyStorage;
 use agdb::DbAny;
+use agdb::DbAnySnapshot;
 use agdb::DbAnyTransaction;
 use agdb::DbAnyTransactionMut;
 use agdb::DbError;
+use agdb::DbErrorType;
+use agdb::DbVerifyReport;
+use agdb::EncryptionKey;
+use agdb::MemoryStorage;
 use agdb::QueryConditionData;
 use agdb::QueryId;
 use agdb::QueryIds;
@@ -15,24 +21,57 @@ use agdb_api::DbKind;
 use agdb_api::Queries;
 use agdb_api::QueryAudit;
 use std::sync::Arc;
+use std::sync::PoisonError;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 use tokio::sync::RwLock;
 
+// Read queries are run against the snapshot published after
+// the last write and never take the database lock so that
+// they neither wait for a running write nor hold it back.
+// The writes remain serialized.
 #[derive(Clone)]
-pub(crate) struct UserDb(pub(crate) Arc<RwLock<DbAny>>);
+pub(crate) struct UserDb {
+    pub(crate) db: Arc<RwLock<DbAny>>,
+    snapshot: Arc<std::sync::RwLock<Arc<DbAnySnapshot>>>,
+}
 
 impl UserDb {
-    pub(crate) fn new(name: &str, db_type: DbKind) -> ServerResult<Self> {
-        match db_type {
-            DbKind::Memory => Ok(Self(Arc::new(RwLock::new(DbAny::new_memory(name)?)))),
-            DbKind::File => Ok(Self(Arc::new(RwLock::new(DbAny::new_file(name)?)))),
-            DbKind::Mapped => Ok(Self(Arc::new(RwLock::new(DbAny::new_mapped(name)?)))),
+    pub(crate) fn new(
+        name: &str,
+        db_type: DbKind,
+        key: Option<&EncryptionKey>,
+    ) -> ServerResult<Self> {
+        match (db_type, key) {
+            (DbKind::Memory, _) => Self::from_db(DbAny::new_memory(name)?),
+            (DbKind::File, _) => Self::from_db(DbAny::new_file(name)?),
+            (DbKind::Mapped, _) => Self::from_db(DbAny::new_mapped(name)?),
+            (DbKind::Encrypted, Some(key)) => Self::from_db(DbAny::new_encrypted(name, key)?),
+            (DbKind::Encrypted, None) => Err(ServerError::from(format!(
+                "encryption key for '{name}' not provided"
+            ))),
         }
     }
 
+    // Database not backed by any file used in place of
+    // a database whose files are being replaced.
+    pub(crate) fn empty(name: &str) -> ServerResult<Self> {
+        Self::from_db(DbAny::with_data(AnyStorage::Memory(
+            MemoryStorage::from_buffer(name, Vec::new()),
+        ))?)
+    }
+
+    pub(crate) fn from_db(mut db: DbAny) -> ServerResult<Self> {
+        Ok(Self {
+            snapshot: Arc::new(std::sync::RwLock::new(Arc::new(db.snapshot()?))),
+            db: Arc::new(RwLock::new(db)),
+        })
+    }
+
     pub(crate) async fn exec(&self, mut queries: Queries) -> ServerResult<Vec<QueryResult>> {
-        self.0.read().await.transaction(|t| {
+        let snapshot = self.snapshot();
+
+        snapshot.transaction(|t| {
             let mut results = vec![];
 
             for q in queries.0.iter_mut() {
@@ -49,7 +88,8 @@ impl UserDb {
         mut queries: Queries,
         username: &str,
     ) -> ServerResult<(Vec<QueryResult>, Vec<QueryAudit>)> {
-        self.0.write().await.transaction_mut(|t| {
+        let mut db = self.db.write().await;
+        let result = db.transaction_mut(|t| {
             let mut audit = vec![];
             let mut results = vec![];
             let mut qs = vec![];
@@ -61,26 +101,54 @@ impl UserDb {
             }
 
             Ok((results, audit))
-        })
+        });
+
+        self.publish_snapshot(&mut db)?;
+        result
     }
 
     pub(crate) async fn optimize_storage(&self) -> ServerResult<()> {
-        self.0.write().await.optimize_storage()?;
-        Ok(())
+        let mut db = self.db.write().await;
+        db.optimize_storage()?;
+        self.publish_snapshot(&mut db)
     }
 
     pub(crate) async fn shrink_to_fit(&self) -> ServerResult<()> {
-        self.0.write().await.shrink_to_fit()?;
-        Ok(())
+        let mut db = self.db.write().await;
+        db.shrink_to_fit()?;
+        self.publish_snapshot(&mut db)
     }
 
     pub(crate) async fn rename(&self, target_name: &str) -> ServerResult<()> {
-        self.0.write().await.rename(target_name)?;
-        Ok(())
+        let mut db = self.db.write().await;
+        db.rename(target_name)?;
+        self.publish_snapshot(&mut db)
     }
 
     pub(crate) async fn size(&self) -> u64 {
-        self.0.read().await.size()
+        self.db.read().await.size()
+    }
+
+    pub(crate) async fn verify(&self) -> DbVerifyReport {
+        self.db.read().await.verify()
+    }
+
+    // Called with the write lock still held so that the published
+    // snapshot is never older than the one it replaces.
+    fn publish_snapshot(&self, db: &mut DbAny) -> ServerResult<()> {
+        let snapshot = Arc::new(db.snapshot()?);
+        *self
+            .snapshot
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = snapshot;
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Arc<DbAnySnapshot> {
+        self.snapshot
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 }
 
@@ -90,6 +158,15 @@ fn t_exec(
     results: &[QueryResult],
 ) -> ServerResult<QueryResult> {
     match q {
+        QueryType::Aggregate(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
+        QueryType::Expect(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
+        QueryType::Match(q) => t.exec(&*q),
         QueryType::Search(q) => {
             inject_results_search(q, results)?;
             t.exec(&*q)
@@ -99,6 +176,10 @@ fn t_exec(
             t.exec(&*q)
         }
         QueryType::SelectAllAliases(q) => t.exec(&*q),
+        QueryType::SelectAnalytics(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
         QueryType::SelectEdgeCount(q) => t.exec(&*q),
         QueryType::SelectIndexes(q) => t.exec(&*q),
         QueryType::SelectKeys(q) => {
@@ -115,11 +196,11 @@ fn t_exec(
             t.exec(&*q)
         }
         _ => Err(DbError::query(
-            agdb::DbErrorType::NotAllowed,
+            DbErrorType::NotAllowed,
             "mutable query not allowed",
         )),
     }
-    .map_err(|e| ServerError::new(ErrorCode::DbError.into(), &e.description))
+    .map_err(db_error)
 }
 
 fn audit_query(user: &str, audit: &mut Vec<QueryAudit>, query: QueryType) {
@@ -143,6 +224,15 @@ fn t_exec_mut(
     let mut do_audit = false;
 
     let r = match &mut q {
+        QueryType::Aggregate(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
+        QueryType::Expect(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
+        QueryType::Match(q) => t.exec(&*q),
         QueryType::Search(q) => {
             inject_results_search(q, results)?;
             t.exec(&*q)
@@ -152,6 +242,10 @@ fn t_exec_mut(
             t.exec(&*q)
         }
         QueryType::SelectAllAliases(q) => t.exec(&*q),
+        QueryType::SelectAnalytics(q) => {
+            inject_results(&mut q.ids, results)?;
+            t.exec(&*q)
+        }
         QueryType::SelectEdgeCount(q) => t.exec(&*q),
         QueryType::SelectIndexes(q) => t.exec(&*q),
         QueryType::SelectKeys(q) => {
@@ -172,6 +266,11 @@ fn t_exec_mut(
             inject_results(&mut q.ids, results)?;
             t.exec_mut(&*q)
         }
+        QueryType::InsertAnalytics(q) => {
+            do_audit = true;
+            inject_results(&mut q.ids, results)?;
+            t.exec_mut(&*q)
+        }
         QueryType::InsertEdges(q) => {
             do_audit = true;
             inject_results(&mut q.ids, results)?;
@@ -190,6 +289,11 @@ fn t_exec_mut(
             inject_results(&mut q.ids, results)?;
             t.exec_mut(&*q)
         }
+        QueryType::UpdateValues(q) => {
+            do_audit = true;
+            inject_results(&mut q.ids, results)?;
+            t.exec_mut(&*q)
+        }
         QueryType::Remove(q) => {
             do_audit = true;
             inject_results(&mut q.0, results)?;
@@ -218,7 +322,17 @@ fn t_exec_mut(
         audit_query(username, audit, q);
     }
 
-    r.map_err(|e| ServerError::new(ErrorCode::DbError.into(), &e.description))
+    r.map_err(db_error)
+}
+
+fn db_error(e: DbError) -> ServerError {
+    let code = if e.ty == DbErrorType::PreconditionFailed {
+        ErrorCode::PreconditionFailed
+    } else {
+        ErrorCode::DbError
+    };
+
+    ServerError::new(code.into(), &e.description)
 }
 
 fn id_or_result(id: QueryId, results: &[QueryResult]) -> ServerResult<QueryId> {
@@ -298,3 +412,90 @@ fn inject_results_ids(ids: &mut Vec<QueryId>, results: &[QueryResult]) -> Server
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agdb::QueryBuilder;
+
+    #[tokio::test]
+    async fn writes_do_not_wait_for_reads() -> ServerResult {
+        let user_db = UserDb::new("writes_do_not_wait_for_reads", DbKind::Memory, None)?;
+        let count = QueryBuilder::select().node_count().query();
+
+        // snapshot of a read request still running
+        let snapshot = user_db.snapshot();
+
+        user_db
+            .exec_mut(
+                Queries(vec![QueryBuilder::insert().nodes().count(2).query().into()]),
+                "user",
+            )
+            .await?;
+
+        assert_eq!(snapshot.exec(&count)?.result, 0);
+        assert_eq!(
+            user_db.exec(Queries(vec![count.into()])).await?[0].result,
+            2
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reads_see_finished_writes() -> ServerResult {
+        let user_db = UserDb::new("reads_see_finished_writes", DbKind::Memory, None)?;
+        let count = || Queries(vec![QueryBuilder::select().node_count().query().into()]);
+
+        assert_eq!(user_db.exec(count()).await?[0].result, 0);
+
+        user_db
+            .exec_mut(
+                Queries(vec![QueryBuilder::insert().nodes().count(2).query().into()]),
+                "user",
+            )
+            .await?;
+
+        assert_eq!(user_db.exec(count()).await?[0].result, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reads_do_not_wait_for_writes() -> ServerResult {
+        let user_db = UserDb::new("reads_do_not_wait_for_writes", DbKind::Memory, None)?;
+        let count = || Queries(vec![QueryBuilder::select().node_count().query().into()]);
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (finish_tx, finish_rx) = std::sync::mpsc::channel::<()>();
+        let writer = user_db.clone();
+
+        let write = std::thread::spawn(move || {
+            writer
+                .db
+                .blocking_write()
+                .transaction_mut(|t| -> Result<(), DbError> {
+                    t.exec_mut(QueryBuilder::insert().nodes().count(2).query())?;
+                    let _ = started_tx.send(());
+                    let _ = finish_rx.recv();
+                    Ok(())
+                })
+        });
+
+        started_rx
+            .recv()
+            .map_err(|e| ServerError::from(e.to_string()))?;
+
+        let read = tokio::time::timeout(std::time::Duration::from_secs(5), user_db.exec(count()))
+            .await
+            .map_err(|e| ServerError::from(e.to_string()))??;
+
+        assert_eq!(read[0].result, 0);
+
+        let _ = finish_tx.send(());
+        write
+            .join()
+            .map_err(|_| ServerError::from("writer panicked"))??;
+
+        Ok(())
+    }
+}
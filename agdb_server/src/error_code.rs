@@ -8,6 +8,7 @@ pub(crate) enum ErrorCode {
     DbExists,
     DbInvalid,
     DbError,
+    PreconditionFailed,
 }
 
 impl From<ErrorCode> for StatusCode {
@@ -25,6 +26,7 @@ impl From<&ErrorCode> for StatusCode {
             ErrorCode::DbExists => 465,
             ErrorCode::DbInvalid => 467,
             ErrorCode::DbError => 470,
+            ErrorCode::PreconditionFailed => 471,
         })
         .unwrap()
     }
@@ -45,6 +47,7 @@ impl ErrorCode {
             ErrorCode::DbExists => "db already exists",
             ErrorCode::DbInvalid => "db invalid",
             ErrorCode::DbError => "db error",
+            ErrorCode::PreconditionFailed => "precondition failed",
         }
     }
 }
@@ -64,5 +67,9 @@ mod tests {
         assert_eq!(ErrorCode::DbExists.as_str(), "db already exists");
         assert_eq!(ErrorCode::DbInvalid.as_str(), "db invalid");
         assert_eq!(ErrorCode::DbError.as_str(), "db error");
+        assert_eq!(
+            ErrorCode::PreconditionFailed.as_str(),
+            "precondition failed"
+        );
     }
 }
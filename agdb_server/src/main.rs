@@ -15,6 +15,7 @@ mod logger;
 mod password;
 mod raft;
 mod routes;
+mod salvage;
 mod server_db;
 mod server_error;
 mod server_state;
@@ -30,6 +31,15 @@ const CONFIG_FILE: &str = "agdb_server.yaml";
 #[tokio::main]
 async fn main() -> ServerResult {
     let config = config::new(CONFIG_FILE)?;
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    if args
+        .first()
+        .is_some_and(|arg| arg == salvage::SALVAGE_COMMAND)
+    {
+        return salvage::run(&config, &args[1..]);
+    }
+
     logger::init(config.log_level);
 
     password::init(config.pepper);
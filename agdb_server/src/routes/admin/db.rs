@@ -25,6 +25,7 @@ use crate::server_error::ServerResponse;
 use crate::server_error::permission_denied;
 use crate::user_id::AdminId;
 use crate::utilities::required_role;
+use agdb::DbVerifyReport;
 use agdb_api::DbAudit;
 use agdb_api::DbUserRole;
 use agdb_api::Queries;
@@ -351,6 +352,7 @@ pub(crate) async fn delete(
          (status = 401, description = "unauthorized"),
          (status = 403, description = "mutable queries not allowed"),
          (status = 404, description = "db not found"),
+         (status = 471, description = "precondition failed"),
     )
 )]
 pub(crate) async fn exec(
@@ -383,6 +385,7 @@ pub(crate) async fn exec(
          (status = 200, description = "ok", body = QueriesResults),
          (status = 401, description = "unauthorized"),
          (status = 404, description = "db not found"),
+         (status = 471, description = "precondition failed"),
     )
 )]
 pub(crate) async fn exec_mut(
@@ -659,3 +662,30 @@ pub(crate) async fn rollback(
         [("commit-index", commit_index.to_string())],
     ))
 }
+
+#[utoipa::path(get,
+    path = "/api/v1/admin/db/{owner}/{db}/verify",
+    operation_id = "admin_db_verify",
+    tag = "agdb",
+    security(("Token" = [])),
+    params(
+        ("owner" = String, Path, description = "user name"),
+        ("db" = String, Path, description = "db name"),
+    ),
+    responses(
+         (status = 200, description = "ok", body = DbVerifyReport),
+         (status = 401, description = "unauthorized"),
+         (status = 404, description = "user / db not found"),
+    )
+)]
+pub(crate) async fn verify(
+    _admin: AdminId,
+    State(db_pool): State<DbPool>,
+    State(server_db): State<ServerDb>,
+    Path((owner, db)): Path<(String, String)>,
+) -> ServerResponse<(StatusCode, Json<DbVerifyReport>)> {
+    let owner_id = server_db.user_id(&owner).await?;
+    server_db.user_db_id(owner_id, &owner, &db).await?;
+
+    Ok((StatusCode::OK, Json(db_pool.verify_db(&owner, &db).await?)))
+}
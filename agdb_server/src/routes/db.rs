@@ -30,11 +30,15 @@ use agdb_api::Queries;
 use agdb_api::QueriesResults;
 use agdb_api::ServerDatabase;
 use axum::Json;
+use axum::extract::FromRequest;
 use axum::extract::Path;
 use axum::extract::Query;
+use axum::extract::Request;
 use axum::extract::State;
 use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
 use axum::response::IntoResponse;
+use axum::response::Response;
 use serde::Deserialize;
 use utoipa::IntoParams;
 use utoipa::ToSchema;
@@ -63,6 +67,37 @@ pub struct OptimizeParam {
     pub shrink_to_fit: Option<bool>,
 }
 
+/// Queries sent either as JSON or, with the `text/plain`
+/// content type, in the text form separated by `;`.
+pub(crate) struct QueriesBody(pub(crate) Queries);
+
+impl<S: Send + Sync> FromRequest<S> for QueriesBody {
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let text = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/plain"));
+
+        if text {
+            let body = String::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            let queries = agdb::parse_queries(&body).map_err(|e| {
+                ServerError::new(ErrorCode::DbError.into(), &e.description).into_response()
+            })?;
+            Ok(Self(Queries(queries)))
+        } else {
+            let Json(queries) = Json::<Queries>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            Ok(Self(queries))
+        }
+    }
+}
+
 #[utoipa::path(post,
     path = "/api/v1/db/{owner}/{db}/add",
     operation_id = "db_add",
@@ -390,12 +425,16 @@ pub(crate) async fn delete(
         ("owner" = String, Path, description = "db owner user name"),
         ("db" = String, Path, description = "db name"),
     ),
-    request_body = Queries,
+    request_body(content(
+        (Queries = "application/json"),
+        (String = "text/plain", example = json!("select node_count; select aliases")),
+    )),
     responses(
          (status = 200, description = "ok", body = QueriesResults),
          (status = 401, description = "unauthorized"),
          (status = 403, description = "mutable queries not allowed"),
          (status = 404, description = "db not found"),
+         (status = 471, description = "precondition failed"),
     )
 )]
 pub(crate) async fn exec(
@@ -403,7 +442,7 @@ pub(crate) async fn exec(
     State(db_pool): State<DbPool>,
     State(server_db): State<ServerDb>,
     Path((owner, db)): Path<(String, String)>,
-    Json(queries): Json<Queries>,
+    QueriesBody(queries): QueriesBody,
 ) -> ServerResponse<impl IntoResponse> {
     let _ = server_db.user_db_id(user.0, &owner, &db).await?;
     let required_role = required_role(&queries);
@@ -425,12 +464,16 @@ pub(crate) async fn exec(
         ("owner" = String, Path, description = "db owner user name"),
         ("db" = String, Path, description = "db name"),
     ),
-    request_body = Queries,
+    request_body(content(
+        (Queries = "application/json"),
+        (String = "text/plain", example = json!("select node_count; select aliases")),
+    )),
     responses(
          (status = 200, description = "ok", body = QueriesResults),
          (status = 401, description = "unauthorized"),
          (status = 403, description = "must have at least write role"),
          (status = 404, description = "db not found"),
+         (status = 471, description = "precondition failed"),
     )
 )]
 pub(crate) async fn exec_mut(
@@ -439,7 +482,7 @@ pub(crate) async fn exec_mut(
     State(cluster): State<Cluster>,
     State(server_db): State<ServerDb>,
     Path((owner, db)): Path<(String, String)>,
-    Json(queries): Json<Queries>,
+    QueriesBody(queries): QueriesBody,
 ) -> ServerResponse<impl IntoResponse> {
     let role = server_db.user_db_role(user.0, &owner, &db).await?;
     let required_role = required_role(&queries);
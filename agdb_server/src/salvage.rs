@@ -0,0 +1,152 @@
+use crate::config::Config;
+use crate::server_error::ServerError;
+use crate::server_error::ServerResult;
+use agdb::DbFile;
+use agdb::DbSalvageReport;
+use std::path::Path;
+
+pub(crate) const SALVAGE_COMMAND: &str = "salvage";
+
+/// Runs `agdb_server salvage <owner>/<db>` against the database
+/// file in the data directory and prints the salvage report. The
+/// server must not be running while the database is being salvaged.
+pub(crate) fn run(config: &Config, args: &[String]) -> ServerResult {
+    let (owner, db) = parse_args(args)?;
+    let report = salvage_db(&config.data_dir, owner, db)?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> ServerResult<(&str, &str)> {
+    let [name] = args else {
+        return Err(ServerError::from("usage: agdb_server salvage <owner>/<db>"));
+    };
+
+    name.split_once('/').ok_or_else(|| {
+        ServerError::from(format!(
+            "invalid database name '{name}', expected <owner>/<db>"
+        ))
+    })
+}
+
+// Rebuilds the database next to the original which is then moved
+// to the owner's backups directory as `<db>.damaged` and replaced
+// with the salvaged database.
+fn salvage_db(data_dir: &str, owner: &str, db: &str) -> ServerResult<DbSalvageReport> {
+    let owner_dir = Path::new(data_dir).join(owner);
+    let source = owner_dir.join(db);
+    let target = owner_dir.join(format!("{db}.salvaged"));
+    let damaged = owner_dir.join("backups").join(format!("{db}.damaged"));
+
+    if std::fs::exists(&damaged)? {
+        return Err(ServerError::from(format!(
+            "damaged database backup '{}' already exists",
+            damaged.display()
+        )));
+    }
+
+    let report = {
+        let (_db, report) = DbFile::salvage(&source.to_string_lossy(), &target.to_string_lossy())?;
+        report
+    };
+
+    std::fs::create_dir_all(owner_dir.join("backups"))?;
+    std::fs::rename(&source, &damaged)?;
+    std::fs::rename(&target, &source)?;
+
+    let target_wal = owner_dir.join(format!(".{db}.salvaged"));
+
+    if std::fs::exists(&target_wal)? {
+        std::fs::remove_file(target_wal)?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agdb::QueryBuilder;
+    use std::path::PathBuf;
+
+    struct TestDir {
+        directory: PathBuf,
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.directory);
+        }
+    }
+
+    fn test_dir(test_name: &str) -> TestDir {
+        let directory =
+            std::env::temp_dir().join(format!("agdb_salvage_{test_name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("user")).unwrap();
+
+        TestDir { directory }
+    }
+
+    #[test]
+    fn salvage_replaces_db() {
+        let dir = test_dir("replaces_db");
+        let data_dir = dir.directory.to_string_lossy().to_string();
+        let db_path = dir.directory.join("user").join("db");
+
+        {
+            let mut db = DbFile::new(&db_path.to_string_lossy()).unwrap();
+            db.exec_mut(QueryBuilder::insert().nodes().aliases("root").query())
+                .unwrap();
+        }
+
+        let report = salvage_db(&data_dir, "user", "db").unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(report.nodes, 1);
+        assert!(
+            std::fs::exists(
+                dir.directory
+                    .join("user")
+                    .join("backups")
+                    .join("db.damaged")
+            )
+            .unwrap()
+        );
+        assert!(!std::fs::exists(dir.directory.join("user").join("db.salvaged")).unwrap());
+
+        let db = DbFile::new(&db_path.to_string_lossy()).unwrap();
+        assert_eq!(
+            db.exec(QueryBuilder::select().ids("root").query())
+                .unwrap()
+                .result,
+            1
+        );
+    }
+
+    #[test]
+    fn salvage_missing_db() {
+        let dir = test_dir("missing_db");
+        let data_dir = dir.directory.to_string_lossy().to_string();
+
+        assert!(salvage_db(&data_dir, "user", "db").is_err());
+        assert!(!std::fs::exists(dir.directory.join("user").join("backups")).unwrap());
+    }
+
+    #[test]
+    fn args() {
+        assert_eq!(
+            parse_args(&["user/db".to_string()]).unwrap(),
+            ("user", "db")
+        );
+        assert_eq!(
+            parse_args(&[]).unwrap_err().description,
+            "usage: agdb_server salvage <owner>/<db>"
+        );
+        assert_eq!(
+            parse_args(&["db".to_string()]).unwrap_err().description,
+            "invalid database name 'db', expected <owner>/<db>"
+        );
+    }
+}
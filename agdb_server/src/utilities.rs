@@ -52,6 +52,7 @@ pub(crate) fn required_role(queries: &Queries) -> DbUserRole {
     for q in &queries.0 {
         match q {
             QueryType::InsertAlias(_)
+            | QueryType::InsertAnalytics(_)
             | QueryType::InsertEdges(_)
             | QueryType::InsertIndex(_)
             | QueryType::InsertNodes(_)
@@ -59,7 +60,8 @@ pub(crate) fn required_role(queries: &Queries) -> DbUserRole {
             | QueryType::Remove(_)
             | QueryType::RemoveAliases(_)
             | QueryType::RemoveIndex(_)
-            | QueryType::RemoveValues(_) => {
+            | QueryType::RemoveValues(_)
+            | QueryType::UpdateValues(_) => {
                 return DbUserRole::Write;
             }
             _ => {}
@@ -0,0 +1,21 @@
+use agdb_api::test_server::test_error::TestError;
+
+#[tokio::test]
+async fn verify() -> Result<(), TestError> {
+    agdb_api::tests::routes::admin_db_verify_test::verify().await
+}
+
+#[tokio::test]
+async fn db_not_found() -> Result<(), TestError> {
+    agdb_api::tests::routes::admin_db_verify_test::db_not_found().await
+}
+
+#[tokio::test]
+async fn non_admin() -> Result<(), TestError> {
+    agdb_api::tests::routes::admin_db_verify_test::non_admin().await
+}
+
+#[tokio::test]
+async fn no_token() -> Result<(), TestError> {
+    agdb_api::tests::routes::admin_db_verify_test::no_token().await
+}
@@ -44,3 +44,8 @@ async fn db_type_invalid() -> Result<(), TestError> {
 async fn no_token() -> Result<(), TestError> {
     agdb_api::tests::routes::db_add_test::no_token().await
 }
+
+#[tokio::test]
+async fn add_encrypted() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_add_test::add_encrypted().await
+}
@@ -29,3 +29,13 @@ async fn non_admin() -> Result<(), TestError> {
 async fn no_token() -> Result<(), TestError> {
     agdb_api::tests::routes::db_convert_test::no_token().await
 }
+
+#[tokio::test]
+async fn file_to_encrypted_preserves_backup() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_convert_test::file_to_encrypted_preserves_backup().await
+}
+
+#[tokio::test]
+async fn encrypted_to_memory_preserves_backup() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_convert_test::encrypted_to_memory_preserves_backup().await
+}
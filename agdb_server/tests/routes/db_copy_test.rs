@@ -39,3 +39,8 @@ async fn db_not_found() -> Result<(), TestError> {
 async fn no_token() -> Result<(), TestError> {
     agdb_api::tests::routes::db_copy_test::no_token().await
 }
+
+#[tokio::test]
+async fn copy_encrypted() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_copy_test::copy_encrypted().await
+}
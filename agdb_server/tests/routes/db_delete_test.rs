@@ -34,3 +34,8 @@ async fn non_owner() -> Result<(), TestError> {
 async fn no_token() -> Result<(), TestError> {
     agdb_api::tests::routes::db_delete_test::no_token().await
 }
+
+#[tokio::test]
+async fn delete_encrypted() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_delete_test::delete_encrypted().await
+}
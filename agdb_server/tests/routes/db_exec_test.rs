@@ -75,6 +75,16 @@ async fn query_error() -> Result<(), TestError> {
     agdb_api::tests::routes::db_exec_test::query_error().await
 }
 
+#[tokio::test]
+async fn text_queries() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_exec_test::text_queries().await
+}
+
+#[tokio::test]
+async fn precondition_failed() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_exec_test::precondition_failed().await
+}
+
 #[tokio::test]
 async fn permission_denied() -> Result<(), TestError> {
     agdb_api::tests::routes::db_exec_test::permission_denied().await
@@ -39,3 +39,8 @@ async fn target_exists() -> Result<(), TestError> {
 async fn no_token() -> Result<(), TestError> {
     agdb_api::tests::routes::db_rename_test::no_token().await
 }
+
+#[tokio::test]
+async fn rename_encrypted() -> Result<(), TestError> {
+    agdb_api::tests::routes::db_rename_test::rename_encrypted().await
+}
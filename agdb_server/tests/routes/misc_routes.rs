@@ -154,6 +154,7 @@ async fn basepath_test() -> anyhow::Result<()> {
         request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
         data_dir: SERVER_DATA_DIR.into(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: String::new(),
         tls_key: String::new(),
         tls_root: String::new(),
@@ -330,6 +331,7 @@ async fn large_payload() -> anyhow::Result<()> {
         request_body_limit: 1024,
         data_dir: SERVER_DATA_DIR.into(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: String::new(),
         tls_key: String::new(),
         tls_root: String::new(),
@@ -420,6 +422,7 @@ async fn static_files() -> anyhow::Result<()> {
         request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
         data_dir: SERVER_DATA_DIR.into(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: String::new(),
         tls_key: String::new(),
         tls_root: String::new(),
@@ -487,6 +490,7 @@ async fn static_files_with_basepath() -> anyhow::Result<()> {
         request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
         data_dir: SERVER_DATA_DIR.into(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: String::new(),
         tls_key: String::new(),
         tls_root: String::new(),
@@ -28,6 +28,7 @@ async fn https() -> anyhow::Result<()> {
         request_body_limit: DEFAULT_REQUEST_BODY_LIMIT,
         data_dir: SERVER_DATA_DIR.into(),
         pepper_path: String::new(),
+        encryption_keys_dir: String::new(),
         tls_certificate: format!("{manifest_dir}/tests/test_cert.pem"),
         tls_key: format!("{manifest_dir}/tests/test_cert.key.pem"),
         tls_root: format!("{manifest_dir}/tests/test_root_ca.pem"),